@@ -8,6 +8,7 @@ Provides three capabilities:
 import sys
 from typing import Any, Dict, List, Optional
 from .db_connection import db
+from .similarity import keyword_similarity, min_source_similarity, query_terms, retain_above
 from amplifier_core import (
     ModuleCoordinator,
     ChatResponse,
@@ -180,7 +181,8 @@ Searches in:
 - Issue/PR bodies
 - Labels
 
-Can filter by state, type, labels, repository."""
+Can filter by state, type, labels, repository.
+Results scoring below the configured minimum similarity are left out."""
 
     parameters = {
         "type": "object",
@@ -234,106 +236,92 @@ Can filter by state, type, labels, repository."""
         if item_type in ["pull_request", "both"]:
             results.extend(self._search_pull_requests(query, state, labels, repository, limit))
 
-        results.sort(key=lambda x: x["created_at"], reverse=True)
+        # Items below the configured floor are withheld, like sources passed to the chat
+        with db as conn:
+            floor = min_source_similarity(conn)
+        results = retain_above(results, floor)
+
+        results.sort(key=lambda x: (x["similarity"], x["created_at"]), reverse=True)
 
         return {
             "results": results[:limit],
             "total": len(results),
-            "query": query
+            "query": query,
+            "min_similarity": floor
         }
 
     def _search_issues(self, query: str, state: str, labels: List[str], repository: Optional[str], limit: int) -> List[Dict]:
         """Search issues table."""
-        with db as conn:
-            cursor = conn.cursor()
-
-            where_parts = ["(title LIKE ? OR body LIKE ?)"]
-            params = [f"%{query}%", f"%{query}%"]
-
-            if state != "all":
-                where_parts.append("state = ?")
-                params.append(state)
-
-            if repository:
-                where_parts.append("repository = ?")
-                params.append(repository)
-
-            where_clause = " AND ".join(where_parts)
-
-            query_sql = f"""
-                SELECT
-                    id, number, title, state, repository,
-                    html_url, created_at, closed_at
-                FROM issues
-                WHERE {where_clause}
-                ORDER BY created_at DESC
-                LIMIT ?
-            """
-
-            params.append(limit)
-            cursor.execute(query_sql, params)
-
-            results = []
-            for row in cursor.fetchall():
-                results.append({
-                    "type": "issue",
-                    "id": row["id"],
-                    "number": row["number"],
-                    "title": row["title"],
-                    "state": row["state"],
-                    "repository": row["repository"],
-                    "url": row["html_url"],
-                    "created_at": row["created_at"],
-                    "closed_at": row.get("closed_at")
-                })
-
-            return results
+        return [
+            {"type": "issue", **item}
+            for item in self._search_table("issues", query, state, repository, limit)
+        ]
 
     def _search_pull_requests(self, query: str, state: str, labels: List[str], repository: Optional[str], limit: int) -> List[Dict]:
         """Search pull requests table."""
+        return [
+            {"type": "pull_request", **item}
+            for item in self._search_table("pull_requests", query, state, repository, limit)
+        ]
+
+    def _search_table(self, table: str, query: str, state: str, repository: Optional[str], limit: int) -> List[Dict]:
+        """Find items matching any query term, scored by the share of terms they contain."""
+        terms = query_terms(query)
+        if not terms:
+            return []
+
         with db as conn:
             cursor = conn.cursor()
 
-            where_parts = ["(title LIKE ? OR body LIKE ?)"]
-            params = [f"%{query}%", f"%{query}%"]
+            where_parts = ["(" + " OR ".join("(t.title LIKE ? OR t.body LIKE ?)" for _ in terms) + ")"]
+            params = [pattern for term in terms for pattern in (f"%{term}%", f"%{term}%")]
 
-            if state != "all":
-                where_parts.append("state = ?")
-                params.append(state)
+            # REST and webhook rows store "open"/"closed", GraphQL rows "OPEN"/"CLOSED"/"MERGED"
+            if state == "open":
+                where_parts.append("UPPER(t.state) = 'OPEN'")
+            elif state == "closed":
+                where_parts.append("UPPER(t.state) != 'OPEN'")
 
             if repository:
-                where_parts.append("repository = ?")
+                where_parts.append("r.owner || '/' || r.name = ?")
                 params.append(repository)
 
             where_clause = " AND ".join(where_parts)
+            merged_at = "t.merged_at" if table == "pull_requests" else "NULL"
 
             query_sql = f"""
                 SELECT
-                    id, number, title, state, repository,
-                    html_url, created_at, merged_at, closed_at
-                FROM pull_requests
+                    t.id, t.number, t.title, t.body, t.state,
+                    r.owner || '/' || r.name AS repository,
+                    t.created_at, t.closed_at, {merged_at} AS merged_at
+                FROM {table} t
+                JOIN repositories r ON r.id = t.repo_id
                 WHERE {where_clause}
-                ORDER BY created_at DESC
+                ORDER BY t.created_at DESC
                 LIMIT ?
             """
 
-            params.append(limit)
+            # Over-fetch so the similarity floor doesn't starve the result list
+            params.append(limit * 5)
             cursor.execute(query_sql, params)
 
+            path = "pull" if table == "pull_requests" else "issues"
             results = []
             for row in cursor.fetchall():
-                results.append({
-                    "type": "pull_request",
+                item = {
                     "id": row["id"],
                     "number": row["number"],
                     "title": row["title"],
                     "state": row["state"],
                     "repository": row["repository"],
-                    "url": row["html_url"],
+                    "url": f"https://github.com/{row['repository']}/{path}/{row['number']}",
                     "created_at": row["created_at"],
-                    "merged_at": row.get("merged_at"),
-                    "closed_at": row.get("closed_at")
-                })
+                    "closed_at": row["closed_at"],
+                    "similarity": keyword_similarity(terms, row["title"], row["body"])
+                }
+                if table == "pull_requests":
+                    item["merged_at"] = row["merged_at"]
+                results.append(item)
 
             return results
 
@@ -50,6 +50,9 @@ db = DatabaseConnection()
 
 
 def set_db_path(path: str):
-    """Configure database path (called by server on startup)."""
-    global db
-    db = DatabaseConnection(path)
+    """Configure database path (called by server on startup).
+
+    Repoints the shared instance in place, since tool modules hold a reference to it.
+    """
+    db.close()
+    db.db_path = path
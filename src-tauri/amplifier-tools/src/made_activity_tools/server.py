@@ -153,6 +153,12 @@ async def chat():
             context_parts.append(f"Filtered squads: {', '.join(squads)}")
         if users := filters.get('users'):
             context_parts.append(f"Filtered users: {', '.join(users)}")
+        # Sources arrive pre-filtered to the configured similarity floor
+        if sources := app_context.get('sources'):
+            context_parts.append("Relevant items you may cite:")
+            for source in sources:
+                ref = f"{source['repo']}#{source['number']}" if source.get('repo') and source.get('number') else f"{source['item_type']} {source['id']}"
+                context_parts.append(f"- {ref}: {source['title']} (similarity {source['similarity']:.2f})")
 
         if _amplifier_session is None:
             print("Creating new Amplifier session...", file=sys.stderr)
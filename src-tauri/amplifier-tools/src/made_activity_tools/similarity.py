@@ -0,0 +1,31 @@
+"""Relevance scoring for items returned by the search tool."""
+
+import sqlite3
+from typing import Dict, List
+
+
+def query_terms(query: str) -> List[str]:
+    """Distinct lowercase terms of a search query."""
+    return list(dict.fromkeys(query.lower().split()))
+
+
+def keyword_similarity(terms: List[str], title: str, body: str) -> float:
+    """Share of query terms found in the title or body (0-1)."""
+    if not terms:
+        return 0.0
+    text = f"{title or ''}\n{body or ''}".lower()
+    return sum(1 for term in terms if term in text) / len(terms)
+
+
+def min_source_similarity(conn: sqlite3.Connection) -> float:
+    """The ai_min_source_similarity setting (0 when unset or on an older database)."""
+    try:
+        row = conn.execute("SELECT ai_min_source_similarity FROM settings WHERE id = 1").fetchone()
+    except sqlite3.OperationalError:
+        return 0.0
+    return float(row[0]) if row and row[0] is not None else 0.0
+
+
+def retain_above(results: List[Dict], floor: float) -> List[Dict]:
+    """Drop results scored below the similarity floor, mirroring the chat's source filter."""
+    return [result for result in results if result["similarity"] >= floor]
@@ -0,0 +1,95 @@
+"""Test that search_github_items applies the ai_min_source_similarity floor and state filter."""
+
+import asyncio
+import os
+import sqlite3
+import sys
+import tempfile
+import types
+import unittest
+from pathlib import Path
+
+sys.path.insert(0, str(Path(__file__).parent / "src"))
+
+# The tool module only needs amplifier_core for mounting; stub it so the search runs standalone
+if "amplifier_core" not in sys.modules:
+    stub = types.ModuleType("amplifier_core")
+    for name in ["ModuleCoordinator", "ChatResponse", "TextBlock", "Usage", "ToolCallBlock",
+                 "ToolResultBlock", "ThinkingBlock", "ImageBlock", "Message"]:
+        setattr(stub, name, type(name, (), {}))
+    sys.modules["amplifier_core"] = stub
+
+from made_activity_tools import SearchGitHubItemsTool, set_db_path
+
+
+SCHEMA = """
+CREATE TABLE settings (id INTEGER PRIMARY KEY, ai_min_source_similarity REAL NOT NULL DEFAULT 0.0);
+CREATE TABLE repositories (id INTEGER PRIMARY KEY, owner TEXT NOT NULL, name TEXT NOT NULL);
+CREATE TABLE issues (
+    id INTEGER PRIMARY KEY, repo_id INTEGER NOT NULL, number INTEGER NOT NULL, title TEXT NOT NULL,
+    body TEXT, state TEXT NOT NULL, created_at TEXT NOT NULL, closed_at TEXT
+);
+CREATE TABLE pull_requests (
+    id INTEGER PRIMARY KEY, repo_id INTEGER NOT NULL, number INTEGER NOT NULL, title TEXT NOT NULL,
+    body TEXT, state TEXT NOT NULL, created_at TEXT NOT NULL, closed_at TEXT, merged_at TEXT
+);
+INSERT INTO repositories (id, owner, name) VALUES (1, 'acme', 'api');
+INSERT INTO issues VALUES (1, 1, 10, 'Login page crashes on submit', NULL, 'OPEN', '2024-03-01T00:00:00Z', NULL);
+INSERT INTO issues VALUES (2, 1, 11, 'Dashboard loads slowly', 'Happens after login', 'OPEN', '2024-03-02T00:00:00Z', NULL);
+INSERT INTO pull_requests VALUES (1, 1, 12, 'Fix login crash on submit', NULL, 'MERGED',
+                                  '2024-03-03T00:00:00Z', '2024-03-04T00:00:00Z', '2024-03-04T00:00:00Z');
+-- Rows written by the REST fallback and webhooks use lowercase states
+INSERT INTO issues VALUES (3, 1, 13, 'Export times out', NULL, 'open', '2024-03-05T00:00:00Z', NULL);
+INSERT INTO pull_requests VALUES (2, 1, 14, 'Retry export on timeout', NULL, 'closed',
+                                  '2024-03-06T00:00:00Z', '2024-03-07T00:00:00Z', NULL);
+"""
+
+
+class SearchSimilarityFloorTest(unittest.TestCase):
+    def setUp(self):
+        handle, self.db_path = tempfile.mkstemp(suffix=".db")
+        os.close(handle)
+        conn = sqlite3.connect(self.db_path)
+        conn.executescript(SCHEMA)
+        conn.commit()
+        conn.close()
+        set_db_path(self.db_path)
+
+    def tearDown(self):
+        os.remove(self.db_path)
+
+    def set_floor(self, floor):
+        conn = sqlite3.connect(self.db_path)
+        conn.execute("INSERT OR REPLACE INTO settings (id, ai_min_source_similarity) VALUES (1, ?)", [floor])
+        conn.commit()
+        conn.close()
+
+    def search(self, query, state="all"):
+        return asyncio.run(SearchGitHubItemsTool().execute(query=query, item_type="both", state=state))
+
+    def test_results_below_floor_are_withheld(self):
+        self.set_floor(0.5)
+        result = self.search("login crash submit")
+
+        numbers = sorted(item["number"] for item in result["results"])
+        # #11 only mentions "login" (1/3 of the terms) and falls under the floor
+        self.assertEqual(numbers, [10, 12])
+        self.assertEqual(result["min_similarity"], 0.5)
+        self.assertTrue(all(item["similarity"] >= 0.5 for item in result["results"]))
+
+    def test_no_floor_keeps_every_match(self):
+        result = self.search("login crash submit")
+
+        self.assertEqual(sorted(item["number"] for item in result["results"]), [10, 11, 12])
+        self.assertEqual(result["results"][0]["similarity"], 1.0)
+
+    def test_state_filter_ignores_case(self):
+        numbers = lambda result: sorted(item["number"] for item in result["results"])
+
+        self.assertEqual(numbers(self.search("export", state="open")), [13])
+        self.assertEqual(numbers(self.search("export", state="closed")), [14])
+        self.assertEqual(numbers(self.search("login crash submit", state="open")), [10, 11])
+
+
+if __name__ == "__main__":
+    unittest.main()
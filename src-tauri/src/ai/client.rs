@@ -1,7 +1,75 @@
 use crate::ai::types::*;
-use anyhow::Result;
+use anyhow::{bail, Result};
 use reqwest;
-use std::time::Duration;
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+/// How long to wait for the sidecar to pass its health check at startup
+pub const DEFAULT_READY_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Pause between startup health checks
+pub const DEFAULT_READY_INTERVAL: Duration = Duration::from_millis(500);
+
+/// How long and how often to poll the sidecar before declaring it unavailable
+#[derive(Debug, Clone, Copy)]
+pub struct ReadinessConfig {
+    pub timeout: Duration,
+    pub interval: Duration,
+}
+
+impl Default for ReadinessConfig {
+    fn default() -> Self {
+        Self {
+            timeout: DEFAULT_READY_TIMEOUT,
+            interval: DEFAULT_READY_INTERVAL,
+        }
+    }
+}
+
+impl ReadinessConfig {
+    /// Defaults overridden by AMPLIFIER_READY_TIMEOUT_SECS and AMPLIFIER_READY_INTERVAL_MS
+    pub fn from_env() -> Self {
+        let env_u64 = |name: &str| std::env::var(name).ok().and_then(|v| v.trim().parse::<u64>().ok());
+        let mut config = Self::default();
+        if let Some(secs) = env_u64("AMPLIFIER_READY_TIMEOUT_SECS") {
+            config.timeout = Duration::from_secs(secs);
+        }
+        if let Some(ms) = env_u64("AMPLIFIER_READY_INTERVAL_MS").filter(|ms| *ms > 0) {
+            config.interval = Duration::from_millis(ms);
+        }
+        config
+    }
+}
+
+/// Call `check` until it reports ready or `config.timeout` elapses, returning the
+/// number of attempts made. Errors from `check` count as "not ready yet".
+pub async fn poll_until_ready<F, Fut>(mut check: F, config: ReadinessConfig) -> Result<u32>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<bool>>,
+{
+    let started = Instant::now();
+    let mut attempts = 0;
+
+    loop {
+        attempts += 1;
+        match check().await {
+            Ok(true) => return Ok(attempts),
+            Ok(false) => tracing::debug!("Sidecar not ready yet (attempt {})", attempts),
+            Err(e) => tracing::debug!("Sidecar not reachable yet (attempt {}): {}", attempts, e),
+        }
+
+        let elapsed = started.elapsed();
+        if elapsed >= config.timeout {
+            bail!(
+                "Sidecar not ready after {} attempts over {:.1}s",
+                attempts,
+                elapsed.as_secs_f64()
+            );
+        }
+        tokio::time::sleep(config.interval.min(config.timeout - elapsed)).await;
+    }
+}
 
 pub struct AmplifierClient {
     base_url: String,
@@ -58,7 +126,19 @@ impl AmplifierClient {
         }
     }
 
-    pub async fn chat(&self, request: ChatRequest) -> Result<ChatResponse> {
+    /// Poll the health endpoint until the sidecar answers or the timeout elapses
+    pub async fn wait_until_ready(&self, config: ReadinessConfig) -> Result<u32> {
+        tracing::info!(
+            "Waiting up to {:?} for Amplifier to become ready (polling every {:?})",
+            config.timeout,
+            config.interval
+        );
+        poll_until_ready(|| self.health_check(), config).await
+    }
+
+    /// Send a chat message; sources below `min_source_similarity` are not passed on
+    pub async fn chat(&self, mut request: ChatRequest, min_source_similarity: f64) -> Result<ChatResponse> {
+        request.context.retain_sources_above(min_source_similarity);
         let url = format!("{}/chat", self.base_url);
         tracing::info!("Sending chat message to: POST {}", url);
         tracing::debug!("  Message preview: {}...",
@@ -95,3 +175,147 @@ impl AmplifierClient {
         Ok(result)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    fn config(timeout_ms: u64, interval_ms: u64) -> ReadinessConfig {
+        ReadinessConfig {
+            timeout: Duration::from_millis(timeout_ms),
+            interval: Duration::from_millis(interval_ms),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_poll_succeeds_once_sidecar_becomes_ready() {
+        let calls = AtomicU32::new(0);
+        let counter = &calls;
+        let attempts = poll_until_ready(
+            move || async move {
+                match counter.fetch_add(1, Ordering::SeqCst) {
+                    0 => Err(anyhow::anyhow!("connection refused")),
+                    1 => Ok(false),
+                    _ => Ok(true),
+                }
+            },
+            config(1000, 5),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(attempts, 3);
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_poll_fails_after_timeout() {
+        let calls = AtomicU32::new(0);
+        let counter = &calls;
+        let started = Instant::now();
+        let result = poll_until_ready(
+            move || async move {
+                counter.fetch_add(1, Ordering::SeqCst);
+                Ok(false)
+            },
+            config(50, 10),
+        )
+        .await;
+
+        assert!(result.is_err());
+        assert!(started.elapsed() >= Duration::from_millis(50));
+        assert!(calls.load(Ordering::SeqCst) >= 2);
+    }
+
+    /// Amplifier stand-in that answers one /chat request and hands back the body it received
+    async fn serve_chat_once() -> (u16, tokio::sync::oneshot::Receiver<serde_json::Value>) {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let (tx, rx) = tokio::sync::oneshot::channel();
+
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0u8; 4096];
+            let body = loop {
+                let n = stream.read(&mut buf).await.unwrap();
+                request.extend_from_slice(&buf[..n]);
+                let Some(header_end) = request.windows(4).position(|w| w == b"\r\n\r\n") else {
+                    continue;
+                };
+                let headers = String::from_utf8_lossy(&request[..header_end]).to_lowercase();
+                let content_length: usize = headers
+                    .lines()
+                    .find_map(|line| line.strip_prefix("content-length:"))
+                    .map(|value| value.trim().parse().unwrap())
+                    .unwrap_or(0);
+                if n == 0 || request.len() >= header_end + 4 + content_length {
+                    break request[header_end + 4..].to_vec();
+                }
+            };
+            let received: serde_json::Value = serde_json::from_slice(&body).unwrap();
+            let payload = serde_json::json!({ "response": "ok", "context": received["context"] }).to_string();
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                payload.len(),
+                payload
+            );
+            stream.write_all(response.as_bytes()).await.unwrap();
+            stream.shutdown().await.ok();
+            tx.send(received).ok();
+        });
+
+        (port, rx)
+    }
+
+    fn source(id: i64, similarity: f64) -> ChatSource {
+        ChatSource {
+            item_type: "issue".to_string(),
+            id,
+            title: format!("Issue {}", id),
+            repo: Some("acme/api".to_string()),
+            number: Some(id as i32),
+            similarity,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_chat_withholds_sources_below_similarity_floor() {
+        let (port, received) = serve_chat_once().await;
+        let client = AmplifierClient::new(port, "token".to_string());
+        let request = ChatRequest {
+            message: "Which issues cover login failures?".to_string(),
+            context: AppContext {
+                current_page: "/search".to_string(),
+                filters: FilterState {
+                    date_range: None,
+                    repositories: Vec::new(),
+                    squads: Vec::new(),
+                    users: Vec::new(),
+                },
+                sources: vec![source(1, 0.92), source(2, 0.31), source(3, 0.5), source(4, 0.49)],
+            },
+        };
+
+        client.chat(request, 0.5).await.unwrap();
+
+        let body = received.await.unwrap();
+        let ids: Vec<i64> = body["context"]["sources"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|s| s["id"].as_i64().unwrap())
+            .collect();
+        assert_eq!(ids, vec![1, 3]);
+    }
+
+    #[test]
+    fn test_context_without_sources_still_parses() {
+        let context: AppContext =
+            serde_json::from_str(r#"{"current_page": "/", "filters": {"date_range": null, "repositories": [], "squads": [], "users": []}}"#)
+                .unwrap();
+        assert!(context.sources.is_empty());
+    }
+}
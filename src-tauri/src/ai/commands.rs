@@ -1,4 +1,5 @@
 use crate::ai::{AmplifierClient, ChatRequest, ChatResponse};
+use crate::db::AppState;
 use crate::AiState;
 use serde::{Deserialize, Serialize};
 use tauri::State;
@@ -14,16 +15,26 @@ pub struct ApiKeyStatus {
 #[tauri::command]
 pub async fn send_chat_message(
     state: State<'_, AiState>,
+    db_state: State<'_, AppState>,
     request: ChatRequest,
 ) -> Result<ChatResponse, String> {
     tracing::info!("[Command] send_chat_message invoked");
     tracing::debug!("  Message: {}...", request.message.chars().take(50).collect::<String>());
 
+    // Never answer from another profile's database while the sidecar restarts after a switch
+    let active_profile = db_state.profile.lock().map_err(|e| e.to_string())?.clone();
+    state.ensure_profile(&active_profile)?;
+
+    let min_source_similarity = {
+        let conn = db_state.sqlite.lock().map_err(|e| e.to_string())?;
+        crate::db::queries::get_settings(&conn).map_err(|e| e.to_string())?.ai_min_source_similarity
+    };
+
     tracing::debug!("  Acquiring client lock...");
     let client_guard = state.amplifier_client.lock().await;
     tracing::debug!("  ✓ Client lock acquired");
 
-    let result = client_guard.chat(request).await;
+    let result = client_guard.chat(request, min_source_similarity).await;
     match &result {
         Ok(_) => tracing::info!("[Command] ✓ send_chat_message completed successfully"),
         Err(e) => tracing::error!("[Command] ✗ send_chat_message failed: {}", e),
@@ -3,7 +3,7 @@ pub mod commands;
 pub mod sidecar;
 pub mod types;
 
-pub use client::AmplifierClient;
+pub use client::{AmplifierClient, ReadinessConfig};
 pub use commands::{check_amplifier_health, send_chat_message};
 pub use sidecar::AmplifierSidecar;
 pub use types::*;
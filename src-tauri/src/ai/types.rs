@@ -4,6 +4,34 @@ use serde::{Deserialize, Serialize};
 pub struct AppContext {
     pub current_page: String,
     pub filters: FilterState,
+    #[serde(default)]
+    pub sources: Vec<ChatSource>, // Search/duplicate results the assistant may cite
+}
+
+/// A search or duplicate-detection hit offered to the assistant as a citable source
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChatSource {
+    pub item_type: String, // "issue" or "pull_request"
+    pub id: i64,
+    pub title: String,
+    pub repo: Option<String>,
+    pub number: Option<i32>,
+    pub similarity: f64, // 0-1, combined search score or duplicate similarity
+}
+
+impl AppContext {
+    /// Drop sources below `min_similarity` so only confidently-relevant items are cited
+    pub fn retain_sources_above(&mut self, min_similarity: f64) {
+        let before = self.sources.len();
+        self.sources.retain(|source| source.similarity >= min_similarity);
+        if self.sources.len() < before {
+            tracing::debug!(
+                "Withheld {} source(s) below similarity {} from the assistant",
+                before - self.sources.len(),
+                min_similarity
+            );
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -0,0 +1,99 @@
+use crate::db::{queries, AppState};
+use anyhow::Result;
+use rusqlite::Connection;
+use serde::Serialize;
+use tauri::State;
+
+#[derive(Debug, Default, Serialize)]
+pub struct RepositoryImportResult {
+    pub added: Vec<String>,
+    pub already_present: Vec<String>,
+    pub invalid: Vec<String>,
+}
+
+/// Import repositories from newline/comma-separated `owner/name` entries
+#[tauri::command]
+pub async fn import_repositories_from_text(
+    text: String,
+    state: State<'_, AppState>,
+) -> Result<RepositoryImportResult, String> {
+    let conn = state.sqlite.lock().map_err(|e| e.to_string())?;
+
+    import_repositories(&conn, &text).map_err(|e| e.to_string())
+}
+
+/// Validate each entry, upsert new ones as enabled, and categorize the results
+fn import_repositories(conn: &Connection, text: &str) -> Result<RepositoryImportResult> {
+    let mut result = RepositoryImportResult::default();
+
+    let entries = text
+        .split([',', '\n'])
+        .map(|entry| entry.trim())
+        .filter(|entry| !entry.is_empty());
+
+    for entry in entries {
+        let Some((owner, name)) = parse_repo_entry(entry) else {
+            result.invalid.push(entry.to_string());
+            continue;
+        };
+        let full_name = format!("{}/{}", owner, name);
+
+        // Repeated entries in the same input count as already present
+        if result.added.contains(&full_name)
+            || queries::get_repository_by_name(conn, owner, name)?.is_some()
+        {
+            result.already_present.push(full_name);
+            continue;
+        }
+
+        queries::upsert_repository(conn, owner, name, None, true)?;
+        result.added.push(full_name);
+    }
+
+    Ok(result)
+}
+
+/// Parse an `owner/name` entry, rejecting characters GitHub doesn't allow
+fn parse_repo_entry(entry: &str) -> Option<(&str, &str)> {
+    let (owner, name) = entry.split_once('/')?;
+
+    let valid_owner = !owner.is_empty()
+        && !owner.starts_with('-')
+        && owner.chars().all(|c| c.is_ascii_alphanumeric() || c == '-');
+    let valid_name = !name.is_empty()
+        && name != "."
+        && name != ".."
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+
+    if valid_owner && valid_name {
+        Some((owner, name))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_import_categorizes_mixed_list() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::db::migrations::run_migrations(&conn).unwrap();
+        queries::upsert_repository(&conn, "acme", "api", None, true).unwrap();
+
+        let text = "acme/api, acme/web\n\n  octo-org/hello.world \nnot-a-repo, bad owner/x, acme/web, a/b/c";
+        let result = import_repositories(&conn, text).unwrap();
+
+        assert_eq!(result.added, vec!["acme/web", "octo-org/hello.world"]);
+        assert_eq!(result.already_present, vec!["acme/api", "acme/web"]);
+        assert_eq!(result.invalid, vec!["not-a-repo", "bad owner/x", "a/b/c"]);
+
+        let repo = queries::get_repository_by_name(&conn, "octo-org", "hello.world")
+            .unwrap()
+            .unwrap();
+        assert!(repo.enabled);
+    }
+}
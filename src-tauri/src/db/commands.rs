@@ -1,8 +1,14 @@
+use super::demo_data;
+use super::item_queries;
 use super::models::*;
+use super::path_owner_queries;
+use super::initiative_queries;
+use super::profiles;
 use super::queries;
 use crate::db::AppState;
+use crate::github::auth;
 use rusqlite::params;
-use tauri::State;
+use tauri::{AppHandle, Manager, State};
 
 // ============================================================================
 // QUERY HELPER COMMANDS
@@ -15,6 +21,66 @@ pub async fn get_sync_stats(state: State<'_, AppState>) -> Result<queries::SyncS
     queries::get_sync_stats(&conn).map_err(|e| e.to_string())
 }
 
+/// Collect database and AI sidecar diagnostics for a "copy diagnostics" button (read-only)
+#[tauri::command]
+pub async fn get_diagnostics(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<queries::Diagnostics, String> {
+    let mut diagnostics = {
+        let conn = state.sqlite.lock().map_err(|e| e.to_string())?;
+        queries::get_diagnostics(&conn).map_err(|e| e.to_string())?
+    };
+
+    // The sidecar state only exists once it started; don't let a hung sidecar stall diagnostics
+    if let Some(ai_state) = app.try_state::<crate::AiState>() {
+        let client = ai_state.amplifier_client.lock().await;
+        let health = tokio::time::timeout(std::time::Duration::from_secs(2), client.health_check()).await;
+        diagnostics.sidecar_healthy = Some(matches!(health, Ok(Ok(true))));
+    }
+
+    Ok(diagnostics)
+}
+
+/// Get how recently each enabled repository was synced, with a fresh/aging/stale tier
+#[tauri::command]
+pub async fn get_repo_freshness(state: State<'_, AppState>) -> Result<Vec<queries::RepoFreshness>, String> {
+    let conn = state.sqlite.lock().map_err(|e| e.to_string())?;
+    let settings = queries::get_settings(&conn).map_err(|e| e.to_string())?;
+    let now = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
+    queries::get_repo_freshness(&conn, &settings, &now).map_err(|e| e.to_string())
+}
+
+/// Find windows with no successful sync per repository, so they can be backfilled
+#[tauri::command]
+pub async fn detect_sync_gaps(state: State<'_, AppState>) -> Result<Vec<queries::SyncGap>, String> {
+    let conn = state.sqlite.lock().map_err(|e| e.to_string())?;
+    let settings = queries::get_settings(&conn).map_err(|e| e.to_string())?;
+    let now = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
+    queries::detect_sync_gaps(&conn, &settings, &now).map_err(|e| e.to_string())
+}
+
+/// List issues and PRs updated after the given sync log completed, for incremental mirrors
+#[tauri::command]
+pub async fn get_changes_since(
+    sync_log_id: i64,
+    state: State<'_, AppState>,
+) -> Result<Vec<queries::ChangedItem>, String> {
+    let conn = state.sqlite.lock().map_err(|e| e.to_string())?;
+    queries::get_changes_since(&conn, sync_log_id).map_err(|e| e.to_string())
+}
+
+/// Recent failed syncs for a repository, newest first (defaults to the last 20)
+#[tauri::command]
+pub async fn get_repo_sync_errors(
+    repo_id: i64,
+    limit: Option<i64>,
+    state: State<'_, AppState>,
+) -> Result<Vec<queries::SyncError>, String> {
+    let conn = state.sqlite.lock().map_err(|e| e.to_string())?;
+    queries::get_repo_sync_errors(&conn, repo_id, limit.unwrap_or(20)).map_err(|e| e.to_string())
+}
+
 /// Get all non-bot users for filtering
 #[tauri::command]
 pub async fn get_all_users(state: State<'_, AppState>) -> Result<Vec<User>, String> {
@@ -29,6 +95,42 @@ pub async fn get_all_repositories(state: State<'_, AppState>) -> Result<Vec<Repo
     queries::get_all_repositories(&conn).map_err(|e| e.to_string())
 }
 
+/// Get distinct labels with usage counts for filtering
+#[tauri::command]
+pub async fn get_all_labels(
+    item_type: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Vec<LabelCount>, String> {
+    let conn = state.sqlite.lock().map_err(|e| e.to_string())?;
+    queries::get_all_labels(&conn, item_type.as_deref()).map_err(|e| e.to_string())
+}
+
+/// Query raw issue/PR rows for custom reports (read-only, paginated)
+#[tauri::command]
+pub async fn query_items(
+    filters: item_queries::ItemFilters,
+    page: Option<u32>,
+    page_size: Option<u32>,
+    state: State<'_, AppState>,
+) -> Result<item_queries::ItemsPage, String> {
+    let conn = state.sqlite.lock().map_err(|e| e.to_string())?;
+
+    let squad_member_ids = if let Some(ref squad_id) = filters.squad_id {
+        Some(queries::get_squad_member_ids(&conn, squad_id).map_err(|e| e.to_string())?)
+    } else {
+        None
+    };
+
+    item_queries::query_items(
+        &conn,
+        &filters,
+        squad_member_ids.as_deref(),
+        page.unwrap_or(1),
+        page_size.unwrap_or(50),
+    )
+    .map_err(|e| e.to_string())
+}
+
 // ============================================================================
 // REPOSITORY COMMANDS
 // ============================================================================
@@ -60,6 +162,7 @@ pub async fn remove_repository(
             |row| row.get(0),
         )
         .map_err(|e| e.to_string())?;
+    ensure_not_demo(&conn, repo_id)?;
 
     // Delete related records first (foreign key constraints)
     // Delete PR reviews (references pull_requests)
@@ -69,6 +172,37 @@ pub async fn remove_repository(
     )
     .map_err(|e| e.to_string())?;
 
+    // Delete PR files (references pull_requests)
+    conn.execute(
+        "DELETE FROM pr_files WHERE pr_id IN (SELECT id FROM pull_requests WHERE repo_id = ?1)",
+        params![repo_id],
+    )
+    .map_err(|e| e.to_string())?;
+
+    // Delete initiative associations (references issues, pull_requests, milestones)
+    conn.execute(
+        "DELETE FROM initiative_items WHERE
+            (item_type = 'issue' AND item_id IN (SELECT id FROM issues WHERE repo_id = ?1))
+            OR (item_type = 'pull_request' AND item_id IN (SELECT id FROM pull_requests WHERE repo_id = ?1))
+            OR (item_type = 'milestone' AND item_id IN (SELECT id FROM milestones WHERE repo_id = ?1))",
+        params![repo_id],
+    )
+    .map_err(|e| e.to_string())?;
+
+    // Delete PR issue links (references pull_requests)
+    conn.execute(
+        "DELETE FROM pr_issue_links WHERE pr_id IN (SELECT id FROM pull_requests WHERE repo_id = ?1)",
+        params![repo_id],
+    )
+    .map_err(|e| e.to_string())?;
+
+    // Delete PR commits (references pull_requests)
+    conn.execute(
+        "DELETE FROM pr_commits WHERE pr_id IN (SELECT id FROM pull_requests WHERE repo_id = ?1)",
+        params![repo_id],
+    )
+    .map_err(|e| e.to_string())?;
+
     // Delete pull requests
     conn.execute(
         "DELETE FROM pull_requests WHERE repo_id = ?1",
@@ -76,6 +210,13 @@ pub async fn remove_repository(
     )
     .map_err(|e| e.to_string())?;
 
+    // Delete issue label events (references issues)
+    conn.execute(
+        "DELETE FROM issue_label_events WHERE issue_id IN (SELECT id FROM issues WHERE repo_id = ?1)",
+        params![repo_id],
+    )
+    .map_err(|e| e.to_string())?;
+
     // Delete issues
     conn.execute(
         "DELETE FROM issues WHERE repo_id = ?1",
@@ -97,6 +238,20 @@ pub async fn remove_repository(
     )
     .map_err(|e| e.to_string())?;
 
+    // Delete cached languages
+    conn.execute(
+        "DELETE FROM repo_languages WHERE repo_id = ?1",
+        params![repo_id],
+    )
+    .map_err(|e| e.to_string())?;
+
+    // Delete commits
+    conn.execute(
+        "DELETE FROM commits WHERE repo_id = ?1",
+        params![repo_id],
+    )
+    .map_err(|e| e.to_string())?;
+
     // Finally, delete the repository
     conn.execute(
         "DELETE FROM repositories WHERE id = ?1",
@@ -120,10 +275,32 @@ pub async fn clear_all_database_data(state: State<'_, AppState>) -> Result<(), S
     conn.execute("DELETE FROM pr_reviews", [])
         .map_err(|e| format!("Failed to clear pr_reviews: {}", e))?;
 
+    // Delete PR files (references pull_requests)
+    conn.execute("DELETE FROM pr_files", [])
+        .map_err(|e| format!("Failed to clear pr_files: {}", e))?;
+
+    // Delete PR issue links (references pull_requests)
+    conn.execute("DELETE FROM pr_issue_links", [])
+        .map_err(|e| format!("Failed to clear pr_issue_links: {}", e))?;
+
+    // Delete PR commits (references pull_requests)
+    conn.execute("DELETE FROM pr_commits", [])
+        .map_err(|e| format!("Failed to clear pr_commits: {}", e))?;
+
+    // Delete initiatives and their item associations
+    conn.execute("DELETE FROM initiative_items", [])
+        .map_err(|e| format!("Failed to clear initiative_items: {}", e))?;
+    conn.execute("DELETE FROM initiatives", [])
+        .map_err(|e| format!("Failed to clear initiatives: {}", e))?;
+
     // Delete pull requests (references repositories, users)
     conn.execute("DELETE FROM pull_requests", [])
         .map_err(|e| format!("Failed to clear pull_requests: {}", e))?;
 
+    // Delete issue label events (references issues)
+    conn.execute("DELETE FROM issue_label_events", [])
+        .map_err(|e| format!("Failed to clear issue_label_events: {}", e))?;
+
     // Delete issues (references repositories, users, milestones)
     conn.execute("DELETE FROM issues", [])
         .map_err(|e| format!("Failed to clear issues: {}", e))?;
@@ -136,10 +313,18 @@ pub async fn clear_all_database_data(state: State<'_, AppState>) -> Result<(), S
     conn.execute("DELETE FROM squad_members", [])
         .map_err(|e| format!("Failed to clear squad_members: {}", e))?;
 
+    // Delete path owners (references squads)
+    conn.execute("DELETE FROM path_owners", [])
+        .map_err(|e| format!("Failed to clear path_owners: {}", e))?;
+
     // Delete squads
     conn.execute("DELETE FROM squads", [])
         .map_err(|e| format!("Failed to clear squads: {}", e))?;
 
+    // Delete user annotations (references users)
+    conn.execute("DELETE FROM user_metadata", [])
+        .map_err(|e| format!("Failed to clear user_metadata: {}", e))?;
+
     // Delete tracked_users (deprecated table, references users)
     conn.execute("DELETE FROM tracked_users", [])
         .map_err(|e| format!("Failed to clear tracked_users: {}", e))?;
@@ -148,6 +333,14 @@ pub async fn clear_all_database_data(state: State<'_, AppState>) -> Result<(), S
     conn.execute("DELETE FROM sync_log", [])
         .map_err(|e| format!("Failed to clear sync_log: {}", e))?;
 
+    // Delete cached languages (references repositories)
+    conn.execute("DELETE FROM repo_languages", [])
+        .map_err(|e| format!("Failed to clear repo_languages: {}", e))?;
+
+    // Delete commits (references repositories and users)
+    conn.execute("DELETE FROM commits", [])
+        .map_err(|e| format!("Failed to clear commits: {}", e))?;
+
     // Delete metrics snapshots
     conn.execute("DELETE FROM metrics_snapshots", [])
         .map_err(|e| format!("Failed to clear metrics_snapshots: {}", e))?;
@@ -167,6 +360,29 @@ pub async fn clear_all_database_data(state: State<'_, AppState>) -> Result<(), S
     Ok(())
 }
 
+/// Refuse to modify a seeded demo repository; clear the database to get rid of demo data
+fn ensure_not_demo(conn: &rusqlite::Connection, repo_id: i64) -> Result<(), String> {
+    if queries::is_demo_repository(conn, repo_id).map_err(|e| e.to_string())? {
+        return Err("Demo repositories are read-only; clear all data to remove them".to_string());
+    }
+    Ok(())
+}
+
+/// Fill an empty database with a synthetic demo team so dashboards render without GitHub.
+/// `confirm` must be true; best used with a dedicated profile (see `switch_profile`).
+#[tauri::command]
+pub async fn seed_demo_data(
+    confirm: bool,
+    state: State<'_, AppState>,
+) -> Result<demo_data::DemoSeedSummary, String> {
+    if !confirm {
+        return Err("Seeding demo data requires confirm = true".to_string());
+    }
+
+    let conn = state.sqlite.lock().map_err(|e| e.to_string())?;
+    demo_data::seed_demo_data(&conn, chrono::Utc::now()).map_err(|e| e.to_string())
+}
+
 #[tauri::command]
 pub async fn toggle_repository(
     owner: String,
@@ -174,6 +390,9 @@ pub async fn toggle_repository(
     state: State<'_, AppState>,
 ) -> Result<(), String> {
     let conn = state.sqlite.lock().map_err(|e| e.to_string())?;
+    if let Some(repo) = queries::get_repository_by_name(&conn, &owner, &name).map_err(|e| e.to_string())? {
+        ensure_not_demo(&conn, repo.id)?;
+    }
     conn.execute(
         "UPDATE repositories SET enabled = NOT enabled WHERE owner = ?1 AND name = ?2",
         params![owner, name],
@@ -182,6 +401,24 @@ pub async fn toggle_repository(
     Ok(())
 }
 
+/// Set bots excluded only for this repository (in addition to the global list)
+#[tauri::command]
+pub async fn set_repo_excluded_bots(
+    owner: String,
+    name: String,
+    excluded_bots: Vec<String>,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let conn = state.sqlite.lock().map_err(|e| e.to_string())?;
+    let repo = queries::get_repository_by_name(&conn, &owner, &name)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Repository {}/{} not found", owner, name))?;
+    ensure_not_demo(&conn, repo.id)?;
+
+    queries::set_repo_excluded_bots(&conn, repo.id, &excluded_bots)
+        .map_err(|e| e.to_string())
+}
+
 // ============================================================================
 // SQUAD COMMANDS
 // ============================================================================
@@ -250,6 +487,13 @@ pub async fn remove_squad(
     )
     .map_err(|e| e.to_string())?;
 
+    // Remove path ownership entries
+    conn.execute(
+        "DELETE FROM path_owners WHERE squad_id = ?1",
+        params![id],
+    )
+    .map_err(|e| e.to_string())?;
+
     // Remove squad
     conn.execute(
         "DELETE FROM squads WHERE id = ?1",
@@ -268,6 +512,140 @@ pub async fn get_all_squads_command(
     queries::get_all_squads(&conn).map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+pub async fn save_filter(
+    name: String,
+    filters: crate::metrics::filter_params::MetricsFilters,
+    state: State<'_, AppState>,
+) -> Result<i64, String> {
+    let conn = state.sqlite.lock().map_err(|e| e.to_string())?;
+    queries::save_filter(&conn, &name, &filters)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_saved_filters(
+    state: State<'_, AppState>,
+) -> Result<Vec<SavedFilter>, String> {
+    let conn = state.sqlite.lock().map_err(|e| e.to_string())?;
+    queries::get_saved_filters(&conn)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn delete_saved_filter(
+    id: i64,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let conn = state.sqlite.lock().map_err(|e| e.to_string())?;
+    queries::delete_saved_filter(&conn, id)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn add_path_owner(
+    pattern: String,
+    squad_id: String,
+    state: State<'_, AppState>,
+) -> Result<i64, String> {
+    let conn = state.sqlite.lock().map_err(|e| e.to_string())?;
+    path_owner_queries::add_path_owner(&conn, &pattern, &squad_id)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn remove_path_owner(
+    id: i64,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let conn = state.sqlite.lock().map_err(|e| e.to_string())?;
+    path_owner_queries::remove_path_owner(&conn, id)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_path_owners(
+    state: State<'_, AppState>,
+) -> Result<Vec<path_owner_queries::PathOwner>, String> {
+    let conn = state.sqlite.lock().map_err(|e| e.to_string())?;
+    path_owner_queries::get_path_owners(&conn)
+        .map_err(|e| e.to_string())
+}
+
+// ============================================================================
+// INITIATIVE COMMANDS
+// ============================================================================
+
+/// Create an initiative (a cross-repo body of work); returns its id
+#[tauri::command]
+pub async fn create_initiative(
+    name: String,
+    description: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<i64, String> {
+    let conn = state.sqlite.lock().map_err(|e| e.to_string())?;
+    initiative_queries::create_initiative(&conn, &name, description.as_deref())
+        .map_err(|e| e.to_string())
+}
+
+/// Delete an initiative and untag its items (the items themselves are kept)
+#[tauri::command]
+pub async fn delete_initiative(
+    id: i64,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let conn = state.sqlite.lock().map_err(|e| e.to_string())?;
+    initiative_queries::delete_initiative(&conn, id)
+        .map_err(|e| e.to_string())
+}
+
+/// List initiatives with their tagged item counts
+#[tauri::command]
+pub async fn get_initiatives(
+    state: State<'_, AppState>,
+) -> Result<Vec<initiative_queries::Initiative>, String> {
+    let conn = state.sqlite.lock().map_err(|e| e.to_string())?;
+    initiative_queries::get_initiatives(&conn)
+        .map_err(|e| e.to_string())
+}
+
+/// Tag an item with an initiative; item_type is issue, pull_request or milestone
+#[tauri::command]
+pub async fn add_initiative_item(
+    initiative_id: i64,
+    item_type: String,
+    item_id: i64,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let conn = state.sqlite.lock().map_err(|e| e.to_string())?;
+    initiative_queries::add_initiative_item(&conn, initiative_id, &item_type, item_id)
+        .map_err(|e| e.to_string())
+}
+
+/// Untag an item from an initiative
+#[tauri::command]
+pub async fn remove_initiative_item(
+    initiative_id: i64,
+    item_type: String,
+    item_id: i64,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let conn = state.sqlite.lock().map_err(|e| e.to_string())?;
+    initiative_queries::remove_initiative_item(&conn, initiative_id, &item_type, item_id)
+        .map_err(|e| e.to_string())
+}
+
+/// Get an initiative's completion: closed issues and milestones, merged PRs
+#[tauri::command]
+pub async fn get_initiative_progress(
+    initiative_id: i64,
+    state: State<'_, AppState>,
+) -> Result<initiative_queries::InitiativeProgress, String> {
+    let conn = state.sqlite.lock().map_err(|e| e.to_string())?;
+    initiative_queries::get_initiative_progress(&conn, initiative_id)
+        .map_err(|e| e.to_string())
+}
+
 // ============================================================================
 // USER COMMANDS (tracked users)
 // ============================================================================
@@ -291,19 +669,24 @@ pub async fn toggle_user_tracked(
     Ok(())
 }
 
-/// Fix users with invalid github_id (0 or negative)
+#[derive(Debug, serde::Serialize)]
+pub struct UserRepairReport {
+    pub fixed: Vec<String>,
+    pub merged: Vec<String>,
+    pub unresolved: Vec<String>,
+}
+
+/// Repair users with missing or placeholder github_ids, reconciling them by login
 #[tauri::command]
 pub async fn fix_invalid_users(
     state: State<'_, AppState>,
-) -> Result<Vec<String>, String> {
-    use crate::github::auth;
+) -> Result<UserRepairReport, String> {
     use reqwest::Client;
     use serde::Deserialize;
 
     #[derive(Deserialize)]
     struct GithubUser {
         id: i64,
-        login: String,
     }
 
     let token = auth::get_token()
@@ -311,56 +694,84 @@ pub async fn fix_invalid_users(
         .ok_or_else(|| "Not authenticated".to_string())?;
 
     // Find users with invalid github_id (in separate scope to drop lock)
-    let invalid_users: Vec<(i64, String)> = {
+    let invalid_users = {
         let conn = state.sqlite.lock().map_err(|e| e.to_string())?;
-        let mut stmt = conn.prepare(
-            "SELECT id, login FROM users WHERE github_id <= 0"
-        ).map_err(|e| e.to_string())?;
-
-        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
-            .map_err(|e| e.to_string())?;
-
-        let results: Result<Vec<_>, _> = rows.collect();
-        results.map_err(|e| e.to_string())?
+        queries::get_users_with_invalid_github_id(&conn).map_err(|e| e.to_string())?
     }; // Lock is dropped here
 
-    let mut fixed_users = Vec::new();
+    let mut report = UserRepairReport {
+        fixed: Vec::new(),
+        merged: Vec::new(),
+        unresolved: Vec::new(),
+    };
     let client = Client::new();
 
     for (db_id, login) in invalid_users {
         tracing::info!("Fixing user '{}' with invalid github_id", login);
 
-        // Fetch correct github_id from GitHub API
-        let url = format!("https://api.github.com/users/{}", login);
-        let response = client
-            .get(&url)
-            .header("Authorization", format!("Bearer {}", token))
-            .header("User-Agent", "MADE-Activity-Tracker")
-            .send()
-            .await
-            .map_err(|e| e.to_string())?;
-
-        if response.status().is_success() {
-            let gh_user: GithubUser = response.json().await.map_err(|e| e.to_string())?;
-
-            // Update the user with correct github_id (in separate scope to drop lock)
-            {
-                let conn = state.sqlite.lock().map_err(|e| e.to_string())?;
-                conn.execute(
-                    "UPDATE users SET github_id = ?1 WHERE id = ?2",
-                    params![gh_user.id, db_id],
-                )
-                .map_err(|e| format!("Failed to update user: {}", e))?;
-            } // Lock is dropped here
-
-            fixed_users.push(format!("{} (github_id: {})", login, gh_user.id));
-            tracing::info!("Fixed user '{}' with github_id {}", login, gh_user.id);
+        // A valid row for the same login already exists: merge locally, no API call needed
+        let local_match = {
+            let conn = state.sqlite.lock().map_err(|e| e.to_string())?;
+            queries::find_valid_user_by_login(&conn, &login, db_id).map_err(|e| e.to_string())?
+        };
+
+        let github_id = match local_match {
+            Some((_, github_id)) => Some(github_id),
+            None => {
+                // Fetch correct github_id from GitHub API
+                let url = format!("https://api.github.com/users/{}", login);
+                let response = client
+                    .get(&url)
+                    .header("Authorization", format!("Bearer {}", token))
+                    .header("User-Agent", "MADE-Activity-Tracker")
+                    .send()
+                    .await;
+
+                match response {
+                    Ok(resp) if resp.status().is_success() => {
+                        resp.json::<GithubUser>().await.ok().map(|u| u.id)
+                    }
+                    Ok(resp) => {
+                        tracing::error!("Failed to fetch GitHub user '{}': {}", login, resp.status());
+                        None
+                    }
+                    Err(e) => {
+                        tracing::error!("Failed to fetch GitHub user '{}': {}", login, e);
+                        None
+                    }
+                }
+            }
+        };
+
+        let Some(github_id) = github_id.filter(|id| *id > 0) else {
+            report.unresolved.push(login);
+            continue;
+        };
+
+        // Update the user with correct github_id (in separate scope to drop lock)
+        let merged = {
+            let conn = state.sqlite.lock().map_err(|e| e.to_string())?;
+            queries::assign_user_github_id(&conn, db_id, github_id)
+                .map_err(|e| format!("Failed to update user: {}", e))?
+        }; // Lock is dropped here
+
+        if merged {
+            tracing::info!("Merged duplicate user '{}' into github_id {}", login, github_id);
+            report.merged.push(format!("{} (github_id: {})", login, github_id));
         } else {
-            tracing::error!("Failed to fetch GitHub user '{}': {}", login, response.status());
+            tracing::info!("Fixed user '{}' with github_id {}", login, github_id);
+            report.fixed.push(format!("{} (github_id: {})", login, github_id));
         }
     }
 
-    Ok(fixed_users)
+    tracing::info!(
+        "User repair complete: {} fixed, {} merged, {} unresolved",
+        report.fixed.len(),
+        report.merged.len(),
+        report.unresolved.len()
+    );
+
+    Ok(report)
 }
 
 // ============================================================================
@@ -381,15 +792,272 @@ pub async fn update_settings(
     excluded_bots: Vec<String>,
     bug_labels: Vec<String>,
     feature_labels: Vec<String>,
+    exclude_queue_merges_from_latency: Option<bool>,
+    exclude_self_reviews: Option<bool>,
+    blocking_labels: Option<Vec<String>>,
+    productivity_weight_speed: Option<f64>,
+    productivity_weight_ease: Option<f64>,
+    productivity_weight_quality: Option<f64>,
+    sync_old_reopened: Option<bool>,
+    loc_basis: Option<String>,
+    sync_pr_files: Option<bool>,
+    metric_precision: Option<u32>,
+    min_active_prs: Option<i32>,
+    exclude_weekends: Option<bool>,
+    enable_webhooks: Option<bool>,
+    webhook_secret: Option<String>,
+    webhook_port: Option<u16>,
+    period_boundary: Option<String>,
+    core_start_hour: Option<i32>,
+    core_end_hour: Option<i32>,
+    sync_interval_minutes: Option<i32>,
+    freshness_aging_multiplier: Option<f64>,
+    freshness_stale_multiplier: Option<f64>,
+    include_merge_commit_loc: Option<bool>,
+    embedding_http_url: Option<String>,
+    embedding_http_concurrency: Option<i32>,
+    sync_strategy: Option<Vec<String>>,
+    rejection_labels: Option<Vec<String>>,
+    internal_logins: Option<Vec<String>>,
+    internal_orgs: Option<Vec<String>>,
+    headcount_basis: Option<String>,
+    sla_rules: Option<Vec<SlaRule>>,
+    ai_min_source_similarity: Option<f64>,
+    pr_type_label_rules: Option<Vec<PrTypeLabelRule>>,
+    exclude_reverts: Option<bool>,
+    planning_labels: Option<Vec<String>>,
+    milestone_counts_source: Option<String>,
+    utc_offset_minutes: Option<i32>,
+    tier_medium_tolerance: Option<f64>,
+    app: AppHandle,
     state: State<'_, AppState>,
 ) -> Result<(), String> {
-    let conn = state.sqlite.lock().map_err(|e| e.to_string())?;
-    queries::update_settings(
-        &conn,
-        history_days,
-        &excluded_bots,
-        &bug_labels,
-        &feature_labels,
-    )
-    .map_err(|e| e.to_string())
+    let (previous, settings) = {
+        let conn = state.sqlite.lock().map_err(|e| e.to_string())?;
+        let mut settings = queries::get_settings(&conn).map_err(|e| e.to_string())?;
+        let previous = settings.clone();
+
+        settings.history_days = history_days;
+        settings.excluded_bots = excluded_bots;
+        settings.bug_labels = bug_labels;
+        settings.feature_labels = feature_labels;
+        // Optional settings keep their stored value when omitted
+        if let Some(value) = exclude_queue_merges_from_latency {
+            settings.exclude_queue_merges_from_latency = value;
+        }
+        if let Some(value) = exclude_self_reviews {
+            settings.exclude_self_reviews = value;
+        }
+        if let Some(value) = blocking_labels {
+            settings.blocking_labels = value;
+        }
+        if let Some(value) = productivity_weight_speed {
+            settings.productivity_weight_speed = value;
+        }
+        if let Some(value) = productivity_weight_ease {
+            settings.productivity_weight_ease = value;
+        }
+        if let Some(value) = productivity_weight_quality {
+            settings.productivity_weight_quality = value;
+        }
+        if let Some(value) = sync_old_reopened {
+            settings.sync_old_reopened = value;
+        }
+        if let Some(value) = loc_basis {
+            settings.loc_basis = value;
+        }
+        if let Some(value) = sync_pr_files {
+            settings.sync_pr_files = value;
+        }
+        if let Some(value) = metric_precision {
+            settings.metric_precision = value;
+        }
+        if let Some(value) = min_active_prs {
+            settings.min_active_prs = value;
+        }
+        if let Some(value) = exclude_weekends {
+            settings.exclude_weekends = value;
+        }
+        if let Some(value) = enable_webhooks {
+            settings.enable_webhooks = value;
+        }
+        if let Some(value) = webhook_port {
+            settings.webhook_port = value;
+        }
+        if let Some(value) = period_boundary {
+            settings.period_boundary = value;
+        }
+        if let Some(value) = core_start_hour {
+            settings.core_start_hour = value;
+        }
+        if let Some(value) = core_end_hour {
+            settings.core_end_hour = value;
+        }
+        if let Some(value) = sync_interval_minutes {
+            settings.sync_interval_minutes = value;
+        }
+        if let Some(value) = freshness_aging_multiplier {
+            settings.freshness_aging_multiplier = value;
+        }
+        if let Some(value) = freshness_stale_multiplier {
+            settings.freshness_stale_multiplier = value;
+        }
+        if let Some(value) = include_merge_commit_loc {
+            settings.include_merge_commit_loc = value;
+        }
+        if let Some(value) = embedding_http_url {
+            settings.embedding_http_url = value;
+        }
+        if let Some(value) = embedding_http_concurrency {
+            settings.embedding_http_concurrency = value;
+        }
+        if let Some(value) = sync_strategy {
+            settings.sync_strategy = value;
+        }
+        if let Some(value) = rejection_labels {
+            settings.rejection_labels = value;
+        }
+        if let Some(value) = internal_logins {
+            settings.internal_logins = value;
+        }
+        if let Some(value) = internal_orgs {
+            settings.internal_orgs = value;
+        }
+        if let Some(value) = headcount_basis {
+            settings.headcount_basis = value;
+        }
+        if let Some(value) = sla_rules {
+            settings.sla_rules = value;
+        }
+        if let Some(value) = ai_min_source_similarity {
+            settings.ai_min_source_similarity = value;
+        }
+        if let Some(value) = pr_type_label_rules {
+            settings.pr_type_label_rules = value;
+        }
+        if let Some(value) = exclude_reverts {
+            settings.exclude_reverts = value;
+        }
+        if let Some(value) = planning_labels {
+            settings.planning_labels = value;
+        }
+        if let Some(value) = milestone_counts_source {
+            settings.milestone_counts_source = value;
+        }
+        if let Some(value) = utc_offset_minutes {
+            settings.utc_offset_minutes = value;
+        }
+        if let Some(value) = tier_medium_tolerance {
+            settings.tier_medium_tolerance = value;
+        }
+
+        // The secret itself goes to the keyring once the settings validate; SQLite only keeps the flag,
+        // which is saved after the keyring write succeeded
+        let webhook_secret = webhook_secret.map(|value| value.trim().to_string());
+        if let Some(value) = &webhook_secret {
+            settings.webhook_secret_set = !value.is_empty();
+        }
+        queries::validate_settings(&settings).map_err(|e| e.to_string())?;
+
+        if let Some(value) = webhook_secret {
+            let profile = state.profile.lock().map_err(|e| e.to_string())?.clone();
+            if value.is_empty() {
+                auth::delete_webhook_secret(&profile).map_err(|e| e.to_string())?;
+            } else {
+                auth::store_webhook_secret(&profile, &value).map_err(|e| e.to_string())?;
+            }
+        }
+
+        queries::update_settings(&conn, &settings).map_err(|e| e.to_string())?;
+        crate::metrics::precision::set_metric_precision(settings.metric_precision);
+
+        (previous, settings)
+    };
+
+    // Start, stop or rebind the receiver now rather than on the next launch
+    if webhook_settings_changed(&previous, &settings) {
+        crate::github::webhooks::stop_webhook_server(&app).await;
+        crate::github::webhooks::start_webhook_server(&app, &settings);
+    }
+
+    Ok(())
+}
+
+/// Whether a settings change requires restarting the webhook receiver
+fn webhook_settings_changed(previous: &Settings, current: &Settings) -> bool {
+    previous.enable_webhooks != current.enable_webhooks || previous.webhook_port != current.webhook_port
+}
+
+// ============================================================================
+// PROFILE COMMANDS
+// ============================================================================
+
+/// Workspace profiles on disk and the one currently open
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ProfileList {
+    pub active: String,
+    pub profiles: Vec<String>,
+}
+
+/// List workspace profiles
+#[tauri::command]
+pub async fn list_profiles(app: AppHandle, state: State<'_, AppState>) -> Result<ProfileList, String> {
+    let app_dir = app
+        .path_resolver()
+        .app_data_dir()
+        .ok_or_else(|| "Failed to get app data directory".to_string())?;
+
+    let active = state.profile.lock().map_err(|e| e.to_string())?.clone();
+    let profiles = profiles::list_profiles(&app_dir).map_err(|e| e.to_string())?;
+
+    Ok(ProfileList { active, profiles })
+}
+
+/// Switch to another workspace profile, creating it if needed.
+/// The open connection is swapped for the profile's database and the choice is kept for next launch.
+/// Refused while a sync is running; the webhook receiver and AI sidecar are restarted for the new profile,
+/// and AI commands fail until the sidecar is back.
+#[tauri::command]
+pub async fn switch_profile(name: String, app: AppHandle, state: State<'_, AppState>) -> Result<String, String> {
+    let app_dir = app
+        .path_resolver()
+        .app_data_dir()
+        .ok_or_else(|| "Failed to get app data directory".to_string())?;
+
+    // Holding the sync guard keeps a sync from starting against either database mid-swap
+    let _sync = state
+        .begin_sync()
+        .map_err(|_| "Cannot switch profiles while a sync is running".to_string())?;
+
+    let new_conn = profiles::open_profile(&app_dir, &name).map_err(|e| e.to_string())?;
+    let new_settings = queries::get_settings(&new_conn).map_err(|e| e.to_string())?;
+
+    crate::github::webhooks::stop_webhook_server(&app).await;
+    {
+        let mut conn = state.sqlite.lock().map_err(|e| e.to_string())?;
+        let mut profile = state.profile.lock().map_err(|e| e.to_string())?;
+        *conn = new_conn;
+        *profile = name.clone();
+    }
+    crate::metrics::precision::set_metric_precision(new_settings.metric_precision);
+    crate::github::webhooks::start_webhook_server(&app, &new_settings);
+
+    profiles::save_active_profile(&app_dir, &name).map_err(|e| e.to_string())?;
+
+    // The AI sidecar reads the previous profile's database file: stop answering until it is restarted
+    if let Some(ai_state) = app.try_state::<crate::AiState>() {
+        ai_state.suspend();
+        let db_path = profiles::profile_db_path(&app_dir, &name).map_err(|e| e.to_string())?;
+        let (ai_app, ai_profile) = (app.clone(), name.clone());
+        tauri::async_runtime::spawn(async move {
+            let ai_state = ai_app.state::<crate::AiState>();
+            match ai_state.restart_for_profile(&ai_profile, db_path).await {
+                Ok(()) => tracing::info!("AI sidecar restarted for profile '{}'", ai_profile),
+                Err(e) => tracing::error!("AI features unavailable for profile '{}': {}", ai_profile, e),
+            }
+        });
+    }
+
+    tracing::info!("Switched to profile '{}'", name);
+    Ok(name)
 }
@@ -0,0 +1,405 @@
+use anyhow::{bail, Result};
+use chrono::{DateTime, Duration, Utc};
+use rusqlite::{params, Connection};
+use serde::Serialize;
+
+// ============================================================================
+// DEMO DATASET
+// ============================================================================
+
+/// Days of synthetic history the demo dataset spans
+const DEMO_HISTORY_DAYS: i64 = 60;
+const DEMO_PULL_REQUESTS: usize = 140;
+const DEMO_ISSUES: usize = 90;
+
+/// Demo github ids start here so they can never collide with real GitHub ids in tests
+const DEMO_GITHUB_ID_BASE: i64 = 900_000_000;
+
+const DEMO_REPOS: [(&str, &str, &str); 3] = [
+    ("made-demo", "api", "Rust"),
+    ("made-demo", "web", "TypeScript"),
+    ("made-demo", "infra", "Go"),
+];
+
+/// (login, name, squad) for tracked demo users
+const DEMO_USERS: [(&str, &str, &str); 6] = [
+    ("ada-demo", "Ada Lovelace", "platform"),
+    ("grace-demo", "Grace Hopper", "platform"),
+    ("linus-demo", "Linus Torvalds", "platform"),
+    ("margaret-demo", "Margaret Hamilton", "product"),
+    ("alan-demo", "Alan Turing", "product"),
+    ("barbara-demo", "Barbara Liskov", "product"),
+];
+
+const DEMO_PR_TITLES: [(&str, &str); 8] = [
+    ("feat: add {} endpoint", "feature"),
+    ("feat: support {} filters", "enhancement"),
+    ("fix: handle empty {} response", "bug"),
+    ("fix: race in {} cache", "bug"),
+    ("refactor: split {} module", "refactor"),
+    ("docs: document {} setup", "documentation"),
+    ("test: cover {} edge cases", "test"),
+    ("chore: bump {} dependencies", "chore"),
+];
+
+const DEMO_TOPICS: [&str; 8] = ["billing", "search", "auth", "export", "webhook", "metrics", "profile", "deploy"];
+
+/// Row counts written by `seed_demo_data`
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DemoSeedSummary {
+    pub repositories: usize,
+    pub users: usize,
+    pub milestones: usize,
+    pub issues: usize,
+    pub pull_requests: usize,
+    pub reviews: usize,
+}
+
+/// Small deterministic generator so every seeded database looks the same
+struct DemoRng(u64);
+
+impl DemoRng {
+    fn next(&mut self) -> u64 {
+        // xorshift64
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// Uniform value in `0..n`
+    fn below(&mut self, n: u64) -> u64 {
+        self.next() % n.max(1)
+    }
+
+    /// True with probability `pct`%
+    fn chance(&mut self, pct: u64) -> bool {
+        self.below(100) < pct
+    }
+}
+
+fn timestamp(at: DateTime<Utc>) -> String {
+    at.format("%Y-%m-%dT%H:%M:%SZ").to_string()
+}
+
+/// Fill an empty database with a synthetic team (repos, tracked users, squads, milestones,
+/// issues, PRs and reviews) spread over the last `DEMO_HISTORY_DAYS` days relative to `now`.
+/// Refuses to touch a database that already has repositories.
+pub fn seed_demo_data(conn: &Connection, now: DateTime<Utc>) -> Result<DemoSeedSummary> {
+    let existing: i64 = conn.query_row("SELECT COUNT(*) FROM repositories", [], |row| row.get(0))?;
+    if existing > 0 {
+        bail!("Demo data can only be seeded into an empty database ({} repositories found)", existing);
+    }
+
+    let tx = conn.unchecked_transaction()?;
+    let mut rng = DemoRng(0x5eed_d3a0_2024_0001);
+    let mut summary = DemoSeedSummary::default();
+    let mut github_id = DEMO_GITHUB_ID_BASE;
+    let mut next_github_id = || {
+        github_id += 1;
+        github_id
+    };
+
+    // Repositories
+    let mut repo_ids = Vec::new();
+    for (owner, name, language) in DEMO_REPOS {
+        tx.execute(
+            "INSERT INTO repositories (owner, name, github_id, enabled, last_synced_at, default_branch, primary_language, is_private, is_demo)
+             VALUES (?1, ?2, ?3, TRUE, ?4, 'main', ?5, FALSE, TRUE)",
+            params![owner, name, next_github_id(), timestamp(now), language],
+        )?;
+        repo_ids.push(tx.last_insert_rowid());
+    }
+    summary.repositories = repo_ids.len();
+
+    // Users: tracked humans, one untracked outside contributor and a bot
+    let mut user_ids = Vec::new();
+    for (login, name, _) in DEMO_USERS {
+        tx.execute(
+            "INSERT INTO users (github_id, login, name, avatar_url, is_bot, tracked, tracked_at)
+             VALUES (?1, ?2, ?3, NULL, FALSE, TRUE, ?4)",
+            params![next_github_id(), login, name, timestamp(now - Duration::days(DEMO_HISTORY_DAYS))],
+        )?;
+        user_ids.push(tx.last_insert_rowid());
+    }
+    tx.execute(
+        "INSERT INTO users (github_id, login, name, is_bot, tracked) VALUES (?1, 'contractor-demo', 'Casey Contractor', FALSE, FALSE)",
+        params![next_github_id()],
+    )?;
+    let contractor_id = tx.last_insert_rowid();
+    tx.execute(
+        "INSERT INTO users (github_id, login, is_bot, tracked) VALUES (?1, 'dependabot[bot]', TRUE, FALSE)",
+        params![next_github_id()],
+    )?;
+    summary.users = user_ids.len() + 2;
+
+    // Squads
+    for (squad_id, name, color) in [("platform", "Platform", "#4f46e5"), ("product", "Product", "#16a34a")] {
+        tx.execute(
+            "INSERT INTO squads (id, name, color) VALUES (?1, ?2, ?3)",
+            params![squad_id, name, color],
+        )?;
+    }
+    for ((_, _, squad_id), user_id) in DEMO_USERS.iter().zip(&user_ids) {
+        tx.execute(
+            "INSERT INTO squad_members (squad_id, user_id) VALUES (?1, ?2)",
+            params![squad_id, user_id],
+        )?;
+    }
+
+    // Milestones: one shipped cycle and one upcoming cycle per repository
+    let mut milestone_ids = Vec::new();
+    for (index, repo_id) in repo_ids.iter().enumerate() {
+        for (title, due_in_days, state) in [("Cycle 1", -14, "closed"), ("Cycle 2", 21, "open")] {
+            tx.execute(
+                "INSERT INTO milestones (github_id, repo_id, title, description, state, due_on, open_issues, closed_issues)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, 0, 0)",
+                params![
+                    next_github_id(),
+                    repo_id,
+                    title,
+                    format!("{} work for {}", title, DEMO_REPOS[index].1),
+                    state,
+                    timestamp(now + Duration::days(due_in_days)),
+                ],
+            )?;
+            milestone_ids.push((*repo_id, tx.last_insert_rowid()));
+        }
+    }
+    summary.milestones = milestone_ids.len();
+
+    // Issues
+    let mut issue_numbers: Vec<(i64, i64)> = Vec::new();
+    for number in 1..=DEMO_ISSUES as i64 {
+        let repo_id = repo_ids[rng.below(repo_ids.len() as u64) as usize];
+        let author_id = user_ids[rng.below(user_ids.len() as u64) as usize];
+        let assignee_id = user_ids[rng.below(user_ids.len() as u64) as usize];
+        let created = now - Duration::minutes(rng.below((DEMO_HISTORY_DAYS * 24 * 60) as u64) as i64);
+        let topic = DEMO_TOPICS[rng.below(DEMO_TOPICS.len() as u64) as usize];
+        let (title, label) = if rng.chance(45) {
+            (format!("{} fails intermittently", topic), "bug")
+        } else {
+            (format!("Improve {} experience", topic), "feature")
+        };
+        let mut labels = vec![label];
+        if rng.chance(20) {
+            labels.push("P1");
+        }
+        let milestone_id = milestone_ids
+            .iter()
+            .filter(|(repo, _)| *repo == repo_id)
+            .map(|(_, id)| *id)
+            .nth(rng.below(3) as usize); // Roughly a third stay unplanned
+
+        let first_response = created + Duration::minutes(30 + rng.below(48 * 60) as i64);
+        let closed = created + Duration::hours(2 + rng.below(240) as i64);
+        let is_closed = rng.chance(60) && closed < now;
+        let updated = if is_closed { closed } else { first_response.min(now) };
+
+        tx.execute(
+            "INSERT INTO issues (github_id, repo_id, number, title, body, state, author_id, assignee_id, milestone_id,
+                                 created_at, updated_at, closed_at, labels, first_response_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
+            params![
+                next_github_id(),
+                repo_id,
+                number,
+                title,
+                "Synthetic demo issue.",
+                if is_closed { "closed" } else { "open" },
+                author_id,
+                assignee_id,
+                milestone_id,
+                timestamp(created),
+                timestamp(updated),
+                is_closed.then(|| timestamp(closed)),
+                serde_json::to_string(&labels)?,
+                (first_response < now).then(|| timestamp(first_response)),
+            ],
+        )?;
+        issue_numbers.push((repo_id, number));
+    }
+    summary.issues = DEMO_ISSUES;
+
+    // Pull requests, numbered after the issues in each repository
+    for index in 0..DEMO_PULL_REQUESTS {
+        let number = DEMO_ISSUES as i64 + index as i64 + 1;
+        let repo_id = repo_ids[rng.below(repo_ids.len() as u64) as usize];
+        let author_id = if rng.chance(8) {
+            contractor_id
+        } else {
+            user_ids[rng.below(user_ids.len() as u64) as usize]
+        };
+        // Mostly core hours on weekdays, with some late-evening work
+        let day_offset = rng.below(DEMO_HISTORY_DAYS as u64) as i64;
+        let hour = if rng.chance(15) { 19 + rng.below(4) } else { 9 + rng.below(8) };
+        let created = (now - Duration::days(day_offset))
+            .date_naive()
+            .and_hms_opt(hour as u32, rng.below(60) as u32, 0)
+            .unwrap_or_default()
+            .and_utc()
+            .min(now - Duration::minutes(30));
+        let (template, label) = DEMO_PR_TITLES[rng.below(DEMO_PR_TITLES.len() as u64) as usize];
+        let title = template.replace("{}", DEMO_TOPICS[rng.below(DEMO_TOPICS.len() as u64) as usize]);
+
+        // Long-tailed size: most PRs are small, a few are very large
+        let additions: i64 = match rng.below(10) {
+            0..=5 => 5 + rng.below(80) as i64,
+            6..=8 => 100 + rng.below(400) as i64,
+            _ => 800 + rng.below(2500) as i64,
+        };
+        let deletions = additions * rng.below(60) as i64 / 100;
+        let changed_files = 1 + (additions / 60).min(30) + rng.below(3) as i64;
+
+        let turnaround = Duration::minutes(60 + rng.below(96 * 60) as i64);
+        let finished = created + turnaround;
+        let outcome = rng.below(100);
+        let (state, merged_at, closed_at) = if finished >= now || outcome >= 92 {
+            ("open", None, None)
+        } else if outcome >= 82 {
+            ("closed", None, Some(timestamp(finished)))
+        } else {
+            ("closed", Some(timestamp(finished)), Some(timestamp(finished)))
+        };
+
+        tx.execute(
+            "INSERT INTO pull_requests (github_id, repo_id, number, title, body, state, author_id, created_at, updated_at,
+                                        merged_at, closed_at, additions, deletions, changed_files, review_comments, labels,
+                                        merge_method, base_branch, commit_count)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, 'manual', 'main', ?17)",
+            params![
+                next_github_id(),
+                repo_id,
+                number,
+                title,
+                "Synthetic demo pull request.",
+                state,
+                author_id,
+                timestamp(created),
+                timestamp(finished.min(now)),
+                merged_at,
+                closed_at,
+                additions,
+                deletions,
+                changed_files,
+                rng.below(12) as i64,
+                serde_json::to_string(&[label])?,
+                1 + rng.below(8) as i64,
+            ],
+        )?;
+        let pr_id = tx.last_insert_rowid();
+
+        // Link some PRs to an issue in the same repository
+        if rng.chance(50) {
+            if let Some((_, issue_number)) = issue_numbers
+                .iter()
+                .filter(|(repo, _)| *repo == repo_id)
+                .nth(rng.below(10) as usize)
+            {
+                tx.execute(
+                    "INSERT OR IGNORE INTO pr_issue_links (pr_id, issue_number) VALUES (?1, ?2)",
+                    params![pr_id, issue_number],
+                )?;
+            }
+        }
+
+        // Reviews from teammates, landing before the PR finished
+        let review_count = if state == "open" { rng.below(2) } else { rng.below(3) };
+        let mut reviewers = Vec::new();
+        for _ in 0..review_count {
+            let reviewer_id = user_ids[rng.below(user_ids.len() as u64) as usize];
+            if reviewer_id == author_id || reviewers.contains(&reviewer_id) {
+                continue;
+            }
+            reviewers.push(reviewer_id);
+            let review_delay = 15 + rng.below(turnaround.num_minutes() as u64 - 15) as i64;
+            let submitted = (created + Duration::minutes(review_delay)).min(now);
+            let review_state = match rng.below(10) {
+                0..=5 => "APPROVED",
+                6..=7 => "CHANGES_REQUESTED",
+                _ => "COMMENTED",
+            };
+            tx.execute(
+                "INSERT INTO pr_reviews (github_id, pr_id, reviewer_id, state, submitted_at) VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![next_github_id(), pr_id, reviewer_id, review_state, timestamp(submitted)],
+            )?;
+            summary.reviews += 1;
+        }
+    }
+    summary.pull_requests = DEMO_PULL_REQUESTS;
+
+    // Keep milestone issue counts consistent with the seeded issues
+    tx.execute(
+        "UPDATE milestones SET
+            open_issues = (SELECT COUNT(*) FROM issues i WHERE i.milestone_id = milestones.id AND i.state = 'open'),
+            closed_issues = (SELECT COUNT(*) FROM issues i WHERE i.milestone_id = milestones.id AND i.state = 'closed')",
+        [],
+    )?;
+
+    tx.commit()?;
+    tracing::info!(
+        "Seeded demo data: {} repositories, {} issues, {} pull requests, {} reviews",
+        summary.repositories,
+        summary.issues,
+        summary.pull_requests,
+        summary.reviews
+    );
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::metrics_queries::{self, Period};
+
+    fn setup_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::db::migrations::run_migrations(&conn).unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_seeded_data_fills_every_metric_category() {
+        let conn = setup_db();
+        let now = Utc::now();
+        let summary = seed_demo_data(&conn, now).unwrap();
+        assert_eq!(summary.repositories, 3);
+        assert_eq!(summary.pull_requests, DEMO_PULL_REQUESTS);
+        assert!(summary.reviews > 0);
+
+        let metrics = metrics_queries::get_dashboard_metrics(&conn, &Period::new(30, "rolling", now)).unwrap();
+        assert!(metrics.speed.prs_per_day > 0.0);
+        assert!(metrics.speed.pr_turnaround_hours > 0.0);
+        assert!(metrics.speed.loc_per_day > 0.0);
+        assert!(metrics.ease.concurrent_repos > 0);
+        assert!(metrics.ease.repos_per_dev > 0.0);
+        assert!(metrics.quality.pr_merge_rate > 0.0);
+        assert!(metrics.quality.review_coverage_pct > 0.0);
+        assert!(metrics.quality.avg_review_cycle_hours > 0.0);
+        assert!(metrics.quality.pr_type_distribution.len() > 1);
+        assert!(metrics.overview.total_prs > 0);
+        assert!(metrics.overview.active_developers > 0);
+    }
+
+    #[test]
+    fn test_seeded_repositories_are_marked_demo_and_skipped_by_syncs() {
+        let conn = setup_db();
+        seed_demo_data(&conn, Utc::now()).unwrap();
+
+        let demo: i64 = conn
+            .query_row("SELECT COUNT(*) FROM repositories WHERE is_demo = TRUE AND enabled = TRUE", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(demo, 3);
+        assert!(crate::db::queries::get_enabled_repositories(&conn).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_seeding_refuses_non_empty_database() {
+        let conn = setup_db();
+        conn.execute("INSERT INTO repositories (owner, name) VALUES ('acme', 'api')", []).unwrap();
+
+        assert!(seed_demo_data(&conn, Utc::now()).is_err());
+        let prs: i64 = conn.query_row("SELECT COUNT(*) FROM pull_requests", [], |row| row.get(0)).unwrap();
+        assert_eq!(prs, 0);
+    }
+}
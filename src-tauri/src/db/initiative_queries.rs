@@ -0,0 +1,267 @@
+use anyhow::{bail, Result};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+// ============================================================================
+// DATA MODELS FOR INITIATIVES
+// ============================================================================
+
+/// Item types that can be tagged with an initiative, matched to their tables
+const ITEM_TABLES: [(&str, &str); 3] = [
+    ("issue", "issues"),
+    ("pull_request", "pull_requests"),
+    ("milestone", "milestones"),
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Initiative {
+    pub id: i64,
+    pub name: String,
+    pub description: Option<String>,
+    pub created_at: String,
+    pub item_count: i32,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct InitiativeProgress {
+    pub initiative_id: i64,
+    pub name: String,
+    pub total_items: i32,
+    pub completed_items: i32,
+    pub completion_pct: f64, // 0-100, 0 when nothing is tagged
+    pub issues: i32,
+    pub closed_issues: i32,
+    pub pull_requests: i32,
+    pub merged_pull_requests: i32, // Closed without merging doesn't count as done
+    pub milestones: i32,
+    pub closed_milestones: i32,
+    pub repos: Vec<String>, // owner/name of every repo the items live in
+}
+
+// ============================================================================
+// INITIATIVE QUERIES
+// ============================================================================
+
+/// Create a named initiative and return its id
+pub fn create_initiative(conn: &Connection, name: &str, description: Option<&str>) -> Result<i64> {
+    conn.execute(
+        "INSERT INTO initiatives (name, description) VALUES (?1, ?2)",
+        params![name, description],
+    )?;
+    Ok(conn.last_insert_rowid())
+}
+
+/// Delete an initiative and its item associations
+pub fn delete_initiative(conn: &Connection, id: i64) -> Result<()> {
+    let tx = conn.unchecked_transaction()?;
+    tx.execute("DELETE FROM initiative_items WHERE initiative_id = ?1", params![id])?;
+    tx.execute("DELETE FROM initiatives WHERE id = ?1", params![id])?;
+    tx.commit()?;
+    Ok(())
+}
+
+/// Get all initiatives with the number of tagged items
+pub fn get_initiatives(conn: &Connection) -> Result<Vec<Initiative>> {
+    let mut stmt = conn.prepare(
+        "SELECT i.id, i.name, i.description, i.created_at, COUNT(ii.item_id)
+         FROM initiatives i
+         LEFT JOIN initiative_items ii ON ii.initiative_id = i.id
+         GROUP BY i.id
+         ORDER BY i.name",
+    )?;
+
+    let initiatives = stmt
+        .query_map([], |row| {
+            Ok(Initiative {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                description: row.get(2)?,
+                created_at: row.get(3)?,
+                item_count: row.get(4)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(initiatives)
+}
+
+/// Tag an issue, pull request or milestone (by local id) with an initiative
+pub fn add_initiative_item(conn: &Connection, initiative_id: i64, item_type: &str, item_id: i64) -> Result<()> {
+    let Some((_, table)) = ITEM_TABLES.iter().find(|(t, _)| *t == item_type) else {
+        bail!("Unknown initiative item type '{}'", item_type);
+    };
+
+    let initiative = conn
+        .query_row("SELECT 1 FROM initiatives WHERE id = ?1", params![initiative_id], |_| Ok(()))
+        .optional()?;
+    if initiative.is_none() {
+        bail!("Initiative {} not found", initiative_id);
+    }
+
+    let item = conn
+        .query_row(&format!("SELECT 1 FROM {} WHERE id = ?1", table), params![item_id], |_| Ok(()))
+        .optional()?;
+    if item.is_none() {
+        bail!("No {} with id {}", item_type, item_id);
+    }
+
+    conn.execute(
+        "INSERT OR IGNORE INTO initiative_items (initiative_id, item_type, item_id) VALUES (?1, ?2, ?3)",
+        params![initiative_id, item_type, item_id],
+    )?;
+    Ok(())
+}
+
+/// Remove an item from an initiative
+pub fn remove_initiative_item(conn: &Connection, initiative_id: i64, item_type: &str, item_id: i64) -> Result<()> {
+    conn.execute(
+        "DELETE FROM initiative_items WHERE initiative_id = ?1 AND item_type = ?2 AND item_id = ?3",
+        params![initiative_id, item_type, item_id],
+    )?;
+    Ok(())
+}
+
+/// Completion of an initiative from the states of its tagged items.
+/// Issues and milestones count once closed; PRs only once merged.
+pub fn get_initiative_progress(conn: &Connection, initiative_id: i64) -> Result<InitiativeProgress> {
+    let name: String = conn
+        .query_row("SELECT name FROM initiatives WHERE id = ?1", params![initiative_id], |row| row.get(0))
+        .optional()?
+        .ok_or_else(|| anyhow::anyhow!("Initiative {} not found", initiative_id))?;
+
+    let mut stmt = conn.prepare(
+        "SELECT 'issue', LOWER(i.state) = 'closed' OR i.closed_at IS NOT NULL, r.owner || '/' || r.name
+         FROM initiative_items ii
+         JOIN issues i ON ii.item_type = 'issue' AND i.id = ii.item_id
+         JOIN repositories r ON i.repo_id = r.id
+         WHERE ii.initiative_id = ?1
+         UNION ALL
+         SELECT 'pull_request', LOWER(p.state) = 'merged' OR p.merged_at IS NOT NULL, r.owner || '/' || r.name
+         FROM initiative_items ii
+         JOIN pull_requests p ON ii.item_type = 'pull_request' AND p.id = ii.item_id
+         JOIN repositories r ON p.repo_id = r.id
+         WHERE ii.initiative_id = ?1
+         UNION ALL
+         SELECT 'milestone', LOWER(m.state) = 'closed', r.owner || '/' || r.name
+         FROM initiative_items ii
+         JOIN milestones m ON ii.item_type = 'milestone' AND m.id = ii.item_id
+         JOIN repositories r ON m.repo_id = r.id
+         WHERE ii.initiative_id = ?1",
+    )?;
+    let items = stmt
+        .query_map(params![initiative_id], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, bool>(1)?, row.get::<_, String>(2)?))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut progress = InitiativeProgress {
+        initiative_id,
+        name,
+        ..Default::default()
+    };
+    let mut repos = BTreeSet::new();
+
+    for (item_type, done, repo) in items {
+        let (total, completed) = match item_type.as_str() {
+            "issue" => (&mut progress.issues, &mut progress.closed_issues),
+            "pull_request" => (&mut progress.pull_requests, &mut progress.merged_pull_requests),
+            _ => (&mut progress.milestones, &mut progress.closed_milestones),
+        };
+        *total += 1;
+        if done {
+            *completed += 1;
+            progress.completed_items += 1;
+        }
+        progress.total_items += 1;
+        repos.insert(repo);
+    }
+
+    if progress.total_items > 0 {
+        progress.completion_pct = progress.completed_items as f64 / progress.total_items as f64 * 100.0;
+    }
+    progress.repos = repos.into_iter().collect();
+
+    Ok(progress)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::db::migrations::run_migrations(&conn).unwrap();
+
+        conn.execute_batch(
+            "INSERT INTO repositories (id, owner, name) VALUES (1, 'acme', 'api'), (2, 'acme', 'web');
+             INSERT INTO milestones (id, github_id, repo_id, title, state) VALUES (1, 1, 2, 'Launch', 'open');
+             INSERT INTO issues (id, github_id, repo_id, number, title, state, created_at, updated_at, closed_at, labels)
+             VALUES
+                 (1, 1, 1, 1, 'Backend endpoint', 'closed', datetime('now', '-9 days'), datetime('now', '-2 days'), datetime('now', '-2 days'), '[]'),
+                 (2, 2, 2, 2, 'Frontend page', 'open', datetime('now', '-9 days'), datetime('now', '-2 days'), NULL, '[]');
+             INSERT INTO pull_requests (id, github_id, repo_id, number, title, state, created_at, updated_at, merged_at, labels)
+             VALUES
+                 (1, 1, 1, 3, 'Add endpoint', 'MERGED', datetime('now', '-5 days'), datetime('now', '-2 days'), datetime('now', '-2 days'), '[]'),
+                 (2, 2, 2, 4, 'Add page', 'OPEN', datetime('now', '-5 days'), datetime('now', '-2 days'), NULL, '[]');",
+        )
+        .unwrap();
+
+        conn
+    }
+
+    #[test]
+    fn test_progress_across_repos_counts_closed_items() {
+        let conn = setup_db();
+        let id = create_initiative(&conn, "Checkout v2", Some("New checkout flow")).unwrap();
+        add_initiative_item(&conn, id, "issue", 1).unwrap();
+        add_initiative_item(&conn, id, "issue", 2).unwrap();
+        add_initiative_item(&conn, id, "pull_request", 1).unwrap();
+        add_initiative_item(&conn, id, "pull_request", 2).unwrap();
+        add_initiative_item(&conn, id, "milestone", 1).unwrap();
+        // Tagging twice is a no-op
+        add_initiative_item(&conn, id, "issue", 1).unwrap();
+
+        let progress = get_initiative_progress(&conn, id).unwrap();
+        assert_eq!(progress.total_items, 5);
+        assert_eq!(progress.completed_items, 2);
+        assert_eq!((progress.issues, progress.closed_issues), (2, 1));
+        assert_eq!((progress.pull_requests, progress.merged_pull_requests), (2, 1));
+        assert_eq!((progress.milestones, progress.closed_milestones), (1, 0));
+        assert!((progress.completion_pct - 40.0).abs() < 1e-9);
+        assert_eq!(progress.repos, vec!["acme/api".to_string(), "acme/web".to_string()]);
+
+        conn.execute("UPDATE issues SET state = 'closed', closed_at = datetime('now') WHERE id = 2", [])
+            .unwrap();
+        remove_initiative_item(&conn, id, "pull_request", 2).unwrap();
+        let progress = get_initiative_progress(&conn, id).unwrap();
+        assert_eq!((progress.total_items, progress.completed_items), (4, 3));
+        assert_eq!(get_initiatives(&conn).unwrap()[0].item_count, 4);
+    }
+
+    #[test]
+    fn test_closed_unmerged_pr_is_not_done() {
+        let conn = setup_db();
+        let id = create_initiative(&conn, "Checkout v2", None).unwrap();
+        add_initiative_item(&conn, id, "pull_request", 2).unwrap();
+
+        conn.execute("UPDATE pull_requests SET state = 'CLOSED', closed_at = datetime('now') WHERE id = 2", [])
+            .unwrap();
+        let progress = get_initiative_progress(&conn, id).unwrap();
+        assert_eq!((progress.pull_requests, progress.merged_pull_requests), (1, 0));
+        assert_eq!(progress.completion_pct, 0.0);
+    }
+
+    #[test]
+    fn test_add_item_rejects_unknown_type_and_missing_item() {
+        let conn = setup_db();
+        let id = create_initiative(&conn, "Checkout v2", None).unwrap();
+        assert!(add_initiative_item(&conn, id, "commit", 1).is_err());
+        assert!(add_initiative_item(&conn, id, "issue", 99).is_err());
+        assert!(add_initiative_item(&conn, id + 1, "issue", 1).is_err());
+
+        let progress = get_initiative_progress(&conn, id).unwrap();
+        assert_eq!(progress.total_items, 0);
+        assert_eq!(progress.completion_pct, 0.0);
+    }
+}
@@ -0,0 +1,287 @@
+use crate::metrics::filter_params::DateRange;
+use anyhow::Result;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+
+// ============================================================================
+// DATA MODELS FOR RAW ITEM ACCESS
+// ============================================================================
+
+/// Filters for raw item queries (mirrors the dashboard metrics filters, plus labels)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ItemFilters {
+    pub item_type: Option<String>, // "issue" or "pull_request"; both when None
+    pub date_range: Option<DateRange>,
+    pub repository_ids: Option<Vec<i64>>,
+    pub squad_id: Option<String>,
+    pub user_id: Option<i64>,
+    pub labels: Option<Vec<String>>, // Matches items carrying any of these labels
+}
+
+/// Shared row shape for issues and pull requests
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ItemRow {
+    pub item_type: String,
+    pub id: i64,
+    pub repo_id: i64,
+    pub repo: String,
+    pub number: i32,
+    pub title: String,
+    pub state: String,
+    pub author_id: Option<i64>,
+    pub author: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+    pub closed_at: Option<String>,
+    pub merged_at: Option<String>,
+    pub merge_method: Option<String>,
+    pub labels: Vec<String>,
+    pub additions: Option<i32>,
+    pub deletions: Option<i32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ItemsPage {
+    pub items: Vec<ItemRow>,
+    pub total: i64,
+    pub page: u32,
+    pub page_size: u32,
+}
+
+// ============================================================================
+// ITEM QUERIES
+// ============================================================================
+
+const ITEMS_CTE: &str = "WITH items AS (
+        SELECT 'issue' as item_type, i.id, i.repo_id, r.owner || '/' || r.name as repo,
+               i.number, i.title, i.state, i.author_id, u.login as author,
+               i.created_at, i.updated_at, i.closed_at, NULL as merged_at, NULL as merge_method, i.labels,
+               NULL as additions, NULL as deletions
+        FROM issues i
+        JOIN repositories r ON i.repo_id = r.id
+        LEFT JOIN users u ON i.author_id = u.id
+        UNION ALL
+        SELECT 'pull_request' as item_type, p.id, p.repo_id, r.owner || '/' || r.name as repo,
+               p.number, p.title, p.state, p.author_id, u.login as author,
+               p.created_at, p.updated_at, p.closed_at, p.merged_at, p.merge_method, p.labels,
+               p.additions, p.deletions
+        FROM pull_requests p
+        JOIN repositories r ON p.repo_id = r.id
+        LEFT JOIN users u ON p.author_id = u.id
+     )";
+
+/// Query raw issue/PR rows with filters and pagination (page is 1-based)
+pub fn query_items(
+    conn: &Connection,
+    filters: &ItemFilters,
+    squad_member_ids: Option<&[i64]>,
+    page: u32,
+    page_size: u32,
+) -> Result<ItemsPage> {
+    let mut where_clause = String::from(" WHERE 1 = 1");
+    let mut params_vec: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
+    let mut param_idx = 1;
+
+    // Add item type filter
+    if let Some(item_type) = &filters.item_type {
+        where_clause.push_str(&format!(" AND item_type = ?{}", param_idx));
+        params_vec.push(Box::new(item_type.clone()));
+        param_idx += 1;
+    }
+
+    // Add date range filter
+    if let Some(range) = &filters.date_range {
+        where_clause.push_str(&format!(" AND created_at >= ?{} AND created_at <= ?{}", param_idx, param_idx + 1));
+        params_vec.push(Box::new(range.start.clone()));
+        params_vec.push(Box::new(range.end.clone()));
+        param_idx += 2;
+    }
+
+    // Add repository filter
+    if let Some(repos) = &filters.repository_ids {
+        if !repos.is_empty() {
+            let placeholders = (0..repos.len())
+                .map(|idx| format!("?{}", param_idx + idx))
+                .collect::<Vec<_>>()
+                .join(", ");
+            where_clause.push_str(&format!(" AND repo_id IN ({})", placeholders));
+            for &repo_id in repos {
+                params_vec.push(Box::new(repo_id));
+            }
+            param_idx += repos.len();
+        }
+    }
+
+    // Add user filter
+    if let Some(uid) = filters.user_id {
+        where_clause.push_str(&format!(" AND author_id = ?{}", param_idx));
+        params_vec.push(Box::new(uid));
+        param_idx += 1;
+    }
+
+    // Add squad filter (members)
+    if let Some(member_ids) = squad_member_ids {
+        if !member_ids.is_empty() {
+            let placeholders = (0..member_ids.len())
+                .map(|idx| format!("?{}", param_idx + idx))
+                .collect::<Vec<_>>()
+                .join(", ");
+            where_clause.push_str(&format!(" AND author_id IN ({})", placeholders));
+            for &member_id in member_ids {
+                params_vec.push(Box::new(member_id));
+            }
+            param_idx += member_ids.len();
+        }
+    }
+
+    // Add label filter (labels are stored as a JSON array)
+    if let Some(labels) = &filters.labels {
+        if !labels.is_empty() {
+            let placeholders = (0..labels.len())
+                .map(|idx| format!("?{}", param_idx + idx))
+                .collect::<Vec<_>>()
+                .join(", ");
+            where_clause.push_str(&format!(
+                " AND EXISTS (SELECT 1 FROM json_each(COALESCE(items.labels, '[]')) WHERE json_each.value IN ({}))",
+                placeholders
+            ));
+            for label in labels {
+                params_vec.push(Box::new(label.clone()));
+            }
+        }
+    }
+
+    let param_refs: Vec<&dyn rusqlite::ToSql> = params_vec.iter()
+        .map(|p| p.as_ref() as &dyn rusqlite::ToSql)
+        .collect();
+
+    let count_query = format!("{} SELECT COUNT(*) FROM items{}", ITEMS_CTE, where_clause);
+    let total: i64 = conn.query_row(&count_query, param_refs.as_slice(), |row| row.get(0))?;
+
+    let page = page.max(1);
+    let page_size = page_size.clamp(1, 500);
+    let offset = (page as i64 - 1) * page_size as i64;
+
+    let page_query = format!(
+        "{} SELECT item_type, id, repo_id, repo, number, title, state, author_id, author,
+                   created_at, updated_at, closed_at, merged_at, merge_method, labels, additions, deletions
+         FROM items{}
+         ORDER BY created_at DESC, item_type ASC, id DESC
+         LIMIT {} OFFSET {}",
+        ITEMS_CTE, where_clause, page_size, offset
+    );
+
+    let mut stmt = conn.prepare(&page_query)?;
+    let items = stmt.query_map(param_refs.as_slice(), |row| {
+        let labels_json: Option<String> = row.get(14)?;
+        let labels: Vec<String> = labels_json
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default();
+
+        Ok(ItemRow {
+            item_type: row.get(0)?,
+            id: row.get(1)?,
+            repo_id: row.get(2)?,
+            repo: row.get(3)?,
+            number: row.get(4)?,
+            title: row.get(5)?,
+            state: row.get(6)?,
+            author_id: row.get(7)?,
+            author: row.get(8)?,
+            created_at: row.get(9)?,
+            updated_at: row.get(10)?,
+            closed_at: row.get(11)?,
+            merged_at: row.get(12)?,
+            merge_method: row.get(13)?,
+            labels,
+            additions: row.get(15)?,
+            deletions: row.get(16)?,
+        })
+    })?
+    .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(ItemsPage {
+        items,
+        total,
+        page,
+        page_size,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rusqlite::params;
+
+    fn setup_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::db::migrations::run_migrations(&conn).unwrap();
+
+        conn.execute_batch(
+            "INSERT INTO repositories (id, owner, name) VALUES (1, 'acme', 'api'), (2, 'acme', 'web');
+             INSERT INTO users (id, github_id, login) VALUES (1, 101, 'alice'), (2, 102, 'bob');",
+        )
+        .unwrap();
+
+        for n in 1..=5 {
+            conn.execute(
+                "INSERT INTO issues (github_id, repo_id, number, title, state, author_id, created_at, updated_at, labels)
+                 VALUES (?1, 1, ?1, 'Issue', 'open', 1, ?2, ?2, ?3)",
+                params![n, format!("2024-01-0{}T00:00:00Z", n), if n % 2 == 0 { r#"["bug"]"# } else { "[]" }],
+            )
+            .unwrap();
+        }
+        for n in 1..=3 {
+            conn.execute(
+                "INSERT INTO pull_requests (github_id, repo_id, number, title, state, author_id, created_at, updated_at, labels)
+                 VALUES (?1, 2, ?1, 'PR', 'open', 2, ?2, ?2, '[\"feature\"]')",
+                params![100 + n, format!("2024-02-0{}T00:00:00Z", n)],
+            )
+            .unwrap();
+        }
+
+        conn
+    }
+
+    #[test]
+    fn test_pagination_returns_subset_and_total() {
+        let conn = setup_db();
+
+        let first = query_items(&conn, &ItemFilters::default(), None, 1, 3).unwrap();
+        assert_eq!(first.total, 8);
+        assert_eq!(first.items.len(), 3);
+        assert!(first.items.iter().all(|i| i.item_type == "pull_request"));
+
+        let last = query_items(&conn, &ItemFilters::default(), None, 3, 3).unwrap();
+        assert_eq!(last.total, 8);
+        assert_eq!(last.items.len(), 2);
+        assert_eq!(last.items[1].created_at, "2024-01-01T00:00:00Z");
+    }
+
+    #[test]
+    fn test_filters_narrow_rows_and_total() {
+        let conn = setup_db();
+
+        let filters = ItemFilters {
+            repository_ids: Some(vec![1]),
+            labels: Some(vec!["bug".to_string()]),
+            ..Default::default()
+        };
+        let page = query_items(&conn, &filters, None, 1, 50).unwrap();
+        assert_eq!(page.total, 2);
+        assert!(page.items.iter().all(|i| i.labels == vec!["bug".to_string()]));
+
+        let filters = ItemFilters {
+            user_id: Some(2),
+            date_range: Some(DateRange {
+                start: "2024-02-02T00:00:00Z".to_string(),
+                end: "2024-02-28T00:00:00Z".to_string(),
+            }),
+            ..Default::default()
+        };
+        let page = query_items(&conn, &filters, None, 1, 50).unwrap();
+        assert_eq!(page.total, 2);
+        assert!(page.items.iter().all(|i| i.author.as_deref() == Some("bob")));
+    }
+}
@@ -1,6 +1,12 @@
+use super::models::{PrTypeLabelRule, Settings, SlaRule};
+use super::queries;
+use crate::metrics::filter_params::MetricsFilters;
+use crate::metrics::precision::serialize_rounded;
 use anyhow::Result;
-use rusqlite::{params, Connection};
+use chrono::{DateTime, Datelike, Duration, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 // ============================================================================
 // METRIC MODELS
@@ -16,10 +22,12 @@ pub struct DashboardMetrics {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OverviewMetrics {
+    #[serde(serialize_with = "serialize_rounded")]
     pub productivity_multiplier: f64,
     pub period_days: i32,
     pub total_prs: i32,
     pub active_developers: i32,
+    pub tiers: PerformanceTiers,
 }
 
 // ============================================================================
@@ -28,9 +36,13 @@ pub struct OverviewMetrics {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SpeedMetrics {
+    #[serde(serialize_with = "serialize_rounded")]
     pub prs_per_day: f64,
+    #[serde(serialize_with = "serialize_rounded")]
     pub prs_per_day_per_dev: f64,
+    #[serde(serialize_with = "serialize_rounded")]
     pub pr_turnaround_hours: f64,
+    #[serde(serialize_with = "serialize_rounded")]
     pub loc_per_day: f64,
     pub cycle_time_distribution: CycleTimeDistribution,
     pub benchmark_comparison: SpeedBenchmarks,
@@ -39,20 +51,28 @@ pub struct SpeedMetrics {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CycleTimeDistribution {
     pub under_4h: i32,
+    #[serde(serialize_with = "serialize_rounded")]
     pub under_4h_pct: f64,
     pub h4_to_12: i32,
+    #[serde(serialize_with = "serialize_rounded")]
     pub h4_to_12_pct: f64,
     pub h12_to_24: i32,
+    #[serde(serialize_with = "serialize_rounded")]
     pub h12_to_24_pct: f64,
     pub over_24h: i32,
+    #[serde(serialize_with = "serialize_rounded")]
     pub over_24h_pct: f64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SpeedBenchmarks {
+    #[serde(serialize_with = "serialize_rounded")]
     pub prs_per_day_industry: f64,
+    #[serde(serialize_with = "serialize_rounded")]
     pub prs_per_day_elite: f64,
+    #[serde(serialize_with = "serialize_rounded")]
     pub pr_turnaround_industry: f64,
+    #[serde(serialize_with = "serialize_rounded")]
     pub pr_turnaround_elite: f64,
 }
 
@@ -63,11 +83,15 @@ pub struct SpeedBenchmarks {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EaseMetrics {
     pub concurrent_repos: i32,
+    #[serde(serialize_with = "serialize_rounded")]
     pub repos_per_dev: f64,
     pub total_active_repos: i32,
     pub active_repos: Vec<ActiveRepository>,
     pub repo_distribution: RepoDistribution,
     pub work_pattern: Vec<WorkPatternCell>,
+    #[serde(serialize_with = "serialize_rounded")]
+    pub after_hours_pct: f64, // Share of PRs opened outside the configured core hours
+    #[serde(serialize_with = "serialize_rounded")]
     pub pr_switch_frequency: f64,
     pub benchmark_comparison: EaseBenchmarks,
 }
@@ -84,8 +108,10 @@ pub struct ActiveRepository {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RepoDistribution {
     pub org_repos: i32,
+    #[serde(serialize_with = "serialize_rounded")]
     pub org_repos_pct: f64,
     pub personal_repos: i32,
+    #[serde(serialize_with = "serialize_rounded")]
     pub personal_repos_pct: f64,
 }
 
@@ -94,11 +120,14 @@ pub struct WorkPatternCell {
     pub day_of_week: i32, // 0=Sunday, 1=Monday, etc.
     pub hour_of_day: i32, // 0-23
     pub activity_count: i32,
+    pub is_core_hours: bool, // Inside the configured core hours (for heatmap highlighting)
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EaseBenchmarks {
+    #[serde(serialize_with = "serialize_rounded")]
     pub concurrent_repos_industry: f64,
+    #[serde(serialize_with = "serialize_rounded")]
     pub concurrent_repos_elite: f64,
 }
 
@@ -108,12 +137,24 @@ pub struct EaseBenchmarks {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct QualityMetrics {
+    #[serde(serialize_with = "serialize_rounded")]
     pub pr_merge_rate: f64,
+    #[serde(serialize_with = "serialize_rounded")]
     pub avg_files_per_pr: f64,
+    #[serde(serialize_with = "serialize_rounded")]
     pub bug_pr_percentage: f64,
+    #[serde(serialize_with = "serialize_rounded")]
     pub feature_pr_percentage: f64,
+    #[serde(serialize_with = "serialize_rounded")]
     pub avg_review_cycle_hours: f64,
+    #[serde(serialize_with = "serialize_rounded")]
     pub avg_review_comments: f64,
+    #[serde(serialize_with = "serialize_rounded")]
+    pub avg_commits_per_pr: f64, // Commits on merged PRs, a proxy for review iterations
+    #[serde(serialize_with = "serialize_rounded")]
+    pub review_coverage_pct: f64, // % of merged PRs with at least one non-author review
+    #[serde(serialize_with = "serialize_rounded")]
+    pub reviewed_loc_pct: f64, // % of merged LOC (additions + deletions) in PRs with a non-author review
     pub pr_type_distribution: Vec<PrTypeBreakdown>,
     pub files_per_pr_distribution: FilesPerPrDistribution,
     pub merge_rate_trend: Vec<MergeRateTrend>,
@@ -124,47 +165,238 @@ pub struct QualityMetrics {
 pub struct PrTypeBreakdown {
     pub pr_type: String,
     pub count: i32,
+    #[serde(serialize_with = "serialize_rounded")]
     pub percentage: f64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FilesPerPrDistribution {
     pub range_1_3: i32,
+    #[serde(serialize_with = "serialize_rounded")]
     pub range_1_3_pct: f64,
     pub range_4_8: i32,
+    #[serde(serialize_with = "serialize_rounded")]
     pub range_4_8_pct: f64,
     pub range_9_15: i32,
+    #[serde(serialize_with = "serialize_rounded")]
     pub range_9_15_pct: f64,
     pub range_16_plus: i32,
+    #[serde(serialize_with = "serialize_rounded")]
     pub range_16_plus_pct: f64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MergeRateTrend {
     pub week: String,
+    #[serde(serialize_with = "serialize_rounded")]
     pub merge_rate: f64,
     pub total_prs: i32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct QualityBenchmarks {
+    #[serde(serialize_with = "serialize_rounded")]
     pub merge_rate_industry: f64,
+    #[serde(serialize_with = "serialize_rounded")]
     pub merge_rate_elite: f64,
+    #[serde(serialize_with = "serialize_rounded")]
     pub bug_ratio_industry: f64,
+    #[serde(serialize_with = "serialize_rounded")]
     pub bug_ratio_elite: f64,
+    #[serde(serialize_with = "serialize_rounded")]
     pub files_per_pr_industry: f64,
 }
 
+// ============================================================================
+// PERFORMANCE TIERS
+// ============================================================================
+
+/// Where a metric sits relative to its industry and elite benchmarks, worst to best
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum PerformanceTier {
+    Low,
+    Medium,
+    High,
+    Elite,
+}
+
+/// Tiers per category; `None` (unrated) when the period has no PRs to rate
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PerformanceTiers {
+    pub speed: Option<PerformanceTier>,
+    pub ease: Option<PerformanceTier>,
+    pub quality: Option<PerformanceTier>,
+    pub overall: Option<PerformanceTier>,
+}
+
+impl PerformanceTier {
+    /// Tier for a single metric:
+    ///
+    /// - `Elite`: meets or beats the elite benchmark
+    /// - `High`: beats the industry benchmark
+    /// - `Medium`: at most `medium_tolerance` (a fraction) worse than the industry benchmark
+    /// - `Low`: anything worse
+    pub fn for_metric(value: f64, industry: f64, elite: f64, higher_is_better: bool, medium_tolerance: f64) -> Self {
+        // Normalize so that larger is always better
+        let (value, industry, elite) = if higher_is_better {
+            (value, industry, elite)
+        } else {
+            (-value, -industry, -elite)
+        };
+        let medium_floor = industry - industry.abs() * medium_tolerance;
+
+        if value >= elite {
+            PerformanceTier::Elite
+        } else if value > industry {
+            PerformanceTier::High
+        } else if value >= medium_floor {
+            PerformanceTier::Medium
+        } else {
+            PerformanceTier::Low
+        }
+    }
+
+    /// Combine tiers by averaging their ranks (Low=0 … Elite=3), rounding down so a
+    /// single weak metric can't be hidden by a strong one
+    pub fn combine(tiers: &[PerformanceTier]) -> Self {
+        if tiers.is_empty() {
+            return PerformanceTier::Low;
+        }
+        let total: usize = tiers.iter().map(|tier| *tier as usize).sum();
+        match total / tiers.len() {
+            0 => PerformanceTier::Low,
+            1 => PerformanceTier::Medium,
+            2 => PerformanceTier::High,
+            _ => PerformanceTier::Elite,
+        }
+    }
+}
+
+/// Tier each category against its benchmarks, plus an overall tier combining the three.
+/// Everything is unrated without PRs in the period, rather than rated Low on zeros.
+pub fn performance_tiers(
+    speed: &SpeedMetrics,
+    ease: &EaseMetrics,
+    quality: &QualityMetrics,
+    total_prs: i32,
+    medium_tolerance: f64,
+) -> PerformanceTiers {
+    if total_prs == 0 {
+        return PerformanceTiers { speed: None, ease: None, quality: None, overall: None };
+    }
+
+    let speed_bench = &speed.benchmark_comparison;
+    let speed_tier = PerformanceTier::combine(&[
+        PerformanceTier::for_metric(
+            speed.prs_per_day_per_dev,
+            speed_bench.prs_per_day_industry,
+            speed_bench.prs_per_day_elite,
+            true,
+            medium_tolerance,
+        ),
+        PerformanceTier::for_metric(
+            speed.pr_turnaround_hours,
+            speed_bench.pr_turnaround_industry,
+            speed_bench.pr_turnaround_elite,
+            false,
+            medium_tolerance,
+        ),
+    ]);
+
+    let ease_bench = &ease.benchmark_comparison;
+    let ease_tier = PerformanceTier::for_metric(
+        ease.repos_per_dev,
+        ease_bench.concurrent_repos_industry,
+        ease_bench.concurrent_repos_elite,
+        true,
+        medium_tolerance,
+    );
+
+    let quality_bench = &quality.benchmark_comparison;
+    let quality_tier = PerformanceTier::combine(&[
+        PerformanceTier::for_metric(
+            quality.pr_merge_rate,
+            quality_bench.merge_rate_industry,
+            quality_bench.merge_rate_elite,
+            true,
+            medium_tolerance,
+        ),
+        PerformanceTier::for_metric(
+            quality.bug_pr_percentage,
+            quality_bench.bug_ratio_industry,
+            quality_bench.bug_ratio_elite,
+            false,
+            medium_tolerance,
+        ),
+    ]);
+
+    PerformanceTiers {
+        speed: Some(speed_tier),
+        ease: Some(ease_tier),
+        quality: Some(quality_tier),
+        overall: Some(PerformanceTier::combine(&[speed_tier, ease_tier, quality_tier])),
+    }
+}
+
+// ============================================================================
+// REPORTING PERIOD
+// ============================================================================
+
+/// The window a "last N days" metric covers. Both modes include an item created
+/// exactly at `since`.
+///
+/// - `"rolling"` (default): the N×24h immediately before now, so the start moves
+///   with the clock and a refresh can drop an item mid-day.
+/// - `"inclusive_start"`: the last N calendar days including today in the team
+///   timezone (`utc_offset_minutes`), starting at local midnight, so every query
+///   run on the same local day sees the same window.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Period {
+    pub days: i32,
+    /// Inclusive start in UTC, formatted like the stored GitHub timestamps so string comparison is exact
+    pub since: String,
+}
+
+impl Period {
+    /// Period ending at `now`, with day-aligned boundaries at UTC midnight
+    pub fn new(days: i32, boundary: &str, now: DateTime<Utc>) -> Self {
+        Self::in_timezone(days, boundary, 0, now)
+    }
+
+    /// Period ending at `now`, with day-aligned boundaries at midnight `utc_offset_minutes` from UTC
+    pub fn in_timezone(days: i32, boundary: &str, utc_offset_minutes: i32, now: DateTime<Utc>) -> Self {
+        let start = if boundary == "inclusive_start" {
+            let offset = Duration::minutes(utc_offset_minutes as i64);
+            let first_day = (now + offset).date_naive() - Duration::days((days as i64 - 1).max(0));
+            first_day.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc() - offset
+        } else {
+            now - Duration::days(days as i64)
+        };
+
+        Period {
+            days,
+            since: start.format("%Y-%m-%dT%H:%M:%SZ").to_string(),
+        }
+    }
+
+    /// Build the period for the configured boundary and team timezone, ending now
+    pub fn from_settings(days: i32, settings: &Settings) -> Self {
+        Self::in_timezone(days, &settings.period_boundary, settings.utc_offset_minutes, Utc::now())
+    }
+}
+
 // ============================================================================
 // QUERY FUNCTIONS
 // ============================================================================
 
 /// Get complete dashboard metrics for a given time period
-pub fn get_dashboard_metrics(conn: &Connection, days: i32) -> Result<DashboardMetrics> {
-    let speed = get_speed_metrics(conn, days)?;
-    let ease = get_ease_metrics(conn, days)?;
-    let quality = get_quality_metrics(conn, days)?;
-    let overview = get_overview_metrics(conn, days, &speed, &ease, &quality)?;
+pub fn get_dashboard_metrics(conn: &Connection, period: &Period) -> Result<DashboardMetrics> {
+    let settings = queries::get_settings(conn)?;
+
+    let speed = get_speed_metrics(conn, period, &settings)?;
+    let ease = get_ease_metrics(conn, period, &settings)?;
+    let quality = get_quality_metrics(conn, period, &settings)?;
+    let overview = get_overview_metrics(conn, period, &speed, &ease, &quality, &settings)?;
 
     Ok(DashboardMetrics {
         speed,
@@ -177,21 +409,25 @@ pub fn get_dashboard_metrics(conn: &Connection, days: i32) -> Result<DashboardMe
 /// Calculate overview metrics including productivity multiplier
 fn get_overview_metrics(
     conn: &Connection,
-    days: i32,
+    period: &Period,
     speed: &SpeedMetrics,
     ease: &EaseMetrics,
     quality: &QualityMetrics,
+    settings: &Settings,
 ) -> Result<OverviewMetrics> {
-    let (total_prs, active_developers): (i32, i32) = conn.query_row(
-        "SELECT
-            COUNT(*) as total_prs,
-            COUNT(DISTINCT author_id) as active_developers
-         FROM pull_requests
-         WHERE created_at > datetime('now', '-' || ?1 || ' days')
-           AND author_id IN (SELECT id FROM users WHERE tracked = 1)",
-        params![days],
-        |row| Ok((row.get(0)?, row.get(1)?)),
+    let reverts = RevertExclusion::from_settings(conn, period, settings)?;
+    let total_prs: i32 = conn.query_row(
+        &format!(
+            "SELECT COUNT(*) as total_prs
+             FROM pull_requests
+             WHERE created_at >= ?1
+               AND author_id IN (SELECT id FROM users WHERE tracked = 1){}",
+            reverts.clause("id")
+        ),
+        params![period.since],
+        |row| row.get(0),
     )?;
+    let active_developers = count_active_developers(conn, period, settings.min_active_prs)?;
 
     // Calculate productivity multiplier
     // Formula: Weighted average of performance vs industry benchmarks
@@ -200,32 +436,124 @@ fn get_overview_metrics(
     let repo_capacity_ratio = ease.repos_per_dev / ease.benchmark_comparison.concurrent_repos_industry;
     let quality_ratio = quality.pr_merge_rate / quality.benchmark_comparison.merge_rate_industry;
 
-    let productivity_multiplier =
-        (pr_velocity_ratio * 0.35) +
-        (pr_speed_ratio * 0.25) +
-        (repo_capacity_ratio * 0.25) +
-        (quality_ratio * 0.15);
+    let productivity_multiplier = weighted_productivity_multiplier(
+        pr_velocity_ratio,
+        pr_speed_ratio,
+        repo_capacity_ratio,
+        quality_ratio,
+        settings,
+    );
 
     Ok(OverviewMetrics {
         productivity_multiplier,
-        period_days: days,
+        period_days: period.days,
         total_prs,
         active_developers,
+        tiers: performance_tiers(speed, ease, quality, total_prs, settings.tier_medium_tolerance),
     })
 }
 
+/// Count tracked users with at least `min_active_prs` PRs in the window
+fn count_active_developers(conn: &Connection, period: &Period, min_active_prs: i32) -> Result<i32> {
+    let count = conn.query_row(
+        "SELECT COUNT(*) FROM (
+             SELECT author_id
+             FROM pull_requests
+             WHERE created_at >= ?1
+               AND author_id IN (SELECT id FROM users WHERE tracked = 1)
+             GROUP BY author_id
+             HAVING COUNT(*) >= ?2
+         )",
+        params![period.since, min_active_prs],
+        |row| row.get(0),
+    )?;
+
+    Ok(count)
+}
+
+/// Lines of code per day; the "merged" basis ignores LOC from PRs that never merged and the
+/// "commits" basis counts tracked users' commits (merge commits per `include_merge_commit_loc`)
+fn get_loc_per_day(conn: &Connection, period: &Period, settings: &Settings, reverts: &RevertExclusion) -> f64 {
+    let loc_basis = settings.loc_basis.as_str();
+    let merged_filter = if loc_basis == "merged" { " AND merged_at IS NOT NULL" } else { "" };
+
+    let total_loc: f64 = if loc_basis == "commits" {
+        queries::get_commit_loc(conn, &period.since, settings.include_merge_commit_loc).unwrap_or(0) as f64
+    } else {
+        conn.query_row(
+            &format!(
+                "SELECT SUM(additions + deletions) * 1.0
+                 FROM pull_requests
+                 WHERE created_at >= ?1
+                   AND author_id IN (SELECT id FROM users WHERE tracked = 1){}{}",
+                merged_filter,
+                reverts.clause("id")
+            ),
+            params![period.since],
+            |row| row.get(0),
+        ).unwrap_or(0.0)
+    };
+
+    let period_days = days_in_period(period.days, settings.exclude_weekends, settings.utc_offset_minutes);
+    if period_days > 0 { total_loc / period_days as f64 } else { 0.0 }
+}
+
+/// SQLite date modifier shifting stored UTC timestamps into the team's timezone
+pub fn utc_offset_modifier(utc_offset_minutes: i32) -> String {
+    format!("{:+} minutes", utc_offset_minutes)
+}
+
+/// Number of days in the last N days, optionally counting weekdays only.
+/// Days are bucketed in the team's timezone (`utc_offset_minutes`).
+fn days_in_period(days: i32, exclude_weekends: bool, utc_offset_minutes: i32) -> i32 {
+    if !exclude_weekends {
+        return days;
+    }
+
+    let today = (chrono::Utc::now() + chrono::Duration::minutes(utc_offset_minutes as i64)).date_naive();
+    (0..days.max(0))
+        .map(|offset| today - chrono::Duration::days(offset as i64))
+        .filter(|date| !matches!(date.weekday(), chrono::Weekday::Sat | chrono::Weekday::Sun))
+        .count() as i32
+}
+
+/// Combine benchmark ratios using the configured speed/ease/quality weights.
+/// The speed weight is split between velocity and turnaround in the original 35:25 ratio.
+fn weighted_productivity_multiplier(
+    pr_velocity_ratio: f64,
+    pr_speed_ratio: f64,
+    repo_capacity_ratio: f64,
+    quality_ratio: f64,
+    settings: &Settings,
+) -> f64 {
+    let velocity_weight = settings.productivity_weight_speed * (0.35 / 0.6);
+    let turnaround_weight = settings.productivity_weight_speed * (0.25 / 0.6);
+
+    (pr_velocity_ratio * velocity_weight)
+        + (pr_speed_ratio * turnaround_weight)
+        + (repo_capacity_ratio * settings.productivity_weight_ease)
+        + (quality_ratio * settings.productivity_weight_quality)
+}
+
 /// Get Speed metrics
-fn get_speed_metrics(conn: &Connection, days: i32) -> Result<SpeedMetrics> {
+fn get_speed_metrics(conn: &Connection, period: &Period, settings: &Settings) -> Result<SpeedMetrics> {
+    let reverts = RevertExclusion::from_settings(conn, period, settings)?;
+
     // PRs per day calculations
     let (total_prs, active_developers, active_days): (f64, f64, f64) = conn.query_row(
-        "SELECT
-            COUNT(*) as total_prs,
-            COUNT(DISTINCT author_id) as active_developers,
-            COUNT(DISTINCT DATE(created_at)) as active_days
-         FROM pull_requests
-         WHERE created_at > datetime('now', '-' || ?1 || ' days')
-           AND author_id IN (SELECT id FROM users WHERE tracked = 1)",
-        params![days],
+        &format!(
+            "SELECT
+                COUNT(*) as total_prs,
+                COUNT(DISTINCT author_id) as active_developers,
+                COUNT(DISTINCT CASE
+                    WHEN ?2 = 0 OR strftime('%w', created_at, ?3) NOT IN ('0', '6') THEN DATE(created_at, ?3)
+                END) as active_days
+             FROM pull_requests
+             WHERE created_at >= ?1
+               AND author_id IN (SELECT id FROM users WHERE tracked = 1){}",
+            reverts.clause("id")
+        ),
+        params![period.since, settings.exclude_weekends, utc_offset_modifier(settings.utc_offset_minutes)],
         |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
     )?;
 
@@ -236,29 +564,28 @@ fn get_speed_metrics(conn: &Connection, days: i32) -> Result<SpeedMetrics> {
         0.0
     };
 
-    // PR turnaround time (merged PRs only)
+    // PR turnaround time (merged PRs only, negative spans clamped to 0)
+    log_negative_turnarounds(conn, period)?;
     let pr_turnaround_hours: f64 = conn.query_row(
-        "SELECT AVG((julianday(merged_at) - julianday(created_at)) * 24.0)
-         FROM pull_requests
-         WHERE merged_at IS NOT NULL
-           AND created_at > datetime('now', '-' || ?1 || ' days')
-           AND author_id IN (SELECT id FROM users WHERE tracked = 1)",
-        params![days],
+        &format!(
+            "SELECT AVG(MAX(0.0, (julianday(merged_at) - julianday(created_at)) * 24.0))
+             FROM pull_requests
+             WHERE merged_at IS NOT NULL
+               AND julianday(merged_at) IS NOT NULL
+               AND julianday(created_at) IS NOT NULL
+               AND created_at >= ?1
+               AND author_id IN (SELECT id FROM users WHERE tracked = 1){}",
+            reverts.clause("id")
+        ),
+        params![period.since],
         |row| row.get(0),
     ).unwrap_or(0.0);
 
     // Lines of code per day
-    let loc_per_day: f64 = conn.query_row(
-        "SELECT SUM(additions + deletions) * 1.0 / ?1
-         FROM pull_requests
-         WHERE created_at > datetime('now', '-' || ?1 || ' days')
-           AND author_id IN (SELECT id FROM users WHERE tracked = 1)",
-        params![days],
-        |row| row.get(0),
-    ).unwrap_or(0.0);
+    let loc_per_day = get_loc_per_day(conn, period, settings, &reverts);
 
     // Cycle time distribution
-    let cycle_time_distribution = get_cycle_time_distribution(conn, days)?;
+    let cycle_time_distribution = get_cycle_time_distribution(conn, period, &reverts)?;
 
     // Benchmarks (industry standards)
     let benchmark_comparison = SpeedBenchmarks {
@@ -278,9 +605,35 @@ fn get_speed_metrics(conn: &Connection, days: i32) -> Result<SpeedMetrics> {
     })
 }
 
-/// Get cycle time distribution
-fn get_cycle_time_distribution(conn: &Connection, days: i32) -> Result<CycleTimeDistribution> {
+/// Warn about merged PRs whose merged_at precedes created_at (clock skew in source data)
+fn log_negative_turnarounds(conn: &Connection, period: &Period) -> Result<()> {
     let mut stmt = conn.prepare(
+        "SELECT id, created_at, merged_at
+         FROM pull_requests
+         WHERE merged_at IS NOT NULL
+           AND julianday(merged_at) < julianday(created_at)
+           AND created_at >= ?1
+           AND author_id IN (SELECT id FROM users WHERE tracked = 1)"
+    )?;
+
+    let rows = stmt.query_map(params![period.since], |row| {
+        Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?))
+    })?;
+
+    for row in rows {
+        let (id, created_at, merged_at) = row?;
+        tracing::warn!(
+            "PR {} merged before it was created ({} -> {}), clamping turnaround to 0",
+            id, created_at, merged_at
+        );
+    }
+
+    Ok(())
+}
+
+/// Get cycle time distribution
+fn get_cycle_time_distribution(conn: &Connection, period: &Period, reverts: &RevertExclusion) -> Result<CycleTimeDistribution> {
+    let mut stmt = conn.prepare(&format!(
         "SELECT
             COALESCE(SUM(CASE WHEN hours_to_merge < 4 THEN 1 ELSE 0 END), 0) as under_4h,
             COALESCE(SUM(CASE WHEN hours_to_merge >= 4 AND hours_to_merge < 12 THEN 1 ELSE 0 END), 0) as h4_to_12,
@@ -288,16 +641,19 @@ fn get_cycle_time_distribution(conn: &Connection, days: i32) -> Result<CycleTime
             COALESCE(SUM(CASE WHEN hours_to_merge >= 24 THEN 1 ELSE 0 END), 0) as over_24h,
             COUNT(*) as total
          FROM (
-            SELECT (julianday(merged_at) - julianday(created_at)) * 24.0 as hours_to_merge
+            SELECT MAX(0.0, (julianday(merged_at) - julianday(created_at)) * 24.0) as hours_to_merge
             FROM pull_requests
             WHERE merged_at IS NOT NULL
-              AND created_at > datetime('now', '-' || ?1 || ' days')
-              AND author_id IN (SELECT id FROM users WHERE tracked = 1)
-         )"
-    )?;
+              AND julianday(merged_at) IS NOT NULL
+              AND julianday(created_at) IS NOT NULL
+              AND created_at >= ?1
+              AND author_id IN (SELECT id FROM users WHERE tracked = 1){}
+         )",
+        reverts.clause("id")
+    ))?;
 
     let (under_4h, h4_to_12, h12_to_24, over_24h, total): (i32, i32, i32, i32, i32) =
-        stmt.query_row(params![days], |row| {
+        stmt.query_row(params![period.since], |row| {
             Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?))
         })?;
 
@@ -315,16 +671,16 @@ fn get_cycle_time_distribution(conn: &Connection, days: i32) -> Result<CycleTime
 }
 
 /// Get Ease metrics
-fn get_ease_metrics(conn: &Connection, days: i32) -> Result<EaseMetrics> {
+fn get_ease_metrics(conn: &Connection, period: &Period, settings: &Settings) -> Result<EaseMetrics> {
     // Concurrent repositories
     let (concurrent_repos, active_developers): (i32, i32) = conn.query_row(
         "SELECT
             COUNT(DISTINCT repo_id) as concurrent_repos,
             COUNT(DISTINCT author_id) as active_developers
          FROM pull_requests
-         WHERE created_at > datetime('now', '-' || ?1 || ' days')
+         WHERE created_at >= ?1
            AND author_id IN (SELECT id FROM users WHERE tracked = 1)",
-        params![days],
+        params![period.since],
         |row| Ok((row.get(0)?, row.get(1)?)),
     )?;
 
@@ -335,17 +691,18 @@ fn get_ease_metrics(conn: &Connection, days: i32) -> Result<EaseMetrics> {
     };
 
     // Active repositories list
-    let active_repos = get_active_repositories(conn, days)?;
+    let active_repos = get_active_repositories(conn, period)?;
     let total_active_repos = active_repos.len() as i32;
 
     // Repository distribution
-    let repo_distribution = get_repo_distribution(conn, days)?;
+    let repo_distribution = get_repo_distribution(conn, period)?;
 
     // Work pattern heatmap
-    let work_pattern = get_work_pattern(conn, days)?;
+    let work_pattern = get_work_pattern(conn, period, settings, None)?;
+    let after_hours_pct = after_hours_percentage(&work_pattern);
 
     // PR switch frequency
-    let pr_switch_frequency = get_pr_switch_frequency(conn, days)?;
+    let pr_switch_frequency = get_pr_switch_frequency(conn, period)?;
 
     // Benchmarks
     let benchmark_comparison = EaseBenchmarks {
@@ -360,13 +717,14 @@ fn get_ease_metrics(conn: &Connection, days: i32) -> Result<EaseMetrics> {
         active_repos,
         repo_distribution,
         work_pattern,
+        after_hours_pct,
         pr_switch_frequency,
         benchmark_comparison,
     })
 }
 
 /// Get active repositories list
-fn get_active_repositories(conn: &Connection, days: i32) -> Result<Vec<ActiveRepository>> {
+fn get_active_repositories(conn: &Connection, period: &Period) -> Result<Vec<ActiveRepository>> {
     let mut stmt = conn.prepare(
         "SELECT
             r.owner || '/' || r.name as repo_name,
@@ -376,14 +734,14 @@ fn get_active_repositories(conn: &Connection, days: i32) -> Result<Vec<ActiveRep
             MAX(pr.created_at) as last_activity
          FROM pull_requests pr
          JOIN repositories r ON pr.repo_id = r.id
-         WHERE pr.created_at > datetime('now', '-' || ?1 || ' days')
+         WHERE pr.created_at >= ?1
            AND pr.author_id IN (SELECT id FROM users WHERE tracked = 1)
          GROUP BY r.id, r.owner, r.name
          ORDER BY pr_count DESC
          LIMIT 20"
     )?;
 
-    let repos = stmt.query_map(params![days], |row| {
+    let repos = stmt.query_map(params![period.since], |row| {
         Ok(ActiveRepository {
             repo_name: row.get(0)?,
             pr_count: row.get(1)?,
@@ -398,16 +756,16 @@ fn get_active_repositories(conn: &Connection, days: i32) -> Result<Vec<ActiveRep
 }
 
 /// Get repository distribution (org vs personal)
-fn get_repo_distribution(conn: &Connection, days: i32) -> Result<RepoDistribution> {
+fn get_repo_distribution(conn: &Connection, period: &Period) -> Result<RepoDistribution> {
     let (org_repos, personal_repos): (i32, i32) = conn.query_row(
         "SELECT
             COUNT(DISTINCT CASE WHEN r.owner IN ('microsoft', 'Microsoft') THEN r.id END) as org_repos,
             COUNT(DISTINCT CASE WHEN r.owner NOT IN ('microsoft', 'Microsoft') THEN r.id END) as personal_repos
          FROM repositories r
          JOIN pull_requests pr ON pr.repo_id = r.id
-         WHERE pr.created_at > datetime('now', '-' || ?1 || ' days')
+         WHERE pr.created_at >= ?1
            AND pr.author_id IN (SELECT id FROM users WHERE tracked = 1)",
-        params![days],
+        params![period.since],
         |row| Ok((row.get(0).unwrap_or(0), row.get(1).unwrap_or(0))),
     )?;
 
@@ -420,25 +778,41 @@ fn get_repo_distribution(conn: &Connection, days: i32) -> Result<RepoDistributio
     })
 }
 
-/// Get work pattern heatmap
-fn get_work_pattern(conn: &Connection, days: i32) -> Result<Vec<WorkPatternCell>> {
-    let mut stmt = conn.prepare(
+/// Get work pattern heatmap, with days and hours in the team's timezone (`utc_offset_minutes`).
+/// Counts PRs by `author_ids` when given, otherwise by all tracked users.
+fn get_work_pattern(
+    conn: &Connection,
+    period: &Period,
+    settings: &Settings,
+    author_ids: Option<&[i64]>,
+) -> Result<Vec<WorkPatternCell>> {
+    let author_filter = match author_ids {
+        Some(ids) => format!(
+            "author_id IN ({})",
+            ids.iter().map(|id| id.to_string()).collect::<Vec<_>>().join(",")
+        ),
+        None => "author_id IN (SELECT id FROM users WHERE tracked = 1)".to_string(),
+    };
+    let mut stmt = conn.prepare(&format!(
         "SELECT
-            CAST(strftime('%w', created_at) AS INTEGER) as day_of_week,
-            CAST(strftime('%H', created_at) AS INTEGER) as hour_of_day,
+            CAST(strftime('%w', created_at, ?2) AS INTEGER) as day_of_week,
+            CAST(strftime('%H', created_at, ?2) AS INTEGER) as hour_of_day,
             COUNT(*) as activity_count
          FROM pull_requests
-         WHERE created_at > datetime('now', '-' || ?1 || ' days')
-           AND author_id IN (SELECT id FROM users WHERE tracked = 1)
+         WHERE created_at >= ?1
+           AND {}
          GROUP BY day_of_week, hour_of_day
-         ORDER BY day_of_week, hour_of_day"
-    )?;
+         ORDER BY day_of_week, hour_of_day",
+        author_filter
+    ))?;
 
-    let pattern = stmt.query_map(params![days], |row| {
+    let pattern = stmt.query_map(params![period.since, utc_offset_modifier(settings.utc_offset_minutes)], |row| {
+        let hour_of_day: i32 = row.get(1)?;
         Ok(WorkPatternCell {
             day_of_week: row.get(0)?,
-            hour_of_day: row.get(1)?,
+            hour_of_day,
             activity_count: row.get(2)?,
+            is_core_hours: hour_of_day >= settings.core_start_hour && hour_of_day < settings.core_end_hour,
         })
     })?
     .collect::<Result<Vec<_>, _>>()?;
@@ -446,8 +820,38 @@ fn get_work_pattern(conn: &Connection, days: i32) -> Result<Vec<WorkPatternCell>
     Ok(pattern)
 }
 
+/// Percentage of activity that falls outside core hours
+fn after_hours_percentage(pattern: &[WorkPatternCell]) -> f64 {
+    let total: i32 = pattern.iter().map(|cell| cell.activity_count).sum();
+    if total == 0 {
+        return 0.0;
+    }
+
+    let after_hours: i32 = pattern
+        .iter()
+        .filter(|cell| !cell.is_core_hours)
+        .map(|cell| cell.activity_count)
+        .sum();
+    after_hours as f64 * 100.0 / total as f64
+}
+
+/// Percentage of activity that falls on Saturday or Sunday (days as bucketed by the pattern)
+fn weekend_percentage(pattern: &[WorkPatternCell]) -> f64 {
+    let total: i32 = pattern.iter().map(|cell| cell.activity_count).sum();
+    if total == 0 {
+        return 0.0;
+    }
+
+    let weekend: i32 = pattern
+        .iter()
+        .filter(|cell| cell.day_of_week == 0 || cell.day_of_week == 6)
+        .map(|cell| cell.activity_count)
+        .sum();
+    weekend as f64 * 100.0 / total as f64
+}
+
 /// Get PR switch frequency (how often consecutive PRs are in different repos)
-fn get_pr_switch_frequency(conn: &Connection, days: i32) -> Result<f64> {
+fn get_pr_switch_frequency(conn: &Connection, period: &Period) -> Result<f64> {
     let switch_pct: f64 = conn.query_row(
         "WITH ordered_prs AS (
             SELECT
@@ -456,7 +860,7 @@ fn get_pr_switch_frequency(conn: &Connection, days: i32) -> Result<f64> {
                 created_at,
                 LAG(repo_id) OVER (PARTITION BY author_id ORDER BY created_at) as prev_repo_id
             FROM pull_requests
-            WHERE created_at > datetime('now', '-' || ?1 || ' days')
+            WHERE created_at >= ?1
               AND author_id IN (SELECT id FROM users WHERE tracked = 1)
         )
         SELECT
@@ -466,7 +870,7 @@ fn get_pr_switch_frequency(conn: &Connection, days: i32) -> Result<f64> {
             END as switch_percentage
         FROM ordered_prs
         WHERE prev_repo_id IS NOT NULL",
-        params![days],
+        params![period.since],
         |row| row.get(0),
     ).unwrap_or(0.0);
 
@@ -474,34 +878,45 @@ fn get_pr_switch_frequency(conn: &Connection, days: i32) -> Result<f64> {
 }
 
 /// Get Quality metrics
-fn get_quality_metrics(conn: &Connection, days: i32) -> Result<QualityMetrics> {
-    // PR merge rate
+fn get_quality_metrics(conn: &Connection, period: &Period, settings: &Settings) -> Result<QualityMetrics> {
+    let reverts = RevertExclusion::from_settings(conn, period, settings)?;
+
+    // PR merge rate (deliberately closed PRs, tagged with a rejection label, don't count)
+    let rejection_labels_json = serde_json::to_string(&settings.rejection_labels)?;
     let pr_merge_rate: f64 = conn.query_row(
-        "SELECT
-            CASE WHEN COUNT(CASE WHEN state != 'open' THEN 1 END) > 0
-            THEN (COUNT(CASE WHEN merged_at IS NOT NULL THEN 1 END) * 100.0 /
-                  COUNT(CASE WHEN state != 'open' THEN 1 END))
-            ELSE 0.0
-            END as merge_rate
-         FROM pull_requests
-         WHERE created_at > datetime('now', '-' || ?1 || ' days')
-           AND author_id IN (SELECT id FROM users WHERE tracked = 1)",
-        params![days],
+        &format!(
+            "SELECT
+                CASE WHEN COUNT(CASE WHEN state != 'open' THEN 1 END) > 0
+                THEN (COUNT(CASE WHEN merged_at IS NOT NULL THEN 1 END) * 100.0 /
+                      COUNT(CASE WHEN state != 'open' THEN 1 END))
+                ELSE 0.0
+                END as merge_rate
+             FROM pull_requests
+             WHERE created_at >= ?1
+               AND author_id IN (SELECT id FROM users WHERE tracked = 1)
+               AND {}{}",
+            NOT_REJECTED_FILTER,
+            reverts.clause("id")
+        ),
+        params![period.since, rejection_labels_json],
         |row| row.get(0),
     ).unwrap_or(0.0);
 
     // Average files per PR
     let avg_files_per_pr: f64 = conn.query_row(
-        "SELECT AVG(changed_files)
-         FROM pull_requests
-         WHERE created_at > datetime('now', '-' || ?1 || ' days')
-           AND author_id IN (SELECT id FROM users WHERE tracked = 1)",
-        params![days],
+        &format!(
+            "SELECT AVG(changed_files)
+             FROM pull_requests
+             WHERE created_at >= ?1
+               AND author_id IN (SELECT id FROM users WHERE tracked = 1){}",
+            reverts.clause("id")
+        ),
+        params![period.since],
         |row| row.get(0),
     ).unwrap_or(0.0);
 
     // PR type distribution
-    let pr_type_distribution = get_pr_type_distribution(conn, days)?;
+    let pr_type_distribution = get_pr_type_distribution(conn, period, &settings.pr_type_label_rules, &reverts)?;
 
     // Calculate bug and feature percentages from distribution
     let bug_pr_percentage = pr_type_distribution.iter()
@@ -514,37 +929,69 @@ fn get_quality_metrics(conn: &Connection, days: i32) -> Result<QualityMetrics> {
         .map(|p| p.percentage)
         .unwrap_or(0.0);
 
-    // Average review cycle time
+    let bot_reviewers = queries::get_bot_reviewer_ids(conn, &settings.excluded_bots)?;
+
+    // Average time to first (non-bot) review, optionally ignoring merge-queue merges
     let avg_review_cycle_hours: f64 = conn.query_row(
-        "SELECT AVG((julianday(r.submitted_at) - julianday(pr.created_at)) * 24.0)
-         FROM pull_requests pr
-         JOIN pr_reviews r ON r.pr_id = pr.id
-         WHERE pr.created_at > datetime('now', '-' || ?1 || ' days')
-           AND pr.author_id IN (SELECT id FROM users WHERE tracked = 1)
-           AND r.submitted_at = (
-                SELECT MIN(submitted_at)
-                FROM pr_reviews
-                WHERE pr_id = pr.id
-           )",
-        params![days],
+        &format!(
+            "SELECT AVG((julianday(first_review_at) - julianday(created_at)) * 24.0)
+             FROM (
+                SELECT pr.created_at,
+                       (SELECT MIN(r.submitted_at)
+                        FROM pr_reviews r
+                        WHERE r.pr_id = pr.id
+                          AND (?3 = 0 OR r.reviewer_id IS NULL OR pr.author_id IS NULL OR r.reviewer_id != pr.author_id){}
+                       ) AS first_review_at
+                FROM pull_requests pr
+                WHERE pr.created_at >= ?1
+                  AND pr.author_id IN (SELECT id FROM users WHERE tracked = 1)
+                  AND (?2 = 0 OR COALESCE(pr.merge_method, '') != 'merge_queue'){}
+             )
+             WHERE first_review_at IS NOT NULL",
+            queries::bot_review_filter(&bot_reviewers),
+            reverts.clause("pr.id")
+        ),
+        params![period.since, settings.exclude_queue_merges_from_latency, settings.exclude_self_reviews],
         |row| row.get(0),
     ).unwrap_or(0.0);
 
     // Average review comments
     let avg_review_comments: f64 = conn.query_row(
-        "SELECT AVG(review_comments)
-         FROM pull_requests
-         WHERE created_at > datetime('now', '-' || ?1 || ' days')
-           AND author_id IN (SELECT id FROM users WHERE tracked = 1)",
-        params![days],
+        &format!(
+            "SELECT AVG(review_comments)
+             FROM pull_requests
+             WHERE created_at >= ?1
+               AND author_id IN (SELECT id FROM users WHERE tracked = 1){}",
+            reverts.clause("id")
+        ),
+        params![period.since],
         |row| row.get(0),
     ).unwrap_or(0.0);
 
+    // Average commits per merged PR (PRs synced before commit counts existed are skipped)
+    let avg_commits_per_pr: f64 = conn.query_row(
+        &format!(
+            "SELECT AVG(commit_count)
+             FROM pull_requests
+             WHERE created_at >= ?1
+               AND merged_at IS NOT NULL
+               AND commit_count IS NOT NULL
+               AND author_id IN (SELECT id FROM users WHERE tracked = 1){}",
+            reverts.clause("id")
+        ),
+        params![period.since],
+        |row| row.get(0),
+    ).unwrap_or(0.0);
+
+    // Review coverage
+    let review_coverage_pct = get_review_coverage_pct(conn, period, &reverts, &bot_reviewers)?;
+    let reviewed_loc_pct = get_reviewed_loc_pct(conn, period, &reverts, &bot_reviewers)?;
+
     // Files per PR distribution
-    let files_per_pr_distribution = get_files_per_pr_distribution(conn, days)?;
+    let files_per_pr_distribution = get_files_per_pr_distribution(conn, period, &reverts)?;
 
     // Merge rate trend
-    let merge_rate_trend = get_merge_rate_trend(conn, 90)?; // Always show 90 days for trend
+    let merge_rate_trend = get_merge_rate_trend(conn, &Period::from_settings(90, settings), &rejection_labels_json, &reverts)?; // Always show 90 days for trend
 
     // Benchmarks
     let benchmark_comparison = QualityBenchmarks {
@@ -562,6 +1009,9 @@ fn get_quality_metrics(conn: &Connection, days: i32) -> Result<QualityMetrics> {
         feature_pr_percentage,
         avg_review_cycle_hours,
         avg_review_comments,
+        avg_commits_per_pr,
+        review_coverage_pct,
+        reviewed_loc_pct,
         pr_type_distribution,
         files_per_pr_distribution,
         merge_rate_trend,
@@ -569,37 +1019,112 @@ fn get_quality_metrics(conn: &Connection, days: i32) -> Result<QualityMetrics> {
     })
 }
 
-/// Classify PR type based on title and labels
-fn get_pr_type_distribution(conn: &Connection, days: i32) -> Result<Vec<PrTypeBreakdown>> {
-    let mut stmt = conn.prepare(
-        "SELECT
-            CASE
-                WHEN LOWER(title) LIKE '%feat%' OR LOWER(title) LIKE '%feature%'
-                     OR LOWER(title) LIKE '%add%' OR LOWER(labels) LIKE '%feature%'
-                     OR LOWER(labels) LIKE '%enhancement%'
-                THEN 'feature'
-                WHEN LOWER(title) LIKE '%fix%' OR LOWER(title) LIKE '%bug%'
-                     OR LOWER(labels) LIKE '%bug%'
-                THEN 'bug_fix'
-                WHEN LOWER(title) LIKE '%refactor%' OR LOWER(title) LIKE '%improve%'
-                THEN 'refactor'
-                WHEN LOWER(title) LIKE '%test%' OR LOWER(title) LIKE '%spec%'
-                THEN 'test'
-                WHEN LOWER(title) LIKE '%doc%' OR LOWER(labels) LIKE '%documentation%'
-                THEN 'docs'
-                ELSE 'other'
-            END as pr_type,
-            COUNT(*) as count
-         FROM pull_requests
-         WHERE created_at > datetime('now', '-' || ?1 || ' days')
-           AND author_id IN (SELECT id FROM users WHERE tracked = 1)
-         GROUP BY pr_type"
+/// Percentage of merged PRs that received at least one review from someone other than the author.
+/// Reviews by `bot_reviewers` don't count.
+fn get_review_coverage_pct(conn: &Connection, period: &Period, reverts: &RevertExclusion, bot_reviewers: &[i64]) -> Result<f64> {
+    let (merged, reviewed): (i32, i32) = conn.query_row(
+        &format!(
+            "SELECT
+                COUNT(*),
+                COUNT(CASE WHEN EXISTS (
+                    SELECT 1 FROM pr_reviews r
+                    WHERE r.pr_id = pr.id
+                      AND (r.reviewer_id IS NULL OR pr.author_id IS NULL OR r.reviewer_id != pr.author_id){}
+                ) THEN 1 END)
+             FROM pull_requests pr
+             WHERE pr.merged_at IS NOT NULL
+               AND pr.created_at >= ?1
+               AND pr.author_id IN (SELECT id FROM users WHERE tracked = 1){}",
+            queries::bot_review_filter(bot_reviewers),
+            reverts.clause("pr.id")
+        ),
+        params![period.since],
+        |row| Ok((row.get(0)?, row.get(1)?)),
     )?;
 
-    let types: Vec<(String, i32)> = stmt.query_map(params![days], |row| {
-        Ok((row.get(0)?, row.get(1)?))
-    })?
-    .collect::<Result<Vec<_>, _>>()?;
+    Ok(if merged > 0 { reviewed as f64 * 100.0 / merged as f64 } else { 0.0 })
+}
+
+/// Share of merged LOC that landed in PRs with at least one non-author review,
+/// so one huge unreviewed PR outweighs several small reviewed ones. Bot reviews don't count.
+fn get_reviewed_loc_pct(conn: &Connection, period: &Period, reverts: &RevertExclusion, bot_reviewers: &[i64]) -> Result<f64> {
+    let (merged_loc, reviewed_loc): (i64, i64) = conn.query_row(
+        &format!(
+            "SELECT
+                COALESCE(SUM(COALESCE(pr.additions, 0) + COALESCE(pr.deletions, 0)), 0),
+                COALESCE(SUM(CASE WHEN EXISTS (
+                    SELECT 1 FROM pr_reviews r
+                    WHERE r.pr_id = pr.id
+                      AND (r.reviewer_id IS NULL OR pr.author_id IS NULL OR r.reviewer_id != pr.author_id){}
+                ) THEN COALESCE(pr.additions, 0) + COALESCE(pr.deletions, 0) END), 0)
+             FROM pull_requests pr
+             WHERE pr.merged_at IS NOT NULL
+               AND pr.created_at >= ?1
+               AND pr.author_id IN (SELECT id FROM users WHERE tracked = 1){}",
+            queries::bot_review_filter(bot_reviewers),
+            reverts.clause("pr.id")
+        ),
+        params![period.since],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )?;
+
+    Ok(if merged_loc > 0 { reviewed_loc as f64 * 100.0 / merged_loc as f64 } else { 0.0 })
+}
+
+/// Classify a PR into one of `PR_TYPES`. Labels are authoritative: the first rule
+/// whose label the PR carries wins; only unmatched PRs fall back to title keywords.
+pub fn classify_pr_type(title: &str, labels: &[String], rules: &[PrTypeLabelRule]) -> String {
+    let labels: HashSet<String> = labels.iter().map(|l| normalize_label(l)).collect();
+    if let Some(rule) = rules.iter().find(|rule| labels.contains(&normalize_label(&rule.label))) {
+        return rule.pr_type.clone();
+    }
+
+    let title = title.to_lowercase();
+    let has = |keywords: &[&str]| keywords.iter().any(|k| title.contains(k));
+    let pr_type = if has(&["feat", "add"]) {
+        "feature"
+    } else if has(&["fix", "bug"]) {
+        "bug_fix"
+    } else if has(&["refactor", "improve"]) {
+        "refactor"
+    } else if has(&["test", "spec"]) {
+        "test"
+    } else if has(&["doc"]) {
+        "docs"
+    } else {
+        "other"
+    };
+    pr_type.to_string()
+}
+
+/// Get the PR type mix for the period, classified by `classify_pr_type`
+fn get_pr_type_distribution(
+    conn: &Connection,
+    period: &Period,
+    rules: &[PrTypeLabelRule],
+    reverts: &RevertExclusion,
+) -> Result<Vec<PrTypeBreakdown>> {
+    let prs = conn
+        .prepare(&format!(
+            "SELECT title, labels
+             FROM pull_requests
+             WHERE created_at >= ?1
+               AND author_id IN (SELECT id FROM users WHERE tracked = 1){}",
+            reverts.clause("id")
+        ))?
+        .query_map(params![period.since], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, Option<String>>(1)?))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut counts: BTreeMap<String, i32> = BTreeMap::new();
+    for (title, labels_json) in prs {
+        let labels: Vec<String> = labels_json
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default();
+        *counts.entry(classify_pr_type(&title, &labels, rules)).or_default() += 1;
+    }
+    let types: Vec<(String, i32)> = counts.into_iter().collect();
 
     let total: i32 = types.iter().map(|(_, count)| count).sum();
     let total_f = total as f64;
@@ -616,18 +1141,21 @@ fn get_pr_type_distribution(conn: &Connection, days: i32) -> Result<Vec<PrTypeBr
 }
 
 /// Get files per PR distribution
-fn get_files_per_pr_distribution(conn: &Connection, days: i32) -> Result<FilesPerPrDistribution> {
+fn get_files_per_pr_distribution(conn: &Connection, period: &Period, reverts: &RevertExclusion) -> Result<FilesPerPrDistribution> {
     let (range_1_3, range_4_8, range_9_15, range_16_plus, total): (i32, i32, i32, i32, i32) = conn.query_row(
-        "SELECT
-            COALESCE(SUM(CASE WHEN changed_files <= 3 THEN 1 ELSE 0 END), 0) as range_1_3,
-            COALESCE(SUM(CASE WHEN changed_files > 3 AND changed_files <= 8 THEN 1 ELSE 0 END), 0) as range_4_8,
-            COALESCE(SUM(CASE WHEN changed_files > 8 AND changed_files <= 15 THEN 1 ELSE 0 END), 0) as range_9_15,
-            COALESCE(SUM(CASE WHEN changed_files > 15 THEN 1 ELSE 0 END), 0) as range_16_plus,
-            COUNT(*) as total
-         FROM pull_requests
-         WHERE created_at > datetime('now', '-' || ?1 || ' days')
-           AND author_id IN (SELECT id FROM users WHERE tracked = 1)",
-        params![days],
+        &format!(
+            "SELECT
+                COALESCE(SUM(CASE WHEN changed_files <= 3 THEN 1 ELSE 0 END), 0) as range_1_3,
+                COALESCE(SUM(CASE WHEN changed_files > 3 AND changed_files <= 8 THEN 1 ELSE 0 END), 0) as range_4_8,
+                COALESCE(SUM(CASE WHEN changed_files > 8 AND changed_files <= 15 THEN 1 ELSE 0 END), 0) as range_9_15,
+                COALESCE(SUM(CASE WHEN changed_files > 15 THEN 1 ELSE 0 END), 0) as range_16_plus,
+                COUNT(*) as total
+             FROM pull_requests
+             WHERE created_at >= ?1
+               AND author_id IN (SELECT id FROM users WHERE tracked = 1){}",
+            reverts.clause("id")
+        ),
+        params![period.since],
         |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?)),
     )?;
 
@@ -644,9 +1172,20 @@ fn get_files_per_pr_distribution(conn: &Connection, days: i32) -> Result<FilesPe
     })
 }
 
+/// Leaves out closed-unmerged PRs carrying any label in the JSON array bound to ?2
+const NOT_REJECTED_FILTER: &str = "NOT (merged_at IS NULL AND state != 'open' AND EXISTS (
+        SELECT 1 FROM json_each(COALESCE(labels, '[]')) l, json_each(?2) r
+        WHERE LOWER(l.value) = LOWER(r.value)
+    ))";
+
 /// Get merge rate trend over time (weekly buckets)
-fn get_merge_rate_trend(conn: &Connection, days: i32) -> Result<Vec<MergeRateTrend>> {
-    let mut stmt = conn.prepare(
+fn get_merge_rate_trend(
+    conn: &Connection,
+    period: &Period,
+    rejection_labels_json: &str,
+    reverts: &RevertExclusion,
+) -> Result<Vec<MergeRateTrend>> {
+    let mut stmt = conn.prepare(&format!(
         "SELECT
             DATE(created_at, 'weekday 0', '-6 days') as week,
             (COUNT(CASE WHEN merged_at IS NOT NULL THEN 1 END) * 100.0 /
@@ -656,14 +1195,17 @@ fn get_merge_rate_trend(conn: &Connection, days: i32) -> Result<Vec<MergeRateTre
              END) as merge_rate,
             COUNT(*) as total_prs
          FROM pull_requests
-         WHERE created_at > datetime('now', '-' || ?1 || ' days')
+         WHERE created_at >= ?1
            AND author_id IN (SELECT id FROM users WHERE tracked = 1)
            AND state != 'open'
+           AND {}{}
          GROUP BY week
-         ORDER BY week"
-    )?;
+         ORDER BY week",
+        NOT_REJECTED_FILTER,
+        reverts.clause("id")
+    ))?;
 
-    let trend = stmt.query_map(params![days], |row| {
+    let trend = stmt.query_map(params![period.since, rejection_labels_json], |row| {
         Ok(MergeRateTrend {
             week: row.get(0)?,
             merge_rate: row.get(1)?,
@@ -674,3 +1216,3652 @@ fn get_merge_rate_trend(conn: &Connection, days: i32) -> Result<Vec<MergeRateTre
 
     Ok(trend)
 }
+
+// ============================================================================
+// REVERTED PRS
+// ============================================================================
+
+/// A revert PR and, when it can be resolved, the PR it reverts
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RevertPair {
+    pub revert_id: i64,
+    pub reverted_id: Option<i64>,
+}
+
+/// PR numbers and commit shas a revert body points at ("Reverts acme/api#12",
+/// "This reverts commit 1a2b3c4.")
+fn parse_revert_refs(body: &str) -> (Vec<i64>, Vec<String>) {
+    let mut numbers = Vec::new();
+    let mut shas = Vec::new();
+
+    for line in body.lines() {
+        let line = line.trim().to_lowercase();
+        if let Some(rest) = line.strip_prefix("reverts ") {
+            let digits: String = rest
+                .rsplit('#')
+                .next()
+                .unwrap_or_default()
+                .chars()
+                .take_while(|c| c.is_ascii_digit())
+                .collect();
+            if rest.contains('#') {
+                if let Ok(number) = digits.parse() {
+                    numbers.push(number);
+                }
+            }
+        }
+        if let Some(idx) = line.find("reverts commit ") {
+            let sha: String = line[idx + "reverts commit ".len()..]
+                .chars()
+                .take_while(|c| c.is_ascii_hexdigit())
+                .collect();
+            if sha.len() >= 7 {
+                shas.push(sha);
+            }
+        }
+    }
+
+    (numbers, shas)
+}
+
+/// Find revert PRs merged (or opened, if unmerged) since `since`: bodies reverting a commit
+/// known to belong to another PR, bodies with GitHub's "Reverts owner/repo#N" marker, and
+/// titles starting "Revert " whose original PR can be found by its quoted title. A
+/// "Revert ..." title alone isn't enough, since ordinary PRs get titled that way too.
+pub fn find_reverts(conn: &Connection, since: &str) -> Result<Vec<RevertPair>> {
+    let prs = conn
+        .prepare(
+            "SELECT id, repo_id, title, body FROM pull_requests
+             WHERE COALESCE(merged_at, created_at) >= ?1",
+        )?
+        .query_map(params![since], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, Option<String>>(3)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // Commits of PRs in the same window, indexed by repo and abbreviated sha
+    let mut commits: HashMap<(i64, String), Vec<(String, i64)>> = HashMap::new();
+    let mut stmt = conn.prepare(
+        "SELECT pr.repo_id, LOWER(c.oid), c.pr_id
+         FROM pr_commits c
+         JOIN pull_requests pr ON pr.id = c.pr_id
+         WHERE COALESCE(pr.merged_at, pr.created_at) >= ?1",
+    )?;
+    let rows = stmt.query_map(params![since], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?, row.get::<_, i64>(2)?)))?;
+    for row in rows {
+        let (repo_id, oid, pr_id) = row?;
+        if oid.len() >= 7 {
+            commits.entry((repo_id, oid[..7].to_string())).or_default().push((oid, pr_id));
+        }
+    }
+    let commit_pr = |repo_id: i64, sha: &str, revert_id: i64| {
+        commits
+            .get(&(repo_id, sha[..7].to_string()))?
+            .iter()
+            .find(|(oid, pr_id)| *pr_id != revert_id && oid.starts_with(sha))
+            .map(|(_, pr_id)| *pr_id)
+    };
+
+    // Reverted PRs may predate the window, so they're looked up without a date filter
+    let mut by_number = conn.prepare("SELECT id FROM pull_requests WHERE repo_id = ?1 AND number = ?2")?;
+    let mut by_title = conn.prepare("SELECT id FROM pull_requests WHERE repo_id = ?1 AND title = ?2 ORDER BY id LIMIT 1")?;
+
+    let mut pairs = Vec::new();
+    for (id, repo_id, title, body) in &prs {
+        let (numbers, shas) = parse_revert_refs(body.as_deref().unwrap_or_default());
+        let sha_target = shas.iter().find_map(|sha| commit_pr(*repo_id, sha, *id));
+        let mut marker_target = None;
+        for number in &numbers {
+            marker_target = by_number.query_row(params![repo_id, number], |row| row.get::<_, i64>(0)).optional()?;
+            if marker_target.is_some() {
+                break;
+            }
+        }
+        let title_target = match title.strip_prefix("Revert ") {
+            Some(original) => by_title
+                .query_row(params![repo_id, original.trim().trim_matches('"')], |row| row.get::<_, i64>(0))
+                .optional()?,
+            None => None,
+        };
+
+        let reverted_id = marker_target.or(sha_target).or(title_target).filter(|target| target != id);
+        if reverted_id.is_none() && numbers.is_empty() {
+            continue;
+        }
+
+        pairs.push(RevertPair { revert_id: *id, reverted_id });
+    }
+
+    Ok(pairs)
+}
+
+/// PRs left out of Speed/Quality aggregates when `exclude_reverts` is on
+#[derive(Debug, Clone, Default)]
+pub struct RevertExclusion {
+    ids: Vec<i64>,
+}
+
+impl RevertExclusion {
+    pub fn from_settings(conn: &Connection, period: &Period, settings: &Settings) -> Result<Self> {
+        if !settings.exclude_reverts {
+            return Ok(Self::default());
+        }
+
+        let mut ids: Vec<i64> = find_reverts(conn, &period.since)?
+            .into_iter()
+            .flat_map(|pair| std::iter::once(pair.revert_id).chain(pair.reverted_id))
+            .collect();
+        ids.sort_unstable();
+        ids.dedup();
+        Ok(Self { ids })
+    }
+
+    /// `AND <column> NOT IN (...)` for appending to a WHERE clause; empty when nothing is excluded
+    pub fn clause(&self, column: &str) -> String {
+        if self.ids.is_empty() {
+            return String::new();
+        }
+        let ids: Vec<String> = self.ids.iter().map(|id| id.to_string()).collect();
+        format!(" AND {} NOT IN ({})", column, ids.join(", "))
+    }
+}
+
+// ============================================================================
+// SQUAD WORK PATTERN
+// ============================================================================
+
+/// Aggregate working-hours pattern for a squad's members (for burnout monitoring)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SquadWorkPattern {
+    pub squad_id: String,
+    pub member_count: usize,
+    pub work_pattern: Vec<WorkPatternCell>,
+    #[serde(serialize_with = "serialize_rounded")]
+    pub after_hours_pct: f64, // Outside the configured core hours
+    #[serde(serialize_with = "serialize_rounded")]
+    pub weekend_pct: f64,
+}
+
+/// Get the PR-opening heatmap and after-hours/weekend shares for a squad's members.
+/// Days and hours are in the team's timezone (`utc_offset_minutes`), like the core hours.
+pub fn get_squad_work_pattern(
+    conn: &Connection,
+    squad_id: &str,
+    period: &Period,
+    settings: &Settings,
+) -> Result<SquadWorkPattern> {
+    let member_ids = queries::get_squad_member_ids(conn, squad_id)?;
+    let work_pattern = if member_ids.is_empty() {
+        Vec::new()
+    } else {
+        get_work_pattern(conn, period, settings, Some(&member_ids))?
+    };
+
+    Ok(SquadWorkPattern {
+        squad_id: squad_id.to_string(),
+        member_count: member_ids.len(),
+        after_hours_pct: after_hours_percentage(&work_pattern),
+        weekend_pct: weekend_percentage(&work_pattern),
+        work_pattern,
+    })
+}
+
+// ============================================================================
+// BLOCKED PRS
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlockedPr {
+    pub id: i64,
+    pub repo: String,
+    pub number: i32,
+    pub title: String,
+    pub author: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+    pub labels: Vec<String>,
+    pub reasons: Vec<String>, // "draft", "label:<name>", "merge_conflict"
+}
+
+/// Get open PRs updated in the period that are drafts, carry a blocking label,
+/// or have merge conflicts. Each PR lists every reason that applies.
+pub fn get_blocked_prs(conn: &Connection, period: &Period, blocking_labels: &[String]) -> Result<Vec<BlockedPr>> {
+    let mut stmt = conn.prepare(
+        "SELECT pr.id, r.owner || '/' || r.name, pr.number, pr.title, u.login,
+                pr.created_at, pr.updated_at, pr.labels, pr.is_draft, pr.mergeable
+         FROM pull_requests pr
+         JOIN repositories r ON pr.repo_id = r.id
+         LEFT JOIN users u ON pr.author_id = u.id
+         WHERE LOWER(pr.state) = 'open'
+           AND pr.updated_at >= ?1
+         ORDER BY pr.updated_at DESC"
+    )?;
+
+    let rows = stmt.query_map(params![period.since], |row| {
+        let labels_json: Option<String> = row.get(7)?;
+        let labels: Vec<String> = labels_json
+            .and_then(|json| serde_json::from_str(&json).ok())
+            .unwrap_or_default();
+        let is_draft: bool = row.get(8)?;
+        let mergeable: Option<String> = row.get(9)?;
+
+        Ok((
+            BlockedPr {
+                id: row.get(0)?,
+                repo: row.get(1)?,
+                number: row.get(2)?,
+                title: row.get(3)?,
+                author: row.get(4)?,
+                created_at: row.get(5)?,
+                updated_at: row.get(6)?,
+                labels,
+                reasons: Vec::new(),
+            },
+            is_draft,
+            mergeable,
+        ))
+    })?
+    .collect::<Result<Vec<_>, _>>()?;
+
+    let blocked = rows
+        .into_iter()
+        .filter_map(|(mut pr, is_draft, mergeable)| {
+            pr.reasons = blocking_reasons(is_draft, mergeable.as_deref(), &pr.labels, blocking_labels);
+            if pr.reasons.is_empty() {
+                None
+            } else {
+                Some(pr)
+            }
+        })
+        .collect();
+
+    Ok(blocked)
+}
+
+/// Collect every blocking reason for a PR (label matching is case-insensitive)
+fn blocking_reasons(
+    is_draft: bool,
+    mergeable: Option<&str>,
+    labels: &[String],
+    blocking_labels: &[String],
+) -> Vec<String> {
+    let mut reasons = Vec::new();
+
+    if is_draft {
+        reasons.push("draft".to_string());
+    }
+
+    for label in labels {
+        if blocking_labels.iter().any(|b| b.eq_ignore_ascii_case(label)) {
+            reasons.push(format!("label:{}", label));
+        }
+    }
+
+    if mergeable == Some("CONFLICTING") {
+        reasons.push("merge_conflict".to_string());
+    }
+
+    reasons
+}
+
+// ============================================================================
+// ABANDONED PRS
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AbandonedPr {
+    pub id: i64,
+    pub repo: String,
+    pub number: i32,
+    pub title: String,
+    pub author: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+    pub days_inactive: i32,
+}
+
+/// Get open PRs with no activity in the period whose author has also been inactive
+/// (no PRs, issues or reviews touched) over the same window
+pub fn get_abandoned_prs(conn: &Connection, period: &Period) -> Result<Vec<AbandonedPr>> {
+    let mut stmt = conn.prepare(
+        "SELECT pr.id, r.owner || '/' || r.name, pr.number, pr.title, u.login,
+                pr.created_at, pr.updated_at,
+                CAST(julianday('now') - julianday(pr.updated_at) AS INTEGER)
+         FROM pull_requests pr
+         JOIN repositories r ON pr.repo_id = r.id
+         LEFT JOIN users u ON pr.author_id = u.id
+         WHERE LOWER(pr.state) = 'open'
+           AND pr.author_id IS NOT NULL
+           AND pr.updated_at < ?1
+           AND NOT EXISTS (
+               SELECT 1 FROM pull_requests p
+               WHERE p.author_id = pr.author_id
+                 AND p.updated_at >= ?1
+           )
+           AND NOT EXISTS (
+               SELECT 1 FROM issues i
+               WHERE i.author_id = pr.author_id
+                 AND i.updated_at >= ?1
+           )
+           AND NOT EXISTS (
+               SELECT 1 FROM pr_reviews rv
+               WHERE rv.reviewer_id = pr.author_id
+                 AND rv.submitted_at >= ?1
+           )
+         ORDER BY pr.updated_at ASC"
+    )?;
+
+    let prs = stmt.query_map(params![period.since], |row| {
+        Ok(AbandonedPr {
+            id: row.get(0)?,
+            repo: row.get(1)?,
+            number: row.get(2)?,
+            title: row.get(3)?,
+            author: row.get(4)?,
+            created_at: row.get(5)?,
+            updated_at: row.get(6)?,
+            days_inactive: row.get::<_, Option<i32>>(7)?.unwrap_or(0),
+        })
+    })?
+    .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(prs)
+}
+
+// ============================================================================
+// SLOWEST PRS
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlowPr {
+    pub id: i64,
+    pub repo: String,
+    pub number: i32,
+    pub title: String,
+    pub author: Option<String>,
+    pub created_at: String,
+    pub merged_at: String,
+    pub duration_hours: f64,
+}
+
+/// Get the N merged PRs that took longest from creation to merge, honouring the dashboard
+/// filters (an explicit date range overrides `period`) and excluding bots
+pub fn get_slowest_prs(
+    conn: &Connection,
+    period: &Period,
+    limit: usize,
+    filters: &MetricsFilters,
+    excluded_bots: &[String],
+) -> Result<Vec<SlowPr>> {
+    let (since, until) = match &filters.date_range {
+        Some(range) => (range.start.clone(), Some(range.end.clone())),
+        None => (period.since.clone(), None),
+    };
+
+    let squad_member_ids = match &filters.squad_id {
+        Some(squad_id) => Some(queries::get_squad_member_ids(conn, squad_id)?),
+        None => None,
+    };
+
+    let prs = queries::get_prs_for_metrics_filtered(
+        conn,
+        &since,
+        until.as_deref(),
+        excluded_bots,
+        filters.repository_ids.as_deref(),
+        filters.user_id,
+        filters.role,
+        squad_member_ids.as_deref(),
+        filters.base_branch.as_deref(),
+        filters.visibility,
+    )?;
+
+    let repo_names: HashMap<i64, String> = conn
+        .prepare("SELECT id, owner || '/' || name FROM repositories")?
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<_, _>>()?;
+    let logins: HashMap<i64, String> = conn
+        .prepare("SELECT id, login FROM users")?
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<_, _>>()?;
+
+    let mut slowest: Vec<SlowPr> = prs
+        .into_iter()
+        .filter_map(|pr| {
+            let merged_at = pr.merged_at?;
+            let created = chrono::DateTime::parse_from_rfc3339(&pr.created_at).ok()?;
+            let merged = chrono::DateTime::parse_from_rfc3339(&merged_at).ok()?;
+            let duration_hours = ((merged - created).num_minutes() as f64 / 60.0).max(0.0);
+
+            Some(SlowPr {
+                id: pr.id,
+                repo: repo_names.get(&pr.repo_id).cloned().unwrap_or_default(),
+                number: pr.number,
+                title: pr.title,
+                author: pr.author_id.and_then(|id| logins.get(&id).cloned()),
+                created_at: pr.created_at,
+                merged_at,
+                duration_hours,
+            })
+        })
+        .collect();
+
+    slowest.sort_by(|a, b| b.duration_hours.total_cmp(&a.duration_hours));
+    slowest.truncate(limit);
+
+    Ok(slowest)
+}
+
+// ============================================================================
+// PR FUNNEL
+// ============================================================================
+
+/// Median hours PRs spend in each stage of open → first review → approved → merged.
+/// A stage's median only includes PRs that reached both ends of it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrFunnel {
+    pub prs_opened: usize,
+    pub prs_reviewed: usize,
+    pub prs_approved: usize,
+    pub prs_merged: usize,
+    pub open_to_first_review_hours: Option<f64>,
+    pub first_review_to_approval_hours: Option<f64>,
+    pub approval_to_merge_hours: Option<f64>,
+    pub open_to_merge_hours: Option<f64>,
+}
+
+/// Compute the review funnel for PRs opened in the period.
+/// Reviews by `bot_reviewers` don't count as a first review or approval.
+pub fn get_pr_funnel(
+    conn: &Connection,
+    period: &Period,
+    exclude_self_reviews: bool,
+    bot_reviewers: &[i64],
+) -> Result<PrFunnel> {
+    let query = format!(
+        "SELECT
+            (julianday(fr.first_review_at) - julianday(pr.created_at)) * 24,
+            (julianday(fr.first_approval_at) - julianday(fr.first_review_at)) * 24,
+            (julianday(pr.merged_at) - julianday(fr.first_approval_at)) * 24,
+            (julianday(pr.merged_at) - julianday(pr.created_at)) * 24
+         FROM pull_requests pr
+         LEFT JOIN (
+             SELECT r.pr_id,
+                    MIN(r.submitted_at) as first_review_at,
+                    MIN(CASE WHEN r.state = 'APPROVED' THEN r.submitted_at END) as first_approval_at
+             FROM pr_reviews r
+             JOIN pull_requests pr ON r.pr_id = pr.id
+             WHERE 1 = 1{}{}
+             GROUP BY r.pr_id
+         ) fr ON fr.pr_id = pr.id
+         WHERE pr.created_at >= ?1",
+        queries::self_review_filter(exclude_self_reviews),
+        queries::bot_review_filter(bot_reviewers)
+    );
+
+    let rows = conn
+        .prepare(&query)?
+        .query_map(params![period.since], |row| {
+            Ok((
+                row.get::<_, Option<f64>>(0)?,
+                row.get::<_, Option<f64>>(1)?,
+                row.get::<_, Option<f64>>(2)?,
+                row.get::<_, Option<f64>>(3)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // Negative spans (e.g. approvals left after merge) don't belong to the stage
+    let stage = |pick: fn(&(Option<f64>, Option<f64>, Option<f64>, Option<f64>)) -> Option<f64>| {
+        rows.iter().filter_map(pick).filter(|h| *h >= 0.0).collect::<Vec<f64>>()
+    };
+    let to_review = stage(|r| r.0);
+    let to_approval = stage(|r| r.1);
+    let to_merge = stage(|r| r.2);
+    let total = stage(|r| r.3);
+
+    Ok(PrFunnel {
+        prs_opened: rows.len(),
+        prs_reviewed: rows.iter().filter(|r| r.0.is_some()).count(),
+        prs_approved: rows.iter().filter(|r| r.1.is_some()).count(),
+        prs_merged: rows.iter().filter(|r| r.3.is_some()).count(),
+        open_to_first_review_hours: median_hours(to_review),
+        first_review_to_approval_hours: median_hours(to_approval),
+        approval_to_merge_hours: median_hours(to_merge),
+        open_to_merge_hours: median_hours(total),
+    })
+}
+
+// ============================================================================
+// REVIEW LATENCY DISTRIBUTION
+// ============================================================================
+
+/// How long PRs waited for their first review, bucketed like the cycle time distribution
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReviewLatencyDistribution {
+    pub under_1h: i32,
+    #[serde(serialize_with = "serialize_rounded")]
+    pub under_1h_pct: f64,
+    pub h1_to_4: i32,
+    #[serde(serialize_with = "serialize_rounded")]
+    pub h1_to_4_pct: f64,
+    pub h4_to_24: i32,
+    #[serde(serialize_with = "serialize_rounded")]
+    pub h4_to_24_pct: f64,
+    pub over_24h: i32,
+    #[serde(serialize_with = "serialize_rounded")]
+    pub over_24h_pct: f64,
+}
+
+/// Bucket first-review latencies for PRs opened in the period (unreviewed PRs are left out).
+/// Reviews by `bot_reviewers` don't count as a first review.
+pub fn get_review_latency_distribution(
+    conn: &Connection,
+    period: &Period,
+    exclude_self_reviews: bool,
+    bot_reviewers: &[i64],
+) -> Result<ReviewLatencyDistribution> {
+    let query = format!(
+        "SELECT MAX(0.0, (julianday(MIN(r.submitted_at)) - julianday(pr.created_at)) * 24.0)
+         FROM pull_requests pr
+         JOIN pr_reviews r ON r.pr_id = pr.id
+         WHERE pr.created_at >= ?1
+           AND julianday(r.submitted_at) IS NOT NULL{}{}
+         GROUP BY pr.id",
+        queries::self_review_filter(exclude_self_reviews),
+        queries::bot_review_filter(bot_reviewers)
+    );
+    let latencies = conn
+        .prepare(&query)?
+        .query_map(params![period.since], |row| row.get::<_, f64>(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let count = |range: std::ops::Range<f64>| latencies.iter().filter(|h| range.contains(h)).count() as i32;
+    let under_1h = count(0.0..1.0);
+    let h1_to_4 = count(1.0..4.0);
+    let h4_to_24 = count(4.0..24.0);
+    let over_24h = count(24.0..f64::INFINITY);
+
+    let total = latencies.len() as f64;
+    let pct = |n: i32| if total > 0.0 { (n as f64 / total) * 100.0 } else { 0.0 };
+    Ok(ReviewLatencyDistribution {
+        under_1h,
+        under_1h_pct: pct(under_1h),
+        h1_to_4,
+        h1_to_4_pct: pct(h1_to_4),
+        h4_to_24,
+        h4_to_24_pct: pct(h4_to_24),
+        over_24h,
+        over_24h_pct: pct(over_24h),
+    })
+}
+
+// ============================================================================
+// APPROVAL LATENCY
+// ============================================================================
+
+/// Created → first approval for one merged PR
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrApprovalLatency {
+    pub pr_id: i64,
+    pub repo: String,
+    pub number: i32,
+    pub title: String,
+    #[serde(serialize_with = "serialize_rounded")]
+    pub approval_hours: f64,
+}
+
+/// Time merged PRs waited for their first APPROVED review
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApprovalLatency {
+    pub merged_prs: usize,
+    pub approved_prs: usize,
+    pub unapproved_prs: usize, // Merged without an approval; left out of the aggregates
+    pub median_hours: Option<f64>,
+    pub p90_hours: Option<f64>,
+    pub prs: Vec<PrApprovalLatency>, // Slowest first
+}
+
+/// Nearest-rank percentile of a sorted slice
+fn percentile(sorted: &[f64], pct: f64) -> Option<f64> {
+    if sorted.is_empty() {
+        return None;
+    }
+    let rank = ((pct / 100.0) * sorted.len() as f64).ceil() as usize;
+    Some(sorted[rank.clamp(1, sorted.len()) - 1])
+}
+
+/// Created → first approval latency for PRs merged since the period start.
+/// Approvals by `bot_reviewers` are ignored.
+pub fn get_approval_latency(
+    conn: &Connection,
+    period: &Period,
+    exclude_self_reviews: bool,
+    bot_reviewers: &[i64],
+) -> Result<ApprovalLatency> {
+    let query = format!(
+        "SELECT pr.id, r.owner || '/' || r.name, pr.number, pr.title,
+                (julianday(fa.first_approval_at) - julianday(pr.created_at)) * 24
+         FROM pull_requests pr
+         JOIN repositories r ON pr.repo_id = r.id
+         LEFT JOIN (
+             SELECT r.pr_id, MIN(r.submitted_at) as first_approval_at
+             FROM pr_reviews r
+             JOIN pull_requests pr ON r.pr_id = pr.id
+             WHERE r.state = 'APPROVED'{}{}
+             GROUP BY r.pr_id
+         ) fa ON fa.pr_id = pr.id
+         WHERE pr.merged_at IS NOT NULL
+           AND pr.merged_at >= ?1",
+        queries::self_review_filter(exclude_self_reviews),
+        queries::bot_review_filter(bot_reviewers)
+    );
+    let rows = conn
+        .prepare(&query)?
+        .query_map(params![period.since], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, i32>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, Option<f64>>(4)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let merged_prs = rows.len();
+    let mut prs: Vec<PrApprovalLatency> = rows
+        .into_iter()
+        .filter_map(|(pr_id, repo, number, title, hours)| {
+            hours.map(|h| PrApprovalLatency {
+                pr_id,
+                repo,
+                number,
+                title,
+                approval_hours: h.max(0.0),
+            })
+        })
+        .collect();
+    prs.sort_by(|a, b| b.approval_hours.total_cmp(&a.approval_hours));
+
+    let mut hours: Vec<f64> = prs.iter().map(|p| p.approval_hours).collect();
+    hours.sort_by(|a, b| a.total_cmp(b));
+
+    Ok(ApprovalLatency {
+        merged_prs,
+        approved_prs: prs.len(),
+        unapproved_prs: merged_prs - prs.len(),
+        median_hours: median_hours(hours.clone()),
+        p90_hours: percentile(&hours, 90.0),
+        prs,
+    })
+}
+
+// ============================================================================
+// TRIAGE TIME BY LABEL
+// ============================================================================
+
+/// Labels with fewer responded issues than this are flagged low-confidence
+pub const MIN_TRIAGE_SAMPLES: usize = 5;
+
+/// Median time from issue creation to first human response for one label
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LabelTriageTime {
+    pub label: String,
+    pub median_hours: Option<f64>,
+    pub sample_size: usize,       // Issues with a response
+    pub awaiting_response: usize, // Issues still without one
+    pub low_confidence: bool,
+}
+
+/// Normalize a label for grouping ("Bug ", "bug" and "BUG" are the same label)
+pub fn normalize_label(label: &str) -> String {
+    label.trim().to_lowercase()
+}
+
+/// Get per-label triage times for issues opened in the period, busiest labels first
+pub fn get_triage_time_by_label(conn: &Connection, period: &Period) -> Result<Vec<LabelTriageTime>> {
+    let rows = conn
+        .prepare(
+            "SELECT labels, (julianday(first_response_at) - julianday(created_at)) * 24
+             FROM issues
+             WHERE created_at >= ?1",
+        )?
+        .query_map(params![period.since], |row| {
+            Ok((row.get::<_, Option<String>>(0)?, row.get::<_, Option<f64>>(1)?))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut by_label: HashMap<String, (Vec<f64>, usize)> = HashMap::new();
+    for (labels_json, hours) in rows {
+        let mut labels: Vec<String> = labels_json
+            .and_then(|json| serde_json::from_str::<Vec<String>>(&json).ok())
+            .unwrap_or_default()
+            .iter()
+            .map(|label| normalize_label(label))
+            .filter(|label| !label.is_empty())
+            .collect();
+        labels.sort();
+        labels.dedup();
+
+        for label in labels {
+            let entry = by_label.entry(label).or_default();
+            match hours {
+                Some(h) if h >= 0.0 => entry.0.push(h),
+                Some(_) => {}
+                None => entry.1 += 1,
+            }
+        }
+    }
+
+    let mut result: Vec<LabelTriageTime> = by_label
+        .into_iter()
+        .map(|(label, (hours, awaiting_response))| LabelTriageTime {
+            label,
+            sample_size: hours.len(),
+            awaiting_response,
+            low_confidence: hours.len() < MIN_TRIAGE_SAMPLES,
+            median_hours: median_hours(hours),
+        })
+        .collect();
+    result.sort_by(|a, b| b.sample_size.cmp(&a.sample_size).then_with(|| a.label.cmp(&b.label)));
+
+    Ok(result)
+}
+
+// ============================================================================
+// LABEL DWELL TIME
+// ============================================================================
+
+/// How long issues carried a label before it was removed (workflow labels like `in-progress`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LabelDwellTime {
+    pub label: String,
+    pub median_hours: Option<f64>,
+    pub sample_size: usize,    // Added→removed spans that ended in the period
+    pub currently_held: usize, // Issues still carrying the label
+}
+
+/// Get median label dwell times from synced label events, for spans removed since the period
+/// start. A removal without a matching earlier add (history older than the synced events) is skipped.
+pub fn get_label_dwell_times(conn: &Connection, period: &Period) -> Result<Vec<LabelDwellTime>> {
+    let mut stmt = conn.prepare(
+        "SELECT issue_id, label, action, created_at
+         FROM issue_label_events
+         ORDER BY issue_id, created_at, action",
+    )?;
+    let events = stmt
+        .query_map([], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?, row.get::<_, String>(3)?))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let since = DateTime::parse_from_rfc3339(&period.since).ok();
+    let mut held: HashMap<(i64, String), DateTime<chrono::FixedOffset>> = HashMap::new();
+    let mut spans: HashMap<String, Vec<f64>> = HashMap::new();
+    for (issue_id, label, action, created_at) in events {
+        let Ok(at) = DateTime::parse_from_rfc3339(&created_at) else {
+            continue;
+        };
+        let label = normalize_label(&label);
+        match action.as_str() {
+            "added" => {
+                held.entry((issue_id, label)).or_insert(at);
+            }
+            "removed" => {
+                if let Some(added_at) = held.remove(&(issue_id, label.clone())) {
+                    let in_period = since.map(|since| at >= since).unwrap_or(true);
+                    let entry = spans.entry(label).or_default();
+                    if in_period {
+                        entry.push((at - added_at).num_seconds() as f64 / 3600.0);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let mut currently_held: HashMap<String, usize> = HashMap::new();
+    for (_, label) in held.into_keys() {
+        *currently_held.entry(label).or_default() += 1;
+    }
+    for label in currently_held.keys() {
+        spans.entry(label.clone()).or_default();
+    }
+
+    let mut result: Vec<LabelDwellTime> = spans
+        .into_iter()
+        .map(|(label, hours)| LabelDwellTime {
+            currently_held: currently_held.get(&label).copied().unwrap_or(0),
+            sample_size: hours.len(),
+            median_hours: median_hours(hours),
+            label,
+        })
+        .collect();
+    result.sort_by(|a, b| b.sample_size.cmp(&a.sample_size).then_with(|| a.label.cmp(&b.label)));
+
+    Ok(result)
+}
+
+// ============================================================================
+// SLA BREACHES
+// ============================================================================
+
+/// An issue that missed the response or resolution target of one of its labels
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlaBreach {
+    pub issue_id: i64,
+    pub repo: String,
+    pub number: i32,
+    pub title: String,
+    pub label: String,
+    pub target: String, // "response" or "resolution"
+    pub target_hours: f64,
+    #[serde(serialize_with = "serialize_rounded")]
+    pub actual_hours: f64,
+    pub ongoing: bool, // Still unanswered/open, so the actual time keeps growing
+}
+
+/// Find issues created since the period start that breached their labels' SLA rules.
+/// An issue with no response yet (or still open) is measured up to `now`; an issue closed
+/// without a comment counts the close as its response.
+pub fn get_sla_breaches(conn: &Connection, period: &Period, rules: &[SlaRule], now: DateTime<Utc>) -> Result<Vec<SlaBreach>> {
+    if rules.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut stmt = conn.prepare(
+        "SELECT i.id, r.owner || '/' || r.name, i.number, i.title, i.labels,
+                (julianday(COALESCE(i.first_response_at, i.closed_at, ?2)) - julianday(i.created_at)) * 24,
+                i.first_response_at IS NULL AND i.closed_at IS NULL,
+                (julianday(COALESCE(i.closed_at, ?2)) - julianday(i.created_at)) * 24,
+                i.closed_at IS NULL
+         FROM issues i
+         JOIN repositories r ON i.repo_id = r.id
+         WHERE i.created_at >= ?1
+         ORDER BY i.created_at",
+    )?;
+    let now = now.format("%Y-%m-%dT%H:%M:%SZ").to_string();
+    let issues = stmt
+        .query_map(params![period.since, now], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, i32>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, Option<String>>(4)?,
+                row.get::<_, f64>(5)?,
+                row.get::<_, bool>(6)?,
+                row.get::<_, f64>(7)?,
+                row.get::<_, bool>(8)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut breaches = Vec::new();
+    for (issue_id, repo, number, title, labels, response, awaiting_response, resolution, open) in issues {
+        let labels: Vec<String> = labels
+            .and_then(|l| serde_json::from_str::<Vec<String>>(&l).ok())
+            .unwrap_or_default()
+            .iter()
+            .map(|l| normalize_label(l))
+            .collect();
+
+        for rule in rules {
+            if !labels.contains(&normalize_label(&rule.label)) {
+                continue;
+            }
+            let checks = [
+                ("response", rule.response_hours, response, awaiting_response),
+                ("resolution", rule.resolution_hours, resolution, open),
+            ];
+            for (target, target_hours, actual_hours, ongoing) in checks {
+                let Some(target_hours) = target_hours else {
+                    continue;
+                };
+                if actual_hours > target_hours {
+                    breaches.push(SlaBreach {
+                        issue_id,
+                        repo: repo.clone(),
+                        number,
+                        title: title.clone(),
+                        label: rule.label.clone(),
+                        target: target.to_string(),
+                        target_hours,
+                        actual_hours,
+                        ongoing,
+                    });
+                }
+            }
+        }
+    }
+
+    Ok(breaches)
+}
+
+// ============================================================================
+// CLOSE VELOCITY TREND
+// ============================================================================
+
+/// Issues closed in one bucket and how long they had been open
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CloseVelocityBucket {
+    pub bucket: String, // Start date of the day/week (Monday)/month
+    pub closed_issues: i32,
+    pub median_open_hours: Option<f64>,
+}
+
+/// Bucket issues closed since the period start by close date, with the median time each
+/// bucket's issues were open. Granularity is "day", "week" (default) or "month".
+pub fn get_close_velocity_trend(conn: &Connection, period: &Period, granularity: &str) -> Result<Vec<CloseVelocityBucket>> {
+    let bucket = match granularity {
+        "day" => "DATE(closed_at)",
+        "month" => "strftime('%Y-%m-01', closed_at)",
+        _ => "DATE(closed_at, 'weekday 0', '-6 days')",
+    };
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {}, (julianday(closed_at) - julianday(created_at)) * 24
+         FROM issues
+         WHERE closed_at IS NOT NULL
+           AND closed_at >= ?1",
+        bucket
+    ))?;
+    let rows = stmt
+        .query_map(params![period.since], |row| Ok((row.get::<_, String>(0)?, row.get::<_, f64>(1)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut by_bucket: std::collections::BTreeMap<String, Vec<f64>> = std::collections::BTreeMap::new();
+    for (bucket, hours) in rows {
+        by_bucket.entry(bucket).or_default().push(hours.max(0.0));
+    }
+
+    Ok(by_bucket
+        .into_iter()
+        .map(|(bucket, hours)| CloseVelocityBucket {
+            bucket,
+            closed_issues: hours.len() as i32,
+            median_open_hours: median_hours(hours),
+        })
+        .collect())
+}
+
+// ============================================================================
+// COLLABORATION INDEX
+// ============================================================================
+
+/// Share of merged PRs that had more than one distinct contributor
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CollaborationIndex {
+    pub merged_prs: usize,
+    pub collaborative_prs: usize,
+    #[serde(serialize_with = "serialize_rounded")]
+    pub index: f64, // 0.0 - 1.0
+}
+
+/// Co-authors named in `Co-authored-by:` trailers of a PR body, as lowercased
+/// (display name, identity) pairs. GitHub noreply addresses give the login as the
+/// identity; other addresses are used as-is.
+pub fn co_authors(body: &str) -> Vec<(String, String)> {
+    body.lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            let (key, value) = line.split_once(':')?;
+            if !key.trim().eq_ignore_ascii_case("co-authored-by") {
+                return None;
+            }
+            let value = value.trim();
+            let name = value.split('<').next().unwrap_or_default().trim().to_lowercase();
+            let identity = match (value.find('<'), value.rfind('>')) {
+                (Some(start), Some(end)) if start < end => {
+                    let email = value[start + 1..end].trim().to_lowercase();
+                    match email.strip_suffix("@users.noreply.github.com") {
+                        // "12345+login@..." or the older "login@..."
+                        Some(local) => local.rsplit('+').next().unwrap_or(local).to_string(),
+                        None => email,
+                    }
+                }
+                _ => value.to_lowercase(),
+            };
+            (!identity.is_empty()).then_some((name, identity))
+        })
+        .collect()
+}
+
+/// Get the share of PRs merged in the period with more than one distinct
+/// contributor (author, non-bot co-authors and non-bot reviewers). Co-authors are
+/// matched to known users by login or display name so a plain email address
+/// doesn't count the author twice.
+pub fn get_collaboration_index(conn: &Connection, period: &Period) -> Result<CollaborationIndex> {
+    let prs = conn
+        .prepare(
+            "SELECT p.id, LOWER(u.login), p.body
+             FROM pull_requests p
+             LEFT JOIN users u ON p.author_id = u.id
+             WHERE p.merged_at IS NOT NULL AND p.merged_at >= ?1",
+        )?
+        .query_map(params![period.since], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, Option<String>>(1)?,
+                row.get::<_, Option<String>>(2)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let reviews = conn
+        .prepare(
+            "SELECT DISTINCT r.pr_id, LOWER(u.login)
+             FROM pr_reviews r
+             JOIN pull_requests p ON r.pr_id = p.id
+             JOIN users u ON r.reviewer_id = u.id
+             WHERE p.merged_at IS NOT NULL AND p.merged_at >= ?1
+               AND COALESCE(u.is_bot, FALSE) = FALSE",
+        )?
+        .query_map(params![period.since], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut reviewers: HashMap<i64, Vec<String>> = HashMap::new();
+    for (pr_id, login) in reviews {
+        reviewers.entry(pr_id).or_default().push(login);
+    }
+
+    // Lowercased login or display name -> (login, is_bot); logins take precedence over names
+    let mut known_users: HashMap<String, (String, bool)> = HashMap::new();
+    let users = conn
+        .prepare("SELECT LOWER(login), LOWER(name), COALESCE(is_bot, FALSE) FROM users")?
+        .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, Option<String>>(1)?, row.get::<_, bool>(2)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+    for (login, _, is_bot) in &users {
+        known_users.insert(login.clone(), (login.clone(), *is_bot));
+    }
+    for (login, name, is_bot) in &users {
+        if let Some(name) = name {
+            known_users.entry(name.clone()).or_insert((login.clone(), *is_bot));
+        }
+    }
+    let resolve_co_author = |(name, identity): (String, String)| match known_users.get(&identity).or_else(|| known_users.get(&name)) {
+        Some((_, true)) => None,
+        Some((login, false)) => Some(login.clone()),
+        None => Some(identity),
+    };
+
+    let collaborative_prs = prs
+        .iter()
+        .filter(|(pr_id, author, body)| {
+            let mut contributors: Vec<String> = author.iter().cloned().collect();
+            contributors.extend(body.as_deref().map(co_authors).unwrap_or_default().into_iter().filter_map(resolve_co_author));
+            contributors.extend(reviewers.get(pr_id).cloned().unwrap_or_default());
+            contributors.sort();
+            contributors.dedup();
+            contributors.len() > 1
+        })
+        .count();
+
+    let merged_prs = prs.len();
+    Ok(CollaborationIndex {
+        merged_prs,
+        collaborative_prs,
+        index: if merged_prs > 0 { collaborative_prs as f64 / merged_prs as f64 } else { 0.0 },
+    })
+}
+
+// ============================================================================
+// REVIEW DEBT
+// ============================================================================
+
+/// Open PRs still waiting on their first review, and how long they've waited in total
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReviewDebt {
+    pub awaiting_review: usize,
+    #[serde(serialize_with = "serialize_rounded")]
+    pub total_wait_hours: f64,
+    pub median_wait_hours: Option<f64>,
+}
+
+/// Get open, non-draft PRs with no review from anyone but their author or `bot_reviewers`,
+/// measured against `now`
+pub fn get_review_debt(conn: &Connection, now: DateTime<Utc>, bot_reviewers: &[i64]) -> Result<ReviewDebt> {
+    let now = now.format("%Y-%m-%dT%H:%M:%SZ").to_string();
+    let waits = conn
+        .prepare(&format!(
+            "SELECT (julianday(?1) - julianday(p.created_at)) * 24
+             FROM pull_requests p
+             WHERE UPPER(p.state) = 'OPEN'
+               AND p.is_draft = FALSE
+               AND NOT EXISTS (
+                   SELECT 1 FROM pr_reviews r
+                   WHERE r.pr_id = p.id AND COALESCE(r.reviewer_id, -1) != COALESCE(p.author_id, -1){}
+               )",
+            queries::bot_review_filter(bot_reviewers)
+        ))?
+        .query_map(params![now], |row| row.get::<_, f64>(0))?
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .map(|hours| hours.max(0.0))
+        .collect::<Vec<_>>();
+
+    Ok(ReviewDebt {
+        awaiting_review: waits.len(),
+        total_wait_hours: waits.iter().sum(),
+        median_wait_hours: median_hours(waits),
+    })
+}
+
+/// Median of a set of durations, rounded to one decimal
+fn median_hours(mut values: Vec<f64>) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_by(|a, b| a.total_cmp(b));
+    let mid = values.len() / 2;
+    let median = if values.len() % 2 == 0 {
+        (values[mid - 1] + values[mid]) / 2.0
+    } else {
+        values[mid]
+    };
+    Some((median * 10.0).round() / 10.0)
+}
+
+// ============================================================================
+// PING-PONG INDEX
+// ============================================================================
+
+/// Review back-and-forth on merged PRs: rounds where a review was followed by another push
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PingPongIndex {
+    pub merged_prs: usize, // Merged PRs with stored commits (others can't be ordered)
+    pub median_rounds: Option<f64>,
+    pub max_rounds: i32,
+    pub prs_with_ping_pong: usize, // PRs with at least one review→push round
+}
+
+/// Count review→push rounds in a time-ordered event list (`true` = non-author review, `false` = push)
+pub fn ping_pong_rounds(events: &[bool]) -> i32 {
+    events
+        .windows(2)
+        .filter(|pair| pair[0] && !pair[1])
+        .count() as i32
+}
+
+/// Get the ping-pong index for tracked users' PRs merged since the period start
+pub fn get_ping_pong_index(conn: &Connection, period: &Period) -> Result<PingPongIndex> {
+    let mut stmt = conn.prepare(
+        "WITH merged AS (
+             SELECT id, author_id FROM pull_requests
+             WHERE merged_at IS NOT NULL
+               AND merged_at >= ?1
+               AND author_id IN (SELECT id FROM users WHERE tracked = 1)
+               AND EXISTS (SELECT 1 FROM pr_commits c WHERE c.pr_id = pull_requests.id)
+         )
+         SELECT c.pr_id, c.committed_at AS at, 0 AS is_review
+         FROM pr_commits c JOIN merged m ON c.pr_id = m.id
+         UNION ALL
+         SELECT r.pr_id, r.submitted_at, 1
+         FROM pr_reviews r JOIN merged m ON r.pr_id = m.id
+         WHERE COALESCE(r.reviewer_id, -1) != COALESCE(m.author_id, -1)
+         ORDER BY 1, 2, 3",
+    )?;
+    let events = stmt
+        .query_map(params![period.since], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, bool>(2)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut rounds = Vec::new();
+    let mut start = 0;
+    while start < events.len() {
+        let pr_id = events[start].0;
+        let end = events[start..]
+            .iter()
+            .position(|(id, _)| *id != pr_id)
+            .map(|offset| start + offset)
+            .unwrap_or(events.len());
+        let kinds: Vec<bool> = events[start..end].iter().map(|(_, is_review)| *is_review).collect();
+        rounds.push(ping_pong_rounds(&kinds));
+        start = end;
+    }
+
+    Ok(PingPongIndex {
+        merged_prs: rounds.len(),
+        max_rounds: rounds.iter().copied().max().unwrap_or(0),
+        prs_with_ping_pong: rounds.iter().filter(|r| **r > 0).count(),
+        median_rounds: median_hours(rounds.iter().map(|r| *r as f64).collect()),
+    })
+}
+
+// ============================================================================
+// REVIEW BALANCE
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReviewerLoad {
+    pub login: String,
+    pub prs_reviewed: i32,
+}
+
+/// How evenly review work is spread across reviewers
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReviewBalance {
+    #[serde(serialize_with = "serialize_rounded")]
+    pub inequality: f64, // Normalized Gini: 0 = perfectly even, 1 = one person does all reviews
+    pub total_reviews: i32,
+    pub reviewers: Vec<ReviewerLoad>, // Busiest first; tracked users with no reviews are included at 0
+}
+
+/// Gini coefficient scaled by n/(n-1) so a single person carrying all the load scores 1
+pub fn normalized_gini(values: &[i32]) -> f64 {
+    let total: i64 = values.iter().map(|v| *v as i64).sum();
+    if total <= 0 {
+        return 0.0;
+    }
+    let n = values.len() as f64;
+    if values.len() == 1 {
+        return 1.0;
+    }
+
+    let mut sorted = values.to_vec();
+    sorted.sort_unstable();
+    let weighted: f64 = sorted.iter().enumerate().map(|(i, v)| (i as f64 + 1.0) * *v as f64).sum();
+    let gini = 2.0 * weighted / (n * total as f64) - (n + 1.0) / n;
+    (gini * n / (n - 1.0)).clamp(0.0, 1.0)
+}
+
+/// Inequality of distinct PRs reviewed per person for reviews submitted since the period start.
+/// Self-reviews and bots are left out; tracked users who reviewed nothing count as zero.
+pub fn get_review_balance(conn: &Connection, period: &Period, excluded_bots: &[String]) -> Result<ReviewBalance> {
+    let mut stmt = conn.prepare(
+        "SELECT u.login, COUNT(DISTINCT r.pr_id)
+         FROM pr_reviews r
+         JOIN pull_requests pr ON r.pr_id = pr.id
+         JOIN users u ON r.reviewer_id = u.id
+         WHERE r.submitted_at >= ?1
+           AND (pr.author_id IS NULL OR r.reviewer_id != pr.author_id)
+           AND COALESCE(u.is_bot, FALSE) = FALSE
+         GROUP BY u.id
+         UNION ALL
+         SELECT u.login, 0
+         FROM users u
+         WHERE u.tracked = 1
+           AND COALESCE(u.is_bot, FALSE) = FALSE
+           AND NOT EXISTS (
+               SELECT 1 FROM pr_reviews r
+               JOIN pull_requests pr ON r.pr_id = pr.id
+               WHERE r.reviewer_id = u.id
+                 AND r.submitted_at >= ?1
+                 AND (pr.author_id IS NULL OR r.reviewer_id != pr.author_id)
+           )
+         ORDER BY 2 DESC, 1",
+    )?;
+    let reviewers = stmt
+        .query_map(params![period.since], |row| {
+            Ok(ReviewerLoad {
+                login: row.get(0)?,
+                prs_reviewed: row.get(1)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .filter(|r| !queries::is_bot_user(&r.login, excluded_bots))
+        .collect::<Vec<_>>();
+
+    let counts: Vec<i32> = reviewers.iter().map(|r| r.prs_reviewed).collect();
+    Ok(ReviewBalance {
+        inequality: normalized_gini(&counts),
+        total_reviews: counts.iter().sum(),
+        reviewers,
+    })
+}
+
+// ============================================================================
+// INTERNAL VS EXTERNAL CONTRIBUTIONS
+// ============================================================================
+
+/// Merged PRs split between the core team and outside contributors
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContributionSplit {
+    pub merged_prs: i32,
+    pub internal_prs: i32,
+    #[serde(serialize_with = "serialize_rounded")]
+    pub internal_pct: f64,
+    pub external_prs: i32,
+    #[serde(serialize_with = "serialize_rounded")]
+    pub external_pct: f64,
+    pub unknown_prs: i32, // Org repo PRs synced without an author association (REST/CLI fallback, older syncs)
+    #[serde(serialize_with = "serialize_rounded")]
+    pub unknown_pct: f64,
+    pub internal_contributors: usize,
+    pub external_contributors: usize,
+}
+
+/// Whether a PR author counts as core team: listed in internal_logins, or a member/owner of
+/// the repository's org when that org is listed in internal_orgs
+pub fn is_internal_author(login: Option<&str>, repo_owner: &str, author_association: Option<&str>, settings: &Settings) -> bool {
+    let listed = |names: &[String], name: &str| names.iter().any(|n| n.eq_ignore_ascii_case(name));
+
+    if login.is_some_and(|login| listed(&settings.internal_logins, login)) {
+        return true;
+    }
+    listed(&settings.internal_orgs, repo_owner) && matches!(author_association, Some("MEMBER") | Some("OWNER"))
+}
+
+/// Split PRs merged since the period start into internal vs external, leaving bots out.
+/// PRs whose author account was deleted count as external. PRs in an internal org's repos
+/// with no recorded author association can't be classified and are counted as unknown.
+pub fn get_contribution_split(conn: &Connection, period: &Period, settings: &Settings) -> Result<ContributionSplit> {
+    let mut stmt = conn.prepare(
+        "SELECT u.login, r.owner, pr.author_association
+         FROM pull_requests pr
+         JOIN repositories r ON pr.repo_id = r.id
+         LEFT JOIN users u ON pr.author_id = u.id
+         WHERE pr.merged_at IS NOT NULL
+           AND pr.merged_at >= ?1
+           AND COALESCE(u.is_bot, FALSE) = FALSE",
+    )?;
+    let rows = stmt
+        .query_map(params![period.since], |row| {
+            Ok((row.get::<_, Option<String>>(0)?, row.get::<_, String>(1)?, row.get::<_, Option<String>>(2)?))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut internal_prs = 0;
+    let mut external_prs = 0;
+    let mut unknown_prs = 0;
+    let mut internal_authors = HashSet::new();
+    let mut external_authors = HashSet::new();
+    for (login, owner, association) in &rows {
+        if login.as_deref().is_some_and(|login| queries::is_bot_user(login, &settings.excluded_bots)) {
+            continue;
+        }
+        if is_internal_author(login.as_deref(), owner, association.as_deref(), settings) {
+            internal_prs += 1;
+            internal_authors.insert(login.clone());
+        } else if association.is_none() && settings.internal_orgs.iter().any(|org| org.eq_ignore_ascii_case(owner)) {
+            unknown_prs += 1;
+        } else {
+            external_prs += 1;
+            external_authors.insert(login.clone());
+        }
+    }
+
+    let merged_prs = internal_prs + external_prs + unknown_prs;
+    let pct = |n: i32| if merged_prs > 0 { n as f64 * 100.0 / merged_prs as f64 } else { 0.0 };
+    Ok(ContributionSplit {
+        merged_prs,
+        internal_prs,
+        internal_pct: pct(internal_prs),
+        external_prs,
+        external_pct: pct(external_prs),
+        unknown_prs,
+        unknown_pct: pct(unknown_prs),
+        internal_contributors: internal_authors.len(),
+        external_contributors: external_authors.len(),
+    })
+}
+
+// ============================================================================
+// PER-CAPITA VELOCITY
+// ============================================================================
+
+/// Merged PRs and LOC divided by team headcount
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PerCapitaVelocity {
+    pub headcount_basis: String, // "active" or "tracked"
+    pub headcount: i32,
+    pub merged_prs: i32,
+    pub merged_loc: i64,
+    #[serde(serialize_with = "serialize_rounded")]
+    pub prs_per_capita: f64,
+    #[serde(serialize_with = "serialize_rounded")]
+    pub loc_per_capita: f64,
+}
+
+/// Per-capita throughput of tracked users' PRs merged since the period start. Headcount is
+/// either the active developers in the window or every tracked user; both are 0-safe.
+pub fn get_per_capita_velocity(conn: &Connection, period: &Period, headcount_basis: &str, min_active_prs: i32) -> Result<PerCapitaVelocity> {
+    let (merged_prs, merged_loc): (i32, i64) = conn.query_row(
+        "SELECT COUNT(*), COALESCE(SUM(COALESCE(additions, 0) + COALESCE(deletions, 0)), 0)
+         FROM pull_requests
+         WHERE merged_at IS NOT NULL
+           AND merged_at >= ?1
+           AND author_id IN (SELECT id FROM users WHERE tracked = 1)",
+        params![period.since],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )?;
+
+    let headcount: i32 = match headcount_basis {
+        "tracked" => conn.query_row("SELECT COUNT(*) FROM users WHERE tracked = 1", [], |row| row.get(0))?,
+        "active" => count_active_developers(conn, period, min_active_prs)?,
+        other => return Err(anyhow::anyhow!("Invalid headcount_basis '{}' (expected 'active' or 'tracked')", other)),
+    };
+
+    let per_capita = |total: f64| if headcount > 0 { total / headcount as f64 } else { 0.0 };
+    Ok(PerCapitaVelocity {
+        headcount_basis: headcount_basis.to_string(),
+        headcount,
+        merged_prs,
+        merged_loc,
+        prs_per_capita: per_capita(merged_prs as f64),
+        loc_per_capita: per_capita(merged_loc as f64),
+    })
+}
+
+// ============================================================================
+// ORG BUS FACTOR
+// ============================================================================
+
+/// Share of merged PRs the bus-factor contributors must cover
+pub const BUS_FACTOR_COVERAGE: f64 = 0.5;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BusFactorContributor {
+    pub login: String,
+    pub merged_prs: i32,
+    #[serde(serialize_with = "serialize_rounded")]
+    pub share_pct: f64,
+}
+
+/// How few people account for half the merged work across all enabled repositories
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrgBusFactor {
+    pub bus_factor: usize,
+    pub merged_prs: i32,
+    pub contributors: usize,
+    pub repos: usize,
+    pub key_contributors: Vec<BusFactorContributor>, // The bus_factor contributors, most PRs first
+}
+
+/// Minimum number of contributors whose counts cover `coverage` of the total.
+/// Counts must be sorted descending; returns 0 when there is no work at all.
+pub fn bus_factor(sorted_counts: &[i32], coverage: f64) -> usize {
+    let total: i32 = sorted_counts.iter().sum();
+    if total <= 0 {
+        return 0;
+    }
+
+    let mut covered = 0;
+    for (i, count) in sorted_counts.iter().enumerate() {
+        covered += count;
+        if covered as f64 >= total as f64 * coverage {
+            return i + 1;
+        }
+    }
+    sorted_counts.len()
+}
+
+/// Bus factor over PRs merged since the period start in enabled repositories, bots excluded
+pub fn get_org_bus_factor(conn: &Connection, period: &Period, settings: &Settings) -> Result<OrgBusFactor> {
+    let mut stmt = conn.prepare(
+        "SELECT u.login, COUNT(*)
+         FROM pull_requests pr
+         JOIN repositories r ON pr.repo_id = r.id
+         JOIN users u ON pr.author_id = u.id
+         WHERE pr.merged_at IS NOT NULL
+           AND pr.merged_at >= ?1
+           AND r.enabled = TRUE
+           AND COALESCE(u.is_bot, FALSE) = FALSE
+         GROUP BY u.id
+         ORDER BY COUNT(*) DESC, u.login",
+    )?;
+    let authors = stmt
+        .query_map(params![period.since], |row| Ok((row.get::<_, String>(0)?, row.get::<_, i32>(1)?)))?
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
+        .filter(|(login, _)| !queries::is_bot_user(login, &settings.excluded_bots))
+        .collect::<Vec<_>>();
+
+    let repos: usize = conn.query_row(
+        "SELECT COUNT(DISTINCT pr.repo_id)
+         FROM pull_requests pr
+         JOIN repositories r ON pr.repo_id = r.id
+         WHERE pr.merged_at IS NOT NULL AND pr.merged_at >= ?1 AND r.enabled = TRUE",
+        params![period.since],
+        |row| row.get(0),
+    )?;
+
+    let counts: Vec<i32> = authors.iter().map(|(_, n)| *n).collect();
+    let merged_prs: i32 = counts.iter().sum();
+    let factor = bus_factor(&counts, BUS_FACTOR_COVERAGE);
+    let key_contributors = authors
+        .into_iter()
+        .take(factor)
+        .map(|(login, n)| BusFactorContributor {
+            login,
+            merged_prs: n,
+            share_pct: n as f64 * 100.0 / merged_prs as f64,
+        })
+        .collect();
+
+    Ok(OrgBusFactor {
+        bus_factor: factor,
+        merged_prs,
+        contributors: counts.len(),
+        repos,
+        key_contributors,
+    })
+}
+
+// ============================================================================
+// CONTRIBUTION BY TENURE
+// ============================================================================
+
+/// Tenure tiers as (name, minimum weeks since the author's first merged PR), most junior first
+pub const TENURE_TIERS: [(&str, i64); 3] = [("new", 0), ("established", 12), ("veteran", 52)];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TenureTier {
+    pub tier: String,
+    pub min_weeks: i64,
+    pub merged_prs: i32,
+    #[serde(serialize_with = "serialize_rounded")]
+    pub merged_pct: f64,
+    pub contributors: usize,
+}
+
+/// Name of the tier for an author `weeks` after their first merged PR
+pub fn tenure_tier(weeks: i64) -> &'static str {
+    TENURE_TIERS
+        .iter()
+        .rev()
+        .find(|(_, min_weeks)| weeks >= *min_weeks)
+        .map(|(tier, _)| *tier)
+        .unwrap_or(TENURE_TIERS[0].0)
+}
+
+/// Attribute PRs merged since the period start to tenure tiers, using the author's
+/// tenure (weeks since their first merged PR anywhere) at the time each PR merged
+pub fn get_contribution_by_tenure(conn: &Connection, period: &Period, excluded_bots: &[String]) -> Result<Vec<TenureTier>> {
+    let mut stmt = conn.prepare(
+        "SELECT u.id, u.login, CAST((julianday(pr.merged_at) - julianday(fm.first_merged_at)) / 7 AS INTEGER)
+         FROM pull_requests pr
+         JOIN users u ON pr.author_id = u.id
+         JOIN (
+             SELECT author_id, MIN(merged_at) as first_merged_at
+             FROM pull_requests
+             WHERE merged_at IS NOT NULL
+             GROUP BY author_id
+         ) fm ON fm.author_id = pr.author_id
+         WHERE pr.merged_at IS NOT NULL
+           AND pr.merged_at >= ?1
+           AND COALESCE(u.is_bot, FALSE) = FALSE",
+    )?;
+    let rows = stmt
+        .query_map(params![period.since], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?, row.get::<_, i64>(2)?))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut counts: HashMap<&str, (i32, HashSet<i64>)> = HashMap::new();
+    let mut total = 0;
+    for (user_id, login, weeks) in &rows {
+        if queries::is_bot_user(login, excluded_bots) {
+            continue;
+        }
+        let entry = counts.entry(tenure_tier(*weeks)).or_default();
+        entry.0 += 1;
+        entry.1.insert(*user_id);
+        total += 1;
+    }
+
+    Ok(TENURE_TIERS
+        .iter()
+        .map(|(tier, min_weeks)| {
+            let (merged_prs, contributors) = counts.remove(tier).unwrap_or_default();
+            TenureTier {
+                tier: tier.to_string(),
+                min_weeks: *min_weeks,
+                merged_prs,
+                merged_pct: if total > 0 { merged_prs as f64 * 100.0 / total as f64 } else { 0.0 },
+                contributors: contributors.len(),
+            }
+        })
+        .collect())
+}
+
+// ============================================================================
+// PLANNED VS UNPLANNED WORK
+// ============================================================================
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PlannedWorkSplit {
+    pub planned_issues: i32,
+    pub unplanned_issues: i32,
+    pub planned_prs: i32,
+    pub unplanned_prs: i32,
+    pub planned: i32,
+    pub unplanned: i32,
+    #[serde(serialize_with = "serialize_rounded")]
+    pub planned_pct: f64,
+    #[serde(serialize_with = "serialize_rounded")]
+    pub unplanned_pct: f64,
+}
+
+/// Whether a JSON label array carries any of the (normalized) planning labels
+fn has_planning_label(labels_json: Option<&str>, planning_labels: &HashSet<String>) -> bool {
+    labels_json
+        .and_then(|json| serde_json::from_str::<Vec<String>>(json).ok())
+        .unwrap_or_default()
+        .iter()
+        .any(|label| planning_labels.contains(&normalize_label(label)))
+}
+
+/// Split issues and tracked authors' PRs opened in the period into planned and unplanned
+/// work. Issues are planned when they have a milestone or a planning label; PRs when they
+/// carry a planning label or link to a planned issue.
+pub fn get_planned_vs_unplanned(conn: &Connection, period: &Period, planning_labels: &[String]) -> Result<PlannedWorkSplit> {
+    let planning_labels: HashSet<String> = planning_labels.iter().map(|l| normalize_label(l)).collect();
+
+    let issues = conn
+        .prepare("SELECT repo_id, number, milestone_id IS NOT NULL, labels, created_at >= ?1 FROM issues")?
+        .query_map(params![period.since], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, bool>(2)?,
+                row.get::<_, Option<String>>(3)?,
+                row.get::<_, bool>(4)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut split = PlannedWorkSplit::default();
+    let mut planned_issues: HashSet<(i64, i64)> = HashSet::new();
+    for (repo_id, number, has_milestone, labels, in_period) in issues {
+        let planned = has_milestone || has_planning_label(labels.as_deref(), &planning_labels);
+        if planned {
+            planned_issues.insert((repo_id, number));
+        }
+        if in_period {
+            if planned {
+                split.planned_issues += 1;
+            } else {
+                split.unplanned_issues += 1;
+            }
+        }
+    }
+
+    let prs = conn
+        .prepare(
+            "SELECT pr.id, pr.repo_id, pr.labels, l.issue_number
+             FROM pull_requests pr
+             LEFT JOIN pr_issue_links l ON l.pr_id = pr.id
+             WHERE pr.created_at >= ?1
+               AND pr.author_id IN (SELECT id FROM users WHERE tracked = 1)",
+        )?
+        .query_map(params![period.since], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, Option<String>>(2)?,
+                row.get::<_, Option<i64>>(3)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // A PR appears once per linked issue; it's planned if any of those rows say so
+    let mut pr_planned: HashMap<i64, bool> = HashMap::new();
+    for (pr_id, repo_id, labels, issue_number) in prs {
+        let planned = has_planning_label(labels.as_deref(), &planning_labels)
+            || issue_number.is_some_and(|number| planned_issues.contains(&(repo_id, number)));
+        *pr_planned.entry(pr_id).or_default() |= planned;
+    }
+    split.planned_prs = pr_planned.values().filter(|planned| **planned).count() as i32;
+    split.unplanned_prs = pr_planned.len() as i32 - split.planned_prs;
+
+    split.planned = split.planned_issues + split.planned_prs;
+    split.unplanned = split.unplanned_issues + split.unplanned_prs;
+    let total = split.planned + split.unplanned;
+    if total > 0 {
+        split.planned_pct = split.planned as f64 * 100.0 / total as f64;
+        split.unplanned_pct = split.unplanned as f64 * 100.0 / total as f64;
+    }
+
+    Ok(split)
+}
+
+// ============================================================================
+// REVIEW BYPASSES
+// ============================================================================
+
+/// A merged PR with no approval from anyone but its author
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReviewBypass {
+    pub id: i64,
+    pub repo: String,
+    pub number: i32,
+    pub title: String,
+    pub author: Option<String>,
+    pub author_is_bot: bool,
+    pub merged_at: String,
+    pub merge_method: Option<String>, // merge_queue, auto_merge or manual; admin overrides aren't synced
+    pub review_count: i32,            // Non-author reviews of any state
+    pub self_approved: bool,
+}
+
+/// Merged PRs in the period without a single non-author, non-bot approval, newest first, for
+/// governance audits. Bot-authored PRs are included (and flagged) since their merges need review too.
+pub fn get_review_bypasses(conn: &Connection, period: &Period, bot_users: &[i64]) -> Result<Vec<ReviewBypass>> {
+    let bot_filter = queries::bot_review_filter(bot_users);
+    let mut stmt = conn.prepare(&format!(
+        "SELECT pr.id, repo.owner || '/' || repo.name, pr.number, pr.title, u.login, pr.author_id,
+                pr.merged_at, pr.merge_method,
+                (SELECT COUNT(*) FROM pr_reviews r
+                 WHERE r.pr_id = pr.id
+                   AND (pr.author_id IS NULL OR r.reviewer_id IS NULL OR r.reviewer_id != pr.author_id){0}),
+                EXISTS (SELECT 1 FROM pr_reviews r
+                        WHERE r.pr_id = pr.id AND r.state = 'APPROVED' AND r.reviewer_id = pr.author_id)
+         FROM pull_requests pr
+         JOIN repositories repo ON pr.repo_id = repo.id
+         LEFT JOIN users u ON pr.author_id = u.id
+         WHERE pr.merged_at IS NOT NULL
+           AND pr.merged_at >= ?1
+           AND NOT EXISTS (
+               SELECT 1 FROM pr_reviews r
+               WHERE r.pr_id = pr.id
+                 AND r.state = 'APPROVED'
+                 AND (pr.author_id IS NULL OR r.reviewer_id IS NULL OR r.reviewer_id != pr.author_id){0}
+           )
+         ORDER BY pr.merged_at DESC, pr.id DESC",
+        bot_filter
+    ))?;
+
+    let bypasses = stmt
+        .query_map(params![period.since], |row| {
+            let author_id: Option<i64> = row.get(5)?;
+            Ok(ReviewBypass {
+                id: row.get(0)?,
+                repo: row.get(1)?,
+                number: row.get(2)?,
+                title: row.get(3)?,
+                author: row.get(4)?,
+                author_is_bot: author_id.is_some_and(|id| bot_users.contains(&id)),
+                merged_at: row.get(6)?,
+                merge_method: row.get(7)?,
+                review_count: row.get(8)?,
+                self_approved: row.get(9)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(bypasses)
+}
+
+// ============================================================================
+// THROUGHPUT BY WEEKDAY
+// ============================================================================
+
+const WEEKDAY_NAMES: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WeekdayThroughput {
+    pub weekday: String, // "Mon".."Sun"
+    pub merged_prs: i64,
+}
+
+/// Merged PRs in the period per local day of the week, Monday first. Merge times are
+/// shifted by the fixed `utc_offset_minutes` before bucketing, so DST isn't followed.
+pub fn get_throughput_by_weekday(
+    conn: &Connection,
+    period: &Period,
+    utc_offset_minutes: i32,
+    reverts: &RevertExclusion,
+) -> Result<Vec<WeekdayThroughput>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT CAST(strftime('%w', merged_at, ?2) AS INTEGER) AS weekday, COUNT(*)
+         FROM pull_requests
+         WHERE merged_at IS NOT NULL
+           AND merged_at >= ?1{}
+         GROUP BY weekday",
+        reverts.clause("id")
+    ))?;
+    let shift = utc_offset_modifier(utc_offset_minutes);
+    let rows = stmt
+        .query_map(params![period.since, shift], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut counts = [0i64; 7];
+    for (weekday, count) in rows {
+        // strftime('%w') counts from Sunday = 0
+        counts[((weekday + 6) % 7) as usize] += count;
+    }
+
+    Ok(WEEKDAY_NAMES
+        .iter()
+        .zip(counts)
+        .map(|(name, merged_prs)| WeekdayThroughput { weekday: name.to_string(), merged_prs })
+        .collect())
+}
+
+// ============================================================================
+// VOLUME FORECAST
+// ============================================================================
+
+/// Weeks of history the forecast is fitted on
+const FORECAST_HISTORY_WEEKS: i64 = 12;
+/// Below this many weeks of history the forecast is flagged as low confidence
+const MIN_FORECAST_HISTORY_WEEKS: i64 = 4;
+/// z-score for the ~95% band around the point forecast
+const FORECAST_BAND_Z: f64 = 1.96;
+
+/// Projected item count for the forecast horizon with a naive confidence band
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SeriesForecast {
+    #[serde(serialize_with = "serialize_rounded")]
+    pub forecast: f64,
+    #[serde(serialize_with = "serialize_rounded")]
+    pub lower: f64,
+    #[serde(serialize_with = "serialize_rounded")]
+    pub upper: f64,
+    pub weekly_history: Vec<i64>, // Oldest week first
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VolumeForecast {
+    pub days_ahead: i32,
+    pub history_weeks: i64,
+    pub low_confidence: bool, // Too little history for the trend to mean much
+    pub prs: SeriesForecast,
+    pub issues: SeriesForecast,
+}
+
+/// Forecast PR and issue volume for the next `days_ahead` days by fitting a linear
+/// trend to weekly counts ending at `now`
+pub fn forecast_volume(conn: &Connection, days_ahead: i32, now: DateTime<Utc>) -> Result<VolumeForecast> {
+    let now_str = now.format("%Y-%m-%dT%H:%M:%SZ").to_string();
+
+    // Only fit on weeks since the first item, so an empty past doesn't read as a rising trend
+    let earliest: Option<String> = conn.query_row(
+        "SELECT MIN(created_at) FROM (
+             SELECT created_at FROM pull_requests
+             UNION ALL
+             SELECT created_at FROM issues
+         )",
+        [],
+        |row| row.get(0),
+    )?;
+    let history_weeks = earliest
+        .and_then(|ts| DateTime::parse_from_rfc3339(&ts).ok())
+        .map(|first| (now - first.with_timezone(&Utc)).num_days() / 7 + 1)
+        .unwrap_or(0)
+        .clamp(0, FORECAST_HISTORY_WEEKS);
+
+    let weeks_ahead = f64::from(days_ahead.max(0)) / 7.0;
+    let prs = weekly_counts(conn, "pull_requests", &now_str, history_weeks)?;
+    let issues = weekly_counts(conn, "issues", &now_str, history_weeks)?;
+
+    Ok(VolumeForecast {
+        days_ahead,
+        history_weeks,
+        low_confidence: history_weeks < MIN_FORECAST_HISTORY_WEEKS,
+        prs: linear_forecast(prs, weeks_ahead),
+        issues: linear_forecast(issues, weeks_ahead),
+    })
+}
+
+/// Items created in each of the last `weeks` full weeks before `now`, oldest first
+fn weekly_counts(conn: &Connection, table: &str, now: &str, weeks: i64) -> Result<Vec<i64>> {
+    let mut counts = vec![0; weeks as usize];
+    let mut stmt = conn.prepare(&format!(
+        "SELECT CAST((julianday(?1) - julianday(created_at)) / 7 AS INTEGER) AS weeks_ago, COUNT(*)
+         FROM {}
+         WHERE created_at <= ?1
+         GROUP BY weeks_ago",
+        table
+    ))?;
+    let rows = stmt
+        .query_map(params![now], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    for (weeks_ago, count) in rows {
+        if (0..weeks).contains(&weeks_ago) {
+            counts[(weeks - 1 - weeks_ago) as usize] = count;
+        }
+    }
+    Ok(counts)
+}
+
+/// Least-squares trend over weekly counts, summed over the next `weeks_ahead` weeks.
+/// The band widens with the residual spread and the horizon.
+fn linear_forecast(weekly_history: Vec<i64>, weeks_ahead: f64) -> SeriesForecast {
+    let n = weekly_history.len();
+    let ys: Vec<f64> = weekly_history.iter().map(|&c| c as f64).collect();
+
+    let (intercept, slope) = match n {
+        0 => (0.0, 0.0),
+        1 => (ys[0], 0.0),
+        _ => {
+            let mean_x = (n - 1) as f64 / 2.0;
+            let mean_y = ys.iter().sum::<f64>() / n as f64;
+            let (mut cov, mut var) = (0.0, 0.0);
+            for (x, y) in ys.iter().enumerate() {
+                let dx = x as f64 - mean_x;
+                cov += dx * (y - mean_y);
+                var += dx * dx;
+            }
+            let slope = cov / var;
+            (mean_y - slope * mean_x, slope)
+        }
+    };
+
+    let residual_sd = if n > 2 {
+        let sse: f64 = ys
+            .iter()
+            .enumerate()
+            .map(|(x, y)| (y - (intercept + slope * x as f64)).powi(2))
+            .sum();
+        (sse / (n - 2) as f64).sqrt()
+    } else {
+        0.0
+    };
+
+    // Sum of the fitted weekly rate across the horizon, taken at its midpoint
+    let midpoint = n as f64 - 1.0 + (weeks_ahead + 1.0) / 2.0;
+    let forecast = (weeks_ahead * (intercept + slope * midpoint)).max(0.0);
+    let spread = FORECAST_BAND_Z * residual_sd * weeks_ahead.sqrt();
+
+    SeriesForecast {
+        forecast,
+        lower: (forecast - spread).max(0.0),
+        upper: forecast + spread,
+        weekly_history,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rolling(days: i32) -> Period {
+        Period::new(days, "rolling", Utc::now())
+    }
+
+    fn setup_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::db::migrations::run_migrations(&conn).unwrap();
+        conn.execute("INSERT INTO repositories (id, owner, name) VALUES (1, 'acme', 'api')", [])
+            .unwrap();
+        conn
+    }
+
+    fn insert_pr(conn: &Connection, number: i32, state: &str, labels: &str, is_draft: bool, mergeable: Option<&str>) {
+        conn.execute(
+            "INSERT INTO pull_requests (github_id, repo_id, number, title, state, created_at, updated_at,
+                                        labels, is_draft, mergeable)
+             VALUES (?1, 1, ?1, 'PR', ?2, datetime('now', '-2 days'), datetime('now', '-1 days'), ?3, ?4, ?5)",
+            params![number, state, labels, is_draft, mergeable],
+        )
+        .unwrap();
+    }
+
+    fn reasons_for(blocked: &[BlockedPr], number: i32) -> Vec<String> {
+        blocked
+            .iter()
+            .find(|pr| pr.number == number)
+            .map(|pr| pr.reasons.clone())
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn test_each_blocking_condition_is_reported() {
+        let conn = setup_db();
+        let blocking = vec!["blocked".to_string(), "do-not-merge".to_string()];
+
+        insert_pr(&conn, 1, "OPEN", "[]", true, Some("MERGEABLE"));
+        insert_pr(&conn, 2, "OPEN", r#"["Blocked", "backend"]"#, false, Some("MERGEABLE"));
+        insert_pr(&conn, 3, "OPEN", "[]", false, Some("CONFLICTING"));
+        insert_pr(&conn, 4, "OPEN", r#"["do-not-merge"]"#, true, Some("CONFLICTING"));
+        insert_pr(&conn, 5, "OPEN", r#"["backend"]"#, false, Some("MERGEABLE"));
+        insert_pr(&conn, 6, "CLOSED", r#"["blocked"]"#, true, Some("CONFLICTING"));
+
+        let blocked = get_blocked_prs(&conn, &rolling(30), &blocking).unwrap();
+        assert_eq!(blocked.len(), 4);
+
+        assert_eq!(reasons_for(&blocked, 1), vec!["draft"]);
+        assert_eq!(reasons_for(&blocked, 2), vec!["label:Blocked"]);
+        assert_eq!(reasons_for(&blocked, 3), vec!["merge_conflict"]);
+        assert_eq!(
+            reasons_for(&blocked, 4),
+            vec!["draft", "label:do-not-merge", "merge_conflict"]
+        );
+        assert!(reasons_for(&blocked, 5).is_empty());
+        assert!(reasons_for(&blocked, 6).is_empty());
+    }
+
+    #[test]
+    fn test_blocking_labels_are_configurable() {
+        let conn = setup_db();
+        insert_pr(&conn, 1, "OPEN", r#"["on-hold"]"#, false, None);
+
+        assert!(get_blocked_prs(&conn, &rolling(30), &["blocked".to_string()]).unwrap().is_empty());
+
+        let blocked = get_blocked_prs(&conn, &rolling(30), &["on-hold".to_string()]).unwrap();
+        assert_eq!(reasons_for(&blocked, 1), vec!["label:on-hold"]);
+    }
+
+    #[test]
+    fn test_productivity_weights_shift_multiplier() {
+        let conn = setup_db();
+        let mut settings = queries::get_settings(&conn).unwrap();
+
+        // Fixed dataset: strong speed (2x benchmark), weak quality (0.5x benchmark)
+        let (velocity, turnaround, capacity, quality) = (2.0, 2.0, 1.0, 0.5);
+
+        let default = weighted_productivity_multiplier(velocity, turnaround, capacity, quality, &settings);
+        // Matches the original fixed formula: 0.35*2 + 0.25*2 + 0.25*1 + 0.15*0.5
+        assert!((default - 1.525).abs() < 1e-9);
+
+        settings.productivity_weight_speed = 0.2;
+        settings.productivity_weight_ease = 0.2;
+        settings.productivity_weight_quality = 0.6;
+        let quality_focused = weighted_productivity_multiplier(velocity, turnaround, capacity, quality, &settings);
+        // 0.2*2 + 0.2*1 + 0.6*0.5
+        assert!((quality_focused - 0.9).abs() < 1e-9);
+        assert!(quality_focused < default);
+    }
+
+    #[test]
+    fn test_abandoned_prs_require_inactive_author() {
+        let conn = setup_db();
+        conn.execute_batch(
+            "INSERT INTO users (id, github_id, login) VALUES (1, 101, 'gone'), (2, 102, 'busy');
+             INSERT INTO pull_requests (github_id, repo_id, number, title, state, author_id, created_at, updated_at)
+             VALUES
+                 (201, 1, 201, 'Stale, inactive author', 'OPEN', 1, datetime('now', '-60 days'), datetime('now', '-45 days')),
+                 (202, 1, 202, 'Stale, active author', 'OPEN', 2, datetime('now', '-60 days'), datetime('now', '-45 days')),
+                 (203, 1, 203, 'Fresh work', 'OPEN', 2, datetime('now', '-3 days'), datetime('now', '-1 days'));",
+        )
+        .unwrap();
+
+        let abandoned = get_abandoned_prs(&conn, &rolling(30)).unwrap();
+        assert_eq!(abandoned.len(), 1);
+        assert_eq!(abandoned[0].number, 201);
+        assert_eq!(abandoned[0].author.as_deref(), Some("gone"));
+        assert!(abandoned[0].days_inactive >= 44);
+    }
+
+    #[test]
+    fn test_recent_review_keeps_author_active() {
+        let conn = setup_db();
+        conn.execute_batch(
+            "INSERT INTO users (id, github_id, login) VALUES (1, 101, 'reviewer');
+             INSERT INTO pull_requests (id, github_id, repo_id, number, title, state, author_id, created_at, updated_at)
+             VALUES
+                 (301, 301, 1, 301, 'Stale', 'OPEN', 1, datetime('now', '-60 days'), datetime('now', '-45 days')),
+                 (302, 302, 1, 302, 'Someone else', 'MERGED', NULL, datetime('now', '-60 days'), datetime('now', '-45 days'));
+             INSERT INTO pr_reviews (github_id, pr_id, reviewer_id, state, submitted_at)
+             VALUES (1, 302, 1, 'APPROVED', datetime('now', '-2 days'));",
+        )
+        .unwrap();
+
+        assert!(get_abandoned_prs(&conn, &rolling(30)).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_review_coverage_counts_only_non_author_reviews() {
+        let conn = setup_db();
+        conn.execute_batch(
+            "INSERT INTO users (id, github_id, login, tracked) VALUES (1, 101, 'alice', 1), (2, 102, 'bob', 1);
+             INSERT INTO pull_requests (id, github_id, repo_id, number, title, state, author_id, created_at, updated_at, merged_at)
+             VALUES
+                 (1, 1, 1, 1, 'Reviewed', 'MERGED', 1, datetime('now', '-5 days'), datetime('now', '-4 days'), datetime('now', '-4 days')),
+                 (2, 2, 1, 2, 'Self-reviewed only', 'MERGED', 1, datetime('now', '-5 days'), datetime('now', '-4 days'), datetime('now', '-4 days')),
+                 (3, 3, 1, 3, 'Unreviewed', 'MERGED', 2, datetime('now', '-5 days'), datetime('now', '-4 days'), datetime('now', '-4 days')),
+                 (4, 4, 1, 4, 'Reviewed too', 'MERGED', 2, datetime('now', '-5 days'), datetime('now', '-4 days'), datetime('now', '-4 days')),
+                 (5, 5, 1, 5, 'Open, reviewed', 'OPEN', 2, datetime('now', '-5 days'), datetime('now', '-4 days'), NULL);
+             INSERT INTO pr_reviews (github_id, pr_id, reviewer_id, state, submitted_at) VALUES
+                 (11, 1, 2, 'APPROVED', datetime('now', '-4 days')),
+                 (12, 2, 1, 'COMMENTED', datetime('now', '-4 days')),
+                 (13, 4, 1, 'APPROVED', datetime('now', '-4 days')),
+                 (14, 5, 1, 'APPROVED', datetime('now', '-4 days'));",
+        )
+        .unwrap();
+
+        let coverage = get_review_coverage_pct(&conn, &rolling(30), &RevertExclusion::default(), &[]).unwrap();
+        assert!((coverage - 50.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_merged_loc_basis_excludes_unmerged_prs() {
+        let conn = setup_db();
+        conn.execute_batch(
+            "INSERT INTO users (id, github_id, login, tracked) VALUES (1, 101, 'alice', 1);
+             INSERT INTO pull_requests (github_id, repo_id, number, title, state, author_id, created_at, updated_at, merged_at, additions, deletions)
+             VALUES
+                 (1, 1, 1, 'Merged', 'MERGED', 1, datetime('now', '-5 days'), datetime('now', '-4 days'), datetime('now', '-4 days'), 80, 20),
+                 (2, 1, 2, 'Abandoned rewrite', 'CLOSED', 1, datetime('now', '-5 days'), datetime('now', '-4 days'), NULL, 5000, 1000);",
+        )
+        .unwrap();
+
+        let mut settings = queries::get_settings(&conn).unwrap();
+        assert_eq!(settings.loc_basis, "all");
+        assert!((get_loc_per_day(&conn, &rolling(10), &settings, &RevertExclusion::default()) - 610.0).abs() < 1e-9);
+
+        settings.loc_basis = "merged".to_string();
+        assert!((get_loc_per_day(&conn, &rolling(10), &settings, &RevertExclusion::default()) - 10.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_commit_loc_basis_honors_merge_commit_setting() {
+        let conn = setup_db();
+        conn.execute("INSERT INTO users (id, github_id, login, tracked) VALUES (1, 101, 'alice', 1)", [])
+            .unwrap();
+        let committed_at = (chrono::Utc::now() - chrono::Duration::days(3)).format("%Y-%m-%dT%H:%M:%SZ").to_string();
+        queries::upsert_commit(&conn, 1, "abc123", Some(1), "Add search", &committed_at, 80, 20, false).unwrap();
+        queries::upsert_commit(&conn, 1, "def456", Some(1), "Merge branch 'main'", &committed_at, 400, 100, true).unwrap();
+
+        let mut settings = queries::get_settings(&conn).unwrap();
+        settings.loc_basis = "commits".to_string();
+        assert!((get_loc_per_day(&conn, &rolling(10), &settings, &RevertExclusion::default()) - 10.0).abs() < 1e-9);
+
+        settings.include_merge_commit_loc = true;
+        assert!((get_loc_per_day(&conn, &rolling(10), &settings, &RevertExclusion::default()) - 60.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_slowest_prs_are_ordered_by_duration() {
+        let conn = setup_db();
+        conn.execute_batch(
+            "INSERT INTO repositories (id, owner, name) VALUES (2, 'acme', 'web');
+             INSERT INTO users (id, github_id, login) VALUES (1, 101, 'alice'), (2, 102, 'dependabot[bot]');",
+        )
+        .unwrap();
+
+        let created = chrono::Utc::now() - chrono::Duration::days(10);
+        let insert = |number: i32, repo_id: i64, author_id: i64, hours: Option<i64>| {
+            let created_at = created.format("%Y-%m-%dT%H:%M:%SZ").to_string();
+            let merged_at = hours.map(|h| (created + chrono::Duration::hours(h)).format("%Y-%m-%dT%H:%M:%SZ").to_string());
+            conn.execute(
+                "INSERT INTO pull_requests (github_id, repo_id, number, title, state, author_id, created_at, updated_at, merged_at, labels)
+                 VALUES (?1, ?2, ?1, 'PR', 'MERGED', ?3, ?4, ?4, ?5, '[]')",
+                params![number, repo_id, author_id, created_at, merged_at],
+            )
+            .unwrap();
+        };
+        insert(1, 1, 1, Some(5));
+        insert(2, 1, 1, Some(72));
+        insert(3, 2, 1, Some(30));
+        insert(4, 1, 1, Some(48));
+        insert(5, 1, 1, None); // Still open
+        insert(6, 1, 2, Some(200)); // Bot
+
+        let bots = vec!["dependabot[bot]".to_string()];
+        let slowest = get_slowest_prs(&conn, &rolling(30), 3, &MetricsFilters::default(), &bots).unwrap();
+        assert_eq!(slowest.iter().map(|p| p.number).collect::<Vec<_>>(), vec![2, 4, 3]);
+        assert!((slowest[0].duration_hours - 72.0).abs() < 1e-9);
+        assert_eq!(slowest[0].author.as_deref(), Some("alice"));
+        assert_eq!(slowest[2].repo, "acme/web");
+
+        let filters = MetricsFilters {
+            repository_ids: Some(vec![2]),
+            ..Default::default()
+        };
+        let only_web = get_slowest_prs(&conn, &rolling(30), 3, &filters, &bots).unwrap();
+        assert_eq!(only_web.iter().map(|p| p.number).collect::<Vec<_>>(), vec![3]);
+    }
+
+    fn insert_funnel_pr(conn: &Connection, number: i32, merged_after_hours: Option<i32>) {
+        conn.execute(
+            "INSERT INTO pull_requests (github_id, repo_id, number, title, state, created_at, updated_at, merged_at)
+             VALUES (?1, 1, ?1, 'PR', ?2, datetime('now', '-5 days'), datetime('now', '-5 days'),
+                     datetime('now', '-5 days', '+' || ?3 || ' hours'))",
+            params![number, if merged_after_hours.is_some() { "MERGED" } else { "OPEN" }, merged_after_hours],
+        )
+        .unwrap();
+    }
+
+    fn insert_funnel_review(conn: &Connection, github_id: i64, number: i32, state: &str, after_hours: i32) {
+        conn.execute(
+            "INSERT INTO pr_reviews (github_id, pr_id, state, submitted_at)
+             SELECT ?1, id, ?2, datetime('now', '-5 days', '+' || ?3 || ' hours') FROM pull_requests WHERE number = ?4",
+            params![github_id, state, after_hours, number],
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_pr_funnel_stage_medians() {
+        let conn = setup_db();
+
+        // Full funnels
+        insert_funnel_pr(&conn, 1, Some(10));
+        insert_funnel_review(&conn, 1, 1, "COMMENTED", 2);
+        insert_funnel_review(&conn, 2, 1, "APPROVED", 6);
+        insert_funnel_pr(&conn, 2, Some(20));
+        insert_funnel_review(&conn, 3, 2, "CHANGES_REQUESTED", 4);
+        insert_funnel_review(&conn, 4, 2, "APPROVED", 12);
+        // Reviewed but never approved, still open
+        insert_funnel_pr(&conn, 3, None);
+        insert_funnel_review(&conn, 5, 3, "COMMENTED", 6);
+        // Merged without any review
+        insert_funnel_pr(&conn, 4, Some(30));
+
+        let funnel = get_pr_funnel(&conn, &rolling(30), false, &[]).unwrap();
+        assert_eq!(
+            (funnel.prs_opened, funnel.prs_reviewed, funnel.prs_approved, funnel.prs_merged),
+            (4, 3, 2, 3)
+        );
+        assert_eq!(funnel.open_to_first_review_hours, Some(4.0)); // 2, 4, 6
+        assert_eq!(funnel.first_review_to_approval_hours, Some(6.0)); // 4, 8
+        assert_eq!(funnel.approval_to_merge_hours, Some(6.0)); // 4, 8
+        assert_eq!(funnel.open_to_merge_hours, Some(20.0)); // 10, 20, 30
+    }
+
+    #[test]
+    fn test_pr_funnel_without_reviews_has_empty_stages() {
+        let conn = setup_db();
+        insert_funnel_pr(&conn, 1, None);
+
+        let funnel = get_pr_funnel(&conn, &rolling(30), false, &[]).unwrap();
+        assert_eq!(funnel.prs_opened, 1);
+        assert_eq!(funnel.open_to_first_review_hours, None);
+        assert_eq!(funnel.approval_to_merge_hours, None);
+        assert_eq!(funnel.open_to_merge_hours, None);
+    }
+
+    #[test]
+    fn test_review_latency_distribution_buckets() {
+        let conn = setup_db();
+
+        // First-review latencies: 0h, 2h, 3h, 10h, 30h; PR 6 is never reviewed
+        for (number, first_review_hours) in [(1, 0), (2, 2), (3, 3), (4, 10), (5, 30)] {
+            insert_funnel_pr(&conn, number, None);
+            insert_funnel_review(&conn, number as i64, number, "COMMENTED", first_review_hours);
+        }
+        // A later review on PR 1 doesn't move its first-review time
+        insert_funnel_review(&conn, 10, 1, "APPROVED", 40);
+        insert_funnel_pr(&conn, 6, None);
+
+        let dist = get_review_latency_distribution(&conn, &rolling(30), false, &[]).unwrap();
+        assert_eq!((dist.under_1h, dist.h1_to_4, dist.h4_to_24, dist.over_24h), (1, 2, 1, 1));
+        assert_eq!(dist.under_1h_pct, 20.0);
+        assert_eq!(dist.h1_to_4_pct, 40.0);
+        assert_eq!(dist.h4_to_24_pct, 20.0);
+        assert_eq!(dist.over_24h_pct, 20.0);
+        let total_pct = dist.under_1h_pct + dist.h1_to_4_pct + dist.h4_to_24_pct + dist.over_24h_pct;
+        assert!((total_pct - 100.0).abs() < 1e-9);
+
+        let empty = get_review_latency_distribution(&conn, &rolling(1), false, &[]).unwrap();
+        assert_eq!(empty.under_1h + empty.h1_to_4 + empty.h4_to_24 + empty.over_24h, 0);
+        assert_eq!(empty.under_1h_pct, 0.0);
+    }
+
+    #[test]
+    fn test_metric_floats_are_rounded_only_when_serialized() {
+        let conn = setup_db();
+        conn.execute("INSERT INTO users (id, github_id, login, tracked) VALUES (1, 101, 'alice', 1)", [])
+            .unwrap();
+        for (number, title) in [(1, "Add search"), (2, "Fix crash"), (3, "Bump deps")] {
+            conn.execute(
+                "INSERT INTO pull_requests (github_id, repo_id, number, title, state, author_id, created_at, updated_at)
+                 VALUES (?1, 1, ?1, ?2, 'open', 1, datetime('now', '-1 days'), datetime('now', '-1 days'))",
+                params![number, title],
+            )
+            .unwrap();
+        }
+
+        let settings = queries::get_settings(&conn).unwrap();
+        assert_eq!(settings.metric_precision, crate::metrics::precision::DEFAULT_METRIC_PRECISION);
+        let quality = get_quality_metrics(&conn, &rolling(30), &settings).unwrap();
+        assert!((quality.feature_pr_percentage - 100.0 / 3.0).abs() < 1e-9);
+
+        let json = serde_json::to_value(&quality).unwrap();
+        assert_eq!(json["feature_pr_percentage"], serde_json::json!(33.33));
+    }
+
+    #[test]
+    fn test_active_developers_respects_min_active_prs() {
+        let conn = setup_db();
+        conn.execute_batch(
+            "INSERT INTO users (id, github_id, login, tracked) VALUES (1, 101, 'once', 1), (2, 102, 'often', 1);",
+        )
+        .unwrap();
+        for (number, author_id) in [(1, 1), (2, 2), (3, 2), (4, 2)] {
+            conn.execute(
+                "INSERT INTO pull_requests (github_id, repo_id, number, title, state, author_id, created_at, updated_at)
+                 VALUES (?1, 1, ?1, 'PR', 'open', ?2, datetime('now', '-1 days'), datetime('now', '-1 days'))",
+                params![number, author_id],
+            )
+            .unwrap();
+        }
+
+        assert_eq!(queries::get_settings(&conn).unwrap().min_active_prs, 1);
+        assert_eq!(count_active_developers(&conn, &rolling(30), 1).unwrap(), 2);
+        assert_eq!(count_active_developers(&conn, &rolling(30), 2).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_excluding_weekends_raises_per_day_rates() {
+        let conn = setup_db();
+        conn.execute("INSERT INTO users (id, github_id, login, tracked) VALUES (1, 101, 'alice', 1)", [])
+            .unwrap();
+        // One PR on each of the last 7 days: always 5 weekdays and 2 weekend days
+        for offset in 0..7 {
+            conn.execute(
+                "INSERT INTO pull_requests (github_id, repo_id, number, title, state, author_id,
+                                            created_at, updated_at, additions, deletions)
+                 VALUES (?1, 1, ?1, 'PR', 'open', 1, datetime('now', '-' || ?2 || ' days', '+1 minutes'),
+                         datetime('now'), 70, 0)",
+                params![offset + 1, offset],
+            )
+            .unwrap();
+        }
+
+        let mut settings = queries::get_settings(&conn).unwrap();
+        assert!(!settings.exclude_weekends);
+        let all_days = get_speed_metrics(&conn, &rolling(7), &settings).unwrap();
+
+        settings.exclude_weekends = true;
+        let weekdays = get_speed_metrics(&conn, &rolling(7), &settings).unwrap();
+
+        assert!((all_days.loc_per_day - 70.0).abs() < 1e-9);
+        assert!((weekdays.loc_per_day - 98.0).abs() < 1e-9);
+        assert!(weekdays.prs_per_day > all_days.prs_per_day);
+        assert_eq!(days_in_period(7, true, 0), 5);
+    }
+
+    #[test]
+    fn test_excluded_weekends_follow_team_timezone() {
+        let conn = setup_db();
+        conn.execute_batch(
+            "INSERT INTO users (id, github_id, login, tracked) VALUES (1, 101, 'alice', 1);
+             INSERT INTO pull_requests (github_id, repo_id, number, title, state, author_id, created_at, updated_at)
+             VALUES
+                 (1, 1, 1, 'Monday 02:00 UTC', 'open', 1, '2024-03-04T02:00:00Z', '2024-03-04T02:00:00Z'),
+                 (2, 1, 2, 'Tuesday noon', 'open', 1, '2024-03-05T12:00:00Z', '2024-03-05T12:00:00Z');",
+        )
+        .unwrap();
+        let now = DateTime::parse_from_rfc3339("2024-03-08T00:00:00Z").unwrap().with_timezone(&Utc);
+        let period = Period::new(30, "rolling", now);
+
+        let mut settings = queries::get_settings(&conn).unwrap();
+        settings.exclude_weekends = true;
+        let utc = get_speed_metrics(&conn, &period, &settings).unwrap();
+        assert!((utc.prs_per_day - 1.0).abs() < 1e-9);
+
+        // In UTC-5 the first PR was opened on Sunday evening, so only one working day remains
+        settings.utc_offset_minutes = -300;
+        let new_york = get_speed_metrics(&conn, &period, &settings).unwrap();
+        assert!((new_york.prs_per_day - 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_period_boundaries() {
+        let now = DateTime::parse_from_rfc3339("2024-03-10T15:30:00Z").unwrap().with_timezone(&Utc);
+
+        let rolling = Period::new(7, "rolling", now);
+        assert_eq!(rolling.since, "2024-03-03T15:30:00Z");
+
+        let inclusive = Period::new(7, "inclusive_start", now);
+        assert_eq!(inclusive.since, "2024-03-04T00:00:00Z");
+        assert_eq!(inclusive.days, 7);
+
+        // UTC-5: it is 10:30 on March 10 locally, so the window opens at local midnight on March 4
+        let new_york = Period::in_timezone(7, "inclusive_start", -300, now);
+        assert_eq!(new_york.since, "2024-03-04T05:00:00Z");
+
+        // UTC+10: already 01:30 on March 11 locally, so the window shifts a day later
+        let sydney = Period::in_timezone(7, "inclusive_start", 600, now);
+        assert_eq!(sydney.since, "2024-03-04T14:00:00Z");
+
+        // The offset doesn't move a rolling window
+        assert_eq!(Period::in_timezone(7, "rolling", -300, now), rolling);
+    }
+
+    #[test]
+    fn test_pr_exactly_at_boundary_is_included() {
+        let conn = setup_db();
+        conn.execute("INSERT INTO users (id, github_id, login, tracked) VALUES (1, 101, 'alice', 1)", [])
+            .unwrap();
+
+        let mut settings = queries::get_settings(&conn).unwrap();
+        assert_eq!(settings.period_boundary, "rolling");
+
+        settings.utc_offset_minutes = -300;
+        for boundary in ["rolling", "inclusive_start"] {
+            settings.period_boundary = boundary.to_string();
+            let period = Period::from_settings(30, &settings);
+            let just_before = (DateTime::parse_from_rfc3339(&period.since).unwrap() - Duration::seconds(1))
+                .format("%Y-%m-%dT%H:%M:%SZ")
+                .to_string();
+            conn.execute("DELETE FROM pull_requests", []).unwrap();
+            conn.execute(
+                "INSERT INTO pull_requests (github_id, repo_id, number, title, state, author_id, created_at, updated_at, labels)
+                 VALUES (1, 1, 1, 'At boundary', 'open', 1, ?1, ?1, '[]'),
+                        (2, 1, 2, 'Just before', 'open', 1, ?2, ?1, '[]')",
+                params![period.since, just_before],
+            )
+            .unwrap();
+
+            assert_eq!(count_active_developers(&conn, &period, 1).unwrap(), 1, "{}", boundary);
+            assert_eq!(get_pr_funnel(&conn, &period, false, &[]).unwrap().prs_opened, 1, "{}", boundary);
+            let quality = get_quality_metrics(&conn, &period, &settings).unwrap();
+            assert_eq!(quality.pr_type_distribution.iter().map(|t| t.count).sum::<i32>(), 1, "{}", boundary);
+        }
+
+        settings.period_boundary = "weekly".to_string();
+        assert!(queries::update_settings(&conn, &settings).is_err());
+    }
+
+    #[test]
+    fn test_triage_time_by_label() {
+        let conn = setup_db();
+        let created = (Utc::now() - Duration::days(5)).format("%Y-%m-%dT%H:%M:%SZ").to_string();
+        let after = |hours: i64| {
+            (DateTime::parse_from_rfc3339(&created).unwrap() + Duration::hours(hours))
+                .format("%Y-%m-%dT%H:%M:%SZ")
+                .to_string()
+        };
+
+        let issues: Vec<(&str, Option<String>)> = vec![
+            (r#"["Bug"]"#, Some(after(2))),
+            (r#"["bug ", "P1"]"#, Some(after(4))),
+            (r#"["BUG"]"#, Some(after(6))),
+            (r#"["bug"]"#, None),
+            (r#"["question"]"#, Some(after(48))),
+            ("[]", Some(after(1))),
+        ];
+        for (number, (labels, first_response_at)) in issues.into_iter().enumerate() {
+            conn.execute(
+                "INSERT INTO issues (github_id, repo_id, number, title, state, created_at, updated_at, labels, first_response_at)
+                 VALUES (?1, 1, ?1, 'Issue', 'OPEN', ?2, ?2, ?3, ?4)",
+                params![number as i64 + 1, created, labels, first_response_at],
+            )
+            .unwrap();
+        }
+
+        let triage = get_triage_time_by_label(&conn, &rolling(30)).unwrap();
+        let labels: Vec<&str> = triage.iter().map(|t| t.label.as_str()).collect();
+        assert_eq!(labels, vec!["bug", "p1", "question"]);
+
+        let bug = &triage[0];
+        assert_eq!(bug.median_hours, Some(4.0));
+        assert_eq!(bug.sample_size, 3);
+        assert_eq!(bug.awaiting_response, 1);
+        assert!(bug.low_confidence);
+
+        assert_eq!(triage[2].median_hours, Some(48.0));
+        assert_eq!(triage[2].sample_size, 1);
+    }
+
+    #[test]
+    fn test_after_hours_follows_core_hours() {
+        let conn = setup_db();
+        conn.execute("INSERT INTO users (id, github_id, login, tracked) VALUES (1, 101, 'alice', 1)", [])
+            .unwrap();
+        let day = (Utc::now() - Duration::days(2)).format("%Y-%m-%d").to_string();
+        for (number, hour) in [(1, 7), (2, 9), (3, 17), (4, 20)] {
+            conn.execute(
+                "INSERT INTO pull_requests (github_id, repo_id, number, title, state, author_id, created_at, updated_at, labels)
+                 VALUES (?1, 1, ?1, 'PR', 'open', 1, ?2, ?2, '[]')",
+                params![number, format!("{}T{:02}:30:00Z", day, hour)],
+            )
+            .unwrap();
+        }
+
+        let mut settings = queries::get_settings(&conn).unwrap();
+        assert_eq!((settings.core_start_hour, settings.core_end_hour), (8, 18));
+        let default_hours = get_ease_metrics(&conn, &rolling(30), &settings).unwrap();
+        assert!((default_hours.after_hours_pct - 50.0).abs() < 1e-9);
+
+        settings.core_start_hour = 6;
+        settings.core_end_hour = 22;
+        let wide_hours = get_ease_metrics(&conn, &rolling(30), &settings).unwrap();
+        assert!(wide_hours.after_hours_pct.abs() < 1e-9);
+        assert!(wide_hours.work_pattern.iter().all(|cell| cell.is_core_hours));
+
+        settings.core_start_hour = 10;
+        settings.core_end_hour = 10;
+        assert!(queries::update_settings(&conn, &settings).is_err());
+
+        // Shifted to UTC+2 the 07:30 and 17:30 PRs land at 09:30 and 19:30 local time
+        settings.core_start_hour = 8;
+        settings.core_end_hour = 18;
+        settings.utc_offset_minutes = 120;
+        let shifted = get_ease_metrics(&conn, &rolling(30), &settings).unwrap();
+        assert!((shifted.after_hours_pct - 50.0).abs() < 1e-9);
+        let mut hours: Vec<i32> = shifted.work_pattern.iter().map(|cell| cell.hour_of_day).collect();
+        hours.sort();
+        assert_eq!(hours, vec![9, 11, 19, 22]);
+    }
+
+    #[test]
+    fn test_collaboration_index_counts_distinct_contributors() {
+        let conn = setup_db();
+        conn.execute_batch(
+            "INSERT INTO users (id, github_id, login, is_bot) VALUES
+                 (1, 101, 'alice', 0), (2, 102, 'bob', 0), (3, 103, 'dependabot[bot]', 1);",
+        )
+        .unwrap();
+        let merged_at = (Utc::now() - Duration::days(2)).format("%Y-%m-%dT%H:%M:%SZ").to_string();
+        let prs: [(i32, Option<&str>, bool); 7] = [
+            (1, None, true), // solo
+            (2, None, true), // self-review only
+            (3, None, true), // reviewed by bob
+            (4, None, true), // bot review only
+            (5, Some("Fix\n\nCo-authored-by: Bob <123+bob@users.noreply.github.com>"), true),
+            (6, Some("Co-authored-by: Alice <alice@users.noreply.github.com>"), true), // author again
+            (7, None, false), // not merged
+        ];
+        for (number, body, merged) in prs {
+            conn.execute(
+                "INSERT INTO pull_requests (github_id, repo_id, number, title, body, state, author_id,
+                                            created_at, updated_at, merged_at, labels)
+                 VALUES (?1, 1, ?1, 'PR', ?2, ?3, 1, ?4, ?4, ?5, '[]')",
+                params![number, body, if merged { "MERGED" } else { "OPEN" }, merged_at, merged.then_some(&merged_at)],
+            )
+            .unwrap();
+        }
+        for (github_id, pr_id, reviewer_id) in [(11, 2, 1), (12, 3, 2), (13, 4, 3), (14, 7, 2)] {
+            conn.execute(
+                "INSERT INTO pr_reviews (github_id, pr_id, reviewer_id, state, submitted_at)
+                 VALUES (?1, ?2, ?3, 'APPROVED', ?4)",
+                params![github_id, pr_id, reviewer_id, merged_at],
+            )
+            .unwrap();
+        }
+
+        let collaboration = get_collaboration_index(&conn, &rolling(30)).unwrap();
+        assert_eq!(collaboration.merged_prs, 6);
+        assert_eq!(collaboration.collaborative_prs, 2);
+        assert!((collaboration.index - 2.0 / 6.0).abs() < 1e-9);
+
+        assert_eq!(
+            co_authors("co-authored-by: Carol <Carol@Example.com>\nSigned-off-by: Dan <dan@x.io>"),
+            vec![("carol".to_string(), "carol@example.com".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_collaboration_index_resolves_co_author_emails_to_users() {
+        let conn = setup_db();
+        conn.execute_batch(
+            "INSERT INTO users (id, github_id, login, name, is_bot) VALUES
+                 (1, 101, 'alice', 'Alice Smith', NULL), (2, 102, 'bob', NULL, 0), (3, 103, 'renovate[bot]', NULL, 1);",
+        )
+        .unwrap();
+        let merged_at = (Utc::now() - Duration::days(2)).format("%Y-%m-%dT%H:%M:%SZ").to_string();
+        let prs = [
+            (1, "Co-authored-by: Alice Smith <alice@work.example>"), // the author's work email
+            (2, "Co-authored-by: renovate[bot] <29139614+renovate[bot]@users.noreply.github.com>"),
+            (3, "Co-authored-by: Bob <bob@home.example>"),
+            (4, ""), // reviewed by alice, whose is_bot was never set
+        ];
+        for (number, body) in prs {
+            conn.execute(
+                "INSERT INTO pull_requests (id, github_id, repo_id, number, title, body, state, author_id,
+                                            created_at, updated_at, merged_at, labels)
+                 VALUES (?1, ?1, 1, ?1, 'PR', ?2, 'MERGED', ?3, ?4, ?4, ?4, '[]')",
+                params![number, body, if number == 4 { 2 } else { 1 }, merged_at],
+            )
+            .unwrap();
+        }
+        conn.execute(
+            "INSERT INTO pr_reviews (github_id, pr_id, reviewer_id, state, submitted_at) VALUES (1, 4, 1, 'APPROVED', ?1)",
+            params![merged_at],
+        )
+        .unwrap();
+
+        // Only PR 3 (bob's email resolves by name) and PR 4 (alice's review) are collaborative
+        let collaboration = get_collaboration_index(&conn, &rolling(30)).unwrap();
+        assert_eq!((collaboration.merged_prs, collaboration.collaborative_prs), (4, 2));
+    }
+
+    #[test]
+    fn test_performance_tiers_against_benchmarks() {
+        let conn = setup_db();
+        let settings = queries::get_settings(&conn).unwrap();
+        let mut speed = get_speed_metrics(&conn, &rolling(30), &settings).unwrap();
+        let mut ease = get_ease_metrics(&conn, &rolling(30), &settings).unwrap();
+        let mut quality = get_quality_metrics(&conn, &rolling(30), &settings).unwrap();
+
+        // Better than every elite benchmark
+        speed.prs_per_day_per_dev = 2.0;
+        speed.pr_turnaround_hours = 12.0;
+        ease.repos_per_dev = 4.0;
+        quality.pr_merge_rate = 90.0;
+        quality.bug_pr_percentage = 10.0;
+        let tiers = performance_tiers(&speed, &ease, &quality, 10, settings.tier_medium_tolerance);
+        assert_eq!(
+            (tiers.speed, tiers.ease, tiers.quality, tiers.overall),
+            (Some(PerformanceTier::Elite), Some(PerformanceTier::Elite), Some(PerformanceTier::Elite), Some(PerformanceTier::Elite))
+        );
+
+        // Well below every industry benchmark
+        speed.prs_per_day_per_dev = 0.2;
+        speed.pr_turnaround_hours = 200.0;
+        ease.repos_per_dev = 1.0;
+        quality.pr_merge_rate = 40.0;
+        quality.bug_pr_percentage = 50.0;
+        let tiers = performance_tiers(&speed, &ease, &quality, 10, settings.tier_medium_tolerance);
+        assert_eq!(
+            (tiers.speed, tiers.ease, tiers.quality, tiers.overall),
+            (Some(PerformanceTier::Low), Some(PerformanceTier::Low), Some(PerformanceTier::Low), Some(PerformanceTier::Low))
+        );
+
+        // Mixed: elite speed, high ease, medium quality
+        speed.prs_per_day_per_dev = 1.5;
+        speed.pr_turnaround_hours = 24.0;
+        ease.repos_per_dev = 2.5;
+        quality.pr_merge_rate = 60.0;
+        quality.bug_pr_percentage = 28.0;
+        let tiers = performance_tiers(&speed, &ease, &quality, 10, settings.tier_medium_tolerance);
+        assert_eq!(
+            (tiers.speed, tiers.ease, tiers.quality, tiers.overall),
+            (Some(PerformanceTier::Elite), Some(PerformanceTier::High), Some(PerformanceTier::Medium), Some(PerformanceTier::High))
+        );
+
+        let json = serde_json::to_value(&tiers).unwrap();
+        assert_eq!(json["overall"], serde_json::json!("High"));
+
+        // A looser tolerance keeps a weaker merge rate at Medium
+        quality.pr_merge_rate = 40.0;
+        assert_eq!(performance_tiers(&speed, &ease, &quality, 10, 0.6).quality, Some(PerformanceTier::Medium));
+        assert_eq!(performance_tiers(&speed, &ease, &quality, 10, settings.tier_medium_tolerance).quality, Some(PerformanceTier::Low));
+
+        // No PRs in the period: unrated instead of Low
+        let empty = performance_tiers(&speed, &ease, &quality, 0, settings.tier_medium_tolerance);
+        assert_eq!((empty.speed, empty.ease, empty.quality, empty.overall), (None, None, None, None));
+        assert_eq!(serde_json::to_value(&empty).unwrap()["overall"], serde_json::Value::Null);
+    }
+
+    #[test]
+    fn test_review_debt_counts_prs_awaiting_first_review() {
+        let conn = setup_db();
+        let now = Utc::now();
+        let ago = |hours: i64| (now - Duration::hours(hours)).format("%Y-%m-%dT%H:%M:%SZ").to_string();
+        conn.execute_batch("INSERT INTO users (id, github_id, login) VALUES (1, 101, 'alice'), (2, 102, 'bob')")
+            .unwrap();
+
+        // (number, state, draft, opened hours ago, reviewer)
+        let prs = [
+            (1, "OPEN", false, 10, None),     // waiting
+            (2, "OPEN", false, 30, Some(1)),  // only a self-review: still waiting
+            (3, "OPEN", false, 50, None),     // waiting
+            (4, "OPEN", false, 70, Some(2)),  // reviewed
+            (5, "OPEN", true, 90, None),      // draft
+            (6, "MERGED", false, 110, None),  // closed out
+        ];
+        for (number, state, draft, hours, reviewer) in prs {
+            conn.execute(
+                "INSERT INTO pull_requests (id, github_id, repo_id, number, title, state, author_id, created_at, updated_at, labels, is_draft)
+                 VALUES (?1, ?1, 1, ?1, 'PR', ?2, 1, ?3, ?3, '[]', ?4)",
+                params![number, state, ago(hours), draft],
+            )
+            .unwrap();
+            if let Some(reviewer_id) = reviewer {
+                conn.execute(
+                    "INSERT INTO pr_reviews (github_id, pr_id, reviewer_id, state, submitted_at)
+                     VALUES (?1, ?1, ?2, 'COMMENTED', ?3)",
+                    params![number, reviewer_id, ago(1)],
+                )
+                .unwrap();
+            }
+        }
+
+        let debt = get_review_debt(&conn, now, &[]).unwrap();
+        assert_eq!(debt.awaiting_review, 3);
+        assert!((debt.total_wait_hours - 90.0).abs() < 0.01);
+        assert_eq!(debt.median_wait_hours, Some(30.0));
+    }
+
+    #[test]
+    fn test_avg_commits_per_merged_pr() {
+        let conn = setup_db();
+        conn.execute("INSERT INTO users (id, github_id, login, tracked) VALUES (1, 101, 'alice', 1)", [])
+            .unwrap();
+        // (number, commits, merged)
+        for (number, commits, merged) in [(1, Some(1), true), (2, Some(5), true), (3, Some(9), false), (4, None, true)] {
+            conn.execute(
+                "INSERT INTO pull_requests (github_id, repo_id, number, title, state, author_id, created_at, updated_at,
+                                            merged_at, labels, commit_count)
+                 VALUES (?1, 1, ?1, 'PR', ?2, 1, datetime('now', '-2 days'), datetime('now', '-1 days'), ?3, '[]', ?4)",
+                params![
+                    number,
+                    if merged { "MERGED" } else { "OPEN" },
+                    merged.then_some("2099-01-01T00:00:00Z"),
+                    commits
+                ],
+            )
+            .unwrap();
+        }
+
+        let settings = queries::get_settings(&conn).unwrap();
+        let quality = get_quality_metrics(&conn, &rolling(30), &settings).unwrap();
+        assert!((quality.avg_commits_per_pr - 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_rejection_labels_leave_deliberate_closes_out_of_merge_rate() {
+        let conn = setup_db();
+        conn.execute("INSERT INTO users (id, github_id, login, tracked) VALUES (1, 101, 'alice', 1)", [])
+            .unwrap();
+        // (number, state, merged, labels)
+        for (number, state, merged, labels) in [
+            (1, "closed", true, "[]"),
+            (2, "closed", false, "[]"),
+            (3, "closed", false, r#"["Duplicate"]"#),
+            (4, "closed", true, r#"["duplicate"]"#),
+        ] {
+            conn.execute(
+                "INSERT INTO pull_requests (github_id, repo_id, number, title, state, author_id, created_at, updated_at,
+                                            merged_at, labels)
+                 VALUES (?1, 1, ?1, 'PR', ?2, 1, datetime('now', '-2 days'), datetime('now', '-1 days'), ?3, ?4)",
+                params![number, state, merged.then_some("2099-01-01T00:00:00Z"), labels],
+            )
+            .unwrap();
+        }
+
+        let mut settings = queries::get_settings(&conn).unwrap();
+        assert!(settings.rejection_labels.is_empty());
+        let quality = get_quality_metrics(&conn, &rolling(30), &settings).unwrap();
+        assert!((quality.pr_merge_rate - 50.0).abs() < 1e-9); // 2 of 4
+
+        // The labeled, closed-unmerged PR leaves the denominator; a merged PR with the label still counts
+        settings.rejection_labels = vec!["duplicate".to_string()];
+        let quality = get_quality_metrics(&conn, &rolling(30), &settings).unwrap();
+        assert!((quality.pr_merge_rate - 200.0 / 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_contribution_split_by_author_membership() {
+        let conn = setup_db();
+        conn.execute_batch(
+            "INSERT INTO repositories (id, owner, name) VALUES (2, 'outside', 'lib');
+             INSERT INTO users (id, github_id, login, is_bot) VALUES
+                 (1, 101, 'maintainer', FALSE), (2, 102, 'staffer', FALSE), (3, 103, 'drive-by', FALSE),
+                 (4, 104, 'dependabot[bot]', TRUE);",
+        )
+        .unwrap();
+        // (number, repo, author, association)
+        for (number, repo_id, author_id, association) in [
+            (1, 1, 1, "CONTRIBUTOR"), // internal via internal_logins
+            (2, 1, 2, "MEMBER"),      // internal via acme org membership
+            (3, 1, 2, "MEMBER"),
+            (4, 2, 2, "MEMBER"),      // member of an org that isn't listed
+            (5, 1, 3, "CONTRIBUTOR"),
+            (6, 1, 4, "NONE"),        // bot, left out
+        ] {
+            conn.execute(
+                "INSERT INTO pull_requests (github_id, repo_id, number, title, state, author_id, created_at, updated_at,
+                                            merged_at, labels, author_association)
+                 VALUES (?1, ?2, ?1, 'PR', 'closed', ?3, datetime('now', '-3 days'), datetime('now', '-1 days'),
+                         datetime('now', '-1 days'), '[]', ?4)",
+                params![number, repo_id, author_id, association],
+            )
+            .unwrap();
+        }
+
+        let mut settings = queries::get_settings(&conn).unwrap();
+        let everyone_external = get_contribution_split(&conn, &rolling(30), &settings).unwrap();
+        assert_eq!((everyone_external.internal_prs, everyone_external.external_prs), (0, 5));
+
+        settings.internal_logins = vec!["Maintainer".to_string()];
+        settings.internal_orgs = vec!["acme".to_string()];
+        let split = get_contribution_split(&conn, &rolling(30), &settings).unwrap();
+        assert_eq!(split.merged_prs, 5);
+        assert_eq!((split.internal_prs, split.external_prs), (3, 2));
+        assert!((split.internal_pct - 60.0).abs() < 1e-9);
+        assert!((split.external_pct - 40.0).abs() < 1e-9);
+        // 'staffer' is internal on acme repos but external elsewhere
+        assert_eq!((split.internal_contributors, split.external_contributors), (2, 2));
+        assert_eq!(split.unknown_prs, 0);
+    }
+
+    #[test]
+    fn test_contribution_split_reports_missing_association_as_unknown() {
+        let conn = setup_db();
+        conn.execute_batch(
+            "INSERT INTO repositories (id, owner, name) VALUES (2, 'outside', 'lib');
+             INSERT INTO users (id, github_id, login) VALUES (1, 101, 'staffer');",
+        )
+        .unwrap();
+        // (number, repo, association); NULL is what REST/CLI-synced PRs leave behind
+        for (number, repo_id, association) in [(1, 1, Some("MEMBER")), (2, 1, None), (3, 2, None)] {
+            conn.execute(
+                "INSERT INTO pull_requests (github_id, repo_id, number, title, state, author_id, created_at, updated_at,
+                                            merged_at, labels, author_association)
+                 VALUES (?1, ?2, ?1, 'PR', 'closed', 1, datetime('now', '-3 days'), datetime('now', '-1 days'),
+                         datetime('now', '-1 days'), '[]', ?3)",
+                params![number, repo_id, association],
+            )
+            .unwrap();
+        }
+
+        let mut settings = queries::get_settings(&conn).unwrap();
+        settings.internal_orgs = vec!["acme".to_string()];
+        let split = get_contribution_split(&conn, &rolling(30), &settings).unwrap();
+
+        // The NULL on an acme repo is unknown rather than external; outside repos don't need it
+        assert_eq!((split.internal_prs, split.external_prs, split.unknown_prs), (1, 1, 1));
+        assert_eq!(split.merged_prs, 3);
+    }
+
+    #[test]
+    fn test_label_dwell_time_for_in_progress() {
+        let conn = setup_db();
+        conn.execute_batch(
+            "INSERT INTO issues (id, github_id, repo_id, number, title, state, created_at, updated_at, labels) VALUES
+                 (1, 1, 1, 1, 'Issue', 'closed', '2024-03-01T00:00:00Z', '2024-03-05T00:00:00Z', '[]'),
+                 (2, 2, 1, 2, 'Issue', 'open', '2024-03-01T00:00:00Z', '2024-03-05T00:00:00Z', '[\"in-progress\"]');
+             INSERT INTO issue_label_events (issue_id, label, action, created_at) VALUES
+                 (1, 'triage', 'added', '2024-03-01T00:00:00Z'),
+                 (1, 'triage', 'removed', '2024-03-01T06:00:00Z'),
+                 (1, 'in-progress', 'added', '2024-03-01T06:00:00Z'),
+                 (1, 'In-Progress', 'removed', '2024-03-03T18:00:00Z'),
+                 (2, 'in-progress', 'added', '2024-03-02T00:00:00Z'),
+                 (2, 'blocked', 'removed', '2024-03-02T00:00:00Z');",
+        )
+        .unwrap();
+
+        let period = Period { days: 30, since: "2024-02-15T00:00:00Z".to_string() };
+        let dwell = get_label_dwell_times(&conn, &period).unwrap();
+        let find = |label: &str| dwell.iter().find(|d| d.label == label).unwrap();
+
+        // Held from Mar 1 06:00 to Mar 3 18:00; issue 2 still has it
+        let in_progress = find("in-progress");
+        assert_eq!(in_progress.median_hours, Some(60.0));
+        assert_eq!((in_progress.sample_size, in_progress.currently_held), (1, 1));
+        assert_eq!(find("triage").median_hours, Some(6.0));
+        // A removal with no recorded add isn't a span
+        assert!(dwell.iter().all(|d| d.label != "blocked"));
+
+        // Spans removed before the period start are left out
+        let later = Period { days: 30, since: "2024-03-02T00:00:00Z".to_string() };
+        let dwell = get_label_dwell_times(&conn, &later).unwrap();
+        assert_eq!(dwell.iter().find(|d| d.label == "triage").unwrap().sample_size, 0);
+        assert_eq!(dwell.iter().find(|d| d.label == "in-progress").unwrap().sample_size, 1);
+    }
+
+    #[test]
+    fn test_forecast_volume_continues_linear_trend() {
+        let conn = setup_db();
+        let now = Utc::now();
+        // Eight weeks of PRs rising by one each week: 1, 2, ..., 8
+        let mut github_id = 0;
+        for week in 0..8i64 {
+            let weeks_ago = 7 - week;
+            for _ in 0..=week {
+                github_id += 1;
+                let created = (now - chrono::Duration::days(weeks_ago * 7 + 3))
+                    .format("%Y-%m-%dT%H:%M:%SZ")
+                    .to_string();
+                conn.execute(
+                    "INSERT INTO pull_requests (github_id, repo_id, number, title, state, created_at, updated_at, labels)
+                     VALUES (?1, 1, ?1, 'PR', 'OPEN', ?2, ?2, '[]')",
+                    params![github_id, created],
+                )
+                .unwrap();
+            }
+        }
+
+        let forecast = forecast_volume(&conn, 7, now).unwrap();
+        assert_eq!(forecast.history_weeks, 8);
+        assert!(!forecast.low_confidence);
+        assert_eq!(forecast.prs.weekly_history, vec![1, 2, 3, 4, 5, 6, 7, 8]);
+        assert!((forecast.prs.forecast - 9.0).abs() < 1e-9);
+        // A perfect line has no residual spread
+        assert!((forecast.prs.upper - forecast.prs.lower).abs() < 1e-9);
+
+        // Two weeks ahead continues the trend: 9 + 10
+        let two_weeks = forecast_volume(&conn, 14, now).unwrap();
+        assert!((two_weeks.prs.forecast - 19.0).abs() < 1e-9);
+        assert_eq!(two_weeks.issues.forecast, 0.0);
+    }
+
+    #[test]
+    fn test_forecast_volume_flags_short_history() {
+        let conn = setup_db();
+        let now = Utc::now();
+        let created = (now - chrono::Duration::days(10)).format("%Y-%m-%dT%H:%M:%SZ").to_string();
+        conn.execute(
+            "INSERT INTO issues (github_id, repo_id, number, title, state, created_at, updated_at, labels)
+             VALUES (1, 1, 1, 'Issue', 'OPEN', ?1, ?1, '[]')",
+            params![created],
+        )
+        .unwrap();
+
+        let forecast = forecast_volume(&conn, 7, now).unwrap();
+        assert_eq!(forecast.history_weeks, 2);
+        assert!(forecast.low_confidence);
+        assert_eq!(forecast.issues.weekly_history, vec![1, 0]);
+
+        let empty = forecast_volume(&setup_db(), 7, now).unwrap();
+        assert!(empty.low_confidence);
+        assert_eq!(empty.prs.forecast, 0.0);
+    }
+
+    #[test]
+    fn test_squad_work_pattern_aggregates_members() {
+        let conn = setup_db();
+        conn.execute_batch(
+            "INSERT INTO users (id, github_id, login) VALUES (1, 101, 'early'), (2, 102, 'late'), (3, 103, 'outsider');
+             INSERT INTO squads (id, name) VALUES ('core', 'Core');
+             INSERT INTO squad_members (squad_id, user_id) VALUES ('core', 1), ('core', 2);",
+        )
+        .unwrap();
+        // 2024-03-04 is a Monday, 2024-03-09 a Saturday
+        for (number, author, created_at) in [
+            (1, 1, "2024-03-04T10:00:00Z"), // early: core hours
+            (2, 1, "2024-03-05T11:00:00Z"),
+            (3, 2, "2024-03-04T22:00:00Z"), // late: evening
+            (4, 2, "2024-03-09T23:00:00Z"), // late: Saturday night
+            (5, 3, "2024-03-09T03:00:00Z"), // not in the squad
+        ] {
+            conn.execute(
+                "INSERT INTO pull_requests (github_id, repo_id, number, title, state, author_id, created_at, updated_at, labels)
+                 VALUES (?1, 1, ?1, 'PR', 'OPEN', ?2, ?3, ?3, '[]')",
+                params![number, author, created_at],
+            )
+            .unwrap();
+        }
+
+        let mut settings = queries::get_settings(&conn).unwrap();
+        settings.core_start_hour = 9;
+        settings.core_end_hour = 18;
+        let period = Period {
+            days: 30,
+            since: "2024-03-01T00:00:00Z".to_string(),
+        };
+
+        let pattern = get_squad_work_pattern(&conn, "core", &period, &settings).unwrap();
+        assert_eq!(pattern.member_count, 2);
+        let total: i32 = pattern.work_pattern.iter().map(|c| c.activity_count).sum();
+        assert_eq!(total, 4);
+        assert!((pattern.after_hours_pct - 50.0).abs() < 1e-9);
+        assert!((pattern.weekend_pct - 25.0).abs() < 1e-9);
+
+        let empty = get_squad_work_pattern(&conn, "missing", &period, &settings).unwrap();
+        assert_eq!(empty.member_count, 0);
+        assert!(empty.work_pattern.is_empty());
+
+        // In UTC-12 the Monday 10:00 PR was opened on Sunday night and the
+        // Saturday 23:00 one during Saturday core hours
+        settings.utc_offset_minutes = -720;
+        let shifted = get_squad_work_pattern(&conn, "core", &period, &settings).unwrap();
+        assert!((shifted.weekend_pct - 50.0).abs() < 1e-9);
+        assert!((shifted.after_hours_pct - 50.0).abs() < 1e-9);
+        assert!(shifted.work_pattern.iter().any(|c| c.day_of_week == 0 && c.hour_of_day == 22));
+        assert!(shifted.work_pattern.iter().any(|c| c.day_of_week == 6 && c.hour_of_day == 11 && c.is_core_hours));
+    }
+
+    #[test]
+    fn test_ping_pong_index_counts_review_push_rounds() {
+        let conn = setup_db();
+        conn.execute_batch(
+            "INSERT INTO users (id, github_id, login, tracked) VALUES (1, 101, 'author', 1), (2, 102, 'reviewer', 0);
+             INSERT INTO pull_requests (id, github_id, repo_id, number, title, state, author_id, created_at, updated_at,
+                                        merged_at, labels)
+             VALUES (1, 1, 1, 1, 'Back and forth', 'MERGED', 1, '2024-03-01T00:00:00Z', '2024-03-05T00:00:00Z',
+                     '2024-03-05T00:00:00Z', '[]'),
+                    (2, 2, 1, 2, 'Clean', 'MERGED', 1, '2024-03-01T00:00:00Z', '2024-03-02T00:00:00Z',
+                     '2024-03-02T00:00:00Z', '[]');
+             -- PR 1: push, review, push, review, push, self-comment, review
+             INSERT INTO pr_commits (pr_id, oid, committed_at) VALUES
+                 (1, 'a1', '2024-03-01T01:00:00Z'), (1, 'a2', '2024-03-02T01:00:00Z'), (1, 'a3', '2024-03-03T01:00:00Z'),
+                 (2, 'b1', '2024-03-01T01:00:00Z'), (2, 'b2', '2024-03-01T02:00:00Z');
+             INSERT INTO pr_reviews (github_id, pr_id, reviewer_id, state, submitted_at) VALUES
+                 (1, 1, 2, 'CHANGES_REQUESTED', '2024-03-01T12:00:00Z'),
+                 (2, 1, 2, 'CHANGES_REQUESTED', '2024-03-02T12:00:00Z'),
+                 (3, 1, 1, 'COMMENTED', '2024-03-03T06:00:00Z'),
+                 (4, 1, 2, 'APPROVED', '2024-03-04T12:00:00Z'),
+                 (5, 2, 2, 'APPROVED', '2024-03-01T12:00:00Z');",
+        )
+        .unwrap();
+
+        let period = Period {
+            days: 30,
+            since: "2024-03-01T00:00:00Z".to_string(),
+        };
+        let index = get_ping_pong_index(&conn, &period).unwrap();
+        assert_eq!(index.merged_prs, 2);
+        assert_eq!(index.max_rounds, 2);
+        assert_eq!(index.prs_with_ping_pong, 1);
+        assert_eq!(index.median_rounds, Some(1.0));
+
+        assert_eq!(ping_pong_rounds(&[false, true, false, true, false, true]), 2);
+        assert_eq!(ping_pong_rounds(&[false, false, true, true]), 0);
+    }
+
+    #[test]
+    fn test_per_capita_velocity_by_headcount_basis() {
+        let conn = setup_db();
+        conn.execute_batch(
+            "INSERT INTO users (id, github_id, login, tracked) VALUES
+                 (1, 101, 'alice', 1), (2, 102, 'bob', 1), (3, 103, 'carol', 1), (4, 104, 'dave', 1), (5, 105, 'outsider', 0);",
+        )
+        .unwrap();
+        for (number, author_id, merged, loc) in [(1, 1, true, 100), (2, 1, true, 50), (3, 2, true, 150), (4, 2, false, 999), (5, 5, true, 999)] {
+            conn.execute(
+                "INSERT INTO pull_requests (github_id, repo_id, number, title, state, author_id, created_at, updated_at, merged_at, additions, deletions, labels)
+                 VALUES (?1, 1, ?1, 'PR', 'MERGED', ?2, datetime('now', '-3 days'), datetime('now', '-2 days'),
+                         CASE WHEN ?3 THEN datetime('now', '-2 days') END, ?4, 0, '[]')",
+                params![number, author_id, merged, loc],
+            )
+            .unwrap();
+        }
+
+        let active = get_per_capita_velocity(&conn, &rolling(30), "active", 1).unwrap();
+        assert_eq!((active.merged_prs, active.merged_loc, active.headcount), (3, 300, 2));
+        assert!((active.prs_per_capita - 1.5).abs() < 1e-9);
+        assert!((active.loc_per_capita - 150.0).abs() < 1e-9);
+
+        let tracked = get_per_capita_velocity(&conn, &rolling(30), "tracked", 1).unwrap();
+        assert_eq!(tracked.headcount, 4);
+        assert!((tracked.prs_per_capita - 0.75).abs() < 1e-9);
+        assert!((tracked.loc_per_capita - 75.0).abs() < 1e-9);
+
+        assert!(get_per_capita_velocity(&conn, &rolling(30), "everyone", 1).is_err());
+    }
+
+    #[test]
+    fn test_per_capita_velocity_without_headcount_is_zero() {
+        let conn = setup_db();
+        let velocity = get_per_capita_velocity(&conn, &rolling(30), "tracked", 1).unwrap();
+        assert_eq!((velocity.headcount, velocity.merged_prs), (0, 0));
+        assert_eq!(velocity.prs_per_capita, 0.0);
+        assert_eq!(velocity.loc_per_capita, 0.0);
+    }
+
+    #[test]
+    fn test_sla_breaches_report_late_p0_response() {
+        let conn = setup_db();
+        conn.execute_batch(
+            "INSERT INTO issues (id, github_id, repo_id, number, title, state, created_at, updated_at, closed_at, first_response_at, labels)
+             VALUES
+                 (1, 1, 1, 1, 'Outage', 'closed', '2024-03-01T00:00:00Z', '2024-03-01T12:00:00Z', '2024-03-01T10:00:00Z', '2024-03-01T06:00:00Z', '[\"P0\"]'),
+                 (2, 2, 1, 2, 'Quick fix', 'closed', '2024-03-02T00:00:00Z', '2024-03-02T03:00:00Z', '2024-03-02T03:00:00Z', '2024-03-02T01:00:00Z', '[\"p0\"]'),
+                 (3, 3, 1, 3, 'Unanswered', 'open', '2024-03-04T00:00:00Z', '2024-03-04T00:00:00Z', NULL, NULL, '[\"P0\"]'),
+                 (4, 4, 1, 4, 'Slow but unlabeled', 'open', '2024-03-01T00:00:00Z', '2024-03-01T00:00:00Z', NULL, NULL, '[]');",
+        )
+        .unwrap();
+        let rules = vec![SlaRule {
+            label: "P0".to_string(),
+            response_hours: Some(4.0),
+            resolution_hours: Some(24.0),
+        }];
+        let now = DateTime::parse_from_rfc3339("2024-03-04T12:00:00Z").unwrap().with_timezone(&Utc);
+        let period = Period::new(30, "rolling", now);
+
+        let breaches = get_sla_breaches(&conn, &period, &rules, now).unwrap();
+        let summary: Vec<(i32, &str, bool)> = breaches.iter().map(|b| (b.number, b.target.as_str(), b.ongoing)).collect();
+        assert_eq!(summary, vec![(1, "response", false), (3, "response", true)]);
+
+        let late = &breaches[0];
+        assert_eq!(late.label, "P0");
+        assert_eq!(late.target_hours, 4.0);
+        assert!((late.actual_hours - 6.0).abs() < 1e-6);
+        assert!((breaches[1].actual_hours - 12.0).abs() < 1e-6);
+
+        assert!(get_sla_breaches(&conn, &period, &[], now).unwrap().is_empty());
+    }
+
+    fn insert_merged_prs(conn: &Connection, repo_id: i64, author_ids: &[i64]) {
+        for author_id in author_ids {
+            conn.execute(
+                "INSERT INTO pull_requests (github_id, repo_id, number, title, state, author_id, created_at, updated_at, merged_at, labels)
+                 VALUES ((SELECT COALESCE(MAX(github_id), 0) + 1 FROM pull_requests), ?1,
+                         (SELECT COALESCE(MAX(number), 0) + 1 FROM pull_requests), 'PR', 'MERGED', ?2,
+                         datetime('now', '-5 days'), datetime('now', '-4 days'), datetime('now', '-4 days'), '[]')",
+                params![repo_id, author_id],
+            )
+            .unwrap();
+        }
+    }
+
+    #[test]
+    fn test_bus_factor_helper() {
+        assert_eq!(bus_factor(&[], 0.5), 0);
+        assert_eq!(bus_factor(&[10], 0.5), 1);
+        assert_eq!(bus_factor(&[5, 5], 0.5), 1);
+        assert_eq!(bus_factor(&[3, 3, 2, 2], 0.5), 2);
+    }
+
+    #[test]
+    fn test_org_bus_factor_concentrated_across_repos() {
+        let conn = setup_db();
+        conn.execute_batch(
+            "INSERT INTO repositories (id, owner, name) VALUES (2, 'acme', 'web');
+             INSERT INTO users (id, github_id, login, is_bot) VALUES
+                 (1, 101, 'hero', FALSE), (2, 102, 'bob', FALSE), (3, 103, 'carol', FALSE), (4, 104, 'renovate[bot]', TRUE);",
+        )
+        .unwrap();
+        // The hero writes 6 of 10 human PRs, spread over both repos; the bot's PRs don't count
+        insert_merged_prs(&conn, 1, &[1, 1, 1, 2, 2, 4, 4, 4, 4]);
+        insert_merged_prs(&conn, 2, &[1, 1, 1, 3, 3, 4, 4, 4, 4]);
+
+        let settings = queries::get_settings(&conn).unwrap();
+        let result = get_org_bus_factor(&conn, &rolling(30), &settings).unwrap();
+        assert_eq!(result.bus_factor, 1);
+        assert_eq!((result.merged_prs, result.contributors, result.repos), (10, 3, 2));
+        assert_eq!(result.key_contributors[0].login, "hero");
+        assert!((result.key_contributors[0].share_pct - 60.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_org_bus_factor_distributed() {
+        let conn = setup_db();
+        conn.execute_batch(
+            "INSERT INTO repositories (id, owner, name, enabled) VALUES (2, 'acme', 'web', TRUE), (3, 'acme', 'archived', FALSE);
+             INSERT INTO users (id, github_id, login) VALUES (1, 101, 'a'), (2, 102, 'b'), (3, 103, 'c'), (4, 104, 'd'), (5, 105, 'e');",
+        )
+        .unwrap();
+        insert_merged_prs(&conn, 1, &[1, 1, 2, 2, 3, 3]);
+        insert_merged_prs(&conn, 2, &[4, 4, 5, 5]);
+        // Disabled repositories are left out
+        insert_merged_prs(&conn, 3, &[1, 1, 1, 1, 1, 1, 1, 1]);
+
+        let settings = queries::get_settings(&conn).unwrap();
+        let result = get_org_bus_factor(&conn, &rolling(30), &settings).unwrap();
+        assert_eq!(result.merged_prs, 10);
+        assert_eq!(result.bus_factor, 3);
+        assert_eq!(result.key_contributors.len(), 3);
+    }
+
+    #[test]
+    fn test_close_velocity_trend_medians_per_week() {
+        let conn = setup_db();
+        // Week of Mon 2024-03-04: closed after 10h, 20h and 90h. Week of Mon 2024-03-11: after 2h and 4h.
+        let issues = [
+            ("2024-03-04T00:00:00Z", "2024-03-04T10:00:00Z"),
+            ("2024-03-05T00:00:00Z", "2024-03-05T20:00:00Z"),
+            ("2024-03-03T00:00:00Z", "2024-03-06T18:00:00Z"),
+            ("2024-03-11T08:00:00Z", "2024-03-11T10:00:00Z"),
+            ("2024-03-14T00:00:00Z", "2024-03-14T04:00:00Z"),
+            ("2024-01-01T00:00:00Z", "2024-01-02T00:00:00Z"), // Closed before the period
+        ];
+        for (number, (created, closed)) in issues.iter().enumerate() {
+            conn.execute(
+                "INSERT INTO issues (github_id, repo_id, number, title, state, created_at, updated_at, closed_at, labels)
+                 VALUES (?1, 1, ?1, 'Issue', 'closed', ?2, ?3, ?3, '[]')",
+                params![number as i64 + 1, created, closed],
+            )
+            .unwrap();
+        }
+        let now = DateTime::parse_from_rfc3339("2024-03-15T00:00:00Z").unwrap().with_timezone(&Utc);
+        let period = Period::new(30, "rolling", now);
+
+        let weekly = get_close_velocity_trend(&conn, &period, "week").unwrap();
+        let summary: Vec<(&str, i32, Option<f64>)> =
+            weekly.iter().map(|b| (b.bucket.as_str(), b.closed_issues, b.median_open_hours)).collect();
+        assert_eq!(summary, vec![("2024-03-04", 3, Some(20.0)), ("2024-03-11", 2, Some(3.0))]);
+
+        let monthly = get_close_velocity_trend(&conn, &period, "month").unwrap();
+        assert_eq!(monthly.len(), 1);
+        assert_eq!((monthly[0].bucket.as_str(), monthly[0].closed_issues), ("2024-03-01", 5));
+        assert_eq!(monthly[0].median_open_hours, Some(10.0));
+    }
+
+    #[test]
+    fn test_approval_latency_excludes_unapproved_merges() {
+        let conn = setup_db();
+        for number in 1..=5 {
+            insert_funnel_pr(&conn, number, Some(48));
+        }
+        insert_funnel_pr(&conn, 6, None);
+
+        insert_funnel_review(&conn, 1, 1, "COMMENTED", 1);
+        insert_funnel_review(&conn, 2, 1, "APPROVED", 2);
+        insert_funnel_review(&conn, 3, 1, "APPROVED", 8);
+        insert_funnel_review(&conn, 4, 2, "APPROVED", 4);
+        insert_funnel_review(&conn, 5, 3, "APPROVED", 6);
+        insert_funnel_review(&conn, 6, 4, "APPROVED", 30);
+        // Merged with comments only, and approved but never merged
+        insert_funnel_review(&conn, 7, 5, "COMMENTED", 3);
+        insert_funnel_review(&conn, 8, 6, "APPROVED", 1);
+
+        let latency = get_approval_latency(&conn, &rolling(30), false, &[]).unwrap();
+        assert_eq!((latency.merged_prs, latency.approved_prs, latency.unapproved_prs), (5, 4, 1));
+        assert_eq!(latency.median_hours, Some(5.0));
+        assert!((latency.p90_hours.unwrap() - 30.0).abs() < 1e-6);
+        assert_eq!(latency.prs.iter().map(|p| p.number).collect::<Vec<_>>(), vec![4, 3, 2, 1]);
+        assert!((latency.prs[3].approval_hours - 2.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_bot_reviews_do_not_count_as_first_review_or_approval() {
+        let conn = setup_db();
+        conn.execute("INSERT INTO users (id, github_id, login, is_bot) VALUES (9, 109, 'ci-bot', 1)", [])
+            .unwrap();
+        insert_funnel_pr(&conn, 1, Some(48));
+        insert_funnel_review(&conn, 1, 1, "APPROVED", 0);
+        insert_funnel_review(&conn, 2, 1, "APPROVED", 10);
+        conn.execute("UPDATE pr_reviews SET reviewer_id = 9 WHERE github_id = 1", []).unwrap();
+
+        let bots = queries::get_bot_reviewer_ids(&conn, &[]).unwrap();
+        let dist = get_review_latency_distribution(&conn, &rolling(30), false, &bots).unwrap();
+        assert_eq!((dist.under_1h, dist.h4_to_24), (0, 1));
+
+        let latency = get_approval_latency(&conn, &rolling(30), false, &bots).unwrap();
+        assert!((latency.prs[0].approval_hours - 10.0).abs() < 1e-6);
+
+        let funnel = get_pr_funnel(&conn, &rolling(30), false, &bots).unwrap();
+        assert_eq!(funnel.open_to_first_review_hours, Some(10.0));
+        assert_eq!(funnel.first_review_to_approval_hours, Some(0.0));
+        assert_eq!(get_pr_funnel(&conn, &rolling(30), false, &[]).unwrap().open_to_first_review_hours, Some(0.0));
+    }
+
+    #[test]
+    fn test_bot_review_does_not_clear_review_debt() {
+        let conn = setup_db();
+        let now = Utc::now();
+        conn.execute_batch(
+            "INSERT INTO users (id, github_id, login) VALUES (1, 101, 'alice');
+             INSERT INTO users (id, github_id, login, is_bot) VALUES (9, 109, 'ci-bot', 1);",
+        )
+        .unwrap();
+        let opened = (now - Duration::hours(12)).format("%Y-%m-%dT%H:%M:%SZ").to_string();
+        conn.execute(
+            "INSERT INTO pull_requests (id, github_id, repo_id, number, title, state, author_id, created_at, updated_at, labels)
+             VALUES (1, 1, 1, 1, 'PR', 'OPEN', 1, ?1, ?1, '[]')",
+            params![opened],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO pr_reviews (github_id, pr_id, reviewer_id, state, submitted_at) VALUES (1, 1, 9, 'COMMENTED', ?1)",
+            params![opened],
+        )
+        .unwrap();
+
+        let bots = queries::get_bot_reviewer_ids(&conn, &[]).unwrap();
+        assert_eq!(get_review_debt(&conn, now, &bots).unwrap().awaiting_review, 1);
+        assert_eq!(get_review_debt(&conn, now, &[]).unwrap().awaiting_review, 0);
+    }
+
+    #[test]
+    fn test_percentile_nearest_rank() {
+        assert_eq!(percentile(&[], 90.0), None);
+        assert_eq!(percentile(&[7.0], 90.0), Some(7.0));
+        let values: Vec<f64> = (1..=10).map(|v| v as f64).collect();
+        assert_eq!(percentile(&values, 90.0), Some(9.0));
+        assert_eq!(percentile(&values, 50.0), Some(5.0));
+    }
+
+    #[test]
+    fn test_contribution_by_tenure_attributes_tiers() {
+        let conn = setup_db();
+        conn.execute_batch(
+            "INSERT INTO users (id, github_id, login) VALUES (1, 101, 'veteran'), (2, 102, 'newcomer');",
+        )
+        .unwrap();
+        // The veteran's first merge was two years ago; the newcomer's first merges are this month
+        for (number, author_id, merged_days_ago) in [(1, 1, 730), (2, 1, 5), (3, 1, 3), (4, 1, 2), (5, 2, 10), (6, 2, 1)] {
+            conn.execute(
+                "INSERT INTO pull_requests (github_id, repo_id, number, title, state, author_id, created_at, updated_at, merged_at, labels)
+                 VALUES (?1, 1, ?1, 'PR', 'MERGED', ?2, datetime('now', '-' || (?3 + 1) || ' days'),
+                         datetime('now', '-' || ?3 || ' days'), datetime('now', '-' || ?3 || ' days'), '[]')",
+                params![number, author_id, merged_days_ago],
+            )
+            .unwrap();
+        }
+
+        let tiers = get_contribution_by_tenure(&conn, &rolling(30), &[]).unwrap();
+        let summary: Vec<(&str, i32, usize)> = tiers.iter().map(|t| (t.tier.as_str(), t.merged_prs, t.contributors)).collect();
+        assert_eq!(summary, vec![("new", 2, 1), ("established", 0, 0), ("veteran", 3, 1)]);
+        assert!((tiers[0].merged_pct - 40.0).abs() < 1e-9);
+        assert!((tiers[2].merged_pct - 60.0).abs() < 1e-9);
+
+        assert_eq!(tenure_tier(11), "new");
+        assert_eq!(tenure_tier(12), "established");
+        assert_eq!(tenure_tier(60), "veteran");
+    }
+
+    fn insert_reviews(conn: &Connection, reviews: &[(i64, i64)]) {
+        for (i, (pr_number, reviewer_id)) in reviews.iter().enumerate() {
+            conn.execute(
+                "INSERT INTO pr_reviews (github_id, pr_id, reviewer_id, state, submitted_at)
+                 SELECT ?1, id, ?2, 'APPROVED', datetime('now', '-2 days') FROM pull_requests WHERE number = ?3",
+                params![i as i64 + 1, reviewer_id, pr_number],
+            )
+            .unwrap();
+        }
+    }
+
+    fn setup_review_balance_db() -> Connection {
+        let conn = setup_db();
+        conn.execute_batch(
+            "INSERT INTO users (id, github_id, login, tracked, is_bot) VALUES
+                 (1, 101, 'alice', 1, FALSE), (2, 102, 'bob', 1, FALSE), (3, 103, 'carol', 1, FALSE),
+                 (4, 104, 'dependabot[bot]', 0, TRUE), (5, 105, 'contractor', 0, FALSE);",
+        )
+        .unwrap();
+        for number in 1..=6 {
+            conn.execute(
+                "INSERT INTO pull_requests (github_id, repo_id, number, title, state, author_id, created_at, updated_at, labels)
+                 VALUES (?1, 1, ?1, 'PR', 'OPEN', 5, datetime('now', '-3 days'), datetime('now', '-2 days'), '[]')",
+                params![number],
+            )
+            .unwrap();
+        }
+        conn
+    }
+
+    #[test]
+    fn test_review_balance_even_vs_lopsided() {
+        let conn = setup_review_balance_db();
+        // The contractor authored every PR; their self-review and the bot's review don't count
+        insert_reviews(&conn, &[(1, 1), (2, 1), (3, 2), (4, 2), (5, 3), (6, 3), (1, 5), (2, 4)]);
+        let even = get_review_balance(&conn, &rolling(30), &[]).unwrap();
+        assert_eq!(even.total_reviews, 6);
+        assert!(even.inequality.abs() < 1e-9);
+        let loads: Vec<(&str, i32)> = even.reviewers.iter().map(|r| (r.login.as_str(), r.prs_reviewed)).collect();
+        assert_eq!(loads, vec![("alice", 2), ("bob", 2), ("carol", 2)]);
+
+        let conn = setup_review_balance_db();
+        insert_reviews(&conn, &[(1, 2), (2, 2), (3, 2), (4, 2), (5, 2), (6, 3)]);
+        let lopsided = get_review_balance(&conn, &rolling(30), &[]).unwrap();
+        // Alice reviewed nothing and still counts toward the spread
+        assert_eq!(lopsided.reviewers.last().map(|r| (r.login.as_str(), r.prs_reviewed)), Some(("alice", 0)));
+        assert!(lopsided.inequality > even.inequality);
+        assert!(lopsided.inequality > 0.8, "{}", lopsided.inequality);
+    }
+
+    #[test]
+    fn test_normalized_gini_bounds() {
+        assert_eq!(normalized_gini(&[]), 0.0);
+        assert_eq!(normalized_gini(&[0, 0]), 0.0);
+        assert!(normalized_gini(&[4, 4, 4, 4]).abs() < 1e-9);
+        assert!((normalized_gini(&[0, 0, 0, 9]) - 1.0).abs() < 1e-9);
+        assert_eq!(normalized_gini(&[5]), 1.0);
+    }
+
+    #[test]
+    fn test_pr_type_labels_take_precedence_over_title() {
+        let conn = setup_db();
+        conn.execute_batch(
+            "INSERT INTO users (id, github_id, login, tracked) VALUES (1, 1, 'alice', 1);
+             INSERT INTO pull_requests (github_id, repo_id, number, title, state, author_id, created_at, updated_at, labels)
+             VALUES
+                 (1, 1, 1, 'fix: x', 'open', 1, datetime('now', '-2 days'), datetime('now', '-2 days'), '[\"Feature \"]'),
+                 (2, 1, 2, 'fix: y', 'open', 1, datetime('now', '-2 days'), datetime('now', '-2 days'), '[\"bug\", \"chore\"]'),
+                 (3, 1, 3, 'fix: z', 'open', 1, datetime('now', '-2 days'), datetime('now', '-2 days'), '[]');",
+        )
+        .unwrap();
+
+        let settings = queries::get_settings(&conn).unwrap();
+        let types = get_pr_type_distribution(&conn, &rolling(30), &settings.pr_type_label_rules, &RevertExclusion::default()).unwrap();
+        let count = |t: &str| types.iter().find(|b| b.pr_type == t).map(|b| b.count).unwrap_or(0);
+        // Label beats the "fix" title keyword; chore outranks bug by default
+        assert_eq!(count("feature"), 1);
+        assert_eq!(count("other"), 1);
+        assert_eq!(count("bug_fix"), 1);
+
+        // Without rules, everything falls back to the title
+        let types = get_pr_type_distribution(&conn, &rolling(30), &[], &RevertExclusion::default()).unwrap();
+        assert_eq!(types.len(), 1);
+        assert_eq!((types[0].pr_type.as_str(), types[0].count), ("bug_fix", 3));
+    }
+
+    #[test]
+    fn test_classify_pr_type_uses_first_matching_rule() {
+        let rules = vec![
+            PrTypeLabelRule { label: "bug".into(), pr_type: "bug_fix".into() },
+            PrTypeLabelRule { label: "feature".into(), pr_type: "feature".into() },
+        ];
+        let labels = vec!["feature".to_string(), "BUG".to_string()];
+        assert_eq!(classify_pr_type("Add thing", &labels, &rules), "bug_fix");
+        assert_eq!(classify_pr_type("Add thing", &[], &rules), "feature");
+        assert_eq!(classify_pr_type("Update README docs", &[], &rules), "docs");
+    }
+
+    #[test]
+    fn test_exclude_reverts_drops_revert_and_reverted_pr() {
+        let conn = setup_db();
+        conn.execute_batch(
+            "INSERT INTO users (id, github_id, login, tracked) VALUES (1, 101, 'alice', 1);
+             INSERT INTO pull_requests (id, github_id, repo_id, number, title, body, state, author_id, created_at, updated_at, merged_at, labels)
+             VALUES
+                 (1, 1, 1, 10, 'Add caching', NULL, 'MERGED', 1, datetime('now', '-6 days'), datetime('now', '-5 days'), datetime('now', '-5 days'), '[]'),
+                 (2, 2, 1, 11, 'Revert \"Add caching\"', 'Reverts acme/api#10', 'MERGED', 1, datetime('now', '-4 days'), datetime('now', '-4 days'), datetime('now', '-4 days'), '[]'),
+                 (3, 3, 1, 12, 'Tune pool size', NULL, 'MERGED', 1, datetime('now', '-3 days'), datetime('now', '-2 days'), datetime('now', '-2 days'), '[]');",
+        )
+        .unwrap();
+
+        assert_eq!(find_reverts(&conn, &rolling(30).since).unwrap(), vec![RevertPair { revert_id: 2, reverted_id: Some(1) }]);
+
+        let period = rolling(30);
+        let mut settings = queries::get_settings(&conn).unwrap();
+        assert!(!settings.exclude_reverts);
+        let speed = get_speed_metrics(&conn, &period, &settings).unwrap();
+        let ease = get_ease_metrics(&conn, &period, &settings).unwrap();
+        let quality = get_quality_metrics(&conn, &period, &settings).unwrap();
+        let overview = get_overview_metrics(&conn, &period, &speed, &ease, &quality, &settings).unwrap();
+        assert_eq!(overview.total_prs, 3);
+
+        settings.exclude_reverts = true;
+        let speed = get_speed_metrics(&conn, &period, &settings).unwrap();
+        let quality = get_quality_metrics(&conn, &period, &settings).unwrap();
+        let overview = get_overview_metrics(&conn, &period, &speed, &ease, &quality, &settings).unwrap();
+        assert_eq!(overview.total_prs, 1);
+        assert_eq!(quality.pr_type_distribution.iter().map(|t| t.count).sum::<i32>(), 1);
+        assert_eq!(quality.files_per_pr_distribution.range_1_3, 1);
+    }
+
+    #[test]
+    fn test_find_reverts_matches_reverted_commit_sha() {
+        let conn = setup_db();
+        conn.execute_batch(
+            "INSERT INTO pull_requests (id, github_id, repo_id, number, title, body, state, created_at, updated_at, labels)
+             VALUES
+                 (1, 1, 1, 10, 'Add caching', NULL, 'MERGED', datetime('now', '-6 days'), datetime('now', '-5 days'), '[]'),
+                 (2, 2, 1, 11, 'Back out cache layer', 'This reverts commit 1A2B3C4D5E.', 'OPEN', datetime('now', '-4 days'), datetime('now', '-4 days'), '[]'),
+                 (3, 3, 1, 12, 'Revert: unrelated wording', 'This reverts commit ffffffffff.', 'OPEN', datetime('now', '-4 days'), datetime('now', '-4 days'), '[]');
+             INSERT INTO pr_commits (pr_id, oid, committed_at) VALUES (1, '1a2b3c4d5e6f', datetime('now', '-6 days'));",
+        )
+        .unwrap();
+
+        // PR 3 reverts an unknown sha and isn't titled like a revert, so it's left alone
+        assert_eq!(find_reverts(&conn, &rolling(30).since).unwrap(), vec![RevertPair { revert_id: 2, reverted_id: Some(1) }]);
+    }
+
+    #[test]
+    fn test_find_reverts_needs_a_target_or_marker_and_stays_in_period() {
+        let conn = setup_db();
+        conn.execute_batch(
+            "INSERT INTO pull_requests (id, github_id, repo_id, number, title, body, state, created_at, updated_at, merged_at, labels)
+             VALUES
+                 (1, 1, 1, 10, 'Revert to the old dashboard colours', 'Designers preferred them', 'MERGED',
+                  datetime('now', '-4 days'), datetime('now', '-3 days'), datetime('now', '-3 days'), '[]'),
+                 (2, 2, 1, 11, 'Add caching', NULL, 'MERGED',
+                  datetime('now', '-90 days'), datetime('now', '-89 days'), datetime('now', '-89 days'), '[]'),
+                 (3, 3, 1, 12, 'Revert \"Add caching\"', 'Reverts acme/api#11', 'MERGED',
+                  datetime('now', '-80 days'), datetime('now', '-80 days'), datetime('now', '-80 days'), '[]'),
+                 (4, 4, 1, 13, 'Revert \"Add caching\"', NULL, 'MERGED',
+                  datetime('now', '-2 days'), datetime('now', '-1 days'), datetime('now', '-1 days'), '[]');",
+        )
+        .unwrap();
+
+        // PR 1 is an ordinary PR; PR 3 falls outside the window; PR 4 resolves by its quoted
+        // title even though the reverted PR is older than the window
+        assert_eq!(find_reverts(&conn, &rolling(30).since).unwrap(), vec![RevertPair { revert_id: 4, reverted_id: Some(2) }]);
+    }
+
+    #[test]
+    fn test_planned_vs_unplanned_by_milestone_label_and_link() {
+        let conn = setup_db();
+        conn.execute_batch(
+            "INSERT INTO users (id, github_id, login, tracked) VALUES (1, 101, 'alice', 1);
+             INSERT INTO milestones (id, github_id, repo_id, title, state) VALUES (1, 1, 1, 'Q3', 'open');
+             INSERT INTO issues (id, github_id, repo_id, number, title, state, milestone_id, created_at, updated_at, labels)
+             VALUES
+                 (1, 1, 1, 1, 'Roadmap item', 'open', 1, datetime('now', '-5 days'), datetime('now', '-5 days'), '[]'),
+                 (2, 2, 1, 2, 'Labeled plan', 'open', NULL, datetime('now', '-5 days'), datetime('now', '-5 days'), '[\"Roadmap\"]'),
+                 (3, 3, 1, 3, 'Orphan bug', 'open', NULL, datetime('now', '-5 days'), datetime('now', '-5 days'), '[\"bug\"]');
+             INSERT INTO pull_requests (id, github_id, repo_id, number, title, state, author_id, created_at, updated_at, labels)
+             VALUES
+                 (1, 1, 1, 10, 'Implement roadmap item', 'OPEN', 1, datetime('now', '-3 days'), datetime('now', '-3 days'), '[]'),
+                 (2, 2, 1, 11, 'Hotfix', 'OPEN', 1, datetime('now', '-3 days'), datetime('now', '-3 days'), '[]');
+             INSERT INTO pr_issue_links (pr_id, issue_number) VALUES (1, 3), (1, 1), (2, 3);",
+        )
+        .unwrap();
+
+        let settings = queries::get_settings(&conn).unwrap();
+        let split = get_planned_vs_unplanned(&conn, &rolling(30), &settings.planning_labels).unwrap();
+        assert_eq!((split.planned_issues, split.unplanned_issues), (2, 1));
+        assert_eq!((split.planned_prs, split.unplanned_prs), (1, 1));
+        assert_eq!((split.planned, split.unplanned), (3, 2));
+        assert!((split.planned_pct - 60.0).abs() < 1e-9);
+        assert!((split.unplanned_pct - 40.0).abs() < 1e-9);
+
+        let split = get_planned_vs_unplanned(&conn, &rolling(30), &[]).unwrap();
+        assert_eq!((split.planned, split.unplanned), (2, 3));
+    }
+
+    #[test]
+    fn test_reviewed_loc_pct_weights_by_lines_changed() {
+        let conn = setup_db();
+        conn.execute_batch(
+            "INSERT INTO users (id, github_id, login, tracked) VALUES (1, 101, 'alice', 1), (2, 102, 'bob', 1);
+             INSERT INTO pull_requests (id, github_id, repo_id, number, title, state, author_id, created_at, updated_at, merged_at, additions, deletions, labels)
+             VALUES
+                 (1, 1, 1, 1, 'Small reviewed', 'MERGED', 1, datetime('now', '-5 days'), datetime('now', '-4 days'), datetime('now', '-4 days'), 40, 10, '[]'),
+                 (2, 2, 1, 2, 'Huge unreviewed', 'MERGED', 2, datetime('now', '-5 days'), datetime('now', '-4 days'), datetime('now', '-4 days'), 1800, 150, '[]'),
+                 (3, 3, 1, 3, 'Open, reviewed', 'OPEN', 2, datetime('now', '-5 days'), datetime('now', '-4 days'), NULL, 5000, 0, '[]');
+             INSERT INTO pr_reviews (github_id, pr_id, reviewer_id, state, submitted_at) VALUES
+                 (11, 1, 2, 'APPROVED', datetime('now', '-4 days')),
+                 (12, 3, 1, 'APPROVED', datetime('now', '-4 days'));",
+        )
+        .unwrap();
+
+        let settings = queries::get_settings(&conn).unwrap();
+        let quality = get_quality_metrics(&conn, &rolling(30), &settings).unwrap();
+        // Half the merged PRs were reviewed, but only 50 of 2000 merged lines
+        assert!((quality.review_coverage_pct - 50.0).abs() < 1e-9);
+        assert!((quality.reviewed_loc_pct - 2.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_review_bypasses_lists_merged_prs_without_outside_approval() {
+        let conn = setup_db();
+        conn.execute_batch(
+            "INSERT INTO users (id, github_id, login, tracked, is_bot) VALUES (1, 101, 'alice', 1, 0), (2, 102, 'bob', 1, 0), (3, 103, 'renovate[bot]', 0, 1);
+             INSERT INTO pull_requests (id, github_id, repo_id, number, title, state, author_id, created_at, updated_at, merged_at, merge_method, labels)
+             VALUES
+                 (1, 1, 1, 1, 'Approved', 'MERGED', 1, datetime('now', '-5 days'), datetime('now', '-4 days'), datetime('now', '-4 days'), 'manual', '[]'),
+                 (2, 2, 1, 2, 'No reviews', 'MERGED', 1, datetime('now', '-5 days'), datetime('now', '-3 days'), datetime('now', '-3 days'), 'manual', '[]'),
+                 (3, 3, 1, 3, 'Commented only', 'MERGED', 2, datetime('now', '-5 days'), datetime('now', '-2 days'), datetime('now', '-2 days'), 'auto_merge', '[]'),
+                 (4, 4, 1, 4, 'Self approved', 'MERGED', 2, datetime('now', '-5 days'), datetime('now', '-1 days'), datetime('now', '-1 days'), 'manual', '[]'),
+                 (5, 5, 1, 5, 'Bump deps', 'MERGED', 3, datetime('now', '-5 days'), datetime('now', '-1 days'), datetime('now', '-1 days'), 'auto_merge', '[]'),
+                 (6, 6, 1, 6, 'Open, unreviewed', 'OPEN', 1, datetime('now', '-5 days'), datetime('now', '-1 days'), NULL, NULL, '[]'),
+                 (7, 7, 1, 7, 'Bot approved', 'MERGED', 1, datetime('now', '-5 days'), datetime('now', '-12 hours'), datetime('now', '-12 hours'), 'manual', '[]');
+             INSERT INTO pr_reviews (github_id, pr_id, reviewer_id, state, submitted_at) VALUES
+                 (11, 1, 2, 'APPROVED', datetime('now', '-4 days')),
+                 (12, 3, 1, 'COMMENTED', datetime('now', '-3 days')),
+                 (13, 4, 2, 'APPROVED', datetime('now', '-2 days')),
+                 (14, 7, 3, 'APPROVED', datetime('now', '-13 hours'));",
+        )
+        .unwrap();
+
+        let bots = queries::get_bot_reviewer_ids(&conn, &[]).unwrap();
+        let bypasses = get_review_bypasses(&conn, &rolling(30), &bots).unwrap();
+        let numbers: Vec<i32> = bypasses.iter().map(|b| b.number).collect();
+        // A bot's approval doesn't clear a PR
+        assert_eq!(numbers, vec![7, 5, 4, 3, 2]);
+        assert_eq!(bypasses[0].review_count, 0);
+
+        let by_number = |n: i32| bypasses.iter().find(|b| b.number == n).unwrap();
+        assert!(by_number(5).author_is_bot);
+        assert!(by_number(4).self_approved);
+        assert_eq!(by_number(3).review_count, 1);
+        assert_eq!(by_number(3).merge_method.as_deref(), Some("auto_merge"));
+        assert_eq!(by_number(2).author.as_deref(), Some("alice"));
+        assert_eq!(by_number(2).review_count, 0);
+    }
+
+    #[test]
+    fn test_throughput_by_weekday_uses_team_timezone() {
+        let conn = setup_db();
+        conn.execute_batch(
+            "INSERT INTO pull_requests (github_id, repo_id, number, title, state, created_at, updated_at, merged_at, labels)
+             VALUES
+                 (1, 1, 1, 'Monday morning', 'MERGED', '2024-03-01T09:00:00Z', '2024-03-04T10:00:00Z', '2024-03-04T10:00:00Z', '[]'),
+                 (2, 1, 2, 'Monday evening in UTC-5', 'MERGED', '2024-03-01T09:00:00Z', '2024-03-05T02:00:00Z', '2024-03-05T02:00:00Z', '[]'),
+                 (3, 1, 3, 'Friday noon', 'MERGED', '2024-03-01T09:00:00Z', '2024-03-08T12:00:00Z', '2024-03-08T12:00:00Z', '[]'),
+                 (4, 1, 4, 'Late Saturday', 'MERGED', '2024-03-01T09:00:00Z', '2024-03-09T23:30:00Z', '2024-03-09T23:30:00Z', '[]'),
+                 (5, 1, 5, 'Still open', 'OPEN', '2024-03-01T09:00:00Z', '2024-03-06T12:00:00Z', NULL, '[]');",
+        )
+        .unwrap();
+        let now = DateTime::parse_from_rfc3339("2024-03-15T00:00:00Z").unwrap().with_timezone(&Utc);
+        let period = Period::new(30, "rolling", now);
+        let counts = |offset| -> Vec<(String, i64)> {
+            get_throughput_by_weekday(&conn, &period, offset, &RevertExclusion::default())
+                .unwrap()
+                .into_iter()
+                .map(|day| (day.weekday, day.merged_prs))
+                .collect()
+        };
+
+        let utc = counts(0);
+        assert_eq!(utc.len(), 7);
+        assert_eq!(utc[0], ("Mon".to_string(), 1));
+        assert_eq!(utc[1].1, 1); // Tue
+        assert_eq!(utc[4].1, 1); // Fri
+        assert_eq!(utc[5].1, 1); // Sat
+        assert_eq!(utc[6], ("Sun".to_string(), 0));
+
+        // UTC-5: the 02:00 Tuesday merge lands on Monday evening
+        let new_york: Vec<i64> = counts(-300).into_iter().map(|(_, n)| n).collect();
+        assert_eq!(new_york, vec![2, 0, 0, 0, 1, 1, 0]);
+
+        // UTC+1: the 23:30 Saturday merge lands on Sunday
+        let berlin: Vec<i64> = counts(60).into_iter().map(|(_, n)| n).collect();
+        assert_eq!(berlin, vec![1, 1, 0, 0, 1, 0, 1]);
+    }
+
+    #[test]
+    fn test_bot_reviews_excluded_from_coverage_and_reviewer_load() {
+        let conn = setup_db();
+        conn.execute_batch(
+            "INSERT INTO users (id, github_id, login, tracked, is_bot) VALUES
+                 (1, 101, 'alice', 1, 0), (2, 102, 'bob', 1, 0), (3, 103, 'codescan[bot]', 0, 0), (4, 104, 'sonar', 0, 1);
+             INSERT INTO pull_requests (id, github_id, repo_id, number, title, state, author_id, created_at, updated_at, merged_at, labels)
+             VALUES
+                 (1, 1, 1, 1, 'Human review', 'MERGED', 1, datetime('now', '-5 days'), datetime('now', '-4 days'), datetime('now', '-4 days'), '[]'),
+                 (2, 2, 1, 2, 'Scanner only', 'MERGED', 1, datetime('now', '-5 days'), datetime('now', '-4 days'), datetime('now', '-4 days'), '[]'),
+                 (3, 3, 1, 3, 'Flagged bot only', 'MERGED', 1, datetime('now', '-5 days'), datetime('now', '-4 days'), datetime('now', '-4 days'), '[]');
+             INSERT INTO pr_reviews (github_id, pr_id, reviewer_id, state, submitted_at) VALUES
+                 (11, 1, 2, 'APPROVED', datetime('now', '-4 days')),
+                 (12, 2, 3, 'COMMENTED', datetime('now', '-4 days')),
+                 (13, 3, 4, 'APPROVED', datetime('now', '-4 days'));",
+        )
+        .unwrap();
+        let excluded_bots = vec!["dependabot".to_string()];
+
+        // Only the synced is_bot flag applies without an excluded-bots list
+        let flagged = queries::get_bot_reviewer_ids(&conn, &[]).unwrap();
+        assert_eq!(flagged, vec![4]);
+        let coverage = get_review_coverage_pct(&conn, &rolling(30), &RevertExclusion::default(), &flagged).unwrap();
+        assert!((coverage - 200.0 / 3.0).abs() < 1e-9);
+
+        let bots = queries::get_bot_reviewer_ids(&conn, &excluded_bots).unwrap();
+        assert_eq!(bots, vec![3, 4]);
+        let coverage = get_review_coverage_pct(&conn, &rolling(30), &RevertExclusion::default(), &bots).unwrap();
+        assert!((coverage - 100.0 / 3.0).abs() < 1e-9);
+
+        let balance = get_review_balance(&conn, &rolling(30), &excluded_bots).unwrap();
+        let load: Vec<(&str, i32)> = balance.reviewers.iter().map(|r| (r.login.as_str(), r.prs_reviewed)).collect();
+        assert_eq!(load, vec![("bob", 1), ("alice", 0)]);
+        assert_eq!(balance.total_reviews, 1);
+    }
+
+    #[test]
+    fn test_blocked_prs_window_follows_period_boundary() {
+        let conn = setup_db();
+        let now = DateTime::parse_from_rfc3339("2024-03-10T15:30:00Z").unwrap().with_timezone(&Utc);
+        // Updated the evening before the 7-day day-aligned window starts, inside the rolling 7x24h one
+        conn.execute(
+            "INSERT INTO pull_requests (github_id, repo_id, number, title, state, created_at, updated_at, labels, is_draft)
+             VALUES (1, 1, 1, 'Draft', 'OPEN', '2024-03-01T00:00:00Z', '2024-03-03T20:00:00Z', '[]', TRUE)",
+            [],
+        )
+        .unwrap();
+
+        let inclusive = get_blocked_prs(&conn, &Period::new(7, "inclusive_start", now), &[]).unwrap();
+        assert!(inclusive.is_empty());
+
+        let rolling = get_blocked_prs(&conn, &Period::new(7, "rolling", now), &[]).unwrap();
+        assert_eq!(rolling.len(), 1);
+    }
+}
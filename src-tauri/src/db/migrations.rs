@@ -1,9 +1,13 @@
 use anyhow::Result;
 use rusqlite::Connection;
 
+/// Stored in `PRAGMA user_version` after migrating; bump whenever a migration is added
+pub const SCHEMA_VERSION: i32 = 53;
+
 /// Run all database migrations
 pub fn run_migrations(conn: &Connection) -> Result<()> {
     conn.execute_batch(SCHEMA)?;
+    conn.execute_batch(SEARCH_FTS_SCHEMA)?;
 
     // Run migrations for existing databases
     migrate_add_embedding_columns(conn)?;
@@ -13,7 +17,54 @@ pub fn run_migrations(conn: &Connection) -> Result<()> {
     migrate_add_milestone_repo_github_index(conn)?;
     migrate_backfill_tracked_users(conn)?;
     migrate_add_settings_table(conn)?;
+    migrate_add_repo_default_branch(conn)?;
+    migrate_add_pr_merge_method(conn)?;
+    migrate_add_exclude_self_reviews_setting(conn)?;
+    migrate_add_pr_blocking_state(conn)?;
+    migrate_add_productivity_weight_settings(conn)?;
+    migrate_add_sync_old_reopened_setting(conn)?;
+    migrate_add_loc_basis_setting(conn)?;
+    migrate_add_sync_pr_files_setting(conn)?;
+    migrate_add_repo_excluded_bots(conn)?;
+    migrate_add_metric_precision_setting(conn)?;
+    migrate_add_min_active_prs_setting(conn)?;
+    migrate_add_exclude_weekends_setting(conn)?;
+    migrate_add_webhook_settings(conn)?;
+    migrate_add_period_boundary_setting(conn)?;
+    migrate_add_repo_primary_language(conn)?;
+    migrate_add_issue_first_response(conn)?;
+    migrate_add_embedding_model_columns(conn)?;
+    migrate_add_core_hours_settings(conn)?;
+    migrate_populate_search_fts(conn)?;
+    migrate_add_freshness_settings(conn)?;
+    migrate_add_sync_log_skipped_prs(conn)?;
+    migrate_add_pr_base_branch(conn)?;
+    migrate_add_include_merge_commit_loc_setting(conn)?;
+    migrate_add_embedding_http_settings(conn)?;
+    migrate_add_pr_commit_count(conn)?;
+    migrate_add_sync_strategy_setting(conn)?;
+    migrate_add_rejection_labels_setting(conn)?;
+    migrate_add_pr_author_association(conn)?;
+    migrate_add_internal_logins_setting(conn)?;
+    migrate_add_internal_orgs_setting(conn)?;
+    migrate_add_headcount_basis_setting(conn)?;
+    migrate_add_sla_rules_setting(conn)?;
+    migrate_add_repo_is_private(conn)?;
+    migrate_add_ai_min_source_similarity_setting(conn)?;
+    migrate_add_pr_type_label_rules_setting(conn)?;
+    migrate_add_exclude_reverts_setting(conn)?;
+    migrate_add_planning_labels_setting(conn)?;
+    migrate_add_user_profile_columns(conn)?;
+    migrate_add_milestone_counts_source_setting(conn)?;
+    migrate_add_utc_offset_minutes_setting(conn)?;
+    migrate_add_webhook_secret_set_setting(conn)?;
+    migrate_add_repo_is_demo(conn)?;
+    migrate_add_tier_medium_tolerance_setting(conn)?;
+    migrate_add_pr_details_fetched_at(conn)?;
+    migrate_rename_calendar_period_boundary(conn)?;
+    migrate_add_sync_log_skipped_old(conn)?;
 
+    conn.pragma_update(None, "user_version", SCHEMA_VERSION)?;
     tracing::info!("Database migrations completed");
     Ok(())
 }
@@ -236,6 +287,726 @@ fn migrate_add_settings_table(conn: &Connection) -> Result<()> {
     Ok(())
 }
 
+/// Add default_branch column to repositories
+fn migrate_add_repo_default_branch(conn: &Connection) -> Result<()> {
+    let has_default_branch: bool = conn
+        .query_row(
+            "SELECT COUNT(*) FROM pragma_table_info('repositories') WHERE name='default_branch'",
+            [],
+            |row| row.get(0),
+        )
+        .map(|count: i32| count > 0)
+        .unwrap_or(false);
+
+    if !has_default_branch {
+        tracing::info!("Adding default_branch column to repositories table...");
+        conn.execute("ALTER TABLE repositories ADD COLUMN default_branch TEXT", [])?;
+    }
+
+    Ok(())
+}
+
+/// Add merge_method to pull_requests and the matching latency setting
+fn migrate_add_pr_merge_method(conn: &Connection) -> Result<()> {
+    let has_merge_method: bool = conn
+        .query_row(
+            "SELECT COUNT(*) FROM pragma_table_info('pull_requests') WHERE name='merge_method'",
+            [],
+            |row| row.get(0),
+        )
+        .map(|count: i32| count > 0)
+        .unwrap_or(false);
+
+    if !has_merge_method {
+        tracing::info!("Adding merge_method column to pull_requests table...");
+        conn.execute("ALTER TABLE pull_requests ADD COLUMN merge_method TEXT", [])?;
+    }
+
+    let has_setting: bool = conn
+        .query_row(
+            "SELECT COUNT(*) FROM pragma_table_info('settings') WHERE name='exclude_queue_merges_from_latency'",
+            [],
+            |row| row.get(0),
+        )
+        .map(|count: i32| count > 0)
+        .unwrap_or(false);
+
+    if !has_setting {
+        tracing::info!("Adding exclude_queue_merges_from_latency to settings...");
+        conn.execute(
+            "ALTER TABLE settings ADD COLUMN exclude_queue_merges_from_latency BOOLEAN NOT NULL DEFAULT FALSE",
+            [],
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Add exclude_self_reviews setting (defaults to excluding self-reviews)
+fn migrate_add_exclude_self_reviews_setting(conn: &Connection) -> Result<()> {
+    if !column_exists(conn, "settings", "exclude_self_reviews") {
+        tracing::info!("Adding exclude_self_reviews to settings...");
+        conn.execute(
+            "ALTER TABLE settings ADD COLUMN exclude_self_reviews BOOLEAN NOT NULL DEFAULT TRUE",
+            [],
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Add draft/mergeable state to pull_requests and the blocking_labels setting
+fn migrate_add_pr_blocking_state(conn: &Connection) -> Result<()> {
+    if !column_exists(conn, "pull_requests", "is_draft") {
+        tracing::info!("Adding is_draft column to pull_requests table...");
+        conn.execute(
+            "ALTER TABLE pull_requests ADD COLUMN is_draft BOOLEAN NOT NULL DEFAULT FALSE",
+            [],
+        )?;
+    }
+
+    if !column_exists(conn, "pull_requests", "mergeable") {
+        tracing::info!("Adding mergeable column to pull_requests table...");
+        conn.execute("ALTER TABLE pull_requests ADD COLUMN mergeable TEXT", [])?;
+    }
+
+    if !column_exists(conn, "settings", "blocking_labels") {
+        tracing::info!("Adding blocking_labels to settings...");
+        conn.execute(
+            r#"ALTER TABLE settings ADD COLUMN blocking_labels TEXT NOT NULL DEFAULT '["blocked", "do-not-merge"]'"#,
+            [],
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Add productivity multiplier weights (defaults match the original fixed formula)
+fn migrate_add_productivity_weight_settings(conn: &Connection) -> Result<()> {
+    let weights = [
+        ("productivity_weight_speed", "0.6"),
+        ("productivity_weight_ease", "0.25"),
+        ("productivity_weight_quality", "0.15"),
+    ];
+
+    for (column, default) in weights {
+        if !column_exists(conn, "settings", column) {
+            tracing::info!("Adding {} to settings...", column);
+            conn.execute(
+                &format!("ALTER TABLE settings ADD COLUMN {} REAL NOT NULL DEFAULT {}", column, default),
+                [],
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Add sync_old_reopened setting (defaults to skipping items created before the history window)
+fn migrate_add_sync_old_reopened_setting(conn: &Connection) -> Result<()> {
+    if !column_exists(conn, "settings", "sync_old_reopened") {
+        tracing::info!("Adding sync_old_reopened to settings...");
+        conn.execute(
+            "ALTER TABLE settings ADD COLUMN sync_old_reopened BOOLEAN NOT NULL DEFAULT FALSE",
+            [],
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Add loc_basis setting ("all" keeps counting LOC from unmerged PRs)
+fn migrate_add_loc_basis_setting(conn: &Connection) -> Result<()> {
+    if !column_exists(conn, "settings", "loc_basis") {
+        tracing::info!("Adding loc_basis to settings...");
+        conn.execute(
+            "ALTER TABLE settings ADD COLUMN loc_basis TEXT NOT NULL DEFAULT 'all'",
+            [],
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Add sync_pr_files setting (file lists are opt-in since they cost an extra query per PR)
+fn migrate_add_sync_pr_files_setting(conn: &Connection) -> Result<()> {
+    if !column_exists(conn, "settings", "sync_pr_files") {
+        tracing::info!("Adding sync_pr_files to settings...");
+        conn.execute(
+            "ALTER TABLE settings ADD COLUMN sync_pr_files BOOLEAN NOT NULL DEFAULT FALSE",
+            [],
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Add per-repository bot exclusions (merged with the global list during that repo's sync)
+fn migrate_add_repo_excluded_bots(conn: &Connection) -> Result<()> {
+    if !column_exists(conn, "repositories", "excluded_bots") {
+        tracing::info!("Adding excluded_bots column to repositories table...");
+        conn.execute(
+            "ALTER TABLE repositories ADD COLUMN excluded_bots TEXT NOT NULL DEFAULT '[]'",
+            [],
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Add metric_precision setting (decimal places for serialized metric floats)
+fn migrate_add_metric_precision_setting(conn: &Connection) -> Result<()> {
+    if !column_exists(conn, "settings", "metric_precision") {
+        tracing::info!("Adding metric_precision to settings...");
+        conn.execute(
+            "ALTER TABLE settings ADD COLUMN metric_precision INTEGER NOT NULL DEFAULT 2",
+            [],
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Add min_active_prs setting (PRs needed in the window to count as an active developer)
+fn migrate_add_min_active_prs_setting(conn: &Connection) -> Result<()> {
+    if !column_exists(conn, "settings", "min_active_prs") {
+        tracing::info!("Adding min_active_prs to settings...");
+        conn.execute(
+            "ALTER TABLE settings ADD COLUMN min_active_prs INTEGER NOT NULL DEFAULT 1",
+            [],
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Add exclude_weekends setting (per-day rates use working days only)
+fn migrate_add_exclude_weekends_setting(conn: &Connection) -> Result<()> {
+    if !column_exists(conn, "settings", "exclude_weekends") {
+        tracing::info!("Adding exclude_weekends to settings...");
+        conn.execute(
+            "ALTER TABLE settings ADD COLUMN exclude_weekends BOOLEAN NOT NULL DEFAULT FALSE",
+            [],
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Add webhook receiver settings (off by default; takes effect on restart)
+fn migrate_add_webhook_settings(conn: &Connection) -> Result<()> {
+    if !column_exists(conn, "settings", "enable_webhooks") {
+        tracing::info!("Adding enable_webhooks to settings...");
+        conn.execute(
+            "ALTER TABLE settings ADD COLUMN enable_webhooks BOOLEAN NOT NULL DEFAULT FALSE",
+            [],
+        )?;
+    }
+
+    if !column_exists(conn, "settings", "webhook_secret") {
+        tracing::info!("Adding webhook_secret to settings...");
+        conn.execute("ALTER TABLE settings ADD COLUMN webhook_secret TEXT NOT NULL DEFAULT ''", [])?;
+    }
+
+    if !column_exists(conn, "settings", "webhook_port") {
+        tracing::info!("Adding webhook_port to settings...");
+        conn.execute(
+            "ALTER TABLE settings ADD COLUMN webhook_port INTEGER NOT NULL DEFAULT 8787",
+            [],
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Add period_boundary setting ("rolling" N×24h window or "calendar" day-aligned window, since renamed)
+fn migrate_add_period_boundary_setting(conn: &Connection) -> Result<()> {
+    if !column_exists(conn, "settings", "period_boundary") {
+        tracing::info!("Adding period_boundary to settings...");
+        conn.execute(
+            "ALTER TABLE settings ADD COLUMN period_boundary TEXT NOT NULL DEFAULT 'rolling'",
+            [],
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Add primary_language column to repositories (filled by repository info sync)
+fn migrate_add_repo_primary_language(conn: &Connection) -> Result<()> {
+    if !column_exists(conn, "repositories", "primary_language") {
+        tracing::info!("Adding primary_language column to repositories table...");
+        conn.execute("ALTER TABLE repositories ADD COLUMN primary_language TEXT", [])?;
+    }
+
+    Ok(())
+}
+
+/// Add first_response_at column to issues (first comment by someone other than the author or a bot)
+fn migrate_add_issue_first_response(conn: &Connection) -> Result<()> {
+    if !column_exists(conn, "issues", "first_response_at") {
+        tracing::info!("Adding first_response_at column to issues table...");
+        conn.execute("ALTER TABLE issues ADD COLUMN first_response_at TEXT", [])?;
+    }
+
+    Ok(())
+}
+
+/// Record which model produced each embedding so vectors from an older model can be regenerated
+fn migrate_add_embedding_model_columns(conn: &Connection) -> Result<()> {
+    for table in ["issues", "pull_requests"] {
+        if !column_exists(conn, table, "embedding_model") {
+            tracing::info!("Adding embedding_model column to {} table...", table);
+            conn.execute(&format!("ALTER TABLE {} ADD COLUMN embedding_model TEXT", table), [])?;
+            // Every embedding stored before this column existed came from MiniLM-L6-v2
+            conn.execute(
+                &format!(
+                    "UPDATE {} SET embedding_model = 'all-MiniLM-L6-v2' WHERE embedding IS NOT NULL",
+                    table
+                ),
+                [],
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Add core_start_hour/core_end_hour settings (core working day in UTC, default 08:00-18:00)
+fn migrate_add_core_hours_settings(conn: &Connection) -> Result<()> {
+    if !column_exists(conn, "settings", "core_start_hour") {
+        tracing::info!("Adding core_start_hour to settings...");
+        conn.execute(
+            "ALTER TABLE settings ADD COLUMN core_start_hour INTEGER NOT NULL DEFAULT 8",
+            [],
+        )?;
+    }
+
+    if !column_exists(conn, "settings", "core_end_hour") {
+        tracing::info!("Adding core_end_hour to settings...");
+        conn.execute(
+            "ALTER TABLE settings ADD COLUMN core_end_hour INTEGER NOT NULL DEFAULT 18",
+            [],
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Index existing issues/PRs the first time the full-text table is created
+fn migrate_populate_search_fts(conn: &Connection) -> Result<()> {
+    let indexed: i64 = conn.query_row("SELECT COUNT(*) FROM search_fts", [], |row| row.get(0))?;
+    if indexed == 0 {
+        let count = crate::search::fts::rebuild_fts_index(conn)?;
+        if count > 0 {
+            tracing::info!("Indexed {} issues/PRs for full-text search", count);
+        }
+    }
+
+    Ok(())
+}
+
+/// Add the sync interval and freshness tier thresholds to settings
+fn migrate_add_freshness_settings(conn: &Connection) -> Result<()> {
+    if !column_exists(conn, "settings", "sync_interval_minutes") {
+        tracing::info!("Adding sync_interval_minutes to settings...");
+        conn.execute(
+            "ALTER TABLE settings ADD COLUMN sync_interval_minutes INTEGER NOT NULL DEFAULT 60",
+            [],
+        )?;
+    }
+
+    if !column_exists(conn, "settings", "freshness_aging_multiplier") {
+        tracing::info!("Adding freshness_aging_multiplier to settings...");
+        conn.execute(
+            "ALTER TABLE settings ADD COLUMN freshness_aging_multiplier REAL NOT NULL DEFAULT 1.5",
+            [],
+        )?;
+    }
+
+    if !column_exists(conn, "settings", "freshness_stale_multiplier") {
+        tracing::info!("Adding freshness_stale_multiplier to settings...");
+        conn.execute(
+            "ALTER TABLE settings ADD COLUMN freshness_stale_multiplier REAL NOT NULL DEFAULT 3.0",
+            [],
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Add skipped_prs to sync_log so REST issue syncs can report filtered PRs
+fn migrate_add_sync_log_skipped_prs(conn: &Connection) -> Result<()> {
+    if !column_exists(conn, "sync_log", "skipped_prs") {
+        tracing::info!("Adding skipped_prs to sync_log...");
+        conn.execute("ALTER TABLE sync_log ADD COLUMN skipped_prs INTEGER DEFAULT 0", [])?;
+    }
+
+    Ok(())
+}
+
+/// Add base_branch to pull_requests for branch-scoped metrics
+fn migrate_add_pr_base_branch(conn: &Connection) -> Result<()> {
+    if !column_exists(conn, "pull_requests", "base_branch") {
+        tracing::info!("Adding base_branch to pull_requests...");
+        conn.execute("ALTER TABLE pull_requests ADD COLUMN base_branch TEXT", [])?;
+    }
+
+    Ok(())
+}
+
+/// Add include_merge_commit_loc setting (count merge commits in commit-based LOC)
+fn migrate_add_include_merge_commit_loc_setting(conn: &Connection) -> Result<()> {
+    if !column_exists(conn, "settings", "include_merge_commit_loc") {
+        tracing::info!("Adding include_merge_commit_loc to settings...");
+        conn.execute(
+            "ALTER TABLE settings ADD COLUMN include_merge_commit_loc BOOLEAN NOT NULL DEFAULT FALSE",
+            [],
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Add the HTTP embedding backend endpoint and concurrency to settings
+fn migrate_add_embedding_http_settings(conn: &Connection) -> Result<()> {
+    if !column_exists(conn, "settings", "embedding_http_url") {
+        tracing::info!("Adding embedding_http_url to settings...");
+        conn.execute(
+            "ALTER TABLE settings ADD COLUMN embedding_http_url TEXT NOT NULL DEFAULT ''",
+            [],
+        )?;
+    }
+
+    if !column_exists(conn, "settings", "embedding_http_concurrency") {
+        tracing::info!("Adding embedding_http_concurrency to settings...");
+        conn.execute(
+            "ALTER TABLE settings ADD COLUMN embedding_http_concurrency INTEGER NOT NULL DEFAULT 4",
+            [],
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Add commit_count to pull_requests for the commits-per-PR metric
+fn migrate_add_pr_commit_count(conn: &Connection) -> Result<()> {
+    if !column_exists(conn, "pull_requests", "commit_count") {
+        tracing::info!("Adding commit_count to pull_requests...");
+        conn.execute("ALTER TABLE pull_requests ADD COLUMN commit_count INTEGER", [])?;
+    }
+
+    Ok(())
+}
+
+/// Add sync_strategy setting (ordered sync methods to attempt, replacing the fixed GraphQL→REST→CLI chain)
+fn migrate_add_sync_strategy_setting(conn: &Connection) -> Result<()> {
+    if !column_exists(conn, "settings", "sync_strategy") {
+        tracing::info!("Adding sync_strategy to settings...");
+        conn.execute(
+            r#"ALTER TABLE settings ADD COLUMN sync_strategy TEXT NOT NULL DEFAULT '["graphql", "rest", "cli"]'"#,
+            [],
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Add rejection_labels setting (closed-unmerged PRs with these labels are left out of merge rate)
+fn migrate_add_rejection_labels_setting(conn: &Connection) -> Result<()> {
+    if !column_exists(conn, "settings", "rejection_labels") {
+        tracing::info!("Adding rejection_labels to settings...");
+        conn.execute(
+            "ALTER TABLE settings ADD COLUMN rejection_labels TEXT NOT NULL DEFAULT '[]'",
+            [],
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Add author_association to pull_requests for the internal vs external split
+fn migrate_add_pr_author_association(conn: &Connection) -> Result<()> {
+    if !column_exists(conn, "pull_requests", "author_association") {
+        tracing::info!("Adding author_association to pull_requests...");
+        conn.execute("ALTER TABLE pull_requests ADD COLUMN author_association TEXT", [])?;
+    }
+
+    Ok(())
+}
+
+/// Add internal_logins setting (authors always counted as core team)
+fn migrate_add_internal_logins_setting(conn: &Connection) -> Result<()> {
+    if !column_exists(conn, "settings", "internal_logins") {
+        tracing::info!("Adding internal_logins to settings...");
+        conn.execute(
+            "ALTER TABLE settings ADD COLUMN internal_logins TEXT NOT NULL DEFAULT '[]'",
+            [],
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Add internal_orgs setting (orgs whose members count as internal on their own repos)
+fn migrate_add_internal_orgs_setting(conn: &Connection) -> Result<()> {
+    if !column_exists(conn, "settings", "internal_orgs") {
+        tracing::info!("Adding internal_orgs to settings...");
+        conn.execute(
+            "ALTER TABLE settings ADD COLUMN internal_orgs TEXT NOT NULL DEFAULT '[]'",
+            [],
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Add headcount_basis setting ("active" developers or all "tracked" users for per-capita metrics)
+fn migrate_add_headcount_basis_setting(conn: &Connection) -> Result<()> {
+    if !column_exists(conn, "settings", "headcount_basis") {
+        tracing::info!("Adding headcount_basis to settings...");
+        conn.execute(
+            "ALTER TABLE settings ADD COLUMN headcount_basis TEXT NOT NULL DEFAULT 'active'",
+            [],
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Add sla_rules setting (per-label response/resolution targets in hours)
+fn migrate_add_sla_rules_setting(conn: &Connection) -> Result<()> {
+    if !column_exists(conn, "settings", "sla_rules") {
+        tracing::info!("Adding sla_rules to settings...");
+        conn.execute(
+            "ALTER TABLE settings ADD COLUMN sla_rules TEXT NOT NULL DEFAULT '[]'",
+            [],
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Add is_private to repositories (GitHub visibility, NULL until synced)
+fn migrate_add_repo_is_private(conn: &Connection) -> Result<()> {
+    if !column_exists(conn, "repositories", "is_private") {
+        tracing::info!("Adding is_private to repositories...");
+        conn.execute("ALTER TABLE repositories ADD COLUMN is_private BOOLEAN", [])?;
+    }
+
+    Ok(())
+}
+
+/// Add ai_min_source_similarity setting (search/duplicate results below it are not shown to the AI chat)
+fn migrate_add_ai_min_source_similarity_setting(conn: &Connection) -> Result<()> {
+    if !column_exists(conn, "settings", "ai_min_source_similarity") {
+        tracing::info!("Adding ai_min_source_similarity to settings...");
+        conn.execute(
+            "ALTER TABLE settings ADD COLUMN ai_min_source_similarity REAL NOT NULL DEFAULT 0.5",
+            [],
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Add pr_type_label_rules setting (ordered label → PR type mappings, first match wins)
+fn migrate_add_pr_type_label_rules_setting(conn: &Connection) -> Result<()> {
+    if !column_exists(conn, "settings", "pr_type_label_rules") {
+        tracing::info!("Adding pr_type_label_rules to settings...");
+        conn.execute(
+            "ALTER TABLE settings ADD COLUMN pr_type_label_rules TEXT NOT NULL DEFAULT '[{\"label\":\"chore\",\"pr_type\":\"other\"},{\"label\":\"feature\",\"pr_type\":\"feature\"},{\"label\":\"enhancement\",\"pr_type\":\"feature\"},{\"label\":\"bug\",\"pr_type\":\"bug_fix\"},{\"label\":\"refactor\",\"pr_type\":\"refactor\"},{\"label\":\"test\",\"pr_type\":\"test\"},{\"label\":\"documentation\",\"pr_type\":\"docs\"}]'",
+            [],
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Add exclude_reverts setting (drop reverts and the PRs they revert from Speed/Quality)
+fn migrate_add_exclude_reverts_setting(conn: &Connection) -> Result<()> {
+    if !column_exists(conn, "settings", "exclude_reverts") {
+        tracing::info!("Adding exclude_reverts to settings...");
+        conn.execute(
+            "ALTER TABLE settings ADD COLUMN exclude_reverts BOOLEAN NOT NULL DEFAULT FALSE",
+            [],
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Add planning_labels setting (labels marking issues/PRs as planned work)
+fn migrate_add_planning_labels_setting(conn: &Connection) -> Result<()> {
+    if !column_exists(conn, "settings", "planning_labels") {
+        tracing::info!("Adding planning_labels to settings...");
+        conn.execute(
+            "ALTER TABLE settings ADD COLUMN planning_labels TEXT NOT NULL DEFAULT '[\"planned\", \"roadmap\"]'",
+            [],
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Add company and profile_fetched_at to users (REST profile enrichment)
+fn migrate_add_user_profile_columns(conn: &Connection) -> Result<()> {
+    if !column_exists(conn, "users", "company") {
+        tracing::info!("Adding company to users...");
+        conn.execute("ALTER TABLE users ADD COLUMN company TEXT", [])?;
+    }
+    if !column_exists(conn, "users", "profile_fetched_at") {
+        tracing::info!("Adding profile_fetched_at to users...");
+        conn.execute("ALTER TABLE users ADD COLUMN profile_fetched_at TEXT", [])?;
+    }
+
+    Ok(())
+}
+
+/// Add milestone_counts_source setting ("github" synced counts or "local" counts from issues)
+fn migrate_add_milestone_counts_source_setting(conn: &Connection) -> Result<()> {
+    if !column_exists(conn, "settings", "milestone_counts_source") {
+        tracing::info!("Adding milestone_counts_source to settings...");
+        conn.execute(
+            "ALTER TABLE settings ADD COLUMN milestone_counts_source TEXT NOT NULL DEFAULT 'github'",
+            [],
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Add the team's UTC offset used for local day-of-week grouping
+fn migrate_add_utc_offset_minutes_setting(conn: &Connection) -> Result<()> {
+    if !column_exists(conn, "settings", "utc_offset_minutes") {
+        tracing::info!("Adding utc_offset_minutes to settings...");
+        conn.execute(
+            "ALTER TABLE settings ADD COLUMN utc_offset_minutes INTEGER NOT NULL DEFAULT 0",
+            [],
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Track whether a webhook secret is stored in the keyring (the secret itself never is in SQLite)
+fn migrate_add_webhook_secret_set_setting(conn: &Connection) -> Result<()> {
+    if !column_exists(conn, "settings", "webhook_secret_set") {
+        tracing::info!("Adding webhook_secret_set to settings...");
+        conn.execute(
+            "ALTER TABLE settings ADD COLUMN webhook_secret_set BOOLEAN NOT NULL DEFAULT FALSE",
+            [],
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Add is_demo to repositories so seeded demo data is never synced or edited
+fn migrate_add_repo_is_demo(conn: &Connection) -> Result<()> {
+    if !column_exists(conn, "repositories", "is_demo") {
+        tracing::info!("Adding is_demo to repositories...");
+        conn.execute("ALTER TABLE repositories ADD COLUMN is_demo BOOLEAN NOT NULL DEFAULT FALSE", [])?;
+    }
+
+    Ok(())
+}
+
+/// Add tier_medium_tolerance setting (how far below the industry benchmark still rates Medium)
+fn migrate_add_tier_medium_tolerance_setting(conn: &Connection) -> Result<()> {
+    if !column_exists(conn, "settings", "tier_medium_tolerance") {
+        tracing::info!("Adding tier_medium_tolerance to settings...");
+        conn.execute(
+            "ALTER TABLE settings ADD COLUMN tier_medium_tolerance REAL NOT NULL DEFAULT 0.25",
+            [],
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Add details_fetched_at to pull_requests so PRs with a real 0 LOC aren't re-fetched every sync
+fn migrate_add_pr_details_fetched_at(conn: &Connection) -> Result<()> {
+    if !column_exists(conn, "pull_requests", "details_fetched_at") {
+        tracing::info!("Adding details_fetched_at to pull_requests...");
+        conn.execute("ALTER TABLE pull_requests ADD COLUMN details_fetched_at TEXT", [])?;
+    }
+
+    Ok(())
+}
+
+/// Rename the day-aligned period_boundary value from "calendar" to "inclusive_start"
+fn migrate_rename_calendar_period_boundary(conn: &Connection) -> Result<()> {
+    conn.execute(
+        "UPDATE settings SET period_boundary = 'inclusive_start' WHERE period_boundary = 'calendar'",
+        [],
+    )?;
+
+    Ok(())
+}
+
+/// Add skipped_old to sync_log so every issue sync method reports items skipped for age
+fn migrate_add_sync_log_skipped_old(conn: &Connection) -> Result<()> {
+    if !column_exists(conn, "sync_log", "skipped_old") {
+        tracing::info!("Adding skipped_old to sync_log...");
+        conn.execute("ALTER TABLE sync_log ADD COLUMN skipped_old INTEGER DEFAULT 0", [])?;
+    }
+
+    Ok(())
+}
+
+/// Check whether a table already has the given column
+fn column_exists(conn: &Connection, table: &str, column: &str) -> bool {
+    conn.query_row(
+        "SELECT COUNT(*) FROM pragma_table_info(?1) WHERE name = ?2",
+        [table, column],
+        |row| row.get(0),
+    )
+    .map(|count: i32| count > 0)
+    .unwrap_or(false)
+}
+
+/// Full-text index over issue/PR titles and bodies, kept current by triggers.
+/// Issues use rowid = id * 2 and PRs rowid = id * 2 + 1 so both fit in one table.
+pub(crate) const SEARCH_FTS_SCHEMA: &str = r#"
+CREATE VIRTUAL TABLE IF NOT EXISTS search_fts USING fts5(
+    item_type UNINDEXED,
+    item_id UNINDEXED,
+    title,
+    body
+);
+
+CREATE TRIGGER IF NOT EXISTS issues_fts_insert AFTER INSERT ON issues BEGIN
+    INSERT INTO search_fts (rowid, item_type, item_id, title, body)
+    VALUES (new.id * 2, 'issue', new.id, new.title, COALESCE(new.body, ''));
+END;
+
+CREATE TRIGGER IF NOT EXISTS issues_fts_update AFTER UPDATE OF title, body ON issues BEGIN
+    DELETE FROM search_fts WHERE rowid = old.id * 2;
+    INSERT INTO search_fts (rowid, item_type, item_id, title, body)
+    VALUES (new.id * 2, 'issue', new.id, new.title, COALESCE(new.body, ''));
+END;
+
+CREATE TRIGGER IF NOT EXISTS issues_fts_delete AFTER DELETE ON issues BEGIN
+    DELETE FROM search_fts WHERE rowid = old.id * 2;
+END;
+
+CREATE TRIGGER IF NOT EXISTS pull_requests_fts_insert AFTER INSERT ON pull_requests BEGIN
+    INSERT INTO search_fts (rowid, item_type, item_id, title, body)
+    VALUES (new.id * 2 + 1, 'pull_request', new.id, new.title, COALESCE(new.body, ''));
+END;
+
+CREATE TRIGGER IF NOT EXISTS pull_requests_fts_update AFTER UPDATE OF title, body ON pull_requests BEGIN
+    DELETE FROM search_fts WHERE rowid = old.id * 2 + 1;
+    INSERT INTO search_fts (rowid, item_type, item_id, title, body)
+    VALUES (new.id * 2 + 1, 'pull_request', new.id, new.title, COALESCE(new.body, ''));
+END;
+
+CREATE TRIGGER IF NOT EXISTS pull_requests_fts_delete AFTER DELETE ON pull_requests BEGIN
+    DELETE FROM search_fts WHERE rowid = old.id * 2 + 1;
+END;
+"#;
+
 const SCHEMA: &str = r#"
 -- Repositories being tracked
 CREATE TABLE IF NOT EXISTS repositories (
@@ -245,9 +1016,23 @@ CREATE TABLE IF NOT EXISTS repositories (
     github_id INTEGER UNIQUE,
     enabled BOOLEAN DEFAULT TRUE,
     last_synced_at TEXT,
+    default_branch TEXT,
+    excluded_bots TEXT NOT NULL DEFAULT '[]', -- JSON array, repo-specific additions to settings.excluded_bots
+    primary_language TEXT,
+    is_private BOOLEAN, -- GitHub visibility, NULL until synced
+    is_demo BOOLEAN NOT NULL DEFAULT FALSE, -- Seeded by seed_demo_data; never synced or edited
     UNIQUE(owner, name)
 );
 
+-- Language breakdown per repository (bytes of code, as reported by GitHub)
+CREATE TABLE IF NOT EXISTS repo_languages (
+    repo_id INTEGER NOT NULL REFERENCES repositories(id),
+    language TEXT NOT NULL,
+    color TEXT,
+    bytes INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY(repo_id, language)
+);
+
 -- GitHub users
 CREATE TABLE IF NOT EXISTS users (
     id INTEGER PRIMARY KEY,
@@ -257,7 +1042,9 @@ CREATE TABLE IF NOT EXISTS users (
     avatar_url TEXT,
     is_bot BOOLEAN DEFAULT FALSE,
     tracked BOOLEAN NOT NULL DEFAULT FALSE,
-    tracked_at TEXT
+    tracked_at TEXT,
+    company TEXT,
+    profile_fetched_at TEXT -- Last REST profile lookup, NULL if never enriched
 );
 
 -- Issues
@@ -278,6 +1065,8 @@ CREATE TABLE IF NOT EXISTS issues (
     closed_at TEXT,
     labels TEXT, -- JSON array of label names
     embedding BLOB, -- 384-dimensional float32 vector (1536 bytes)
+    embedding_model TEXT, -- Model that produced the embedding
+    first_response_at TEXT, -- First comment by someone other than the author or a bot
     UNIQUE(repo_id, number)
 );
 
@@ -301,7 +1090,15 @@ CREATE TABLE IF NOT EXISTS pull_requests (
     changed_files INTEGER DEFAULT 0,
     review_comments INTEGER DEFAULT 0,
     labels TEXT, -- JSON array of label names
+    merge_method TEXT, -- merge_queue, auto_merge, manual
+    is_draft BOOLEAN NOT NULL DEFAULT FALSE,
+    mergeable TEXT, -- MERGEABLE, CONFLICTING, UNKNOWN
+    base_branch TEXT, -- Branch the PR targets (baseRefName)
+    commit_count INTEGER, -- Commits on the PR, NULL until synced
+    details_fetched_at TEXT, -- When LOC/merge details were last fetched per-PR, NULL if never
+    author_association TEXT, -- GitHub authorAssociation (MEMBER, OWNER, CONTRIBUTOR, ...)
     embedding BLOB, -- 384-dimensional float32 vector (1536 bytes)
+    embedding_model TEXT, -- Model that produced the embedding
     UNIQUE(repo_id, number)
 );
 
@@ -353,14 +1150,112 @@ CREATE TABLE IF NOT EXISTS tracked_users (
     UNIQUE(user_id)
 );
 
+-- Free-form annotations on users (role, location, manager, ...)
+CREATE TABLE IF NOT EXISTS user_metadata (
+    user_id INTEGER NOT NULL REFERENCES users(id),
+    key TEXT NOT NULL,
+    value TEXT NOT NULL,
+    updated_at TEXT NOT NULL DEFAULT (datetime('now')),
+    PRIMARY KEY(user_id, key)
+);
+
+-- Changed files per PR (only synced when sync_pr_files is enabled)
+CREATE TABLE IF NOT EXISTS pr_files (
+    pr_id INTEGER NOT NULL REFERENCES pull_requests(id),
+    path TEXT NOT NULL,
+    additions INTEGER DEFAULT 0,
+    deletions INTEGER DEFAULT 0,
+    PRIMARY KEY(pr_id, path)
+);
+
+-- Issues a PR closes (closingIssuesReferences); the issue may not be synced yet
+CREATE TABLE IF NOT EXISTS pr_issue_links (
+    pr_id INTEGER NOT NULL REFERENCES pull_requests(id),
+    issue_number INTEGER NOT NULL,
+    PRIMARY KEY(pr_id, issue_number)
+);
+
+-- PR assignees (issues keep their single assignee on issues.assignee_id)
+CREATE TABLE IF NOT EXISTS pr_assignees (
+    pr_id INTEGER NOT NULL REFERENCES pull_requests(id),
+    user_id INTEGER NOT NULL REFERENCES users(id),
+    PRIMARY KEY(pr_id, user_id)
+);
+
+-- Commits on each PR (most recent 50), for ordering pushes against reviews
+CREATE TABLE IF NOT EXISTS pr_commits (
+    pr_id INTEGER NOT NULL REFERENCES pull_requests(id),
+    oid TEXT NOT NULL,
+    committed_at TEXT NOT NULL,
+    PRIMARY KEY(pr_id, oid)
+);
+
+-- Label added/removed events on issues (most recent 50 per issue), for label dwell times
+CREATE TABLE IF NOT EXISTS issue_label_events (
+    issue_id INTEGER NOT NULL REFERENCES issues(id),
+    label TEXT NOT NULL,
+    action TEXT NOT NULL, -- 'added' or 'removed'
+    created_at TEXT NOT NULL,
+    PRIMARY KEY(issue_id, label, action, created_at)
+);
+
+-- Commits on each repository's default branch
+CREATE TABLE IF NOT EXISTS commits (
+    id INTEGER PRIMARY KEY,
+    repo_id INTEGER NOT NULL REFERENCES repositories(id),
+    oid TEXT NOT NULL,
+    author_id INTEGER REFERENCES users(id),
+    message_headline TEXT NOT NULL,
+    committed_at TEXT NOT NULL,
+    additions INTEGER DEFAULT 0,
+    deletions INTEGER DEFAULT 0,
+    is_merge BOOLEAN NOT NULL DEFAULT FALSE, -- More than one parent; its LOC duplicates the merged branch
+    UNIQUE(repo_id, oid)
+);
+
+-- Path ownership for monorepo sub-teams (glob -> squad)
+CREATE TABLE IF NOT EXISTS path_owners (
+    id INTEGER PRIMARY KEY,
+    pattern TEXT NOT NULL,
+    squad_id TEXT NOT NULL REFERENCES squads(id),
+    UNIQUE(pattern, squad_id)
+);
+
+-- Cross-repo initiatives spanning milestones, issues and PRs
+CREATE TABLE IF NOT EXISTS initiatives (
+    id INTEGER PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE,
+    description TEXT,
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+-- Items tagged with an initiative
+CREATE TABLE IF NOT EXISTS initiative_items (
+    initiative_id INTEGER NOT NULL REFERENCES initiatives(id),
+    item_type TEXT NOT NULL, -- issue, pull_request, milestone
+    item_id INTEGER NOT NULL, -- Local id in issues, pull_requests or milestones
+    PRIMARY KEY(initiative_id, item_type, item_id)
+);
+
+-- Named dashboard filter presets
+CREATE TABLE IF NOT EXISTS saved_filters (
+    id INTEGER PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE,
+    filters TEXT NOT NULL, -- JSON-encoded MetricsFilters
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
 -- Sync log for tracking incremental updates
 CREATE TABLE IF NOT EXISTS sync_log (
     id INTEGER PRIMARY KEY,
     repo_id INTEGER NOT NULL REFERENCES repositories(id),
-    sync_type TEXT NOT NULL, -- issues, pull_requests, milestones
+    sync_type TEXT NOT NULL, -- issues, pull_requests, milestones, commits
     started_at TEXT NOT NULL,
     completed_at TEXT,
     items_synced INTEGER DEFAULT 0,
+    skipped_prs INTEGER DEFAULT 0, -- PRs filtered out of the REST issues endpoint
+    skipped_old INTEGER DEFAULT 0, -- Issues skipped for predating the history window
     error TEXT
 );
 
@@ -386,6 +1281,10 @@ CREATE INDEX IF NOT EXISTS idx_prs_state ON pull_requests(state);
 CREATE INDEX IF NOT EXISTS idx_prs_created ON pull_requests(created_at);
 CREATE INDEX IF NOT EXISTS idx_prs_merged ON pull_requests(merged_at);
 
+CREATE INDEX IF NOT EXISTS idx_pr_files_path ON pr_files(path);
+
+CREATE INDEX IF NOT EXISTS idx_commits_committed ON commits(committed_at);
+
 CREATE INDEX IF NOT EXISTS idx_milestones_repo ON milestones(repo_id);
 CREATE INDEX IF NOT EXISTS idx_milestones_due ON milestones(due_on);
 
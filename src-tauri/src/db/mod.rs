@@ -5,10 +5,16 @@ pub mod queries;
 pub mod project_queries;
 pub mod user_queries;
 pub mod metrics_queries;
+pub mod item_queries;
+pub mod path_owner_queries;
+pub mod initiative_queries;
+pub mod demo_data;
+pub mod profiles;
 
 use anyhow::Result;
 use rusqlite::Connection;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Mutex;
 use tauri::{AppHandle, Manager}; // Added Manager import
 
@@ -16,16 +22,38 @@ use tauri::{AppHandle, Manager}; // Added Manager import
 pub struct AppState {
     pub sqlite: Mutex<Connection>,
     pub lancedb_path: PathBuf, // Kept for future use
+    pub profile: Mutex<String>, // Active workspace profile
+    pub sync_in_progress: AtomicBool,
+    pub webhook_server: Mutex<Option<tauri::async_runtime::JoinHandle<()>>>,
 }
 
-/// Get the database file path
+/// Marks a sync as running until dropped
+pub struct SyncGuard<'a>(&'a AtomicBool);
+
+impl Drop for SyncGuard<'_> {
+    fn drop(&mut self) {
+        self.0.store(false, Ordering::SeqCst);
+    }
+}
+
+impl AppState {
+    /// Mark a sync (or profile switch) as running, failing if one already is
+    pub fn begin_sync(&self) -> Result<SyncGuard<'_>> {
+        self.sync_in_progress
+            .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
+            .map_err(|_| anyhow::anyhow!("A sync is already running"))?;
+        Ok(SyncGuard(&self.sync_in_progress))
+    }
+}
+
+/// Get the database file path for the profile selected at startup
 pub fn get_db_path(app: &AppHandle) -> Result<PathBuf> {
     let app_dir = app
         .path_resolver()
         .app_data_dir()
         .expect("Failed to get app data directory");
 
-    Ok(app_dir.join("made.db"))
+    profiles::profile_db_path(&app_dir, &profiles::startup_profile(&app_dir))
 }
 
 /// Initialize SQLite database
@@ -37,10 +65,11 @@ pub async fn init_databases(app: &AppHandle) -> Result<()> {
 
     std::fs::create_dir_all(&app_dir)?;
 
-    // Initialize SQLite
-    let sqlite_path = app_dir.join("made.db");
-    let conn = Connection::open(&sqlite_path)?;
-    migrations::run_migrations(&conn)?;
+    // Initialize SQLite for the selected profile
+    let profile = profiles::startup_profile(&app_dir);
+    let conn = profiles::open_profile(&app_dir, &profile)?;
+    let settings = queries::get_settings(&conn)?;
+    crate::metrics::precision::set_metric_precision(settings.metric_precision);
 
     // LanceDB path for future use (Phase 3)
     let lancedb_path = app_dir.join("vectors");
@@ -50,10 +79,13 @@ pub async fn init_databases(app: &AppHandle) -> Result<()> {
     let state = AppState {
         sqlite: Mutex::new(conn),
         lancedb_path,
+        profile: Mutex::new(profile.clone()),
+        sync_in_progress: AtomicBool::new(false),
+        webhook_server: Mutex::new(None),
     };
 
     app.manage(state);
 
-    tracing::info!("Databases initialized at {:?}", app_dir);
+    tracing::info!("Databases initialized at {:?} (profile: {})", app_dir, profile);
     Ok(())
 }
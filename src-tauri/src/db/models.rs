@@ -1,3 +1,4 @@
+use crate::metrics::filter_params::MetricsFilters;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -8,6 +9,7 @@ pub struct Repository {
     pub github_id: Option<i64>,
     pub enabled: bool,
     pub last_synced_at: Option<String>,
+    pub default_branch: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -97,6 +99,39 @@ pub struct Squad {
     pub members: Vec<String>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LabelCount {
+    pub label: String,
+    pub count: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedFilter {
+    pub id: i64,
+    pub name: String,
+    pub filters: MetricsFilters,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Service-level targets for issues carrying a label (e.g. `P0`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SlaRule {
+    pub label: String,
+    pub response_hours: Option<f64>,   // Created → first response
+    pub resolution_hours: Option<f64>, // Created → closed
+}
+
+/// Type buckets a PR can be classified into
+pub const PR_TYPES: [&str; 6] = ["feature", "bug_fix", "refactor", "test", "docs", "other"];
+
+/// Maps a PR label to one of `PR_TYPES`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PrTypeLabelRule {
+    pub label: String,
+    pub pr_type: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Settings {
     pub id: i64,
@@ -106,4 +141,73 @@ pub struct Settings {
     pub feature_labels: Vec<String>,
     pub created_at: String,
     pub updated_at: String,
+    pub exclude_queue_merges_from_latency: bool,
+    pub exclude_self_reviews: bool,
+    pub blocking_labels: Vec<String>,
+    pub productivity_weight_speed: f64,
+    pub productivity_weight_ease: f64,
+    pub productivity_weight_quality: f64,
+    pub sync_old_reopened: bool,
+    pub loc_basis: String, // "all", "merged" or "commits"
+    pub sync_pr_files: bool,
+    pub metric_precision: u32,
+    pub min_active_prs: i32,
+    pub exclude_weekends: bool,
+    pub enable_webhooks: bool,
+    pub webhook_secret_set: bool, // Secret lives in the keyring; only this flag is exposed
+    pub webhook_port: u16,
+    pub period_boundary: String, // "rolling" or "inclusive_start" (day-aligned in the team timezone)
+    pub core_start_hour: i32, // Local hour (utc_offset_minutes) core hours start, inclusive (0-23)
+    pub core_end_hour: i32,   // Local hour core hours end, exclusive (1-24)
+    pub sync_interval_minutes: i32, // Expected minutes between syncs
+    pub freshness_aging_multiplier: f64, // Repo data is "aging" after this many sync intervals
+    pub freshness_stale_multiplier: f64, // Repo data is "stale" after this many sync intervals
+    pub include_merge_commit_loc: bool, // Count merge commits when loc_basis is "commits"
+    pub embedding_http_url: String, // Embedding endpoint serving ACTIVE_EMBEDDING_MODEL; empty uses local FastEmbed
+    pub embedding_http_concurrency: i32, // Parallel requests to the embedding endpoint
+    pub sync_strategy: Vec<String>, // Ordered methods: "graphql", "rest", "cli"
+    pub rejection_labels: Vec<String>, // Closed-unmerged PRs with these labels don't count against merge rate
+    pub internal_logins: Vec<String>, // Authors always counted as internal (core team)
+    pub internal_orgs: Vec<String>, // Org members (MEMBER/OWNER association) on these orgs' repos count as internal
+    pub headcount_basis: String, // "active" or "tracked"
+    pub sla_rules: Vec<SlaRule>, // Per-label response/resolution targets
+    pub ai_min_source_similarity: f64, // Sources below this similarity (0-1) are withheld from the AI chat
+    pub pr_type_label_rules: Vec<PrTypeLabelRule>, // Checked in order before title keywords
+    pub exclude_reverts: bool, // Leave revert PRs and their targets out of Speed/Quality
+    pub planning_labels: Vec<String>, // Items with these labels (or a milestone) count as planned
+    pub milestone_counts_source: String, // "github" (synced counts) or "local" (counted from issues)
+    pub utc_offset_minutes: i32, // Team timezone offset from UTC in minutes (-720..=840)
+    pub tier_medium_tolerance: f64, // Fraction below the industry benchmark that still rates Medium
+}
+
+/// Which repositories to include by GitHub visibility
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RepoVisibility {
+    #[default]
+    All,
+    Public,
+    Private,
+}
+
+impl RepoVisibility {
+    /// Required `repositories.is_private` value, or None for all repositories.
+    /// Repositories whose visibility hasn't been synced yet match neither.
+    pub fn is_private(self) -> Option<bool> {
+        match self {
+            RepoVisibility::All => None,
+            RepoVisibility::Public => Some(false),
+            RepoVisibility::Private => Some(true),
+        }
+    }
+}
+
+/// Which relationship to a user the user filter matches on
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UserRole {
+    #[default]
+    Author,
+    Reviewer,
+    Assignee,
 }
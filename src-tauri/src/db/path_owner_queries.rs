@@ -0,0 +1,287 @@
+use super::metrics_queries::Period;
+use anyhow::Result;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+// ============================================================================
+// DATA MODELS FOR PATH OWNERSHIP
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrFile {
+    pub path: String,
+    pub additions: i32,
+    pub deletions: i32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PathOwner {
+    pub id: i64,
+    pub pattern: String, // Glob: `*` within a segment, `**` across segments, trailing `/` for a directory
+    pub squad_id: String,
+    pub squad_name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PathOwnerMetrics {
+    pub squad_id: String,
+    pub squad_name: String,
+    pub patterns: Vec<String>,
+    pub pr_count: i32,
+    pub merged_pr_count: i32,
+    pub lines_changed: i64, // additions + deletions in owned files only
+}
+
+// ============================================================================
+// PR FILE QUERIES
+// ============================================================================
+
+/// Replace the stored changed-file list for a PR
+pub fn replace_pr_files(conn: &Connection, pr_id: i64, files: &[PrFile]) -> Result<()> {
+    let tx = conn.unchecked_transaction()?;
+    tx.execute("DELETE FROM pr_files WHERE pr_id = ?1", params![pr_id])?;
+    for file in files {
+        tx.execute(
+            "INSERT OR REPLACE INTO pr_files (pr_id, path, additions, deletions) VALUES (?1, ?2, ?3, ?4)",
+            params![pr_id, file.path, file.additions, file.deletions],
+        )?;
+    }
+    tx.commit()?;
+    Ok(())
+}
+
+/// Whether any changed files are stored for a PR
+pub fn has_pr_files(conn: &Connection, pr_id: i64) -> Result<bool> {
+    let found = conn
+        .query_row("SELECT 1 FROM pr_files WHERE pr_id = ?1 LIMIT 1", params![pr_id], |_| Ok(()))
+        .optional()?;
+    Ok(found.is_some())
+}
+
+// ============================================================================
+// PATH OWNER QUERIES
+// ============================================================================
+
+/// Map a path glob to a squad
+pub fn add_path_owner(conn: &Connection, pattern: &str, squad_id: &str) -> Result<i64> {
+    conn.execute(
+        "INSERT OR IGNORE INTO path_owners (pattern, squad_id) VALUES (?1, ?2)",
+        params![pattern, squad_id],
+    )?;
+
+    let id: i64 = conn.query_row(
+        "SELECT id FROM path_owners WHERE pattern = ?1 AND squad_id = ?2",
+        params![pattern, squad_id],
+        |row| row.get(0),
+    )?;
+
+    Ok(id)
+}
+
+/// Remove a path ownership entry
+pub fn remove_path_owner(conn: &Connection, id: i64) -> Result<()> {
+    conn.execute("DELETE FROM path_owners WHERE id = ?1", params![id])?;
+    Ok(())
+}
+
+/// Get all path ownership entries
+pub fn get_path_owners(conn: &Connection) -> Result<Vec<PathOwner>> {
+    let mut stmt = conn.prepare(
+        "SELECT po.id, po.pattern, po.squad_id, s.name
+         FROM path_owners po
+         JOIN squads s ON po.squad_id = s.id
+         ORDER BY s.name, po.pattern",
+    )?;
+
+    let owners = stmt
+        .query_map([], |row| {
+            Ok(PathOwner {
+                id: row.get(0)?,
+                pattern: row.get(1)?,
+                squad_id: row.get(2)?,
+                squad_name: row.get(3)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(owners)
+}
+
+// ============================================================================
+// PATH OWNER METRICS
+// ============================================================================
+
+#[derive(Default)]
+struct OwnerAccumulator {
+    prs: HashSet<i64>,
+    merged_prs: HashSet<i64>,
+    files: HashSet<(i64, String)>,
+    lines_changed: i64,
+}
+
+/// Attribute PRs created in the period to squads by the paths they touch.
+/// A PR touching paths owned by several squads is credited to each of them.
+pub fn get_path_owner_metrics(conn: &Connection, period: &Period) -> Result<Vec<PathOwnerMetrics>> {
+    let owners = get_path_owners(conn)?;
+
+    let mut stmt = conn.prepare(
+        "SELECT f.pr_id, f.path, COALESCE(f.additions, 0) + COALESCE(f.deletions, 0), pr.merged_at IS NOT NULL
+         FROM pr_files f
+         JOIN pull_requests pr ON f.pr_id = pr.id
+         WHERE pr.created_at >= ?1",
+    )?;
+    let files = stmt
+        .query_map(params![period.since], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, i64>(2)?,
+                row.get::<_, bool>(3)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut accumulators: HashMap<&str, OwnerAccumulator> = HashMap::new();
+    for (pr_id, path, lines, merged) in &files {
+        for owner in owners.iter().filter(|o| glob_matches(&o.pattern, path)) {
+            let acc = accumulators.entry(owner.squad_id.as_str()).or_default();
+            acc.prs.insert(*pr_id);
+            if *merged {
+                acc.merged_prs.insert(*pr_id);
+            }
+            // Several patterns of the same squad may match one file; count its lines once
+            if acc.files.insert((*pr_id, path.clone())) {
+                acc.lines_changed += lines;
+            }
+        }
+    }
+
+    let mut metrics: Vec<PathOwnerMetrics> = Vec::new();
+    for owner in &owners {
+        if let Some(existing) = metrics.iter_mut().find(|m| m.squad_id == owner.squad_id) {
+            existing.patterns.push(owner.pattern.clone());
+            continue;
+        }
+
+        let acc = accumulators.remove(owner.squad_id.as_str()).unwrap_or_default();
+        metrics.push(PathOwnerMetrics {
+            squad_id: owner.squad_id.clone(),
+            squad_name: owner.squad_name.clone(),
+            patterns: vec![owner.pattern.clone()],
+            pr_count: acc.prs.len() as i32,
+            merged_pr_count: acc.merged_prs.len() as i32,
+            lines_changed: acc.lines_changed,
+        });
+    }
+
+    metrics.sort_by(|a, b| b.pr_count.cmp(&a.pr_count).then_with(|| a.squad_name.cmp(&b.squad_name)));
+    Ok(metrics)
+}
+
+/// Match a repository path against an ownership glob
+pub fn glob_matches(pattern: &str, path: &str) -> bool {
+    let pattern = if pattern.ends_with('/') {
+        format!("{}**", pattern)
+    } else {
+        pattern.to_string()
+    };
+    glob_match_bytes(pattern.as_bytes(), path.as_bytes())
+}
+
+fn glob_match_bytes(pattern: &[u8], path: &[u8]) -> bool {
+    match pattern.first() {
+        None => path.is_empty(),
+        Some(b'*') if pattern.get(1) == Some(&b'*') => {
+            let rest = &pattern[2..];
+            // `**/` may also match zero directories
+            if rest.first() == Some(&b'/') && glob_match_bytes(&rest[1..], path) {
+                return true;
+            }
+            (0..=path.len()).any(|i| glob_match_bytes(rest, &path[i..]))
+        }
+        Some(b'*') => (0..=path.len())
+            .take_while(|&i| i == 0 || path[i - 1] != b'/')
+            .any(|i| glob_match_bytes(&pattern[1..], &path[i..])),
+        Some(b'?') => {
+            !path.is_empty() && path[0] != b'/' && glob_match_bytes(&pattern[1..], &path[1..])
+        }
+        Some(&c) => path.first() == Some(&c) && glob_match_bytes(&pattern[1..], &path[1..]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::db::migrations::run_migrations(&conn).unwrap();
+
+        conn.execute_batch(
+            "INSERT INTO repositories (id, owner, name) VALUES (1, 'acme', 'monorepo');
+             INSERT INTO squads (id, name) VALUES ('payments', 'Payments'), ('web', 'Web'), ('infra', 'Infra');
+             INSERT INTO pull_requests (id, github_id, repo_id, number, title, state, created_at, updated_at, merged_at)
+             VALUES
+                 (1, 1, 1, 1, 'Cross-cutting change', 'MERGED', datetime('now', '-3 days'), datetime('now', '-2 days'), datetime('now', '-2 days')),
+                 (2, 2, 1, 2, 'Web only', 'OPEN', datetime('now', '-3 days'), datetime('now', '-2 days'), NULL);",
+        )
+        .unwrap();
+
+        conn
+    }
+
+    fn file(path: &str, additions: i32, deletions: i32) -> PrFile {
+        PrFile { path: path.to_string(), additions, deletions }
+    }
+
+    #[test]
+    fn test_glob_matching() {
+        assert!(glob_matches("services/payments/**", "services/payments/api/handler.rs"));
+        assert!(glob_matches("services/payments/", "services/payments/README.md"));
+        assert!(glob_matches("web/*.ts", "web/index.ts"));
+        assert!(!glob_matches("web/*.ts", "web/src/index.ts"));
+        assert!(glob_matches("**/BUILD", "BUILD"));
+        assert!(glob_matches("**/BUILD", "services/web/BUILD"));
+        assert!(!glob_matches("services/payments/**", "services/web/app.ts"));
+    }
+
+    #[test]
+    fn test_pr_touching_two_owned_paths_credits_both_owners() {
+        let conn = setup_db();
+        add_path_owner(&conn, "services/payments/**", "payments").unwrap();
+        add_path_owner(&conn, "services/payments/api/", "payments").unwrap();
+        add_path_owner(&conn, "web/**", "web").unwrap();
+        add_path_owner(&conn, "infra/**", "infra").unwrap();
+
+        replace_pr_files(
+            &conn,
+            1,
+            &[
+                file("services/payments/api/charge.rs", 30, 10),
+                file("web/src/checkout.tsx", 5, 5),
+                file("README.md", 1, 0),
+            ],
+        )
+        .unwrap();
+        replace_pr_files(&conn, 2, &[file("web/src/home.tsx", 2, 2)]).unwrap();
+        assert!(has_pr_files(&conn, 1).unwrap());
+
+        let metrics = get_path_owner_metrics(&conn, &Period::new(30, "rolling", chrono::Utc::now())).unwrap();
+        let by_squad = |id: &str| metrics.iter().find(|m| m.squad_id == id).unwrap();
+
+        let payments = by_squad("payments");
+        assert_eq!(payments.pr_count, 1);
+        assert_eq!(payments.merged_pr_count, 1);
+        assert_eq!(payments.lines_changed, 40);
+        assert_eq!(payments.patterns.len(), 2);
+
+        let web = by_squad("web");
+        assert_eq!(web.pr_count, 2);
+        assert_eq!(web.merged_pr_count, 1);
+        assert_eq!(web.lines_changed, 14);
+
+        assert_eq!(by_squad("infra").pr_count, 0);
+        assert_eq!(metrics[0].squad_id, "web");
+    }
+}
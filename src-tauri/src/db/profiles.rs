@@ -0,0 +1,182 @@
+//! Named workspace profiles, each with its own SQLite database (and therefore its own settings).
+//!
+//! The default profile keeps the original layout (`<app dir>/made.db`); named profiles live
+//! under `<app dir>/profiles/<name>/made.db`.
+
+use super::migrations;
+use anyhow::{bail, Result};
+use rusqlite::Connection;
+use std::path::{Path, PathBuf};
+
+/// Profile used when none has been selected
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// Environment variable that selects a profile at startup, overriding the last one used
+pub const PROFILE_ENV_VAR: &str = "MADE_PROFILE";
+
+/// File in the app dir remembering the last profile switched to
+const ACTIVE_PROFILE_FILE: &str = "active_profile";
+
+const PROFILES_DIR: &str = "profiles";
+const DB_FILE: &str = "made.db";
+const MAX_PROFILE_NAME_LEN: usize = 64;
+
+/// Profile names double as directory names, so keep them to a safe character set
+pub fn validate_profile_name(name: &str) -> Result<()> {
+    if name.is_empty() || name.len() > MAX_PROFILE_NAME_LEN {
+        bail!("Profile name must be 1-{} characters", MAX_PROFILE_NAME_LEN);
+    }
+    if !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+        bail!("Profile name may only contain letters, digits, '-' and '_'");
+    }
+    Ok(())
+}
+
+/// Directory holding a profile's data
+pub fn profile_dir(app_dir: &Path, name: &str) -> Result<PathBuf> {
+    validate_profile_name(name)?;
+    if name == DEFAULT_PROFILE {
+        Ok(app_dir.to_path_buf())
+    } else {
+        Ok(app_dir.join(PROFILES_DIR).join(name))
+    }
+}
+
+/// SQLite file for a profile
+pub fn profile_db_path(app_dir: &Path, name: &str) -> Result<PathBuf> {
+    Ok(profile_dir(app_dir, name)?.join(DB_FILE))
+}
+
+/// Open (creating if needed) a profile's database and bring its schema up to date
+pub fn open_profile(app_dir: &Path, name: &str) -> Result<Connection> {
+    let dir = profile_dir(app_dir, name)?;
+    std::fs::create_dir_all(&dir)?;
+
+    let conn = Connection::open(dir.join(DB_FILE))?;
+    migrations::run_migrations(&conn)?;
+
+    // Syncs left open by a crash or forced quit can't still be running
+    let interrupted = super::queries::mark_interrupted_syncs(&conn)?;
+    if interrupted > 0 {
+        tracing::warn!("Marked {} unfinished sync(s) from a previous run as interrupted", interrupted);
+    }
+
+    migrate_plaintext_webhook_secret(&conn, name)?;
+    Ok(conn)
+}
+
+/// Move a webhook secret older versions stored in the settings table into the keyring
+fn migrate_plaintext_webhook_secret(conn: &Connection, name: &str) -> Result<()> {
+    let secret: String = conn.query_row("SELECT webhook_secret FROM settings WHERE id = 1", [], |row| row.get(0))?;
+    if !secret.is_empty() {
+        crate::github::auth::store_webhook_secret(name, &secret)?;
+        conn.execute(
+            "UPDATE settings SET webhook_secret = '', webhook_secret_set = TRUE WHERE id = 1",
+            [],
+        )?;
+        tracing::info!("Moved the webhook secret for profile '{}' into the keyring", name);
+    }
+    Ok(())
+}
+
+/// All profiles with a database on disk, default first
+pub fn list_profiles(app_dir: &Path) -> Result<Vec<String>> {
+    let mut profiles = Vec::new();
+    let profiles_dir = app_dir.join(PROFILES_DIR);
+    if profiles_dir.is_dir() {
+        for entry in std::fs::read_dir(&profiles_dir)? {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().to_string();
+            if validate_profile_name(&name).is_ok() && entry.path().join(DB_FILE).exists() {
+                profiles.push(name);
+            }
+        }
+    }
+    profiles.sort();
+    profiles.insert(0, DEFAULT_PROFILE.to_string());
+    Ok(profiles)
+}
+
+/// Profile to open at startup: MADE_PROFILE if set, else the last one switched to, else default
+pub fn startup_profile(app_dir: &Path) -> String {
+    let requested = std::env::var(PROFILE_ENV_VAR)
+        .ok()
+        .or_else(|| std::fs::read_to_string(app_dir.join(ACTIVE_PROFILE_FILE)).ok())
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty());
+
+    match requested {
+        Some(name) if validate_profile_name(&name).is_ok() => name,
+        Some(name) => {
+            tracing::warn!("Ignoring invalid profile name {:?}, using default profile", name);
+            DEFAULT_PROFILE.to_string()
+        }
+        None => DEFAULT_PROFILE.to_string(),
+    }
+}
+
+/// Remember the active profile so the next launch reopens it
+pub fn save_active_profile(app_dir: &Path, name: &str) -> Result<()> {
+    validate_profile_name(name)?;
+    std::fs::write(app_dir.join(ACTIVE_PROFILE_FILE), name)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_app_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("made-profiles-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_profiles_keep_data_isolated() {
+        let app_dir = temp_app_dir();
+
+        let client_a = open_profile(&app_dir, "client-a").unwrap();
+        let client_b = open_profile(&app_dir, "client-b").unwrap();
+        client_a
+            .execute("INSERT INTO repositories (owner, name) VALUES ('acme', 'api')", [])
+            .unwrap();
+        client_a.execute("UPDATE settings SET history_days = 7", []).unwrap();
+
+        let count = |conn: &Connection| -> i64 {
+            conn.query_row("SELECT COUNT(*) FROM repositories", [], |row| row.get(0)).unwrap()
+        };
+        assert_eq!(count(&client_a), 1);
+        assert_eq!(count(&client_b), 0);
+
+        // Settings are per profile too
+        let history_days = |conn: &Connection| -> i32 {
+            conn.query_row("SELECT history_days FROM settings", [], |row| row.get(0)).unwrap()
+        };
+        assert_eq!(history_days(&client_a), 7);
+        assert_ne!(history_days(&client_b), 7);
+
+        // Reopening a profile finds its own data again
+        drop(client_a);
+        assert_eq!(count(&open_profile(&app_dir, "client-a").unwrap()), 1);
+
+        assert_eq!(
+            list_profiles(&app_dir).unwrap(),
+            vec!["default".to_string(), "client-a".to_string(), "client-b".to_string()]
+        );
+
+        std::fs::remove_dir_all(&app_dir).unwrap();
+    }
+
+    #[test]
+    fn test_default_profile_uses_original_db_location() {
+        let app_dir = Path::new("/data/made");
+        assert_eq!(profile_db_path(app_dir, DEFAULT_PROFILE).unwrap(), app_dir.join("made.db"));
+        assert_eq!(
+            profile_db_path(app_dir, "client-a").unwrap(),
+            app_dir.join("profiles").join("client-a").join("made.db")
+        );
+        assert!(profile_db_path(app_dir, "../escape").is_err());
+        assert!(profile_db_path(app_dir, "").is_err());
+    }
+}
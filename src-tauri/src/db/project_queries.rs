@@ -1,6 +1,6 @@
 use super::models::*;
 use anyhow::Result;
-use rusqlite::{params, Connection};
+use rusqlite::{params, Connection, OptionalExtension};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -62,6 +62,38 @@ pub struct ProjectSummary {
     pub total_prs: i32,
     pub total_issues: i32,
     pub last_synced_at: Option<String>,
+    pub default_branch: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LeaderboardEntry {
+    pub user: User,
+    pub merged_prs: i32,
+    pub lines_changed: i64, // additions + deletions of merged PRs
+    pub reviews: i32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LeaderboardSort {
+    MergedPrs,
+    Loc,
+    Reviews,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepoLanguage {
+    pub name: String,
+    pub color: Option<String>,
+    pub bytes: i64,
+    pub percentage: f64, // Share of the repository's total bytes, 0-100
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepoLanguages {
+    pub primary_language: Option<String>,
+    pub total_bytes: i64,
+    pub languages: Vec<RepoLanguage>,
 }
 
 // ============================================================================
@@ -481,6 +513,9 @@ pub fn get_contributor_stats(
         (None, None) => String::new(),
     };
 
+    let settings = crate::db::queries::get_settings(conn)?;
+    let self_review_filter = crate::db::queries::self_review_filter(settings.exclude_self_reviews);
+
     // Get all contributors from PRs and issues
     let query = format!(
         "SELECT DISTINCT u.id, u.github_id, u.login, u.name, u.avatar_url, u.is_bot
@@ -540,8 +575,8 @@ pub fn get_contributor_stats(
         let review_query = format!(
             "SELECT COUNT(*) FROM pr_reviews r
              JOIN pull_requests pr ON r.pr_id = pr.id
-             WHERE pr.repo_id = ?1 AND r.reviewer_id = ?2{}",
-            review_date_filter
+             WHERE pr.repo_id = ?1 AND r.reviewer_id = ?2{}{}",
+            review_date_filter, self_review_filter
         );
         let total_prs_reviewed: i32 = conn.query_row(&review_query, params![repo_id, user.id], |row| row.get(0))?;
 
@@ -585,6 +620,79 @@ pub fn get_contributor_stats(
     Ok(stats)
 }
 
+/// Get a contributor leaderboard for a repository over the last N days, excluding bots
+pub fn get_contributor_leaderboard(
+    conn: &Connection,
+    repo_id: i64,
+    days: i32,
+    sort_by: LeaderboardSort,
+) -> Result<Vec<LeaderboardEntry>> {
+    let settings = crate::db::queries::get_settings(conn)?;
+    let period = super::metrics_queries::Period::from_settings(days, &settings);
+    let self_review_filter = crate::db::queries::self_review_filter(settings.exclude_self_reviews);
+
+    let query = format!(
+        "WITH merged AS (
+             SELECT author_id, COUNT(*) as merged_prs,
+                    COALESCE(SUM(additions), 0) + COALESCE(SUM(deletions), 0) as lines_changed
+             FROM pull_requests
+             WHERE repo_id = ?1 AND merged_at IS NOT NULL
+               AND merged_at >= ?2
+             GROUP BY author_id
+         ),
+         reviewed AS (
+             SELECT r.reviewer_id, COUNT(*) as reviews
+             FROM pr_reviews r
+             JOIN pull_requests pr ON r.pr_id = pr.id
+             WHERE pr.repo_id = ?1
+               AND r.submitted_at >= ?2{}
+             GROUP BY r.reviewer_id
+         )
+         SELECT u.id, u.github_id, u.login, u.name, u.avatar_url, u.is_bot,
+                COALESCE(m.merged_prs, 0), COALESCE(m.lines_changed, 0), COALESCE(rv.reviews, 0)
+         FROM users u
+         LEFT JOIN merged m ON m.author_id = u.id
+         LEFT JOIN reviewed rv ON rv.reviewer_id = u.id
+         WHERE u.is_bot = FALSE AND (m.author_id IS NOT NULL OR rv.reviewer_id IS NOT NULL)",
+        self_review_filter
+    );
+
+    let mut stmt = conn.prepare(&query)?;
+    let mut entries: Vec<LeaderboardEntry> = stmt
+        .query_map(params![repo_id, period.since], |row| {
+            Ok(LeaderboardEntry {
+                user: User {
+                    id: row.get(0)?,
+                    github_id: row.get(1)?,
+                    login: row.get(2)?,
+                    name: row.get(3)?,
+                    avatar_url: row.get(4)?,
+                    is_bot: row.get(5)?,
+                    tracked: false,
+                    tracked_at: None,
+                },
+                merged_prs: row.get(6)?,
+                lines_changed: row.get(7)?,
+                reviews: row.get(8)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    entries.retain(|e| !crate::db::queries::is_bot_user(&e.user.login, &settings.excluded_bots));
+
+    // Highest first; ties broken by login for a stable order
+    entries.sort_by(|a, b| {
+        let key = match sort_by {
+            LeaderboardSort::MergedPrs => b.merged_prs.cmp(&a.merged_prs),
+            LeaderboardSort::Loc => b.lines_changed.cmp(&a.lines_changed),
+            LeaderboardSort::Reviews => b.reviews.cmp(&a.reviews),
+        };
+        key.then_with(|| a.user.login.cmp(&b.user.login))
+    });
+
+    Ok(entries)
+}
+
 // ============================================================================
 // ACTIVITY HEATMAP QUERIES
 // ============================================================================
@@ -722,7 +830,7 @@ pub fn get_lifecycle_metrics(
 
     // Calculate time to merge for merged PRs (in hours)
     let merge_time_query = format!(
-        "SELECT (julianday(merged_at) - julianday(created_at)) * 24 as hours
+        "SELECT id, (julianday(merged_at) - julianday(created_at)) * 24 as hours
          FROM pull_requests
          WHERE repo_id = ?1 AND merged_at IS NOT NULL{}
          ORDER BY hours",
@@ -734,12 +842,19 @@ pub fn get_lifecycle_metrics(
     let mut avg_time_to_merge = 0.0;
 
     let time_rows = stmt.query_map([repo_id], |row| {
-        Ok(row.get::<_, f64>(0)?)
+        Ok((row.get::<_, i64>(0)?, row.get::<_, Option<f64>>(1)?))
     })?;
 
     for time in time_rows {
-        let t = time?;
-        times.push(t);
+        let (pr_id, hours) = time?;
+        match hours {
+            Some(t) if t < 0.0 => {
+                tracing::warn!("PR {} merged before it was created, clamping time to merge to 0", pr_id);
+                times.push(0.0);
+            }
+            Some(t) => times.push(t),
+            None => tracing::warn!("Skipping PR {} with unparseable timestamps", pr_id),
+        }
     }
 
     if !times.is_empty() {
@@ -767,17 +882,26 @@ pub fn get_lifecycle_metrics(
         0.0
     };
 
-    // Calculate time to first review (in hours)
+    // Calculate time to first review (in hours), optionally ignoring merge-queue merges
+    let settings = crate::db::queries::get_settings(conn)?;
+    let self_review_filter = crate::db::queries::self_review_filter(settings.exclude_self_reviews);
+    let queue_filter = if settings.exclude_queue_merges_from_latency {
+        " AND COALESCE(pr.merge_method, '') != 'merge_queue'"
+    } else {
+        ""
+    };
     let first_review_query = format!(
         "SELECT AVG((julianday(r.submitted_at) - julianday(pr.created_at)) * 24) as avg_hours
          FROM pull_requests pr
          JOIN (
-             SELECT pr_id, MIN(submitted_at) as submitted_at
-             FROM pr_reviews
-             GROUP BY pr_id
+             SELECT r.pr_id, MIN(r.submitted_at) as submitted_at
+             FROM pr_reviews r
+             JOIN pull_requests pr ON r.pr_id = pr.id
+             WHERE 1 = 1{}
+             GROUP BY r.pr_id
          ) r ON pr.id = r.pr_id
-         WHERE pr.repo_id = ?1{}",
-        date_filter
+         WHERE pr.repo_id = ?1{}{}",
+        self_review_filter, date_filter, queue_filter
     );
 
     let avg_time_to_first_review: f64 = conn.query_row(&first_review_query, [repo_id], |row| {
@@ -789,11 +913,11 @@ pub fn get_lifecycle_metrics(
         "SELECT AVG(review_count) FROM (
              SELECT pr.id, COUNT(r.id) as review_count
              FROM pull_requests pr
-             LEFT JOIN pr_reviews r ON pr.id = r.pr_id
+             LEFT JOIN pr_reviews r ON pr.id = r.pr_id{}
              WHERE pr.repo_id = ?1{}
              GROUP BY pr.id
          )",
-        date_filter
+        self_review_filter, date_filter
     );
 
     let avg_review_cycles: f64 = conn.query_row(&review_cycles_query, [repo_id], |row| {
@@ -945,11 +1069,419 @@ pub fn get_project_summary(
         |row| row.get(0),
     ).ok();
 
+    let default_branch = crate::db::queries::get_repo_default_branch(conn, repo_id)?;
+
     Ok(ProjectSummary {
         total_contributors,
         total_commits: 0, // We don't track individual commits yet
         total_prs,
         total_issues,
         last_synced_at,
+        default_branch,
     })
 }
+
+// ============================================================================
+// LANGUAGE QUERIES
+// ============================================================================
+
+/// Get the cached language breakdown for a repository (populated by repository info sync)
+pub fn get_repo_languages(conn: &Connection, owner: &str, repo: &str) -> Result<RepoLanguages> {
+    let (repo_id, primary_language): (i64, Option<String>) = conn
+        .query_row(
+            "SELECT id, primary_language FROM repositories WHERE owner = ?1 AND name = ?2",
+            params![owner, repo],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .map_err(|_| anyhow::anyhow!("Repository {}/{} not found", owner, repo))?;
+
+    let sizes = conn
+        .prepare("SELECT language, color, bytes FROM repo_languages WHERE repo_id = ?1")?
+        .query_map(params![repo_id], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+        .collect::<Result<Vec<(String, Option<String>, i64)>, _>>()?;
+
+    let total_bytes = sizes.iter().map(|(_, _, bytes)| bytes).sum();
+    Ok(RepoLanguages {
+        primary_language,
+        total_bytes,
+        languages: language_breakdown(sizes),
+    })
+}
+
+/// Convert (name, color, bytes) sizes into percentages, largest first
+pub fn language_breakdown(sizes: Vec<(String, Option<String>, i64)>) -> Vec<RepoLanguage> {
+    let total: i64 = sizes.iter().map(|(_, _, bytes)| bytes).sum();
+
+    let mut languages: Vec<RepoLanguage> = sizes
+        .into_iter()
+        .map(|(name, color, bytes)| RepoLanguage {
+            percentage: if total > 0 { bytes as f64 * 100.0 / total as f64 } else { 0.0 },
+            name,
+            color,
+            bytes,
+        })
+        .collect();
+    languages.sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.name.cmp(&b.name)));
+    languages
+}
+
+// ============================================================================
+// PR DETAIL
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrDetailReview {
+    pub reviewer: Option<String>, // Login, None if the reviewer was never synced
+    pub state: String,
+    pub submitted_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LinkedIssue {
+    pub number: i32,
+    pub title: Option<String>, // None until the issue itself is synced
+    pub state: Option<String>,
+}
+
+/// Everything stored about a single PR, for the PR deep-dive view
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrDetail {
+    pub pr: PullRequest,
+    pub author: Option<String>,
+    pub base_branch: Option<String>,
+    pub commit_count: Option<i32>, // None for PRs synced before commit counts were stored
+    pub reviews: Vec<PrDetailReview>,
+    pub linked_issues: Vec<LinkedIssue>,
+    pub files: Vec<super::path_owner_queries::PrFile>, // Empty unless sync_pr_files is enabled
+}
+
+/// Get a PR with its reviews, commit count, linked issues and changed files (None if the PR isn't synced)
+pub fn get_pr_detail(conn: &Connection, repo_id: i64, number: i32) -> Result<Option<PrDetail>> {
+    let found = conn
+        .query_row(
+            "SELECT p.id, p.github_id, p.repo_id, p.number, p.title, p.body, p.state, p.author_id,
+                    p.created_at, p.updated_at, p.sync_updated_at, p.merged_at, p.closed_at,
+                    p.additions, p.deletions, p.changed_files, p.review_comments, p.labels,
+                    u.login, p.base_branch, p.commit_count
+             FROM pull_requests p
+             LEFT JOIN users u ON p.author_id = u.id
+             WHERE p.repo_id = ?1 AND p.number = ?2",
+            params![repo_id, number],
+            |row| {
+                let pr = PullRequest {
+                    id: row.get(0)?,
+                    github_id: row.get(1)?,
+                    repo_id: row.get(2)?,
+                    number: row.get(3)?,
+                    title: row.get(4)?,
+                    body: row.get(5)?,
+                    state: row.get(6)?,
+                    author_id: row.get(7)?,
+                    created_at: row.get(8)?,
+                    updated_at: row.get(9)?,
+                    sync_updated_at: row.get(10)?,
+                    merged_at: row.get(11)?,
+                    closed_at: row.get(12)?,
+                    additions: row.get::<_, Option<i32>>(13)?.unwrap_or(0),
+                    deletions: row.get::<_, Option<i32>>(14)?.unwrap_or(0),
+                    changed_files: row.get::<_, Option<i32>>(15)?.unwrap_or(0),
+                    review_comments: row.get::<_, Option<i32>>(16)?.unwrap_or(0),
+                    labels: row
+                        .get::<_, Option<String>>(17)?
+                        .and_then(|json| serde_json::from_str(&json).ok())
+                        .unwrap_or_default(),
+                };
+                Ok((pr, row.get(18)?, row.get(19)?, row.get(20)?))
+            },
+        )
+        .optional()?;
+
+    let Some((pr, author, base_branch, commit_count)) = found else {
+        return Ok(None);
+    };
+
+    let reviews = conn
+        .prepare(
+            "SELECT u.login, r.state, r.submitted_at
+             FROM pr_reviews r
+             LEFT JOIN users u ON r.reviewer_id = u.id
+             WHERE r.pr_id = ?1
+             ORDER BY r.submitted_at ASC",
+        )?
+        .query_map(params![pr.id], |row| {
+            Ok(PrDetailReview {
+                reviewer: row.get(0)?,
+                state: row.get(1)?,
+                submitted_at: row.get(2)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let linked_issues = conn
+        .prepare(
+            "SELECT l.issue_number, i.title, i.state
+             FROM pr_issue_links l
+             LEFT JOIN issues i ON i.repo_id = ?2 AND i.number = l.issue_number
+             WHERE l.pr_id = ?1
+             ORDER BY l.issue_number ASC",
+        )?
+        .query_map(params![pr.id, pr.repo_id], |row| {
+            Ok(LinkedIssue {
+                number: row.get(0)?,
+                title: row.get(1)?,
+                state: row.get(2)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let files = conn
+        .prepare(
+            "SELECT path, COALESCE(additions, 0), COALESCE(deletions, 0)
+             FROM pr_files
+             WHERE pr_id = ?1
+             ORDER BY path ASC",
+        )?
+        .query_map(params![pr.id], |row| {
+            Ok(super::path_owner_queries::PrFile {
+                path: row.get(0)?,
+                additions: row.get(1)?,
+                deletions: row.get(2)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(Some(PrDetail {
+        pr,
+        author,
+        base_branch,
+        commit_count,
+        reviews,
+        linked_issues,
+        files,
+    }))
+}
+
+// ============================================================================
+// FILE HOTSPOTS
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileHotspot {
+    pub path: String,
+    pub pr_count: i32,
+    pub additions: i64,
+    pub deletions: i64,
+    pub lines_changed: i64, // additions + deletions across those PRs
+}
+
+/// Get the files changed by the most PRs opened in the period (needs sync_pr_files).
+/// Ties are broken by lines changed, then path.
+pub fn get_file_hotspots(conn: &Connection, repo_id: i64, period: &super::metrics_queries::Period, limit: usize) -> Result<Vec<FileHotspot>> {
+    let mut stmt = conn.prepare(
+        "SELECT f.path, COUNT(DISTINCT f.pr_id),
+                COALESCE(SUM(f.additions), 0), COALESCE(SUM(f.deletions), 0)
+         FROM pr_files f
+         JOIN pull_requests pr ON f.pr_id = pr.id
+         WHERE pr.repo_id = ?1
+           AND pr.created_at >= ?2
+         GROUP BY f.path
+         ORDER BY COUNT(DISTINCT f.pr_id) DESC,
+                  COALESCE(SUM(f.additions), 0) + COALESCE(SUM(f.deletions), 0) DESC,
+                  f.path ASC
+         LIMIT ?3",
+    )?;
+
+    let hotspots = stmt
+        .query_map(params![repo_id, period.since, limit as i64], |row| {
+            let additions: i64 = row.get(2)?;
+            let deletions: i64 = row.get(3)?;
+            Ok(FileHotspot {
+                path: row.get(0)?,
+                pr_count: row.get(1)?,
+                additions,
+                deletions,
+                lines_changed: additions + deletions,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(hotspots)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::metrics_queries::Period;
+
+    fn rolling(days: i32) -> Period {
+        Period::new(days, "rolling", chrono::Utc::now())
+    }
+
+    fn setup_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::db::migrations::run_migrations(&conn).unwrap();
+
+        conn.execute_batch(
+            "INSERT INTO repositories (id, owner, name) VALUES (1, 'acme', 'api');
+             INSERT INTO users (id, github_id, login, is_bot) VALUES
+                 (1, 101, 'alice', FALSE), (2, 102, 'bob', FALSE), (3, 103, 'carol', FALSE),
+                 (4, 104, 'renovate[bot]', TRUE);
+             INSERT INTO pull_requests (id, github_id, repo_id, number, title, state, author_id,
+                                        created_at, updated_at, merged_at, additions, deletions)
+             VALUES
+                 (1, 1, 1, 1, 'a1', 'MERGED', 1, datetime('now', '-5 days'), datetime('now', '-4 days'), datetime('now', '-4 days'), 10, 5),
+                 (2, 2, 1, 2, 'a2', 'MERGED', 1, datetime('now', '-5 days'), datetime('now', '-3 days'), datetime('now', '-3 days'), 5, 0),
+                 (3, 3, 1, 3, 'a3', 'MERGED', 1, datetime('now', '-5 days'), datetime('now', '-2 days'), datetime('now', '-2 days'), 5, 0),
+                 (4, 4, 1, 4, 'b1', 'MERGED', 2, datetime('now', '-5 days'), datetime('now', '-2 days'), datetime('now', '-2 days'), 900, 100),
+                 (5, 5, 1, 5, 'bot', 'MERGED', 4, datetime('now', '-5 days'), datetime('now', '-2 days'), datetime('now', '-2 days'), 5000, 0),
+                 (6, 6, 1, 6, 'old', 'MERGED', 3, datetime('now', '-90 days'), datetime('now', '-80 days'), datetime('now', '-80 days'), 9999, 0);
+             INSERT INTO pr_reviews (github_id, pr_id, reviewer_id, state, submitted_at) VALUES
+                 (11, 1, 3, 'APPROVED', datetime('now', '-4 days')),
+                 (12, 2, 3, 'APPROVED', datetime('now', '-3 days')),
+                 (13, 3, 3, 'APPROVED', datetime('now', '-2 days')),
+                 (14, 4, 3, 'APPROVED', datetime('now', '-2 days')),
+                 (15, 4, 1, 'COMMENTED', datetime('now', '-2 days'));",
+        )
+        .unwrap();
+
+        conn
+    }
+
+    fn logins(entries: &[LeaderboardEntry]) -> Vec<&str> {
+        entries.iter().map(|e| e.user.login.as_str()).collect()
+    }
+
+    #[test]
+    fn test_leaderboard_sort_keys_reorder_entries() {
+        let conn = setup_db();
+
+        let by_prs = get_contributor_leaderboard(&conn, 1, 30, LeaderboardSort::MergedPrs).unwrap();
+        assert_eq!(logins(&by_prs), vec!["alice", "bob", "carol"]);
+        assert_eq!(by_prs[0].merged_prs, 3);
+        assert_eq!(by_prs[0].lines_changed, 25);
+
+        let by_loc = get_contributor_leaderboard(&conn, 1, 30, LeaderboardSort::Loc).unwrap();
+        assert_eq!(logins(&by_loc), vec!["bob", "alice", "carol"]);
+
+        let by_reviews = get_contributor_leaderboard(&conn, 1, 30, LeaderboardSort::Reviews).unwrap();
+        assert_eq!(logins(&by_reviews), vec!["carol", "alice", "bob"]);
+        assert_eq!(by_reviews[0].reviews, 4);
+    }
+
+    #[test]
+    fn test_leaderboard_excludes_bots_and_old_activity() {
+        let conn = setup_db();
+
+        let entries = get_contributor_leaderboard(&conn, 1, 30, LeaderboardSort::Loc).unwrap();
+        assert!(entries.iter().all(|e| !e.user.login.ends_with("[bot]")));
+
+        let carol = entries.iter().find(|e| e.user.login == "carol").unwrap();
+        assert_eq!(carol.merged_prs, 0);
+        assert_eq!(carol.lines_changed, 0);
+    }
+
+    #[test]
+    fn test_repo_languages_round_trip() {
+        let conn = setup_db();
+        let sizes = vec![
+            ("TypeScript".to_string(), Some("#3178c6".to_string()), 300),
+            ("Rust".to_string(), Some("#dea584".to_string()), 600),
+            ("CSS".to_string(), None, 100),
+        ];
+        crate::db::queries::replace_repo_languages(&conn, 1, Some("Rust"), &sizes).unwrap();
+
+        let langs = get_repo_languages(&conn, "acme", "api").unwrap();
+        assert_eq!(langs.primary_language.as_deref(), Some("Rust"));
+        assert_eq!(langs.total_bytes, 1000);
+        let names: Vec<&str> = langs.languages.iter().map(|l| l.name.as_str()).collect();
+        assert_eq!(names, vec!["Rust", "TypeScript", "CSS"]);
+        let total: f64 = langs.languages.iter().map(|l| l.percentage).sum();
+        assert!((total - 100.0).abs() < 1e-6);
+
+        // A re-sync replaces the cached breakdown
+        crate::db::queries::replace_repo_languages(&conn, 1, None, &[]).unwrap();
+        let langs = get_repo_languages(&conn, "acme", "api").unwrap();
+        assert!(langs.primary_language.is_none());
+        assert!(langs.languages.is_empty());
+
+        assert!(get_repo_languages(&conn, "acme", "missing").is_err());
+    }
+
+    #[test]
+    fn test_pr_detail_bundles_related_sections() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::db::migrations::run_migrations(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO repositories (id, owner, name) VALUES (1, 'acme', 'api');
+             INSERT INTO users (id, github_id, login) VALUES (1, 101, 'alice'), (2, 102, 'bob');
+             INSERT INTO pull_requests (id, github_id, repo_id, number, title, state, author_id, created_at, updated_at,
+                                        merged_at, labels, base_branch, commit_count)
+             VALUES (10, 1000, 1, 42, 'Fix login', 'MERGED', 1, '2024-03-01T00:00:00Z', '2024-03-02T00:00:00Z',
+                     '2024-03-02T00:00:00Z', '[\"bug\"]', 'main', 3),
+                    (11, 1001, 1, 43, 'Bare PR', 'OPEN', 2, '2024-03-01T00:00:00Z', '2024-03-01T00:00:00Z',
+                     NULL, '[]', NULL, NULL);
+             INSERT INTO pr_reviews (github_id, pr_id, reviewer_id, state, submitted_at) VALUES
+                 (1, 10, 2, 'CHANGES_REQUESTED', '2024-03-01T06:00:00Z'),
+                 (2, 10, 2, 'APPROVED', '2024-03-01T12:00:00Z');
+             INSERT INTO issues (github_id, repo_id, number, title, state, created_at, updated_at, labels)
+             VALUES (2000, 1, 7, 'Login broken', 'CLOSED', '2024-02-01T00:00:00Z', '2024-03-02T00:00:00Z', '[]');
+             INSERT INTO pr_issue_links (pr_id, issue_number) VALUES (10, 7), (10, 8);
+             INSERT INTO pr_files (pr_id, path, additions, deletions) VALUES
+                 (10, 'src/auth.rs', 10, 2), (10, 'src/main.rs', 1, 0);",
+        )
+        .unwrap();
+
+        let detail = get_pr_detail(&conn, 1, 42).unwrap().unwrap();
+        assert_eq!(detail.pr.title, "Fix login");
+        assert_eq!(detail.pr.labels, vec!["bug".to_string()]);
+        assert_eq!(detail.author.as_deref(), Some("alice"));
+        assert_eq!(detail.base_branch.as_deref(), Some("main"));
+        assert_eq!(detail.commit_count, Some(3));
+
+        let review_states: Vec<_> = detail.reviews.iter().map(|r| r.state.as_str()).collect();
+        assert_eq!(review_states, vec!["CHANGES_REQUESTED", "APPROVED"]);
+        assert_eq!(detail.reviews[0].reviewer.as_deref(), Some("bob"));
+
+        // Issue 8 is linked but not synced yet
+        assert_eq!(detail.linked_issues.len(), 2);
+        assert_eq!(detail.linked_issues[0].title.as_deref(), Some("Login broken"));
+        assert!(detail.linked_issues[1].title.is_none());
+
+        let paths: Vec<_> = detail.files.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(paths, vec!["src/auth.rs", "src/main.rs"]);
+
+        // A PR with no related data still comes back, with empty sections
+        let bare = get_pr_detail(&conn, 1, 43).unwrap().unwrap();
+        assert!(bare.reviews.is_empty() && bare.linked_issues.is_empty() && bare.files.is_empty());
+        assert_eq!(bare.commit_count, None);
+
+        assert!(get_pr_detail(&conn, 1, 99).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_file_hotspots_ranked_by_pr_count() {
+        let conn = setup_db();
+        conn.execute_batch(
+            "INSERT INTO pr_files (pr_id, path, additions, deletions) VALUES
+                 (1, 'src/auth.rs', 10, 2), (2, 'src/auth.rs', 4, 0), (3, 'src/auth.rs', 1, 1),
+                 (1, 'src/db.rs', 3, 0), (4, 'src/db.rs', 50, 10),
+                 (2, 'README.md', 1, 0), (3, 'README.md', 1, 0),
+                 (5, 'Cargo.lock', 500, 0),
+                 (6, 'src/auth.rs', 1, 1), (6, 'src/legacy.rs', 1, 0);",
+        )
+        .unwrap();
+
+        let hotspots = get_file_hotspots(&conn, 1, &rolling(30), 10).unwrap();
+        let ranking: Vec<(&str, i32)> = hotspots.iter().map(|h| (h.path.as_str(), h.pr_count)).collect();
+        // PR 6 is outside the window; db.rs and README.md tie on PR count but db.rs changed more lines
+        assert_eq!(
+            ranking,
+            vec![("src/auth.rs", 3), ("src/db.rs", 2), ("README.md", 2), ("Cargo.lock", 1)]
+        );
+        assert_eq!(hotspots[0].lines_changed, 18);
+        assert_eq!((hotspots[1].additions, hotspots[1].deletions), (53, 10));
+
+        assert_eq!(get_file_hotspots(&conn, 1, &rolling(30), 2).unwrap().len(), 2);
+        assert!(get_file_hotspots(&conn, 2, &rolling(30), 10).unwrap().is_empty());
+    }
+}
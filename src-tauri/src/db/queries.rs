@@ -1,4 +1,5 @@
 use super::models::*;
+use crate::metrics::filter_params::MetricsFilters;
 use anyhow::Result;
 use rusqlite::{params, Connection, OptionalExtension};
 
@@ -6,11 +7,14 @@ use rusqlite::{params, Connection, OptionalExtension};
 // REPOSITORY QUERIES
 // ============================================================================
 
-/// Get all enabled repositories
+/// Branch assumed when a repository's default branch hasn't been synced yet
+pub const DEFAULT_BRANCH: &str = "main";
+
+/// Get all enabled repositories that syncs should fetch (demo repositories never are)
 pub fn get_enabled_repositories(conn: &Connection) -> Result<Vec<Repository>> {
     let mut stmt = conn.prepare(
-        "SELECT id, owner, name, github_id, enabled, last_synced_at 
-         FROM repositories WHERE enabled = TRUE"
+        "SELECT id, owner, name, github_id, enabled, last_synced_at, default_branch 
+         FROM repositories WHERE enabled = TRUE AND is_demo = FALSE"
     )?;
     
     let repos = stmt.query_map([], |row| {
@@ -21,6 +25,7 @@ pub fn get_enabled_repositories(conn: &Connection) -> Result<Vec<Repository>> {
             github_id: row.get(3)?,
             enabled: row.get(4)?,
             last_synced_at: row.get(5)?,
+            default_branch: row.get(6)?,
         })
     })?
     .collect::<Result<Vec<_>, _>>()?;
@@ -31,7 +36,7 @@ pub fn get_enabled_repositories(conn: &Connection) -> Result<Vec<Repository>> {
 /// Get a repository by ID
 pub fn get_repository_by_id(conn: &Connection, id: i64) -> Result<Repository> {
     let repo = conn.query_row(
-        "SELECT id, owner, name, github_id, enabled, last_synced_at 
+        "SELECT id, owner, name, github_id, enabled, last_synced_at, default_branch 
          FROM repositories WHERE id = ?1",
         params![id],
         |row| {
@@ -42,6 +47,7 @@ pub fn get_repository_by_id(conn: &Connection, id: i64) -> Result<Repository> {
                 github_id: row.get(3)?,
                 enabled: row.get(4)?,
                 last_synced_at: row.get(5)?,
+                default_branch: row.get(6)?,
             })
         },
     )?;
@@ -49,6 +55,14 @@ pub fn get_repository_by_id(conn: &Connection, id: i64) -> Result<Repository> {
     Ok(repo)
 }
 
+/// Whether a repository holds seeded demo data (read-only and never synced)
+pub fn is_demo_repository(conn: &Connection, repo_id: i64) -> Result<bool> {
+    let is_demo = conn
+        .query_row("SELECT is_demo FROM repositories WHERE id = ?1", params![repo_id], |row| row.get(0))
+        .optional()?;
+    Ok(is_demo.unwrap_or(false))
+}
+
 /// Insert or update a repository
 pub fn upsert_repository(
     conn: &Connection,
@@ -84,10 +98,106 @@ pub fn update_repo_synced_at(conn: &Connection, repo_id: i64) -> Result<()> {
     Ok(())
 }
 
+/// Store the repository's default branch as reported by GitHub
+pub fn update_repo_default_branch(conn: &Connection, repo_id: i64, branch: &str) -> Result<()> {
+    conn.execute(
+        "UPDATE repositories SET default_branch = ?1 WHERE id = ?2",
+        params![branch, repo_id],
+    )?;
+    Ok(())
+}
+
+/// Record whether a repository is private on GitHub
+pub fn set_repo_private(conn: &Connection, repo_id: i64, is_private: bool) -> Result<()> {
+    conn.execute(
+        "UPDATE repositories SET is_private = ?1 WHERE id = ?2",
+        params![is_private, repo_id],
+    )?;
+    Ok(())
+}
+
+/// Update a repository's owner/name in place after it was renamed or transferred on GitHub
+pub fn update_repo_path(conn: &Connection, repo_id: i64, owner: &str, name: &str) -> Result<()> {
+    conn.execute(
+        "UPDATE repositories SET owner = ?1, name = ?2 WHERE id = ?3",
+        params![owner, name, repo_id],
+    )?;
+    Ok(())
+}
+
+/// Replace the cached language breakdown for a repository
+pub fn replace_repo_languages(
+    conn: &Connection,
+    repo_id: i64,
+    primary_language: Option<&str>,
+    languages: &[(String, Option<String>, i64)],
+) -> Result<()> {
+    conn.execute(
+        "UPDATE repositories SET primary_language = ?1 WHERE id = ?2",
+        params![primary_language, repo_id],
+    )?;
+    conn.execute("DELETE FROM repo_languages WHERE repo_id = ?1", params![repo_id])?;
+    for (language, color, bytes) in languages {
+        conn.execute(
+            "INSERT INTO repo_languages (repo_id, language, color, bytes) VALUES (?1, ?2, ?3, ?4)",
+            params![repo_id, language, color, bytes],
+        )?;
+    }
+    Ok(())
+}
+
+/// Set the repository-specific bot exclusions
+pub fn set_repo_excluded_bots(conn: &Connection, repo_id: i64, excluded_bots: &[String]) -> Result<()> {
+    let excluded_bots_json = serde_json::to_string(excluded_bots)?;
+    conn.execute(
+        "UPDATE repositories SET excluded_bots = ?1 WHERE id = ?2",
+        params![excluded_bots_json, repo_id],
+    )?;
+    Ok(())
+}
+
+/// Get the global bot exclusions merged with the repository-specific ones
+pub fn get_repo_excluded_bots(conn: &Connection, repo_id: i64, global_excluded_bots: &[String]) -> Result<Vec<String>> {
+    let excluded_bots_json: Option<String> = conn
+        .query_row(
+            "SELECT excluded_bots FROM repositories WHERE id = ?1",
+            params![repo_id],
+            |row| row.get(0),
+        )
+        .optional()?;
+    let repo_bots: Vec<String> = excluded_bots_json
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .unwrap_or_default();
+
+    let mut merged = global_excluded_bots.to_vec();
+    for bot in repo_bots {
+        if !merged.iter().any(|b| b.eq_ignore_ascii_case(&bot)) {
+            merged.push(bot);
+        }
+    }
+    Ok(merged)
+}
+
+/// Get the repository's default branch, falling back to "main" when unknown
+pub fn get_repo_default_branch(conn: &Connection, repo_id: i64) -> Result<String> {
+    let branch: Option<String> = conn
+        .query_row(
+            "SELECT default_branch FROM repositories WHERE id = ?1",
+            params![repo_id],
+            |row| row.get(0),
+        )
+        .optional()?
+        .flatten();
+
+    Ok(branch
+        .filter(|b| !b.is_empty())
+        .unwrap_or_else(|| DEFAULT_BRANCH.to_string()))
+}
+
 /// Get repository by owner and name
 pub fn get_repository_by_name(conn: &Connection, owner: &str, name: &str) -> Result<Option<Repository>> {
     let result = conn.query_row(
-        "SELECT id, owner, name, github_id, enabled, last_synced_at 
+        "SELECT id, owner, name, github_id, enabled, last_synced_at, default_branch 
          FROM repositories WHERE owner = ?1 AND name = ?2",
         params![owner, name],
         |row| Ok(Repository {
@@ -97,6 +207,7 @@ pub fn get_repository_by_name(conn: &Connection, owner: &str, name: &str) -> Res
             github_id: row.get(3)?,
             enabled: row.get(4)?,
             last_synced_at: row.get(5)?,
+            default_branch: row.get(6)?,
         }),
     ).optional()?;
     
@@ -192,6 +303,15 @@ pub fn get_user_by_login(conn: &Connection, login: &str) -> Result<Option<User>>
 }
 
 /// Check if a login is a known bot
+/// Record when an issue first got a human response (None clears it)
+pub fn set_issue_first_response(conn: &Connection, github_id: i64, first_response_at: Option<&str>) -> Result<()> {
+    conn.execute(
+        "UPDATE issues SET first_response_at = ?1 WHERE github_id = ?2",
+        params![first_response_at, github_id],
+    )?;
+    Ok(())
+}
+
 pub fn is_bot_user(login: &str, excluded_bots: &[String]) -> bool {
     excluded_bots.iter().any(|bot| {
         login.eq_ignore_ascii_case(bot) || 
@@ -200,6 +320,107 @@ pub fn is_bot_user(login: &str, excluded_bots: &[String]) -> bool {
     })
 }
 
+/// Get users whose github_id is missing or a placeholder (<= 0)
+pub fn get_users_with_invalid_github_id(conn: &Connection) -> Result<Vec<(i64, String)>> {
+    let mut stmt = conn.prepare("SELECT id, login FROM users WHERE github_id <= 0 ORDER BY id")?;
+    let users = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(users)
+}
+
+/// Find another user row with a valid github_id for the same login (case-insensitive)
+pub fn find_valid_user_by_login(conn: &Connection, login: &str, exclude_id: i64) -> Result<Option<(i64, i64)>> {
+    let result = conn
+        .query_row(
+            "SELECT id, github_id FROM users
+             WHERE LOWER(login) = LOWER(?1) AND github_id > 0 AND id != ?2
+             ORDER BY id LIMIT 1",
+            params![login, exclude_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()?;
+    Ok(result)
+}
+
+/// Assign a real github_id to a user, merging into the existing row if the id is already taken.
+/// Returns true if the user was merged into another row.
+pub fn assign_user_github_id(conn: &Connection, user_id: i64, github_id: i64) -> Result<bool> {
+    let existing: Option<i64> = conn
+        .query_row(
+            "SELECT id FROM users WHERE github_id = ?1 AND id != ?2",
+            params![github_id, user_id],
+            |row| row.get(0),
+        )
+        .optional()?;
+
+    match existing {
+        Some(keep_id) => {
+            merge_users(conn, user_id, keep_id)?;
+            Ok(true)
+        }
+        None => {
+            conn.execute(
+                "UPDATE users SET github_id = ?1 WHERE id = ?2",
+                params![github_id, user_id],
+            )?;
+            Ok(false)
+        }
+    }
+}
+
+/// Merge a duplicate user into another, re-pointing all references before deleting it
+pub fn merge_users(conn: &Connection, duplicate_id: i64, keep_id: i64) -> Result<()> {
+    let tx = conn.unchecked_transaction()?;
+
+    tx.execute("UPDATE issues SET author_id = ?1 WHERE author_id = ?2", params![keep_id, duplicate_id])?;
+    tx.execute("UPDATE issues SET assignee_id = ?1 WHERE assignee_id = ?2", params![keep_id, duplicate_id])?;
+    tx.execute("UPDATE pull_requests SET author_id = ?1 WHERE author_id = ?2", params![keep_id, duplicate_id])?;
+    tx.execute("UPDATE pr_reviews SET reviewer_id = ?1 WHERE reviewer_id = ?2", params![keep_id, duplicate_id])?;
+    tx.execute("UPDATE commits SET author_id = ?1 WHERE author_id = ?2", params![keep_id, duplicate_id])?;
+
+    // Membership tables have composite keys, so copy then drop the duplicate's rows
+    tx.execute(
+        "INSERT OR IGNORE INTO squad_members (squad_id, user_id)
+         SELECT squad_id, ?1 FROM squad_members WHERE user_id = ?2",
+        params![keep_id, duplicate_id],
+    )?;
+    tx.execute("DELETE FROM squad_members WHERE user_id = ?1", params![duplicate_id])?;
+    tx.execute(
+        "INSERT OR IGNORE INTO tracked_users (user_id, added_at)
+         SELECT ?1, added_at FROM tracked_users WHERE user_id = ?2",
+        params![keep_id, duplicate_id],
+    )?;
+    tx.execute("DELETE FROM tracked_users WHERE user_id = ?1", params![duplicate_id])?;
+    tx.execute(
+        "INSERT OR IGNORE INTO user_metadata (user_id, key, value, updated_at)
+         SELECT ?1, key, value, updated_at FROM user_metadata WHERE user_id = ?2",
+        params![keep_id, duplicate_id],
+    )?;
+    tx.execute("DELETE FROM user_metadata WHERE user_id = ?1", params![duplicate_id])?;
+    tx.execute(
+        "INSERT OR IGNORE INTO pr_assignees (pr_id, user_id)
+         SELECT pr_id, ?1 FROM pr_assignees WHERE user_id = ?2",
+        params![keep_id, duplicate_id],
+    )?;
+    tx.execute("DELETE FROM pr_assignees WHERE user_id = ?1", params![duplicate_id])?;
+
+    // Keep the user tracked if either row was tracked
+    tx.execute(
+        "UPDATE users SET
+            tracked = 1,
+            tracked_at = COALESCE(tracked_at, (SELECT tracked_at FROM users WHERE id = ?2))
+         WHERE id = ?1 AND tracked = 0
+           AND (SELECT tracked FROM users WHERE id = ?2) = 1",
+        params![keep_id, duplicate_id],
+    )?;
+
+    tx.execute("DELETE FROM users WHERE id = ?1", params![duplicate_id])?;
+    tx.commit()?;
+
+    Ok(())
+}
+
 // ============================================================================
 // ISSUE QUERIES
 // ============================================================================
@@ -229,6 +450,8 @@ pub fn upsert_issue(
                             assignee_id, milestone_id, created_at, updated_at, closed_at, labels, sync_updated_at)
          VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)
          ON CONFLICT(github_id) DO UPDATE SET
+            embedding = CASE WHEN excluded.title IS NOT title OR excluded.body IS NOT body THEN NULL ELSE embedding END,
+            embedding_model = CASE WHEN excluded.title IS NOT title OR excluded.body IS NOT body THEN NULL ELSE embedding_model END,
             title = excluded.title,
             body = excluded.body,
             state = excluded.state,
@@ -333,7 +556,7 @@ pub fn get_issues_without_embeddings(conn: &Connection, limit: i64) -> Result<Ve
         "SELECT id, github_id, repo_id, number, title, body, state, author_id,
                 assignee_id, milestone_id, created_at, updated_at, closed_at, labels
          FROM issues
-         WHERE embedding IS NULL
+         WHERE embedding IS NULL AND embedding_model IS NULL
          LIMIT ?1"
     )?;
 
@@ -364,8 +587,113 @@ pub fn get_issues_without_embeddings(conn: &Connection, limit: i64) -> Result<Ve
     Ok(issues)
 }
 
+/// Count stored issue and PR embeddings produced by a model other than `model`
+pub fn count_stale_embeddings(conn: &Connection, model: &str) -> Result<(i64, i64)> {
+    let count = |table: &str| -> Result<i64> {
+        Ok(conn.query_row(
+            &format!(
+                "SELECT COUNT(*) FROM {}
+                 WHERE embedding IS NOT NULL AND (embedding_model IS NULL OR embedding_model != ?1)",
+                table
+            ),
+            params![model],
+            |row| row.get(0),
+        )?)
+    };
+    Ok((count("issues")?, count("pull_requests")?))
+}
+
+/// How much of a repository's issues and PRs semantic search can see
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct RepoEmbeddingCoverage {
+    pub repo_id: i64,
+    pub repo: String, // owner/name
+    pub total_items: i64,
+    pub embedded_items: i64,
+    pub stale_items: i64,   // Embedded by a model other than the active one
+    pub skipped_items: i64, // Too little text to embed; excluded from the coverage fraction
+    pub coverage: f64,      // embedded / (total - skipped), 0-1; 1 when nothing is embeddable
+}
+
+/// Per-repository share of issues and PRs with an embedding, lowest coverage first
+pub fn get_embedding_coverage(conn: &Connection, model: &str) -> Result<Vec<RepoEmbeddingCoverage>> {
+    let mut stmt = conn.prepare(
+        "SELECT r.id, r.owner || '/' || r.name,
+                COUNT(i.repo_id),
+                COALESCE(SUM(i.embedding IS NOT NULL), 0),
+                COALESCE(SUM(i.embedding IS NOT NULL AND (i.embedding_model IS NULL OR i.embedding_model != ?1)), 0),
+                COALESCE(SUM(i.embedding IS NULL AND i.embedding_model IS NOT NULL), 0)
+         FROM repositories r
+         LEFT JOIN (
+             SELECT repo_id, embedding, embedding_model FROM issues
+             UNION ALL
+             SELECT repo_id, embedding, embedding_model FROM pull_requests
+         ) i ON i.repo_id = r.id
+         WHERE r.enabled = TRUE
+         GROUP BY r.id",
+    )?;
+
+    let mut coverage = stmt
+        .query_map(params![model], |row| {
+            let total_items: i64 = row.get(2)?;
+            let embedded_items: i64 = row.get(3)?;
+            let skipped_items: i64 = row.get(5)?;
+            let embeddable = total_items - skipped_items;
+            Ok(RepoEmbeddingCoverage {
+                repo_id: row.get(0)?,
+                repo: row.get(1)?,
+                total_items,
+                embedded_items,
+                stale_items: row.get(4)?,
+                skipped_items,
+                coverage: if embeddable > 0 { embedded_items as f64 / embeddable as f64 } else { 1.0 },
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    coverage.sort_by(|a, b| a.coverage.total_cmp(&b.coverage).then_with(|| a.repo.cmp(&b.repo)));
+    Ok(coverage)
+}
+
+/// Get (id, title, body) of issues or PRs whose embedding came from a model other than `model`
+pub fn get_stale_embedding_items(
+    conn: &Connection,
+    table: &str,
+    model: &str,
+    limit: i64,
+) -> Result<Vec<(i64, String, Option<String>)>> {
+    if !matches!(table, "issues" | "pull_requests") {
+        return Err(anyhow::anyhow!("Unsupported embedding table '{}'", table));
+    }
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT id, title, body FROM {}
+         WHERE embedding IS NOT NULL AND (embedding_model IS NULL OR embedding_model != ?1)
+         ORDER BY id
+         LIMIT ?2",
+        table
+    ))?;
+    let items = stmt
+        .query_map(params![model, limit], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(items)
+}
+
+/// Leave an issue or PR without an embedding (too little text) and stop it being picked up again
+pub fn mark_embedding_skipped(conn: &Connection, table: &str, id: i64) -> Result<()> {
+    if !matches!(table, "issues" | "pull_requests") {
+        return Err(anyhow::anyhow!("Unsupported embedding table '{}'", table));
+    }
+
+    conn.execute(
+        &format!("UPDATE {} SET embedding = NULL, embedding_model = ?1 WHERE id = ?2", table),
+        params![crate::embeddings::generator::SKIPPED_EMBEDDING_MODEL, id],
+    )?;
+    Ok(())
+}
+
 /// Store embedding vector for an issue
-pub fn set_issue_embedding(conn: &Connection, issue_id: i64, embedding: &[f32]) -> Result<()> {
+pub fn set_issue_embedding(conn: &Connection, issue_id: i64, embedding: &[f32], model: &str) -> Result<()> {
     // Convert f32 vector to bytes
     let bytes: Vec<u8> = embedding
         .iter()
@@ -373,8 +701,8 @@ pub fn set_issue_embedding(conn: &Connection, issue_id: i64, embedding: &[f32])
         .collect();
 
     conn.execute(
-        "UPDATE issues SET embedding = ?1 WHERE id = ?2",
-        params![bytes, issue_id],
+        "UPDATE issues SET embedding = ?1, embedding_model = ?2 WHERE id = ?3",
+        params![bytes, model, issue_id],
     )?;
     Ok(())
 }
@@ -429,6 +757,8 @@ pub fn upsert_pull_request(
                                    additions, deletions, changed_files, labels, sync_updated_at)
          VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16)
          ON CONFLICT(github_id) DO UPDATE SET
+            embedding = CASE WHEN excluded.title IS NOT title OR excluded.body IS NOT body THEN NULL ELSE embedding END,
+            embedding_model = CASE WHEN excluded.title IS NOT title OR excluded.body IS NOT body THEN NULL ELSE embedding_model END,
             title = excluded.title,
             body = excluded.body,
             state = excluded.state,
@@ -436,9 +766,10 @@ pub fn upsert_pull_request(
             updated_at = excluded.updated_at,
             merged_at = excluded.merged_at,
             closed_at = excluded.closed_at,
-            additions = excluded.additions,
-            deletions = excluded.deletions,
-            changed_files = excluded.changed_files,
+            additions = CASE WHEN details_fetched_at IS NOT NULL AND excluded.updated_at IS updated_at THEN additions ELSE excluded.additions END,
+            deletions = CASE WHEN details_fetched_at IS NOT NULL AND excluded.updated_at IS updated_at THEN deletions ELSE excluded.deletions END,
+            changed_files = CASE WHEN details_fetched_at IS NOT NULL AND excluded.updated_at IS updated_at THEN changed_files ELSE excluded.changed_files END,
+            details_fetched_at = CASE WHEN excluded.updated_at IS updated_at THEN details_fetched_at ELSE NULL END,
             labels = excluded.labels,
             sync_updated_at = excluded.sync_updated_at
          WHERE excluded.sync_updated_at >= COALESCE(sync_updated_at, excluded.sync_updated_at) OR sync_updated_at IS NULL",
@@ -456,6 +787,149 @@ pub fn upsert_pull_request(
     Ok(id)
 }
 
+/// Record how a PR was merged ("merge_queue", "auto_merge", "manual")
+pub fn set_pr_merge_method(conn: &Connection, pr_id: i64, merge_method: Option<&str>) -> Result<()> {
+    conn.execute(
+        "UPDATE pull_requests SET merge_method = ?1 WHERE id = ?2",
+        params![merge_method, pr_id],
+    )?;
+    Ok(())
+}
+
+/// Replace the issues a PR is linked to as closing
+pub fn replace_pr_issue_links(conn: &Connection, pr_id: i64, issue_numbers: &[i32]) -> Result<()> {
+    let tx = conn.unchecked_transaction()?;
+    tx.execute("DELETE FROM pr_issue_links WHERE pr_id = ?1", params![pr_id])?;
+    for number in issue_numbers {
+        tx.execute(
+            "INSERT OR IGNORE INTO pr_issue_links (pr_id, issue_number) VALUES (?1, ?2)",
+            params![pr_id, number],
+        )?;
+    }
+    tx.commit()?;
+    Ok(())
+}
+
+/// Replace the stored assignees of a PR
+pub fn replace_pr_assignees(conn: &Connection, pr_id: i64, user_ids: &[i64]) -> Result<()> {
+    let tx = conn.unchecked_transaction()?;
+    tx.execute("DELETE FROM pr_assignees WHERE pr_id = ?1", params![pr_id])?;
+    for user_id in user_ids {
+        tx.execute(
+            "INSERT OR IGNORE INTO pr_assignees (pr_id, user_id) VALUES (?1, ?2)",
+            params![pr_id, user_id],
+        )?;
+    }
+    tx.commit()?;
+    Ok(())
+}
+
+/// Replace the stored (label, action, created_at) label events for an issue
+pub fn replace_issue_label_events(conn: &Connection, issue_id: i64, events: &[(&str, &str, &str)]) -> Result<()> {
+    let tx = conn.unchecked_transaction()?;
+    tx.execute("DELETE FROM issue_label_events WHERE issue_id = ?1", params![issue_id])?;
+    for (label, action, created_at) in events {
+        tx.execute(
+            "INSERT OR IGNORE INTO issue_label_events (issue_id, label, action, created_at) VALUES (?1, ?2, ?3, ?4)",
+            params![issue_id, label, action, created_at],
+        )?;
+    }
+    tx.commit()?;
+    Ok(())
+}
+
+/// Replace the stored (oid, committed_at) commits for a PR
+pub fn replace_pr_commits(conn: &Connection, pr_id: i64, commits: &[(&str, &str)]) -> Result<()> {
+    let tx = conn.unchecked_transaction()?;
+    tx.execute("DELETE FROM pr_commits WHERE pr_id = ?1", params![pr_id])?;
+    for (oid, committed_at) in commits {
+        tx.execute(
+            "INSERT OR REPLACE INTO pr_commits (pr_id, oid, committed_at) VALUES (?1, ?2, ?3)",
+            params![pr_id, oid, committed_at],
+        )?;
+    }
+    tx.commit()?;
+    Ok(())
+}
+
+/// Record how many commits a PR has (a proxy for review iterations)
+pub fn set_pr_commit_count(conn: &Connection, pr_id: i64, commit_count: Option<i32>) -> Result<()> {
+    conn.execute(
+        "UPDATE pull_requests SET commit_count = ?1 WHERE id = ?2",
+        params![commit_count, pr_id],
+    )?;
+    Ok(())
+}
+
+/// Record the PR author's relationship to the repository (GitHub authorAssociation)
+pub fn set_pr_author_association(conn: &Connection, pr_id: i64, author_association: Option<&str>) -> Result<()> {
+    conn.execute(
+        "UPDATE pull_requests SET author_association = ?1 WHERE id = ?2",
+        params![author_association, pr_id],
+    )?;
+    Ok(())
+}
+
+/// Record the branch a PR targets
+pub fn set_pr_base_branch(conn: &Connection, pr_id: i64, base_branch: Option<&str>) -> Result<()> {
+    conn.execute(
+        "UPDATE pull_requests SET base_branch = ?1 WHERE id = ?2",
+        params![base_branch, pr_id],
+    )?;
+    Ok(())
+}
+
+/// Record a PR's draft flag and GitHub mergeable state (MERGEABLE, CONFLICTING, UNKNOWN)
+pub fn set_pr_blocking_state(conn: &Connection, pr_id: i64, is_draft: bool, mergeable: Option<&str>) -> Result<()> {
+    conn.execute(
+        "UPDATE pull_requests SET is_draft = ?1, mergeable = ?2 WHERE id = ?3",
+        params![is_draft, mergeable, pr_id],
+    )?;
+    Ok(())
+}
+
+/// Get PRs in a repo whose details were never fetched and that have no LOC counts
+/// or a merged state without merged_at (id, number)
+pub fn get_prs_missing_details(conn: &Connection, repo_id: i64) -> Result<Vec<(i64, i32)>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, number FROM pull_requests
+         WHERE repo_id = ?1
+           AND details_fetched_at IS NULL
+           AND ((COALESCE(additions, 0) = 0 AND COALESCE(deletions, 0) = 0)
+                OR (UPPER(state) = 'MERGED' AND merged_at IS NULL))
+         ORDER BY number ASC",
+    )?;
+
+    let prs = stmt
+        .query_map(params![repo_id], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(prs)
+}
+
+/// Fill in a PR's LOC and merge data without touching the rest of the row,
+/// recording that the details were fetched
+pub fn update_pr_details(
+    conn: &Connection,
+    pr_id: i64,
+    additions: i32,
+    deletions: i32,
+    changed_files: i32,
+    merged_at: Option<&str>,
+) -> Result<()> {
+    conn.execute(
+        "UPDATE pull_requests SET
+            additions = ?1,
+            deletions = ?2,
+            changed_files = ?3,
+            merged_at = COALESCE(?4, merged_at),
+            details_fetched_at = datetime('now')
+         WHERE id = ?5",
+        params![additions, deletions, changed_files, merged_at, pr_id],
+    )?;
+    Ok(())
+}
+
 /// Get PRs within a date range, excluding bots
 pub fn get_prs_for_metrics(
     conn: &Connection,
@@ -525,7 +999,7 @@ pub fn get_prs_without_embeddings(conn: &Connection, limit: i64) -> Result<Vec<P
                 created_at, updated_at, merged_at, closed_at, additions, deletions,
                 changed_files, review_comments, labels
          FROM pull_requests
-         WHERE embedding IS NULL
+         WHERE embedding IS NULL AND embedding_model IS NULL
          LIMIT ?1"
     )?;
 
@@ -560,7 +1034,7 @@ pub fn get_prs_without_embeddings(conn: &Connection, limit: i64) -> Result<Vec<P
 }
 
 /// Store embedding vector for a PR
-pub fn set_pr_embedding(conn: &Connection, pr_id: i64, embedding: &[f32]) -> Result<()> {
+pub fn set_pr_embedding(conn: &Connection, pr_id: i64, embedding: &[f32], model: &str) -> Result<()> {
     // Convert f32 vector to bytes
     let bytes: Vec<u8> = embedding
         .iter()
@@ -568,8 +1042,8 @@ pub fn set_pr_embedding(conn: &Connection, pr_id: i64, embedding: &[f32]) -> Res
         .collect();
 
     conn.execute(
-        "UPDATE pull_requests SET embedding = ?1 WHERE id = ?2",
-        params![bytes, pr_id],
+        "UPDATE pull_requests SET embedding = ?1, embedding_model = ?2 WHERE id = ?3",
+        params![bytes, model, pr_id],
     )?;
     Ok(())
 }
@@ -626,27 +1100,69 @@ pub fn upsert_pr_review(
     Ok(id)
 }
 
-/// Get review rounds for a PR (distinct review submissions)
-pub fn get_pr_review_count(conn: &Connection, pr_id: i64) -> Result<i32> {
-    let count: i32 = conn.query_row(
-        "SELECT COUNT(DISTINCT reviewer_id || '-' || date(submitted_at)) 
-         FROM pr_reviews WHERE pr_id = ?1",
-        params![pr_id],
-        |row| row.get(0),
-    )?;
+/// Get review rounds for a PR (distinct review submissions), ignoring bot reviewers
+pub fn get_pr_review_count(conn: &Connection, pr_id: i64, exclude_self_reviews: bool, bot_reviewers: &[i64]) -> Result<i32> {
+    let query = format!(
+        "SELECT COUNT(DISTINCT r.reviewer_id || '-' || date(r.submitted_at))
+         FROM pr_reviews r
+         JOIN pull_requests pr ON r.pr_id = pr.id
+         WHERE r.pr_id = ?1{}{}",
+        self_review_filter(exclude_self_reviews),
+        bot_review_filter(bot_reviewers)
+    );
+    let count: i32 = conn.query_row(&query, params![pr_id], |row| row.get(0))?;
     Ok(count)
 }
 
-/// Get first review timestamp for a PR
-pub fn get_first_review_time(conn: &Connection, pr_id: i64) -> Result<Option<String>> {
-    let result = conn.query_row(
-        "SELECT MIN(submitted_at) FROM pr_reviews WHERE pr_id = ?1",
-        params![pr_id],
-        |row| row.get(0),
-    ).optional()?;
+/// Get first review timestamp for a PR, ignoring bot reviewers
+pub fn get_first_review_time(conn: &Connection, pr_id: i64, exclude_self_reviews: bool, bot_reviewers: &[i64]) -> Result<Option<String>> {
+    let query = format!(
+        "SELECT MIN(r.submitted_at)
+         FROM pr_reviews r
+         JOIN pull_requests pr ON r.pr_id = pr.id
+         WHERE r.pr_id = ?1{}{}",
+        self_review_filter(exclude_self_reviews),
+        bot_review_filter(bot_reviewers)
+    );
+    let result = conn.query_row(&query, params![pr_id], |row| row.get(0)).optional()?;
     Ok(result.flatten())
 }
 
+/// SQL condition dropping reviews left by the PR's own author.
+/// Expects the review aliased as `r` and its pull request as `pr`.
+pub fn self_review_filter(exclude_self_reviews: bool) -> &'static str {
+    if exclude_self_reviews {
+        " AND (r.reviewer_id IS NULL OR pr.author_id IS NULL OR r.reviewer_id != pr.author_id)"
+    } else {
+        ""
+    }
+}
+
+/// Ids of users whose reviews don't count toward review metrics: accounts synced
+/// as bots plus any login `is_bot_user` matches against the excluded bots
+pub fn get_bot_reviewer_ids(conn: &Connection, excluded_bots: &[String]) -> Result<Vec<i64>> {
+    let mut stmt = conn.prepare("SELECT id, login, COALESCE(is_bot, FALSE) FROM users ORDER BY id")?;
+    let users = stmt
+        .query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?, row.get::<_, bool>(2)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(users
+        .into_iter()
+        .filter(|(_, login, is_bot)| *is_bot || is_bot_user(login, excluded_bots))
+        .map(|(id, _, _)| id)
+        .collect())
+}
+
+/// SQL condition dropping reviews left by the given bot users.
+/// Expects the review aliased as `r`; reviews from deleted accounts are kept.
+pub fn bot_review_filter(bot_reviewer_ids: &[i64]) -> String {
+    if bot_reviewer_ids.is_empty() {
+        return String::new();
+    }
+    let ids: Vec<String> = bot_reviewer_ids.iter().map(|id| id.to_string()).collect();
+    format!(" AND (r.reviewer_id IS NULL OR r.reviewer_id NOT IN ({}))", ids.join(", "))
+}
+
 // ============================================================================
 // WATERMARK QUERIES (for incremental sync)
 // ============================================================================
@@ -734,12 +1250,20 @@ pub fn get_milestone_id_by_github_id(conn: &Connection, github_id: i64) -> Resul
 }
 
 /// Get all open milestones grouped by title (for roadmap)
-pub fn get_milestones_by_cycle(conn: &Connection) -> Result<Vec<Milestone>> {
-    let mut stmt = conn.prepare(
-        "SELECT id, github_id, repo_id, title, description, state, due_on, open_issues, closed_issues
-         FROM milestones
-         ORDER BY due_on ASC NULLS LAST, title ASC"
-    )?;
+pub fn get_milestones_by_cycle(conn: &Connection, counts_source: &str) -> Result<Vec<Milestone>> {
+    // "local" ignores the synced counters, which drift, and counts the issues we hold
+    let counts = if counts_source == "local" {
+        "(SELECT COUNT(*) FROM issues i WHERE i.milestone_id = m.id AND LOWER(i.state) = 'open'),
+         (SELECT COUNT(*) FROM issues i WHERE i.milestone_id = m.id AND LOWER(i.state) != 'open')"
+    } else {
+        "m.open_issues, m.closed_issues"
+    };
+    let mut stmt = conn.prepare(&format!(
+        "SELECT m.id, m.github_id, m.repo_id, m.title, m.description, m.state, m.due_on, {}
+         FROM milestones m
+         ORDER BY m.due_on ASC NULLS LAST, m.title ASC",
+        counts
+    ))?;
     
     let milestones = stmt.query_map([], |row| {
         Ok(Milestone {
@@ -832,6 +1356,52 @@ pub fn get_all_squads(conn: &Connection) -> Result<Vec<Squad>> {
     Ok(squads_with_members)
 }
 
+// ============================================================================
+// COMMIT QUERIES
+// ============================================================================
+
+/// Insert or update a commit on a repository's default branch
+pub fn upsert_commit(
+    conn: &Connection,
+    repo_id: i64,
+    oid: &str,
+    author_id: Option<i64>,
+    message_headline: &str,
+    committed_at: &str,
+    additions: i32,
+    deletions: i32,
+    is_merge: bool,
+) -> Result<()> {
+    conn.execute(
+        "INSERT INTO commits (repo_id, oid, author_id, message_headline, committed_at, additions, deletions, is_merge)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+         ON CONFLICT(repo_id, oid) DO UPDATE SET
+            author_id = excluded.author_id,
+            message_headline = excluded.message_headline,
+            committed_at = excluded.committed_at,
+            additions = excluded.additions,
+            deletions = excluded.deletions,
+            is_merge = excluded.is_merge",
+        params![repo_id, oid, author_id, message_headline, committed_at, additions, deletions, is_merge],
+    )?;
+    Ok(())
+}
+
+/// Total lines changed by tracked users' commits since `since`. Merge commits repeat the LOC
+/// of the branch they merge, so they're skipped unless `include_merge_commits` is set.
+pub fn get_commit_loc(conn: &Connection, since: &str, include_merge_commits: bool) -> Result<i64> {
+    let loc = conn.query_row(
+        "SELECT COALESCE(SUM(additions + deletions), 0)
+         FROM commits
+         WHERE committed_at >= ?1
+           AND author_id IN (SELECT id FROM users WHERE tracked = 1)
+           AND (?2 OR is_merge = FALSE)",
+        params![since, include_merge_commits],
+        |row| row.get(0),
+    )?;
+    Ok(loc)
+}
+
 // ============================================================================
 // SYNC LOG QUERIES
 // ============================================================================
@@ -854,6 +1424,24 @@ pub fn record_sync_complete(conn: &Connection, log_id: i64, items_synced: i32) -
     Ok(())
 }
 
+/// Record how many pull requests were filtered out of an issues sync
+pub fn record_sync_skipped_prs(conn: &Connection, log_id: i64, skipped: i32) -> Result<()> {
+    conn.execute(
+        "UPDATE sync_log SET skipped_prs = ?1 WHERE id = ?2",
+        params![skipped, log_id],
+    )?;
+    Ok(())
+}
+
+/// Record how many issues a sync skipped for predating the history window
+pub fn record_sync_skipped_old(conn: &Connection, log_id: i64, skipped: i32) -> Result<()> {
+    conn.execute(
+        "UPDATE sync_log SET skipped_old = ?1 WHERE id = ?2",
+        params![skipped, log_id],
+    )?;
+    Ok(())
+}
+
 /// Record sync error
 pub fn record_sync_error(conn: &Connection, log_id: i64, error: &str) -> Result<()> {
     conn.execute(
@@ -863,6 +1451,55 @@ pub fn record_sync_error(conn: &Connection, log_id: i64, error: &str) -> Result<
     Ok(())
 }
 
+/// A failed sync run, as recorded by `record_sync_error`
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SyncError {
+    pub log_id: i64,
+    pub sync_type: String,
+    pub started_at: String,
+    pub failed_at: String,
+    pub error: String,
+}
+
+/// Get the most recent failed syncs for a repository, newest first
+pub fn get_repo_sync_errors(conn: &Connection, repo_id: i64, limit: i64) -> Result<Vec<SyncError>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, sync_type, started_at, completed_at, error
+         FROM sync_log
+         WHERE repo_id = ?1 AND error IS NOT NULL AND completed_at IS NOT NULL
+         ORDER BY completed_at DESC, id DESC
+         LIMIT ?2",
+    )?;
+
+    let errors = stmt
+        .query_map(params![repo_id, limit], |row| {
+            Ok(SyncError {
+                log_id: row.get(0)?,
+                sync_type: row.get(1)?,
+                started_at: row.get(2)?,
+                failed_at: row.get(3)?,
+                error: row.get(4)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(errors)
+}
+
+/// Error recorded on syncs that never finished because the app exited or crashed
+pub const INTERRUPTED_SYNC_ERROR: &str = "interrupted";
+
+/// Close every unfinished sync log as interrupted, returning how many were closed.
+/// Runs on exit and when a database is opened, since no sync can be running then.
+pub fn mark_interrupted_syncs(conn: &Connection) -> Result<usize> {
+    let closed = conn.execute(
+        "UPDATE sync_log SET completed_at = datetime('now'), error = ?1
+         WHERE completed_at IS NULL",
+        params![INTERRUPTED_SYNC_ERROR],
+    )?;
+    Ok(closed)
+}
+
 // ============================================================================
 // STATS QUERIES
 // ============================================================================
@@ -891,21 +1528,282 @@ pub struct SyncStats {
 }
 
 // ============================================================================
-// FILTERED METRICS QUERIES (for dashboard filters)
+// FRESHNESS QUERIES
 // ============================================================================
 
-/// Get issues with optional filters for metrics
-pub fn get_issues_for_metrics_filtered(
-    conn: &Connection,
-    since: &str,
-    until: Option<&str>,
-    excluded_bots: &[String],
-    repo_ids: Option<&[i64]>,
-    user_id: Option<i64>,
-    squad_member_ids: Option<&[i64]>,
-) -> Result<Vec<Issue>> {
-    let mut query = String::from(
-        "SELECT i.id, i.github_id, i.repo_id, i.number, i.title, i.body, i.state,
+/// How recently an enabled repository was synced
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct RepoFreshness {
+    pub repo_id: i64,
+    pub owner: String,
+    pub name: String,
+    pub last_synced_at: Option<String>,
+    pub minutes_since_sync: Option<i64>,
+    pub tier: String, // "fresh", "aging" or "stale"
+}
+
+/// Classify minutes since the last sync against the configured sync interval.
+/// Never-synced repos are always stale.
+pub fn freshness_tier(minutes_since_sync: Option<i64>, settings: &Settings) -> &'static str {
+    let Some(minutes) = minutes_since_sync else {
+        return "stale";
+    };
+    let interval = settings.sync_interval_minutes as f64;
+    let minutes = minutes as f64;
+    if minutes <= interval * settings.freshness_aging_multiplier {
+        "fresh"
+    } else if minutes <= interval * settings.freshness_stale_multiplier {
+        "aging"
+    } else {
+        "stale"
+    }
+}
+
+/// Get sync freshness for every enabled repository, measured against `now` ('YYYY-MM-DD HH:MM:SS' UTC)
+pub fn get_repo_freshness(conn: &Connection, settings: &Settings, now: &str) -> Result<Vec<RepoFreshness>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, owner, name, last_synced_at,
+                (CAST(strftime('%s', ?1) AS INTEGER) - CAST(strftime('%s', last_synced_at) AS INTEGER)) / 60
+         FROM repositories
+         WHERE enabled = TRUE
+         ORDER BY owner, name",
+    )?;
+
+    let repos = stmt
+        .query_map(params![now], |row| {
+            let minutes_since_sync: Option<i64> = row.get(4)?;
+            Ok(RepoFreshness {
+                repo_id: row.get(0)?,
+                owner: row.get(1)?,
+                name: row.get(2)?,
+                last_synced_at: row.get(3)?,
+                minutes_since_sync,
+                tier: freshness_tier(minutes_since_sync, settings).to_string(),
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(repos)
+}
+
+// ============================================================================
+// SYNC GAP QUERIES
+// ============================================================================
+
+/// A stretch with no successful sync of one type for a repository
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SyncGap {
+    pub repo_id: i64,
+    pub repo: String,
+    pub sync_type: String,
+    pub gap_start: String, // Start of the last successful sync before the gap
+    pub gap_end: String,   // Start of the next successful sync, or now if still open
+    pub gap_minutes: i64,
+    pub ongoing: bool,
+}
+
+/// Find windows longer than the stale threshold (sync interval × stale multiplier) between
+/// successful syncs of each enabled repository and sync type, measured to `now`
+/// ('YYYY-MM-DD HH:MM:SS' UTC) for the latest one. Failed or unfinished syncs don't close a gap.
+pub fn detect_sync_gaps(conn: &Connection, settings: &Settings, now: &str) -> Result<Vec<SyncGap>> {
+    let mut stmt = conn.prepare(
+        "SELECT r.id, r.owner || '/' || r.name, l.sync_type, l.started_at
+         FROM sync_log l
+         JOIN repositories r ON l.repo_id = r.id
+         WHERE r.enabled = TRUE
+           AND l.completed_at IS NOT NULL
+           AND l.error IS NULL
+         ORDER BY r.owner, r.name, l.sync_type, l.started_at",
+    )?;
+    let syncs = stmt
+        .query_map([], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?, row.get::<_, String>(3)?))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let parse = |at: &str| chrono::NaiveDateTime::parse_from_str(at, "%Y-%m-%d %H:%M:%S").ok();
+    let threshold = (settings.sync_interval_minutes as f64 * settings.freshness_stale_multiplier) as i64;
+    let mut gaps = Vec::new();
+    for (i, (repo_id, repo, sync_type, started_at)) in syncs.iter().enumerate() {
+        let next = syncs
+            .get(i + 1)
+            .filter(|(next_repo, _, next_type, _)| next_repo == repo_id && next_type == sync_type);
+        let (gap_end, ongoing) = match next {
+            Some((_, _, _, next_started)) => (next_started.as_str(), false),
+            None => (now, true),
+        };
+        let (Some(start), Some(end)) = (parse(started_at), parse(gap_end)) else {
+            continue;
+        };
+
+        let gap_minutes = (end - start).num_minutes();
+        if gap_minutes > threshold {
+            gaps.push(SyncGap {
+                repo_id: *repo_id,
+                repo: repo.clone(),
+                sync_type: sync_type.clone(),
+                gap_start: started_at.clone(),
+                gap_end: gap_end.to_string(),
+                gap_minutes,
+                ongoing,
+            });
+        }
+    }
+
+    Ok(gaps)
+}
+
+// ============================================================================
+// CHANGEFEED QUERIES
+// ============================================================================
+
+/// An issue or PR whose synced GitHub update is newer than a sync log's completion
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct ChangedItem {
+    pub item_type: String, // "issue" or "pull_request"
+    pub id: i64,
+    pub repo_id: i64,
+    pub number: i32,
+    pub title: String,
+    pub state: String,
+    pub sync_updated_at: String,
+}
+
+/// Items with `sync_updated_at` after the completion of sync log `sync_log_id`, oldest first.
+/// Errors if the sync log doesn't exist or hasn't completed.
+pub fn get_changes_since(conn: &Connection, sync_log_id: i64) -> Result<Vec<ChangedItem>> {
+    let completed_at: Option<String> = conn
+        .query_row("SELECT completed_at FROM sync_log WHERE id = ?1", params![sync_log_id], |row| row.get(0))
+        .optional()?
+        .ok_or_else(|| anyhow::anyhow!("Sync log {} not found", sync_log_id))?;
+    let completed_at = completed_at.ok_or_else(|| anyhow::anyhow!("Sync log {} has not completed", sync_log_id))?;
+
+    // Stored timestamps mix GitHub's ISO format and SQLite's, so compare as julian days
+    let mut stmt = conn.prepare(
+        "SELECT 'issue', id, repo_id, number, title, state, sync_updated_at
+         FROM issues
+         WHERE julianday(sync_updated_at) > julianday(?1)
+         UNION ALL
+         SELECT 'pull_request', id, repo_id, number, title, state, sync_updated_at
+         FROM pull_requests
+         WHERE julianday(sync_updated_at) > julianday(?1)
+         ORDER BY 7, 1, 2",
+    )?;
+    let items = stmt
+        .query_map(params![completed_at], |row| {
+            Ok(ChangedItem {
+                item_type: row.get(0)?,
+                id: row.get(1)?,
+                repo_id: row.get(2)?,
+                number: row.get(3)?,
+                title: row.get(4)?,
+                state: row.get(5)?,
+                sync_updated_at: row.get(6)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(items)
+}
+
+// ============================================================================
+// DIAGNOSTICS QUERIES
+// ============================================================================
+
+/// Snapshot of the app's local state for support requests
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct Diagnostics {
+    pub schema_version: i32,
+    pub db_size_bytes: i64,
+    pub table_row_counts: Vec<TableRowCount>,
+    pub repositories: Vec<RepoSyncStatus>,
+    pub embedding_coverage_pct: f64, // Issues and PRs that have an embedding
+    pub sidecar_healthy: Option<bool>, // None when the AI sidecar isn't running
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct TableRowCount {
+    pub table: String,
+    pub rows: i64,
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct RepoSyncStatus {
+    pub repo: String,
+    pub enabled: bool,
+    pub last_synced_at: Option<String>,
+}
+
+/// Collect read-only diagnostics from the database. `sidecar_healthy` is left unset
+/// for the caller to fill in.
+pub fn get_diagnostics(conn: &Connection) -> Result<Diagnostics> {
+    let schema_version: i32 = conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
+    let page_count: i64 = conn.pragma_query_value(None, "page_count", |row| row.get(0))?;
+    let page_size: i64 = conn.pragma_query_value(None, "page_size", |row| row.get(0))?;
+
+    // FTS5 keeps its data in shadow tables, so only the user tables are counted
+    let tables: Vec<String> = conn
+        .prepare(
+            "SELECT name FROM sqlite_master
+             WHERE type = 'table' AND name NOT LIKE 'sqlite_%' AND name NOT LIKE 'search_fts%'
+             ORDER BY name",
+        )?
+        .query_map([], |row| row.get(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+    let table_row_counts = tables
+        .into_iter()
+        .map(|table| -> Result<TableRowCount> {
+            let rows = conn.query_row(&format!("SELECT COUNT(*) FROM \"{}\"", table), [], |row| row.get(0))?;
+            Ok(TableRowCount { table, rows })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let repositories = conn
+        .prepare("SELECT owner || '/' || name, enabled, last_synced_at FROM repositories ORDER BY owner, name")?
+        .query_map([], |row| {
+            Ok(RepoSyncStatus {
+                repo: row.get(0)?,
+                enabled: row.get(1)?,
+                last_synced_at: row.get(2)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let (embedded, total): (i64, i64) = conn.query_row(
+        "SELECT COALESCE(SUM(embedding IS NOT NULL), 0), COUNT(*)
+         FROM (SELECT embedding FROM issues UNION ALL SELECT embedding FROM pull_requests)",
+        [],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )?;
+
+    Ok(Diagnostics {
+        schema_version,
+        db_size_bytes: page_count * page_size,
+        table_row_counts,
+        repositories,
+        embedding_coverage_pct: if total > 0 { embedded as f64 * 100.0 / total as f64 } else { 0.0 },
+        sidecar_healthy: None,
+    })
+}
+
+// ============================================================================
+// FILTERED METRICS QUERIES (for dashboard filters)
+// ============================================================================
+
+/// Get issues with optional filters for metrics
+pub fn get_issues_for_metrics_filtered(
+    conn: &Connection,
+    since: &str,
+    until: Option<&str>,
+    excluded_bots: &[String],
+    repo_ids: Option<&[i64]>,
+    user_id: Option<i64>,
+    role: UserRole,
+    squad_member_ids: Option<&[i64]>,
+    visibility: RepoVisibility,
+) -> Result<Vec<Issue>> {
+    let mut query = String::from(
+        "SELECT i.id, i.github_id, i.repo_id, i.number, i.title, i.body, i.state,
                 i.author_id, i.assignee_id, i.milestone_id, i.created_at, i.updated_at,
                 i.closed_at, i.labels, u.login
          FROM issues i
@@ -938,9 +1836,14 @@ pub fn get_issues_for_metrics_filtered(
         }
     }
 
-    // Add user filter
+    // Add user filter (issues have no reviewers, so the reviewer role matches no issues)
     if let Some(uid) = user_id {
-        query.push_str(&format!(" AND i.author_id = ?{}", param_idx));
+        let clause = match role {
+            UserRole::Author => format!(" AND i.author_id = ?{}", param_idx),
+            UserRole::Assignee => format!(" AND i.assignee_id = ?{}", param_idx),
+            UserRole::Reviewer => format!(" AND 0 = ?{}", param_idx),
+        };
+        query.push_str(&clause);
         params_vec.push(Box::new(uid));
         param_idx += 1;
     }
@@ -959,6 +1862,9 @@ pub fn get_issues_for_metrics_filtered(
         }
     }
 
+    // Add repository visibility filter
+    query.push_str(&visibility_clause("i.repo_id", visibility));
+
     let mut stmt = conn.prepare(&query)?;
     let param_refs: Vec<&dyn rusqlite::ToSql> = params_vec.iter()
         .map(|p| p.as_ref() as &dyn rusqlite::ToSql)
@@ -1013,7 +1919,10 @@ pub fn get_prs_for_metrics_filtered(
     excluded_bots: &[String],
     repo_ids: Option<&[i64]>,
     user_id: Option<i64>,
+    role: UserRole,
     squad_member_ids: Option<&[i64]>,
+    base_branch: Option<&str>,
+    visibility: RepoVisibility,
 ) -> Result<Vec<PullRequest>> {
     let mut query = String::from(
         "SELECT p.id, p.github_id, p.repo_id, p.number, p.title, p.body, p.state,
@@ -1052,7 +1961,19 @@ pub fn get_prs_for_metrics_filtered(
 
     // Add user filter
     if let Some(uid) = user_id {
-        query.push_str(&format!(" AND p.author_id = ?{}", param_idx));
+        let clause = match role {
+            UserRole::Reviewer => format!(
+                " AND EXISTS (SELECT 1 FROM pr_reviews r WHERE r.pr_id = p.id AND r.reviewer_id = ?{0})
+                  AND COALESCE(p.author_id, -1) != ?{0}",
+                param_idx
+            ),
+            UserRole::Assignee => format!(
+                " AND EXISTS (SELECT 1 FROM pr_assignees a WHERE a.pr_id = p.id AND a.user_id = ?{})",
+                param_idx
+            ),
+            UserRole::Author => format!(" AND p.author_id = ?{}", param_idx),
+        };
+        query.push_str(&clause);
         params_vec.push(Box::new(uid));
         param_idx += 1;
     }
@@ -1068,9 +1989,19 @@ pub fn get_prs_for_metrics_filtered(
             for &member_id in member_ids {
                 params_vec.push(Box::new(member_id));
             }
+            param_idx += member_ids.len();
         }
     }
 
+    // Add base branch filter
+    if let Some(branch) = base_branch {
+        query.push_str(&format!(" AND p.base_branch = ?{}", param_idx));
+        params_vec.push(Box::new(branch.to_string()));
+    }
+
+    // Add repository visibility filter
+    query.push_str(&visibility_clause("p.repo_id", visibility));
+
     let mut stmt = conn.prepare(&query)?;
     let param_refs: Vec<&dyn rusqlite::ToSql> = params_vec.iter()
         .map(|p| p.as_ref() as &dyn rusqlite::ToSql)
@@ -1124,6 +2055,18 @@ pub fn get_prs_for_metrics_filtered(
 // HELPER QUERIES FOR FILTERS
 // ============================================================================
 
+/// SQL restricting `repo_column` to repositories with the requested visibility
+fn visibility_clause(repo_column: &str, visibility: RepoVisibility) -> String {
+    match visibility.is_private() {
+        Some(is_private) => format!(
+            " AND {} IN (SELECT id FROM repositories WHERE is_private = {})",
+            repo_column,
+            if is_private { "TRUE" } else { "FALSE" }
+        ),
+        None => String::new(),
+    }
+}
+
 /// Get squad member user IDs
 pub fn get_squad_member_ids(conn: &Connection, squad_id: &str) -> Result<Vec<i64>> {
     let mut stmt = conn.prepare(
@@ -1136,6 +2079,28 @@ pub fn get_squad_member_ids(conn: &Connection, squad_id: &str) -> Result<Vec<i64
     Ok(ids)
 }
 
+/// Mark every current squad member as tracked; returns how many weren't tracked yet.
+/// With `dry_run` nothing is changed and only the count is returned.
+pub fn track_squad_members(conn: &Connection, dry_run: bool) -> Result<usize> {
+    let untracked = "id IN (SELECT user_id FROM squad_members) AND tracked = FALSE";
+
+    if dry_run {
+        let count: i64 = conn.query_row(
+            &format!("SELECT COUNT(*) FROM users WHERE {}", untracked),
+            [],
+            |row| row.get(0),
+        )?;
+        return Ok(count as usize);
+    }
+
+    let now = chrono::Utc::now().to_rfc3339();
+    let updated = conn.execute(
+        &format!("UPDATE users SET tracked = TRUE, tracked_at = ?1 WHERE {}", untracked),
+        params![now],
+    )?;
+    Ok(updated)
+}
+
 /// Get all non-bot users for filtering
 pub fn get_all_users(conn: &Connection) -> Result<Vec<User>> {
     let mut stmt = conn.prepare(
@@ -1165,7 +2130,7 @@ pub fn get_all_users(conn: &Connection) -> Result<Vec<User>> {
 /// Get all repositories for filtering
 pub fn get_all_repositories(conn: &Connection) -> Result<Vec<Repository>> {
     let mut stmt = conn.prepare(
-        "SELECT id, owner, name, github_id, enabled, last_synced_at
+        "SELECT id, owner, name, github_id, enabled, last_synced_at, default_branch
          FROM repositories
          ORDER BY owner ASC, name ASC"
     )?;
@@ -1178,6 +2143,7 @@ pub fn get_all_repositories(conn: &Connection) -> Result<Vec<Repository>> {
             github_id: row.get(3)?,
             enabled: row.get(4)?,
             last_synced_at: row.get(5)?,
+            default_branch: row.get(6)?,
         })
     })?
     .collect::<Result<Vec<_>, _>>()?;
@@ -1185,6 +2151,110 @@ pub fn get_all_repositories(conn: &Connection) -> Result<Vec<Repository>> {
     Ok(repos)
 }
 
+/// Get distinct labels with usage counts, most used first.
+/// `item_type` limits to "issue" or "pull_request"; both are counted when None.
+pub fn get_all_labels(conn: &Connection, item_type: Option<&str>) -> Result<Vec<LabelCount>> {
+    let include_issues = item_type.map_or(true, |t| t == "issue");
+    let include_prs = item_type.map_or(true, |t| t == "pull_request");
+
+    let mut sources = Vec::new();
+    if include_issues {
+        sources.push("SELECT j.value as label FROM issues, json_each(COALESCE(issues.labels, '[]')) j");
+    }
+    if include_prs {
+        sources.push("SELECT j.value as label FROM pull_requests, json_each(COALESCE(pull_requests.labels, '[]')) j");
+    }
+    if sources.is_empty() {
+        return Err(anyhow::anyhow!("Invalid item_type (expected 'issue' or 'pull_request')"));
+    }
+
+    let query = format!(
+        "SELECT label, COUNT(*) as uses
+         FROM ({})
+         WHERE label IS NOT NULL AND label != ''
+         GROUP BY label
+         ORDER BY uses DESC, label ASC",
+        sources.join(" UNION ALL ")
+    );
+
+    let mut stmt = conn.prepare(&query)?;
+    let labels = stmt.query_map([], |row| {
+        Ok(LabelCount {
+            label: row.get(0)?,
+            count: row.get(1)?,
+        })
+    })?
+    .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(labels)
+}
+
+// ============================================================================
+// SAVED FILTER QUERIES
+// ============================================================================
+
+/// Save a named filter preset (overwrites an existing preset with the same name)
+pub fn save_filter(conn: &Connection, name: &str, filters: &MetricsFilters) -> Result<i64> {
+    let filters_json = serde_json::to_string(filters)?;
+
+    conn.execute(
+        "INSERT INTO saved_filters (name, filters)
+         VALUES (?1, ?2)
+         ON CONFLICT(name) DO UPDATE SET
+            filters = excluded.filters,
+            updated_at = datetime('now')",
+        params![name, filters_json],
+    )?;
+
+    let id: i64 = conn.query_row(
+        "SELECT id FROM saved_filters WHERE name = ?1",
+        params![name],
+        |row| row.get(0),
+    )?;
+
+    Ok(id)
+}
+
+/// Get all saved filter presets
+pub fn get_saved_filters(conn: &Connection) -> Result<Vec<SavedFilter>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, name, filters, created_at, updated_at FROM saved_filters ORDER BY name ASC"
+    )?;
+
+    let rows = stmt.query_map([], |row| {
+        Ok((
+            row.get::<_, i64>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, String>(2)?,
+            row.get::<_, String>(3)?,
+            row.get::<_, String>(4)?,
+        ))
+    })?
+    .collect::<Result<Vec<_>, _>>()?;
+
+    let mut filters = Vec::new();
+    for (id, name, filters_json, created_at, updated_at) in rows {
+        match serde_json::from_str(&filters_json) {
+            Ok(parsed) => filters.push(SavedFilter {
+                id,
+                name,
+                filters: parsed,
+                created_at,
+                updated_at,
+            }),
+            Err(e) => tracing::warn!("Skipping saved filter '{}' with invalid JSON: {}", name, e),
+        }
+    }
+
+    Ok(filters)
+}
+
+/// Delete a saved filter preset
+pub fn delete_saved_filter(conn: &Connection, id: i64) -> Result<()> {
+    conn.execute("DELETE FROM saved_filters WHERE id = ?1", params![id])?;
+    Ok(())
+}
+
 // ============================================================================
 // SETTINGS QUERIES
 // ============================================================================
@@ -1192,13 +2262,17 @@ pub fn get_all_repositories(conn: &Connection) -> Result<Vec<Repository>> {
 /// Get application settings (always returns the single row)
 pub fn get_settings(conn: &Connection) -> Result<Settings> {
     let row = conn.query_row(
-        "SELECT id, history_days, excluded_bots, bug_labels, feature_labels, created_at, updated_at
+        "SELECT id, history_days, excluded_bots, bug_labels, feature_labels, created_at, updated_at,
+                exclude_queue_merges_from_latency, exclude_self_reviews, blocking_labels,
+                productivity_weight_speed, productivity_weight_ease, productivity_weight_quality,
+                sync_old_reopened, loc_basis, sync_pr_files, metric_precision, min_active_prs, exclude_weekends, enable_webhooks, webhook_secret_set, webhook_port, period_boundary, core_start_hour, core_end_hour, sync_interval_minutes, freshness_aging_multiplier, freshness_stale_multiplier, include_merge_commit_loc, embedding_http_url, embedding_http_concurrency, sync_strategy, rejection_labels, internal_logins, internal_orgs, headcount_basis, sla_rules, ai_min_source_similarity, pr_type_label_rules, exclude_reverts, planning_labels, milestone_counts_source, utc_offset_minutes, tier_medium_tolerance
          FROM settings WHERE id = 1",
         [],
         |row| {
             let excluded_bots_json: String = row.get(2)?;
             let bug_labels_json: String = row.get(3)?;
             let feature_labels_json: String = row.get(4)?;
+            let blocking_labels_json: String = row.get(9)?;
 
             Ok(Settings {
                 id: row.get(0)?,
@@ -1208,6 +2282,43 @@ pub fn get_settings(conn: &Connection) -> Result<Settings> {
                 feature_labels: serde_json::from_str(&feature_labels_json).unwrap_or_default(),
                 created_at: row.get(5)?,
                 updated_at: row.get(6)?,
+                exclude_queue_merges_from_latency: row.get(7)?,
+                exclude_self_reviews: row.get(8)?,
+                blocking_labels: serde_json::from_str(&blocking_labels_json).unwrap_or_default(),
+                productivity_weight_speed: row.get(10)?,
+                productivity_weight_ease: row.get(11)?,
+                productivity_weight_quality: row.get(12)?,
+                sync_old_reopened: row.get(13)?,
+                loc_basis: row.get(14)?,
+                sync_pr_files: row.get(15)?,
+                metric_precision: row.get(16)?,
+                min_active_prs: row.get(17)?,
+                exclude_weekends: row.get(18)?,
+                enable_webhooks: row.get(19)?,
+                webhook_secret_set: row.get(20)?,
+                webhook_port: row.get(21)?,
+                period_boundary: row.get(22)?,
+                core_start_hour: row.get(23)?,
+                core_end_hour: row.get(24)?,
+                sync_interval_minutes: row.get(25)?,
+                freshness_aging_multiplier: row.get(26)?,
+                freshness_stale_multiplier: row.get(27)?,
+                include_merge_commit_loc: row.get(28)?,
+                embedding_http_url: row.get(29)?,
+                embedding_http_concurrency: row.get(30)?,
+                sync_strategy: serde_json::from_str(&row.get::<_, String>(31)?).unwrap_or_default(),
+                rejection_labels: serde_json::from_str(&row.get::<_, String>(32)?).unwrap_or_default(),
+                internal_logins: serde_json::from_str(&row.get::<_, String>(33)?).unwrap_or_default(),
+                internal_orgs: serde_json::from_str(&row.get::<_, String>(34)?).unwrap_or_default(),
+                headcount_basis: row.get(35)?,
+                sla_rules: serde_json::from_str(&row.get::<_, String>(36)?).unwrap_or_default(),
+                ai_min_source_similarity: row.get(37)?,
+                pr_type_label_rules: serde_json::from_str(&row.get::<_, String>(38)?).unwrap_or_default(),
+                exclude_reverts: row.get(39)?,
+                planning_labels: serde_json::from_str(&row.get::<_, String>(40)?).unwrap_or_default(),
+                milestone_counts_source: row.get(41)?,
+                utc_offset_minutes: row.get(42)?,
+                tier_medium_tolerance: row.get(43)?,
             })
         },
     )?;
@@ -1216,16 +2327,20 @@ pub fn get_settings(conn: &Connection) -> Result<Settings> {
 }
 
 /// Update application settings
-pub fn update_settings(
-    conn: &Connection,
-    history_days: i32,
-    excluded_bots: &[String],
-    bug_labels: &[String],
-    feature_labels: &[String],
-) -> Result<()> {
-    let excluded_bots_json = serde_json::to_string(excluded_bots)?;
-    let bug_labels_json = serde_json::to_string(bug_labels)?;
-    let feature_labels_json = serde_json::to_string(feature_labels)?;
+pub fn update_settings(conn: &Connection, settings: &Settings) -> Result<()> {
+    validate_settings(settings)?;
+
+    let excluded_bots_json = serde_json::to_string(&settings.excluded_bots)?;
+    let bug_labels_json = serde_json::to_string(&settings.bug_labels)?;
+    let feature_labels_json = serde_json::to_string(&settings.feature_labels)?;
+    let blocking_labels_json = serde_json::to_string(&settings.blocking_labels)?;
+    let sync_strategy_json = serde_json::to_string(&settings.sync_strategy)?;
+    let sla_rules_json = serde_json::to_string(&settings.sla_rules)?;
+    let pr_type_label_rules_json = serde_json::to_string(&settings.pr_type_label_rules)?;
+    let planning_labels_json = serde_json::to_string(&settings.planning_labels)?;
+    let rejection_labels_json = serde_json::to_string(&settings.rejection_labels)?;
+    let internal_logins_json = serde_json::to_string(&settings.internal_logins)?;
+    let internal_orgs_json = serde_json::to_string(&settings.internal_orgs)?;
 
     conn.execute(
         "UPDATE settings SET
@@ -1233,10 +2348,917 @@ pub fn update_settings(
             excluded_bots = ?2,
             bug_labels = ?3,
             feature_labels = ?4,
+            exclude_queue_merges_from_latency = ?5,
+            exclude_self_reviews = ?6,
+            blocking_labels = ?7,
+            productivity_weight_speed = ?8,
+            productivity_weight_ease = ?9,
+            productivity_weight_quality = ?10,
+            sync_old_reopened = ?11,
+            loc_basis = ?12,
+            sync_pr_files = ?13,
+            metric_precision = ?14,
+            min_active_prs = ?15,
+            exclude_weekends = ?16,
+            enable_webhooks = ?17,
+            webhook_secret_set = ?18,
+            webhook_port = ?19,
+            period_boundary = ?20,
+            core_start_hour = ?21,
+            core_end_hour = ?22,
+            sync_interval_minutes = ?23,
+            freshness_aging_multiplier = ?24,
+            freshness_stale_multiplier = ?25,
+            include_merge_commit_loc = ?26,
+            embedding_http_url = ?27,
+            embedding_http_concurrency = ?28,
+            sync_strategy = ?29,
+            rejection_labels = ?30,
+            internal_logins = ?31,
+            internal_orgs = ?32,
+            headcount_basis = ?33,
+            sla_rules = ?34,
+            ai_min_source_similarity = ?35,
+            pr_type_label_rules = ?36,
+            exclude_reverts = ?37,
+            planning_labels = ?38,
+            milestone_counts_source = ?39,
+            utc_offset_minutes = ?40,
+            tier_medium_tolerance = ?41,
             updated_at = datetime('now')
          WHERE id = 1",
-        params![history_days, excluded_bots_json, bug_labels_json, feature_labels_json],
+        params![
+            settings.history_days,
+            excluded_bots_json,
+            bug_labels_json,
+            feature_labels_json,
+            settings.exclude_queue_merges_from_latency,
+            settings.exclude_self_reviews,
+            blocking_labels_json,
+            settings.productivity_weight_speed,
+            settings.productivity_weight_ease,
+            settings.productivity_weight_quality,
+            settings.sync_old_reopened,
+            settings.loc_basis,
+            settings.sync_pr_files,
+            settings.metric_precision,
+            settings.min_active_prs,
+            settings.exclude_weekends,
+            settings.enable_webhooks,
+            settings.webhook_secret_set,
+            settings.webhook_port,
+            settings.period_boundary,
+            settings.core_start_hour,
+            settings.core_end_hour,
+            settings.sync_interval_minutes,
+            settings.freshness_aging_multiplier,
+            settings.freshness_stale_multiplier,
+            settings.include_merge_commit_loc,
+            settings.embedding_http_url,
+            settings.embedding_http_concurrency,
+            sync_strategy_json,
+            rejection_labels_json,
+            internal_logins_json,
+            internal_orgs_json,
+            settings.headcount_basis,
+            sla_rules_json,
+            settings.ai_min_source_similarity,
+            pr_type_label_rules_json,
+            settings.exclude_reverts,
+            planning_labels_json,
+            settings.milestone_counts_source,
+            settings.utc_offset_minutes,
+            settings.tier_medium_tolerance,
+        ],
     )?;
 
     Ok(())
 }
+
+/// Check settings values before they are saved
+pub fn validate_settings(settings: &Settings) -> Result<()> {
+    validate_productivity_weights(
+        settings.productivity_weight_speed,
+        settings.productivity_weight_ease,
+        settings.productivity_weight_quality,
+    )?;
+    if !(0.0..=1.0).contains(&settings.tier_medium_tolerance) {
+        return Err(anyhow::anyhow!("tier_medium_tolerance must be between 0 and 1"));
+    }
+    if !matches!(settings.loc_basis.as_str(), "all" | "merged" | "commits") {
+        return Err(anyhow::anyhow!(
+            "Invalid loc_basis '{}' (expected 'all', 'merged' or 'commits')",
+            settings.loc_basis
+        ));
+    }
+    if !matches!(settings.period_boundary.as_str(), "rolling" | "inclusive_start") {
+        return Err(anyhow::anyhow!(
+            "Invalid period_boundary '{}' (expected 'rolling' or 'inclusive_start')",
+            settings.period_boundary
+        ));
+    }
+    if !matches!(settings.headcount_basis.as_str(), "active" | "tracked") {
+        return Err(anyhow::anyhow!(
+            "Invalid headcount_basis '{}' (expected 'active' or 'tracked')",
+            settings.headcount_basis
+        ));
+    }
+    if !matches!(settings.milestone_counts_source.as_str(), "github" | "local") {
+        return Err(anyhow::anyhow!(
+            "Invalid milestone_counts_source '{}' (expected 'github' or 'local')",
+            settings.milestone_counts_source
+        ));
+    }
+    if !(-720..=840).contains(&settings.utc_offset_minutes) {
+        return Err(anyhow::anyhow!(
+            "utc_offset_minutes must be between -720 and 840, got {}",
+            settings.utc_offset_minutes
+        ));
+    }
+    if !(0.0..=1.0).contains(&settings.ai_min_source_similarity) {
+        return Err(anyhow::anyhow!(
+            "ai_min_source_similarity must be between 0 and 1, got {}",
+            settings.ai_min_source_similarity
+        ));
+    }
+    for rule in &settings.sla_rules {
+        if rule.label.trim().is_empty() {
+            return Err(anyhow::anyhow!("SLA rules need a label"));
+        }
+        if [rule.response_hours, rule.resolution_hours].iter().flatten().any(|h| *h <= 0.0) {
+            return Err(anyhow::anyhow!("SLA targets for '{}' must be positive hours", rule.label));
+        }
+    }
+    for rule in &settings.pr_type_label_rules {
+        if rule.label.trim().is_empty() {
+            return Err(anyhow::anyhow!("PR type label rules need a label"));
+        }
+        if !PR_TYPES.contains(&rule.pr_type.as_str()) {
+            return Err(anyhow::anyhow!(
+                "Invalid PR type '{}' for label '{}' (expected one of {})",
+                rule.pr_type,
+                rule.label,
+                PR_TYPES.join(", ")
+            ));
+        }
+    }
+    if settings.enable_webhooks && !settings.webhook_secret_set {
+        return Err(anyhow::anyhow!("A webhook secret is required to enable webhooks"));
+    }
+    if settings.core_start_hour < 0 || settings.core_end_hour > 24 || settings.core_start_hour >= settings.core_end_hour {
+        return Err(anyhow::anyhow!(
+            "Invalid core hours {}-{} (start must be before end, within 0-24)",
+            settings.core_start_hour,
+            settings.core_end_hour
+        ));
+    }
+    if settings.min_active_prs < 1 {
+        return Err(anyhow::anyhow!("min_active_prs must be at least 1"));
+    }
+    if !(1..=crate::embeddings::http::MAX_CONCURRENCY).contains(&settings.embedding_http_concurrency) {
+        return Err(anyhow::anyhow!(
+            "embedding_http_concurrency must be between 1 and {}",
+            crate::embeddings::http::MAX_CONCURRENCY
+        ));
+    }
+    if settings.sync_strategy.is_empty() {
+        return Err(anyhow::anyhow!("sync_strategy must list at least one method"));
+    }
+    if let Some(unknown) = settings
+        .sync_strategy
+        .iter()
+        .find(|method| crate::github::sync::SyncMethod::parse(method).is_none())
+    {
+        return Err(anyhow::anyhow!(
+            "Unknown sync method '{}' (expected graphql, rest or cli)",
+            unknown
+        ));
+    }
+    if settings.sync_interval_minutes < 1 {
+        return Err(anyhow::anyhow!("sync_interval_minutes must be at least 1"));
+    }
+    if settings.freshness_aging_multiplier <= 0.0
+        || settings.freshness_aging_multiplier >= settings.freshness_stale_multiplier
+    {
+        return Err(anyhow::anyhow!(
+            "Invalid freshness thresholds (aging {} must be positive and below stale {})",
+            settings.freshness_aging_multiplier,
+            settings.freshness_stale_multiplier
+        ));
+    }
+    if settings.metric_precision > crate::metrics::precision::MAX_METRIC_PRECISION {
+        return Err(anyhow::anyhow!(
+            "metric_precision must be between 0 and {}",
+            crate::metrics::precision::MAX_METRIC_PRECISION
+        ));
+    }
+
+    Ok(())
+}
+
+/// Ensure productivity weights are non-negative and sum to 1.0
+pub fn validate_productivity_weights(speed: f64, ease: f64, quality: f64) -> Result<()> {
+    if speed < 0.0 || ease < 0.0 || quality < 0.0 {
+        return Err(anyhow::anyhow!("Productivity weights must not be negative"));
+    }
+
+    let sum = speed + ease + quality;
+    if (sum - 1.0).abs() > 1e-6 {
+        return Err(anyhow::anyhow!(
+            "Productivity weights must sum to 1.0 (got {:.3})",
+            sum
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::db::migrations::run_migrations(&conn).unwrap();
+        conn
+    }
+
+    fn insert_raw_user(conn: &Connection, github_id: i64, login: &str) -> i64 {
+        conn.execute(
+            "INSERT INTO users (github_id, login) VALUES (?1, ?2)",
+            params![github_id, login],
+        )
+        .unwrap();
+        conn.last_insert_rowid()
+    }
+
+    #[test]
+    fn test_zero_id_user_gets_real_id() {
+        let conn = setup_db();
+        let user_id = insert_raw_user(&conn, 0, "octocat");
+
+        assert_eq!(get_users_with_invalid_github_id(&conn).unwrap(), vec![(user_id, "octocat".to_string())]);
+
+        let merged = assign_user_github_id(&conn, user_id, 583231).unwrap();
+        assert!(!merged);
+
+        let github_id: i64 = conn
+            .query_row("SELECT github_id FROM users WHERE id = ?1", [user_id], |row| row.get(0))
+            .unwrap();
+        assert_eq!(github_id, 583231);
+        assert!(get_users_with_invalid_github_id(&conn).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_duplicate_user_is_merged() {
+        let conn = setup_db();
+        let real_id = insert_raw_user(&conn, 583231, "octocat");
+        let dup_id = insert_raw_user(&conn, -1, "Octocat");
+
+        conn.execute("INSERT INTO repositories (owner, name) VALUES ('o', 'r')", []).unwrap();
+        conn.execute(
+            "INSERT INTO pull_requests (github_id, repo_id, number, title, state, author_id, created_at, updated_at)
+             VALUES (1, 1, 1, 'PR', 'open', ?1, '2024-01-01', '2024-01-01')",
+            [dup_id],
+        )
+        .unwrap();
+        conn.execute("UPDATE users SET tracked = 1 WHERE id = ?1", [dup_id]).unwrap();
+        upsert_commit(&conn, 1, "abc123", Some(dup_id), "Add search", "2024-01-01T00:00:00Z", 10, 2, false).unwrap();
+
+        assert_eq!(find_valid_user_by_login(&conn, "Octocat", dup_id).unwrap(), Some((real_id, 583231)));
+
+        let merged = assign_user_github_id(&conn, dup_id, 583231).unwrap();
+        assert!(merged);
+
+        let user_count: i64 = conn.query_row("SELECT COUNT(*) FROM users", [], |row| row.get(0)).unwrap();
+        assert_eq!(user_count, 1);
+
+        let author_id: i64 = conn
+            .query_row("SELECT author_id FROM pull_requests WHERE github_id = 1", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(author_id, real_id);
+
+        let commit_author: i64 = conn
+            .query_row("SELECT author_id FROM commits WHERE oid = 'abc123'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(commit_author, real_id);
+
+        let tracked: bool = conn
+            .query_row("SELECT tracked FROM users WHERE id = ?1", [real_id], |row| row.get(0))
+            .unwrap();
+        assert!(tracked);
+    }
+
+    #[test]
+    fn test_edited_text_clears_embedding_and_skip_marker() {
+        let conn = setup_db();
+        let repo_id = upsert_repository(&conn, "o", "r", None, true).unwrap();
+        let upsert = |title: &str, synced: &str| {
+            upsert_pull_request(
+                &conn, 1, repo_id, 1, title, None, "OPEN", None,
+                "2024-01-01T00:00:00Z", synced, None, None, 1, 1, 1, &[], synced,
+            )
+            .unwrap()
+        };
+        let pr_id = upsert("wip", "2024-01-01T00:00:00Z");
+        mark_embedding_skipped(&conn, "pull_requests", pr_id).unwrap();
+
+        // Unchanged text keeps the marker so the item isn't re-queued every sync
+        upsert("wip", "2024-01-02T00:00:00Z");
+        assert!(get_prs_without_embeddings(&conn, 10).unwrap().is_empty());
+
+        upsert("Rework the login flow to use OAuth device codes", "2024-01-03T00:00:00Z");
+        let model: Option<String> = conn
+            .query_row("SELECT embedding_model FROM pull_requests WHERE id = ?1", [pr_id], |row| row.get(0))
+            .unwrap();
+        assert_eq!(model, None);
+        assert_eq!(get_prs_without_embeddings(&conn, 10).unwrap().len(), 1);
+
+        let issue_id = upsert_issue(
+            &conn, 2, repo_id, 2, "Login", None, "OPEN", None, None, None,
+            "2024-01-01T00:00:00Z", "2024-01-01T00:00:00Z", None, &[], "2024-01-01T00:00:00Z",
+        )
+        .unwrap();
+        set_issue_embedding(&conn, issue_id, &[0.5; 4], "old-model").unwrap();
+        upsert_issue(
+            &conn, 2, repo_id, 2, "Login", Some("Crashes on submit"), "OPEN", None, None, None,
+            "2024-01-01T00:00:00Z", "2024-01-02T00:00:00Z", None, &[], "2024-01-02T00:00:00Z",
+        )
+        .unwrap();
+        assert_eq!(get_issue_embedding(&conn, issue_id).unwrap(), None);
+    }
+
+    #[test]
+    fn test_merge_method_is_stored() {
+        let conn = setup_db();
+        let repo_id = upsert_repository(&conn, "o", "r", None, true).unwrap();
+        let pr_id = upsert_pull_request(
+            &conn, 1, repo_id, 1, "Bump deps", None, "MERGED", None,
+            "2024-01-01T00:00:00Z", "2024-01-02T00:00:00Z", Some("2024-01-02T00:00:00Z"), Some("2024-01-02T00:00:00Z"),
+            1, 1, 1, &[], "2024-01-02T00:00:00Z",
+        )
+        .unwrap();
+
+        set_pr_merge_method(&conn, pr_id, Some("auto_merge")).unwrap();
+
+        let merge_method: Option<String> = conn
+            .query_row("SELECT merge_method FROM pull_requests WHERE id = ?1", [pr_id], |row| row.get(0))
+            .unwrap();
+        assert_eq!(merge_method.as_deref(), Some("auto_merge"));
+    }
+
+    #[test]
+    fn test_self_reviews_excluded_by_default() {
+        let conn = setup_db();
+        let author = insert_raw_user(&conn, 1, "author");
+        let reviewer = insert_raw_user(&conn, 2, "reviewer");
+        let repo_id = upsert_repository(&conn, "o", "r", None, true).unwrap();
+        let pr_id = upsert_pull_request(
+            &conn, 10, repo_id, 1, "Change", None, "OPEN", Some(author),
+            "2024-01-01T00:00:00Z", "2024-01-01T00:00:00Z", None, None,
+            1, 1, 1, &[], "2024-01-01T00:00:00Z",
+        )
+        .unwrap();
+        upsert_pr_review(&conn, 100, pr_id, Some(author), "COMMENTED", "2024-01-01T01:00:00Z", "2024-01-01T01:00:00Z").unwrap();
+        upsert_pr_review(&conn, 101, pr_id, Some(reviewer), "APPROVED", "2024-01-01T05:00:00Z", "2024-01-01T05:00:00Z").unwrap();
+
+        let settings = get_settings(&conn).unwrap();
+        assert!(settings.exclude_self_reviews);
+        let bots = get_bot_reviewer_ids(&conn, &settings.excluded_bots).unwrap();
+
+        assert_eq!(get_pr_review_count(&conn, pr_id, settings.exclude_self_reviews, &bots).unwrap(), 1);
+        assert_eq!(
+            get_first_review_time(&conn, pr_id, settings.exclude_self_reviews, &bots).unwrap().as_deref(),
+            Some("2024-01-01T05:00:00Z")
+        );
+
+        assert_eq!(get_pr_review_count(&conn, pr_id, false, &bots).unwrap(), 2);
+    }
+
+    #[test]
+    fn test_default_branch_falls_back_to_main() {
+        let conn = setup_db();
+        let repo_id = upsert_repository(&conn, "o", "r", None, true).unwrap();
+
+        assert_eq!(get_repo_default_branch(&conn, repo_id).unwrap(), "main");
+
+        update_repo_default_branch(&conn, repo_id, "develop").unwrap();
+        assert_eq!(get_repo_default_branch(&conn, repo_id).unwrap(), "develop");
+        assert_eq!(
+            get_repository_by_id(&conn, repo_id).unwrap().default_branch.as_deref(),
+            Some("develop")
+        );
+    }
+
+    #[test]
+    fn test_repo_specific_bot_only_excluded_for_that_repo() {
+        let conn = setup_db();
+        let repo_a = upsert_repository(&conn, "acme", "a", None, true).unwrap();
+        let repo_b = upsert_repository(&conn, "acme", "b", None, true).unwrap();
+        let global = vec!["dependabot".to_string()];
+
+        set_repo_excluded_bots(&conn, repo_a, &["release-svc".to_string()]).unwrap();
+
+        let bots_a = get_repo_excluded_bots(&conn, repo_a, &global).unwrap();
+        let bots_b = get_repo_excluded_bots(&conn, repo_b, &global).unwrap();
+        assert_eq!(bots_a, vec!["dependabot".to_string(), "release-svc".to_string()]);
+        assert_eq!(bots_b, global);
+
+        assert!(is_bot_user("release-svc", &bots_a));
+        assert!(!is_bot_user("release-svc", &bots_b));
+    }
+
+    #[test]
+    fn test_all_labels_are_distinct_with_counts() {
+        let conn = setup_db();
+        conn.execute_batch(
+            r#"INSERT INTO repositories (id, owner, name) VALUES (1, 'acme', 'api');
+               INSERT INTO issues (github_id, repo_id, number, title, state, created_at, updated_at, labels)
+               VALUES (1, 1, 1, 'Issue', 'open', '2024-01-01', '2024-01-01', '["bug", "ui"]'),
+                      (2, 1, 2, 'Issue', 'open', '2024-01-01', '2024-01-01', '["bug"]'),
+                      (3, 1, 3, 'Issue', 'open', '2024-01-01', '2024-01-01', NULL);
+               INSERT INTO pull_requests (github_id, repo_id, number, title, state, created_at, updated_at, labels)
+               VALUES (10, 1, 10, 'PR', 'open', '2024-01-01', '2024-01-01', '["bug", "feature"]');"#,
+        )
+        .unwrap();
+
+        let all = get_all_labels(&conn, None).unwrap();
+        let counts: Vec<(&str, i64)> = all.iter().map(|l| (l.label.as_str(), l.count)).collect();
+        assert_eq!(counts, vec![("bug", 3), ("feature", 1), ("ui", 1)]);
+
+        let issue_labels = get_all_labels(&conn, Some("issue")).unwrap();
+        assert_eq!(issue_labels.len(), 2);
+        assert_eq!((issue_labels[0].label.as_str(), issue_labels[0].count), ("bug", 2));
+
+        assert!(get_all_labels(&conn, Some("commit")).is_err());
+    }
+
+    #[test]
+    fn test_productivity_weights_must_sum_to_one() {
+        let conn = setup_db();
+        let mut settings = get_settings(&conn).unwrap();
+        assert!((settings.productivity_weight_speed - 0.6).abs() < 1e-9);
+
+        settings.productivity_weight_speed = 0.5;
+        assert!(update_settings(&conn, &settings).is_err());
+
+        settings.productivity_weight_ease = 0.35;
+        update_settings(&conn, &settings).unwrap();
+        assert!((get_settings(&conn).unwrap().productivity_weight_ease - 0.35).abs() < 1e-9);
+
+        assert!(validate_productivity_weights(1.2, -0.1, -0.1).is_err());
+    }
+
+    #[test]
+    fn test_reviewer_role_aggregates_reviewed_prs() {
+        let conn = setup_db();
+        conn.execute_batch(
+            "INSERT INTO repositories (id, owner, name) VALUES (1, 'acme', 'api');
+             INSERT INTO users (id, github_id, login) VALUES (1, 101, 'lead'), (2, 102, 'dev');
+             INSERT INTO pull_requests (id, github_id, repo_id, number, title, state, author_id, created_at, updated_at, merged_at, additions, labels)
+             VALUES
+                 (1, 1, 1, 1, 'Lead PR', 'MERGED', 1, '2024-01-02T00:00:00Z', '2024-01-03T00:00:00Z', '2024-01-03T00:00:00Z', 10, '[]'),
+                 (2, 2, 1, 2, 'Dev PR 1', 'MERGED', 2, '2024-01-02T00:00:00Z', '2024-01-03T00:00:00Z', '2024-01-03T00:00:00Z', 100, '[]'),
+                 (3, 3, 1, 3, 'Dev PR 2', 'MERGED', 2, '2024-01-02T00:00:00Z', '2024-01-03T00:00:00Z', '2024-01-03T00:00:00Z', 100, '[]'),
+                 (4, 4, 1, 4, 'Dev PR 3', 'OPEN', 2, '2024-01-02T00:00:00Z', '2024-01-03T00:00:00Z', NULL, 100, '[]');
+             INSERT INTO pr_reviews (github_id, pr_id, reviewer_id, state, submitted_at) VALUES
+                 (11, 1, 1, 'COMMENTED', '2024-01-02T10:00:00Z'),
+                 (12, 2, 1, 'APPROVED', '2024-01-02T10:00:00Z'),
+                 (13, 3, 1, 'APPROVED', '2024-01-02T10:00:00Z'),
+                 (14, 4, 1, 'CHANGES_REQUESTED', '2024-01-02T10:00:00Z');
+             INSERT INTO issues (github_id, repo_id, number, title, state, author_id, assignee_id, created_at, updated_at, labels)
+             VALUES (21, 1, 5, 'Issue', 'open', 2, 1, '2024-01-02T00:00:00Z', '2024-01-02T00:00:00Z', '[]');",
+        )
+        .unwrap();
+
+        let since = "2024-01-01T00:00:00Z";
+        let authored = get_prs_for_metrics_filtered(&conn, since, None, &[], None, Some(1), UserRole::Author, None, None, RepoVisibility::All).unwrap();
+        let reviewed = get_prs_for_metrics_filtered(&conn, since, None, &[], None, Some(1), UserRole::Reviewer, None, None, RepoVisibility::All).unwrap();
+
+        assert_eq!(authored.iter().map(|p| p.number).collect::<Vec<_>>(), vec![1]);
+        let mut reviewed_numbers: Vec<i32> = reviewed.iter().map(|p| p.number).collect();
+        reviewed_numbers.sort();
+        assert_eq!(reviewed_numbers, vec![2, 3, 4]);
+
+        let authored_loc: i32 = authored.iter().map(|p| p.additions).sum();
+        let reviewed_loc: i32 = reviewed.iter().map(|p| p.additions).sum();
+        assert!(reviewed_loc > authored_loc);
+
+        let assigned = get_issues_for_metrics_filtered(&conn, since, None, &[], None, Some(1), UserRole::Assignee, None, RepoVisibility::All).unwrap();
+        assert_eq!(assigned.len(), 1);
+        let as_reviewer = get_issues_for_metrics_filtered(&conn, since, None, &[], None, Some(1), UserRole::Reviewer, None, RepoVisibility::All).unwrap();
+        assert!(as_reviewer.is_empty());
+    }
+
+    #[test]
+    fn test_assignee_role_matches_pr_assignees_not_authors() {
+        let conn = setup_db();
+        conn.execute_batch(
+            "INSERT INTO repositories (id, owner, name) VALUES (1, 'acme', 'api');
+             INSERT INTO users (id, github_id, login) VALUES (1, 101, 'lead'), (2, 102, 'dev');
+             INSERT INTO pull_requests (id, github_id, repo_id, number, title, state, author_id, created_at, updated_at, labels)
+             VALUES
+                 (1, 1, 1, 1, 'Lead PR', 'OPEN', 1, '2024-01-02T00:00:00Z', '2024-01-03T00:00:00Z', '[]'),
+                 (2, 2, 1, 2, 'Dev PR', 'OPEN', 2, '2024-01-02T00:00:00Z', '2024-01-03T00:00:00Z', '[]');",
+        )
+        .unwrap();
+        replace_pr_assignees(&conn, 2, &[1]).unwrap();
+
+        let since = "2024-01-01T00:00:00Z";
+        let assigned = get_prs_for_metrics_filtered(&conn, since, None, &[], None, Some(1), UserRole::Assignee, None, None, RepoVisibility::All).unwrap();
+        assert_eq!(assigned.iter().map(|p| p.number).collect::<Vec<_>>(), vec![2]);
+
+        // Re-syncing replaces the assignee list
+        replace_pr_assignees(&conn, 2, &[2]).unwrap();
+        let assigned = get_prs_for_metrics_filtered(&conn, since, None, &[], None, Some(1), UserRole::Assignee, None, None, RepoVisibility::All).unwrap();
+        assert!(assigned.is_empty());
+    }
+
+    #[test]
+    fn test_detect_sync_gaps_reports_missing_windows() {
+        let conn = setup_db();
+        conn.execute_batch(
+            "INSERT INTO repositories (id, owner, name, enabled) VALUES (10, 'acme', 'web', 1), (11, 'acme', 'old', 0);
+             INSERT INTO sync_log (repo_id, sync_type, started_at, completed_at, error) VALUES
+                 (10, 'issues', '2024-06-01 08:00:00', '2024-06-01 08:01:00', NULL),
+                 (10, 'issues', '2024-06-01 09:00:00', '2024-06-01 09:01:00', NULL),
+                 (10, 'issues', '2024-06-03 10:00:00', '2024-06-03 10:01:00', 'rate limited'),
+                 (10, 'issues', '2024-06-08 09:00:00', '2024-06-08 09:01:00', NULL),
+                 (10, 'issues', '2024-06-08 10:00:00', NULL, NULL),
+                 (10, 'pull_requests', '2024-06-08 11:00:00', '2024-06-08 11:02:00', NULL),
+                 (11, 'issues', '2024-01-01 00:00:00', '2024-01-01 00:01:00', NULL);",
+        )
+        .unwrap();
+        let settings = get_settings(&conn).unwrap();
+
+        // Default 60 minute interval × 3: gaps over 180 minutes. The failed and unfinished syncs don't count.
+        let gaps = detect_sync_gaps(&conn, &settings, "2024-06-08 12:00:00").unwrap();
+        let summary: Vec<(&str, &str, &str, bool)> = gaps
+            .iter()
+            .map(|g| (g.sync_type.as_str(), g.gap_start.as_str(), g.gap_end.as_str(), g.ongoing))
+            .collect();
+        assert_eq!(summary, vec![("issues", "2024-06-01 09:00:00", "2024-06-08 09:00:00", false)]);
+        assert_eq!(gaps[0].repo, "acme/web");
+        assert_eq!(gaps[0].gap_minutes, 7 * 24 * 60);
+
+        // Left alone long enough, the latest sync opens an ongoing gap
+        let later = detect_sync_gaps(&conn, &settings, "2024-06-08 15:00:00").unwrap();
+        assert_eq!(later.len(), 3);
+        assert!(later[1].ongoing && later[2].ongoing);
+        assert_eq!(later[2].sync_type, "pull_requests");
+    }
+
+    #[test]
+    fn test_repo_freshness_tiers_follow_sync_interval() {
+        let conn = setup_db();
+        conn.execute_batch(
+            "INSERT INTO repositories (owner, name, enabled, last_synced_at) VALUES
+                 ('acme', 'a-fresh', 1, '2024-06-01 11:30:00'),
+                 ('acme', 'b-aging', 1, '2024-06-01 10:00:00'),
+                 ('acme', 'c-stale', 1, '2024-06-01 08:00:00'),
+                 ('acme', 'd-never', 1, NULL),
+                 ('acme', 'e-disabled', 0, '2024-06-01 11:59:00');",
+        )
+        .unwrap();
+        let mut settings = get_settings(&conn).unwrap();
+        let now = "2024-06-01 12:00:00";
+
+        // Default 60 minute interval: aging after 90 minutes, stale after 180
+        let freshness = get_repo_freshness(&conn, &settings, now).unwrap();
+        let summary: Vec<(&str, Option<i64>, &str)> = freshness
+            .iter()
+            .map(|r| (r.name.as_str(), r.minutes_since_sync, r.tier.as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("a-fresh", Some(30), "fresh"),
+                ("b-aging", Some(120), "aging"),
+                ("c-stale", Some(240), "stale"),
+                ("d-never", None, "stale"),
+            ]
+        );
+
+        // A longer interval shifts the thresholds
+        settings.sync_interval_minutes = 120;
+        let tiers: Vec<String> = get_repo_freshness(&conn, &settings, now)
+            .unwrap()
+            .into_iter()
+            .map(|r| r.tier)
+            .collect();
+        assert_eq!(tiers, vec!["fresh", "fresh", "aging", "stale"]);
+
+        settings.freshness_aging_multiplier = 4.0;
+        assert!(update_settings(&conn, &settings).is_err());
+    }
+
+    #[test]
+    fn test_base_branch_filter_narrows_prs() {
+        let conn = setup_db();
+        conn.execute_batch(
+            "INSERT INTO repositories (id, owner, name) VALUES (1, 'acme', 'api'), (2, 'acme', 'web');
+             INSERT INTO users (id, github_id, login) VALUES (1, 101, 'alice'), (2, 102, 'bob');
+             INSERT INTO pull_requests (github_id, repo_id, number, title, state, author_id, created_at, updated_at, labels, base_branch)
+             VALUES
+                 (1, 1, 1, 'Feature', 'MERGED', 1, '2024-01-02T00:00:00Z', '2024-01-02T00:00:00Z', '[]', 'main'),
+                 (2, 1, 2, 'Backport', 'MERGED', 1, '2024-01-02T00:00:00Z', '2024-01-02T00:00:00Z', '[]', 'release/1.x'),
+                 (3, 1, 3, 'Hotfix', 'OPEN', 2, '2024-01-02T00:00:00Z', '2024-01-02T00:00:00Z', '[]', 'release/1.x'),
+                 (4, 2, 4, 'Web backport', 'OPEN', 1, '2024-01-02T00:00:00Z', '2024-01-02T00:00:00Z', '[]', 'release/1.x'),
+                 (5, 1, 5, 'Unsynced base', 'OPEN', 1, '2024-01-02T00:00:00Z', '2024-01-02T00:00:00Z', '[]', NULL);",
+        )
+        .unwrap();
+        let since = "2024-01-01T00:00:00Z";
+        let numbers = |prs: Vec<PullRequest>| prs.iter().map(|p| p.number).collect::<Vec<_>>();
+
+        let all = get_prs_for_metrics_filtered(&conn, since, None, &[], None, None, UserRole::Author, None, None, RepoVisibility::All).unwrap();
+        assert_eq!(all.len(), 5);
+
+        let main = get_prs_for_metrics_filtered(&conn, since, None, &[], None, None, UserRole::Author, None, Some("main"), RepoVisibility::All).unwrap();
+        assert_eq!(numbers(main), vec![1]);
+
+        let release = get_prs_for_metrics_filtered(&conn, since, None, &[], None, None, UserRole::Author, None, Some("release/1.x"), RepoVisibility::All).unwrap();
+        assert_eq!(numbers(release), vec![2, 3, 4]);
+
+        // Combines with the other filters
+        let alice_api_release = get_prs_for_metrics_filtered(
+            &conn, since, None, &[], Some(&[1]), None, UserRole::Author, Some(&[1]), Some("release/1.x"), RepoVisibility::All,
+        )
+        .unwrap();
+        assert_eq!(numbers(alice_api_release), vec![2]);
+    }
+
+    #[test]
+    fn test_merge_commit_loc_is_excluded_by_default() {
+        let conn = setup_db();
+        conn.execute("INSERT INTO repositories (id, owner, name) VALUES (1, 'acme', 'api')", []).unwrap();
+        let alice = insert_raw_user(&conn, 1, "alice");
+        conn.execute("UPDATE users SET tracked = 1 WHERE id = ?1", [alice]).unwrap();
+        let outsider = insert_raw_user(&conn, 2, "outsider");
+        upsert_commit(&conn, 1, "abc123", Some(alice), "Add search", "2024-03-01T10:00:00Z", 120, 30, false).unwrap();
+        upsert_commit(&conn, 1, "def456", Some(alice), "Merge pull request #7", "2024-03-01T11:00:00Z", 120, 30, true).unwrap();
+        upsert_commit(&conn, 1, "0ld000", Some(alice), "Old work", "2023-01-01T00:00:00Z", 500, 0, false).unwrap();
+        upsert_commit(&conn, 1, "fff999", Some(outsider), "Untracked work", "2024-03-01T12:00:00Z", 70, 0, false).unwrap();
+
+        let since = "2024-01-01T00:00:00Z";
+        let settings = get_settings(&conn).unwrap();
+        assert!(!settings.include_merge_commit_loc);
+        assert_eq!(get_commit_loc(&conn, since, settings.include_merge_commit_loc).unwrap(), 150);
+        assert_eq!(get_commit_loc(&conn, since, true).unwrap(), 300);
+
+        // Re-syncing a commit updates it in place
+        upsert_commit(&conn, 1, "abc123", Some(alice), "Add search", "2024-03-01T10:00:00Z", 100, 0, false).unwrap();
+        assert_eq!(get_commit_loc(&conn, since, false).unwrap(), 100);
+    }
+
+    #[test]
+    fn test_track_squad_members() {
+        let conn = setup_db();
+        conn.execute_batch(
+            "INSERT INTO users (id, github_id, login, tracked, tracked_at) VALUES
+                 (1, 101, 'member', 0, NULL),
+                 (2, 102, 'tracked-member', 1, '2024-01-01T00:00:00Z'),
+                 (3, 103, 'outsider', 0, NULL);
+             INSERT INTO squads (id, name, color) VALUES ('core', 'Core', '#000000');
+             INSERT INTO squad_members (squad_id, user_id) VALUES ('core', 1), ('core', 2);",
+        )
+        .unwrap();
+        let tracked = |id: i64| -> (bool, Option<String>) {
+            conn.query_row("SELECT tracked, tracked_at FROM users WHERE id = ?1", [id], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })
+            .unwrap()
+        };
+
+        assert_eq!(track_squad_members(&conn, true).unwrap(), 1);
+        assert!(!tracked(1).0);
+
+        assert_eq!(track_squad_members(&conn, false).unwrap(), 1);
+        let (is_tracked, tracked_at) = tracked(1);
+        assert!(is_tracked);
+        assert!(tracked_at.is_some());
+        assert_eq!(tracked(2).1.as_deref(), Some("2024-01-01T00:00:00Z"));
+        assert!(!tracked(3).0);
+
+        assert_eq!(track_squad_members(&conn, false).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_diagnostics_for_populated_db() {
+        let conn = setup_db();
+        conn.execute_batch(
+            "INSERT INTO repositories (id, owner, name, last_synced_at) VALUES
+                 (1, 'acme', 'api', '2024-06-01 12:00:00'), (2, 'acme', 'web', NULL);
+             INSERT INTO issues (github_id, repo_id, number, title, state, created_at, updated_at, labels, embedding)
+             VALUES (1, 1, 1, 'Issue', 'OPEN', '2024-01-01T00:00:00Z', '2024-01-01T00:00:00Z', '[]', X'00');
+             INSERT INTO pull_requests (github_id, repo_id, number, title, state, created_at, updated_at, labels)
+             VALUES (2, 1, 2, 'PR', 'OPEN', '2024-01-01T00:00:00Z', '2024-01-01T00:00:00Z', '[]');",
+        )
+        .unwrap();
+
+        let diagnostics = get_diagnostics(&conn).unwrap();
+        assert_eq!(diagnostics.schema_version, crate::db::migrations::SCHEMA_VERSION);
+        assert!(diagnostics.db_size_bytes > 0);
+        assert!((diagnostics.embedding_coverage_pct - 50.0).abs() < 1e-9);
+        assert_eq!(diagnostics.sidecar_healthy, None);
+
+        let rows = |table: &str| diagnostics.table_row_counts.iter().find(|t| t.table == table).map(|t| t.rows);
+        assert_eq!(rows("repositories"), Some(2));
+        assert_eq!(rows("issues"), Some(1));
+        assert_eq!(rows("pull_requests"), Some(1));
+        assert_eq!(rows("settings"), Some(1));
+        assert_eq!(rows("search_fts"), None);
+
+        assert_eq!(diagnostics.repositories.len(), 2);
+        assert_eq!(diagnostics.repositories[0].repo, "acme/api");
+        assert_eq!(diagnostics.repositories[0].last_synced_at.as_deref(), Some("2024-06-01 12:00:00"));
+        assert_eq!(diagnostics.repositories[1].last_synced_at, None);
+    }
+
+    #[test]
+    fn test_changes_since_returns_items_updated_after_sync_completed() {
+        let conn = setup_db();
+        conn.execute_batch(
+            "INSERT INTO repositories (id, owner, name) VALUES (1, 'acme', 'api');
+             INSERT INTO sync_log (id, repo_id, sync_type, started_at, completed_at) VALUES
+                 (1, 1, 'issues', '2024-06-01 08:00:00', '2024-06-01 08:05:00'),
+                 (2, 1, 'issues', '2024-06-02 08:00:00', NULL);
+             INSERT INTO issues (id, github_id, repo_id, number, title, state, created_at, updated_at, labels, sync_updated_at) VALUES
+                 (1, 1, 1, 1, 'Old', 'open', '2024-05-01T00:00:00Z', '2024-05-31T00:00:00Z', '[]', '2024-05-31T00:00:00Z'),
+                 (2, 2, 1, 2, 'Same second', 'open', '2024-05-01T00:00:00Z', '2024-06-01T08:05:00Z', '[]', '2024-06-01T08:05:00Z'),
+                 (3, 3, 1, 3, 'New', 'closed', '2024-05-01T00:00:00Z', '2024-06-01T09:00:00Z', '[]', '2024-06-01T09:00:00Z'),
+                 (4, 4, 1, 4, 'Never synced', 'open', '2024-05-01T00:00:00Z', '2024-06-05T00:00:00Z', '[]', NULL);
+             INSERT INTO pull_requests (id, github_id, repo_id, number, title, state, created_at, updated_at, labels, sync_updated_at) VALUES
+                 (1, 11, 1, 10, 'Old PR', 'MERGED', '2024-05-01T00:00:00Z', '2024-05-20T00:00:00Z', '[]', '2024-05-20T00:00:00Z'),
+                 (2, 12, 1, 11, 'New PR', 'OPEN', '2024-05-01T00:00:00Z', '2024-06-01T08:30:00Z', '[]', '2024-06-01T08:30:00Z');",
+        )
+        .unwrap();
+
+        let changes = get_changes_since(&conn, 1).unwrap();
+        let summary: Vec<(&str, i32)> = changes.iter().map(|c| (c.item_type.as_str(), c.number)).collect();
+        assert_eq!(summary, vec![("pull_request", 11), ("issue", 3)]);
+        assert_eq!(changes[1].state, "closed");
+
+        assert!(get_changes_since(&conn, 2).is_err());
+        assert!(get_changes_since(&conn, 99).is_err());
+    }
+
+    #[test]
+    fn test_mark_interrupted_syncs_closes_unfinished_logs() {
+        let conn = setup_db();
+        conn.execute_batch(
+            "INSERT INTO repositories (id, owner, name) VALUES (1, 'acme', 'api');
+             INSERT INTO sync_log (id, repo_id, sync_type, started_at, completed_at, items_synced, error) VALUES
+                 (1, 1, 'issues', '2024-06-01 08:00:00', '2024-06-01 08:05:00', 12, NULL),
+                 (2, 1, 'pull_requests', '2024-06-01 08:05:00', '2024-06-01 08:06:00', 0, 'rate limited'),
+                 (3, 1, 'issues', '2024-06-02 08:00:00', NULL, 0, NULL),
+                 (4, 1, 'pull_requests', '2024-06-02 08:00:00', NULL, 0, NULL);",
+        )
+        .unwrap();
+
+        assert_eq!(mark_interrupted_syncs(&conn).unwrap(), 2);
+
+        let logs: Vec<(i64, bool, Option<String>)> = conn
+            .prepare("SELECT id, completed_at IS NOT NULL, error FROM sync_log ORDER BY id")
+            .unwrap()
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(
+            logs,
+            vec![
+                (1, true, None),
+                (2, true, Some("rate limited".to_string())),
+                (3, true, Some(INTERRUPTED_SYNC_ERROR.to_string())),
+                (4, true, Some(INTERRUPTED_SYNC_ERROR.to_string())),
+            ]
+        );
+
+        // Finished logs are left alone on the next run
+        assert_eq!(mark_interrupted_syncs(&conn).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_get_repo_sync_errors_newest_first_with_limit() {
+        let conn = setup_db();
+        conn.execute_batch(
+            "INSERT INTO repositories (id, owner, name) VALUES (1, 'acme', 'api'), (2, 'acme', 'web');
+             INSERT INTO sync_log (id, repo_id, sync_type, started_at, completed_at, items_synced, error) VALUES
+                 (1, 1, 'issues', '2024-06-01 08:00:00', '2024-06-01 08:01:00', 0, 'Bad credentials'),
+                 (2, 1, 'pull_requests', '2024-06-02 08:00:00', '2024-06-02 08:05:00', 40, NULL),
+                 (3, 1, 'pull_requests', '2024-06-03 08:00:00', '2024-06-03 08:01:00', 0, 'SAML enforcement'),
+                 (4, 2, 'issues', '2024-06-04 08:00:00', '2024-06-04 08:01:00', 0, 'Not Found'),
+                 (5, 1, 'commits', '2024-06-05 08:00:00', '2024-06-05 08:02:00', 0, 'rate limited'),
+                 (6, 1, 'issues', '2024-06-06 08:00:00', NULL, 0, NULL);",
+        )
+        .unwrap();
+
+        let errors = get_repo_sync_errors(&conn, 1, 10).unwrap();
+        let ids: Vec<i64> = errors.iter().map(|e| e.log_id).collect();
+        assert_eq!(ids, vec![5, 3, 1]);
+        assert_eq!(errors[0].sync_type, "commits");
+        assert_eq!(errors[0].failed_at, "2024-06-05 08:02:00");
+        assert_eq!(errors[1].error, "SAML enforcement");
+
+        let errors = get_repo_sync_errors(&conn, 1, 2).unwrap();
+        assert_eq!(errors.iter().map(|e| e.log_id).collect::<Vec<_>>(), vec![5, 3]);
+    }
+
+    #[test]
+    fn test_embedding_coverage_per_repo() {
+        let conn = setup_db();
+        conn.execute_batch(
+            "INSERT INTO repositories (id, owner, name) VALUES (1, 'acme', 'api'), (2, 'acme', 'web');
+             INSERT INTO issues (id, github_id, repo_id, number, title, state, created_at, updated_at, embedding, embedding_model)
+             VALUES
+                 (1, 1, 1, 1, 'Embedded', 'open', '2024-06-01', '2024-06-01', X'00', 'current'),
+                 (2, 2, 1, 2, 'Old model', 'open', '2024-06-01', '2024-06-01', X'00', 'legacy'),
+                 (3, 3, 1, 3, 'Missing', 'open', '2024-06-01', '2024-06-01', NULL, NULL),
+                 (4, 4, 1, 4, 'x', 'open', '2024-06-01', '2024-06-01', NULL, 'current'),
+                 (5, 5, 2, 1, 'Web issue', 'open', '2024-06-01', '2024-06-01', X'00', 'current');
+             INSERT INTO pull_requests (id, github_id, repo_id, number, title, state, created_at, updated_at, embedding, embedding_model)
+             VALUES
+                 (1, 1, 1, 10, 'Unembedded PR', 'OPEN', '2024-06-01', '2024-06-01', NULL, NULL),
+                 (2, 2, 1, 11, 'Also unembedded', 'OPEN', '2024-06-01', '2024-06-01', NULL, NULL);",
+        )
+        .unwrap();
+
+        let coverage = get_embedding_coverage(&conn, "current").unwrap();
+        assert_eq!(coverage.len(), 2);
+
+        // acme/api: 2 of 5 embeddable items embedded (the one-letter issue was skipped)
+        let api = &coverage[0];
+        assert_eq!(api.repo, "acme/api");
+        assert_eq!((api.total_items, api.embedded_items, api.stale_items, api.skipped_items), (6, 2, 1, 1));
+        assert!((api.coverage - 0.4).abs() < 1e-9);
+
+        assert_eq!(coverage[1].repo, "acme/web");
+        assert!((coverage[1].coverage - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_milestone_counts_source_github_vs_local() {
+        let conn = setup_db();
+        conn.execute_batch(
+            "INSERT INTO repositories (id, owner, name) VALUES (1, 'acme', 'api');
+             INSERT INTO milestones (id, github_id, repo_id, title, state, due_on, open_issues, closed_issues)
+             VALUES (1, 1, 1, 'Cycle 1', 'open', '2024-07-01T00:00:00Z', 10, 2);
+             INSERT INTO issues (id, github_id, repo_id, number, title, state, milestone_id, created_at, updated_at)
+             VALUES
+                 (1, 1, 1, 1, 'Open one', 'OPEN', 1, '2024-06-01', '2024-06-01'),
+                 (2, 2, 1, 2, 'Closed one', 'CLOSED', 1, '2024-06-01', '2024-06-01'),
+                 (3, 3, 1, 3, 'Closed two', 'closed', 1, '2024-06-01', '2024-06-01'),
+                 (4, 4, 1, 4, 'No milestone', 'open', NULL, '2024-06-01', '2024-06-01');",
+        )
+        .unwrap();
+
+        let github = get_milestones_by_cycle(&conn, "github").unwrap();
+        assert_eq!((github[0].open_issues, github[0].closed_issues), (10, 2));
+
+        let local = get_milestones_by_cycle(&conn, "local").unwrap();
+        assert_eq!((local[0].open_issues, local[0].closed_issues), (1, 2));
+
+        let mut settings = get_settings(&conn).unwrap();
+        assert_eq!(settings.milestone_counts_source, "github");
+        settings.milestone_counts_source = "remote".to_string();
+        assert!(update_settings(&conn, &settings).is_err());
+    }
+
+    #[test]
+    fn test_utc_offset_setting_round_trips_and_is_bounded() {
+        let conn = setup_db();
+        let mut settings = get_settings(&conn).unwrap();
+        assert_eq!(settings.utc_offset_minutes, 0);
+
+        settings.utc_offset_minutes = -300;
+        update_settings(&conn, &settings).unwrap();
+        assert_eq!(get_settings(&conn).unwrap().utc_offset_minutes, -300);
+
+        settings.utc_offset_minutes = 900;
+        assert!(update_settings(&conn, &settings).is_err());
+    }
+
+    #[test]
+    fn test_bot_reviews_excluded_from_review_count() {
+        let conn = setup_db();
+        let author = insert_raw_user(&conn, 1, "author");
+        let reviewer = insert_raw_user(&conn, 2, "reviewer");
+        let scanner = insert_raw_user(&conn, 3, "codescan[bot]");
+        let repo_id = upsert_repository(&conn, "o", "r", None, true).unwrap();
+        let pr_id = upsert_pull_request(
+            &conn, 10, repo_id, 1, "Change", None, "OPEN", Some(author),
+            "2024-01-01T00:00:00Z", "2024-01-01T00:00:00Z", None, None,
+            1, 1, 1, &[], "2024-01-01T00:00:00Z",
+        )
+        .unwrap();
+        upsert_pr_review(&conn, 100, pr_id, Some(scanner), "COMMENTED", "2024-01-01T00:05:00Z", "2024-01-01T00:05:00Z").unwrap();
+        upsert_pr_review(&conn, 101, pr_id, Some(reviewer), "APPROVED", "2024-01-02T05:00:00Z", "2024-01-02T05:00:00Z").unwrap();
+
+        let bots = get_bot_reviewer_ids(&conn, &["dependabot".to_string()]).unwrap();
+        assert_eq!(get_pr_review_count(&conn, pr_id, true, &bots).unwrap(), 1);
+        assert_eq!(
+            get_first_review_time(&conn, pr_id, true, &bots).unwrap().as_deref(),
+            Some("2024-01-02T05:00:00Z")
+        );
+    }
+}
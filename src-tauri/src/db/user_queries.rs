@@ -1,8 +1,10 @@
 use super::models::User;
 use super::project_queries::TimelineEvent;
 use anyhow::Result;
+use chrono::Datelike;
 use rusqlite::{params, Connection};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 // ============================================================================
 // DATA MODELS FOR USER-CENTRIC VIEW
@@ -23,6 +25,7 @@ pub struct UserSummary {
     pub first_activity: Option<String>,
     pub last_activity: Option<String>,
     pub activity_status: String, // "active", "quiet", "idle"
+    pub metadata: BTreeMap<String, String>, // Manager-set annotations (role, location, ...)
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -97,9 +100,13 @@ pub fn get_user_summary_data(
         (None, None) => String::new(),
     };
 
+    let settings = super::queries::get_settings(conn)?;
     let review_query = format!(
-        "SELECT COUNT(*) FROM pr_reviews r WHERE r.reviewer_id = ?1{}",
-        review_date_filter
+        "SELECT COUNT(*) FROM pr_reviews r
+         JOIN pull_requests pr ON r.pr_id = pr.id
+         WHERE r.reviewer_id = ?1{}{}",
+        review_date_filter,
+        super::queries::self_review_filter(settings.exclude_self_reviews)
     );
     let total_prs_reviewed: i32 = conn.query_row(&review_query, params![user_id], |row| row.get(0))?;
 
@@ -195,9 +202,85 @@ pub fn get_user_summary_data(
         first_activity,
         last_activity,
         activity_status,
+        metadata: get_user_metadata(conn, user_id)?,
     })
 }
 
+// ============================================================================
+// USER METADATA
+// ============================================================================
+
+/// Set (or overwrite) a free-form annotation on a user
+pub fn set_user_metadata(conn: &Connection, user_id: i64, key: &str, value: &str) -> Result<()> {
+    conn.execute(
+        "INSERT INTO user_metadata (user_id, key, value, updated_at)
+         VALUES (?1, ?2, ?3, datetime('now'))
+         ON CONFLICT(user_id, key) DO UPDATE SET value = excluded.value, updated_at = excluded.updated_at",
+        params![user_id, key, value],
+    )?;
+    Ok(())
+}
+
+/// Remove an annotation from a user (returns whether it existed)
+pub fn delete_user_metadata(conn: &Connection, user_id: i64, key: &str) -> Result<bool> {
+    let deleted = conn.execute(
+        "DELETE FROM user_metadata WHERE user_id = ?1 AND key = ?2",
+        params![user_id, key],
+    )?;
+    Ok(deleted > 0)
+}
+
+/// All annotations on a user, keyed by name
+pub fn get_user_metadata(conn: &Connection, user_id: i64) -> Result<BTreeMap<String, String>> {
+    let mut stmt = conn.prepare("SELECT key, value FROM user_metadata WHERE user_id = ?1")?;
+    let metadata = stmt
+        .query_map(params![user_id], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<BTreeMap<_, _>, _>>()?;
+    Ok(metadata)
+}
+
+// ============================================================================
+// PROFILE ENRICHMENT
+// ============================================================================
+
+/// Non-bot users missing a name or avatar whose profile wasn't looked up in the last
+/// `refresh_days` days, as (id, login), never-looked-up users first
+pub fn get_users_missing_profile(conn: &Connection, refresh_days: i64, limit: i64) -> Result<Vec<(i64, String)>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, login
+         FROM users
+         WHERE COALESCE(is_bot, FALSE) = FALSE
+           AND (name IS NULL OR name = '' OR avatar_url IS NULL OR avatar_url = '')
+           AND (profile_fetched_at IS NULL OR profile_fetched_at < datetime('now', '-' || ?1 || ' days'))
+         ORDER BY profile_fetched_at IS NOT NULL, profile_fetched_at, id
+         LIMIT ?2",
+    )?;
+    let users = stmt
+        .query_map(params![refresh_days, limit], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(users)
+}
+
+/// Store profile fields fetched from GitHub; missing values keep what the row already has
+pub fn update_user_profile(
+    conn: &Connection,
+    user_id: i64,
+    name: Option<&str>,
+    avatar_url: Option<&str>,
+    company: Option<&str>,
+) -> Result<()> {
+    conn.execute(
+        "UPDATE users SET
+            name = COALESCE(NULLIF(?2, ''), name),
+            avatar_url = COALESCE(NULLIF(?3, ''), avatar_url),
+            company = COALESCE(NULLIF(?4, ''), company),
+            profile_fetched_at = datetime('now')
+         WHERE id = ?1",
+        params![user_id, name, avatar_url, company],
+    )?;
+    Ok(())
+}
+
 // ============================================================================
 // USER TIMELINE QUERIES
 // ============================================================================
@@ -412,6 +495,9 @@ pub fn get_user_repo_distribution(
         (None, None) => String::new(),
     };
 
+    let settings = super::queries::get_settings(conn)?;
+    let self_review_filter = super::queries::self_review_filter(settings.exclude_self_reviews);
+
     // Get all repositories the user has contributed to
     let repo_query = format!(
         "SELECT DISTINCT repo_id, owner, name FROM (
@@ -462,8 +548,8 @@ pub fn get_user_repo_distribution(
         let review_query = format!(
             "SELECT COUNT(*) FROM pr_reviews r
              JOIN pull_requests pr ON r.pr_id = pr.id
-             WHERE pr.repo_id = ?1 AND r.reviewer_id = ?2{}",
-            review_date_filter
+             WHERE pr.repo_id = ?1 AND r.reviewer_id = ?2{}{}",
+            review_date_filter, self_review_filter
         );
         let review_count: i32 = conn.query_row(&review_query, params![repo_id, user_id], |row| row.get(0))?;
 
@@ -821,3 +907,958 @@ pub fn get_user_focus_metrics(
         repos_distribution,
     })
 }
+
+// ============================================================================
+// SPECIALIZATION
+// ============================================================================
+
+/// Concentration index at or above which a contributor counts as a specialist
+pub const SPECIALIST_THRESHOLD: f64 = 0.5;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Specialization {
+    pub user_id: i64,
+    pub repos_touched: i32,
+    pub concentration_index: f64, // Herfindahl index of repo shares: 1.0 = one repo, 1/n = even across n
+    pub profile: String,          // "specialist", "generalist" or "inactive"
+}
+
+/// Get how concentrated a user's PRs, issues and reviews since `since` are across repositories
+pub fn get_specialization(conn: &Connection, user_id: i64, since: &str) -> Result<Specialization> {
+    let focus = get_user_focus_metrics(conn, user_id, Some(since), None)?;
+
+    let profile = if focus.repos_touched == 0 {
+        "inactive"
+    } else if focus.concentration_score >= SPECIALIST_THRESHOLD {
+        "specialist"
+    } else {
+        "generalist"
+    };
+
+    Ok(Specialization {
+        user_id,
+        repos_touched: focus.repos_touched,
+        concentration_index: focus.concentration_score,
+        profile: profile.to_string(),
+    })
+}
+
+// ============================================================================
+// ONBOARDING RAMP TIME
+// ============================================================================
+
+/// Minimum weeks of merged-PR history needed to judge ramp time
+pub const MIN_RAMP_WEEKS: usize = 6;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RampTime {
+    pub status: String, // "ramped", "not_ramped", "insufficient_data"
+    pub first_merged_at: Option<String>,
+    pub weeks_observed: i32,
+    pub median_weekly_merged: f64,
+    pub target_weekly_merged: f64,
+    pub ramp_weeks: Option<i32>, // weeks from first merged PR until target was first reached
+}
+
+/// Compute how long a user took to reach `target_fraction` of their eventual median
+/// weekly merged-PR count, starting from their first merged PR
+pub fn get_user_ramp_time(conn: &Connection, user_id: i64, target_fraction: f64) -> Result<RampTime> {
+    let mut stmt = conn.prepare(
+        "SELECT merged_at FROM pull_requests
+         WHERE author_id = ?1 AND merged_at IS NOT NULL
+         ORDER BY merged_at ASC",
+    )?;
+    let merged_at: Vec<String> = stmt
+        .query_map(params![user_id], |row| row.get(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+    let merged_dates: Vec<chrono::NaiveDate> = merged_at
+        .iter()
+        .filter_map(|date| crate::metrics::business_days::parse_date(date))
+        .collect();
+
+    let weekly_counts = weekly_merged_counts(&merged_dates);
+    let mut ramp = compute_ramp_time(&weekly_counts, target_fraction);
+    ramp.first_merged_at = merged_at.first().cloned();
+
+    Ok(ramp)
+}
+
+/// Bucket merge dates into consecutive weeks starting at the first merge (empty weeks count as 0)
+fn weekly_merged_counts(merged_dates: &[chrono::NaiveDate]) -> Vec<i32> {
+    let Some(first) = merged_dates.iter().min() else {
+        return Vec::new();
+    };
+
+    let mut counts: Vec<i32> = Vec::new();
+    for date in merged_dates {
+        let week = ((*date - *first).num_days() / 7) as usize;
+        if counts.len() <= week {
+            counts.resize(week + 1, 0);
+        }
+        counts[week] += 1;
+    }
+
+    counts
+}
+
+/// Find the first week whose count reaches `target_fraction` of the median weekly count
+fn compute_ramp_time(weekly_counts: &[i32], target_fraction: f64) -> RampTime {
+    let weeks_observed = weekly_counts.len() as i32;
+
+    let mut sorted = weekly_counts.to_vec();
+    sorted.sort_unstable();
+    let median = match sorted.len() {
+        0 => 0.0,
+        n if n % 2 == 0 => (sorted[n / 2 - 1] + sorted[n / 2]) as f64 / 2.0,
+        n => sorted[n / 2] as f64,
+    };
+    let target = median * target_fraction;
+
+    if weekly_counts.len() < MIN_RAMP_WEEKS || median <= 0.0 {
+        return RampTime {
+            status: "insufficient_data".to_string(),
+            first_merged_at: None,
+            weeks_observed,
+            median_weekly_merged: median,
+            target_weekly_merged: target,
+            ramp_weeks: None,
+        };
+    }
+
+    let ramp_weeks = weekly_counts
+        .iter()
+        .position(|&count| count as f64 >= target)
+        .map(|week| week as i32);
+
+    RampTime {
+        status: if ramp_weeks.is_some() { "ramped" } else { "not_ramped" }.to_string(),
+        first_merged_at: None,
+        weeks_observed,
+        median_weekly_merged: median,
+        target_weekly_merged: target,
+        ramp_weeks,
+    }
+}
+
+// ============================================================================
+// FIRST CONTRIBUTIONS
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FirstContribution {
+    pub user_id: i64,
+    pub login: String,
+    pub name: Option<String>,
+    pub avatar_url: Option<String>,
+    pub repo: String,
+    pub pr_number: i32,
+    pub pr_title: String,
+    pub pr_url: String,
+    pub merged_at: String,
+}
+
+/// Get users whose first-ever merged PR (across all synced data) merged in the period
+pub fn get_first_contributions(
+    conn: &Connection,
+    period: &super::metrics_queries::Period,
+    excluded_bots: &[String],
+) -> Result<Vec<FirstContribution>> {
+    let mut stmt = conn.prepare(
+        "WITH first_merges AS (
+             SELECT pr.*,
+                    ROW_NUMBER() OVER (PARTITION BY pr.author_id ORDER BY pr.merged_at ASC, pr.id ASC) as merge_rank
+             FROM pull_requests pr
+             WHERE pr.merged_at IS NOT NULL AND pr.author_id IS NOT NULL
+         )
+         SELECT u.id, u.login, u.name, u.avatar_url, r.owner || '/' || r.name,
+                fm.number, fm.title, fm.merged_at
+         FROM first_merges fm
+         JOIN users u ON fm.author_id = u.id
+         JOIN repositories r ON fm.repo_id = r.id
+         WHERE fm.merge_rank = 1
+           AND fm.merged_at >= ?1
+         ORDER BY fm.merged_at DESC",
+    )?;
+
+    let contributions = stmt
+        .query_map(params![period.since], |row| {
+            let repo: String = row.get(4)?;
+            let pr_number: i32 = row.get(5)?;
+            Ok(FirstContribution {
+                user_id: row.get(0)?,
+                login: row.get(1)?,
+                name: row.get(2)?,
+                avatar_url: row.get(3)?,
+                pr_url: format!("https://github.com/{}/pull/{}", repo, pr_number),
+                repo,
+                pr_number,
+                pr_title: row.get(6)?,
+                merged_at: row.get(7)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(contributions
+        .into_iter()
+        .filter(|c| !super::queries::is_bot_user(&c.login, excluded_bots))
+        .collect())
+}
+
+// ============================================================================
+// SHIPPING STREAKS
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShippingStreaks {
+    pub user_id: i64,
+    pub current_streak_weeks: i32,
+    pub longest_streak_weeks: i32,
+    pub last_shipped_week: Option<String>, // Monday (UTC) of the latest week with a merged PR
+}
+
+/// Get a user's current and longest runs of consecutive weeks with at least one merged PR.
+/// Weeks start on Monday (UTC), matching the weekly merge-rate rollup. The current streak
+/// still counts while this week has no merge yet, as long as last week had one.
+pub fn get_shipping_streaks(conn: &Connection, user_id: i64, today: chrono::NaiveDate) -> Result<ShippingStreaks> {
+    let mut stmt = conn.prepare(
+        "SELECT DISTINCT DATE(merged_at, 'weekday 0', '-6 days') as week
+         FROM pull_requests
+         WHERE author_id = ?1 AND merged_at IS NOT NULL
+         ORDER BY week ASC",
+    )?;
+    let weeks: Vec<chrono::NaiveDate> = stmt
+        .query_map(params![user_id], |row| row.get::<_, String>(0))?
+        .collect::<Result<Vec<_>, _>>()?
+        .iter()
+        .filter_map(|week| chrono::NaiveDate::parse_from_str(week, "%Y-%m-%d").ok())
+        .collect();
+
+    let current_week = today - chrono::Duration::days(today.weekday().num_days_from_monday() as i64);
+    let (current_streak_weeks, longest_streak_weeks) = compute_streaks(&weeks, current_week);
+
+    Ok(ShippingStreaks {
+        user_id,
+        current_streak_weeks,
+        longest_streak_weeks,
+        last_shipped_week: weeks.last().map(|week| week.to_string()),
+    })
+}
+
+/// (current, longest) streaks from sorted, de-duplicated week start dates
+fn compute_streaks(weeks: &[chrono::NaiveDate], current_week: chrono::NaiveDate) -> (i32, i32) {
+    let mut longest = 0;
+    let mut run = 0;
+    for (i, week) in weeks.iter().enumerate() {
+        run = if i > 0 && (*week - weeks[i - 1]).num_days() == 7 { run + 1 } else { 1 };
+        longest = longest.max(run);
+    }
+
+    let current = match weeks.last() {
+        Some(last) if (current_week - *last).num_days() <= 7 => run,
+        _ => 0,
+    };
+
+    (current, longest)
+}
+
+// ============================================================================
+// REVIEWER SUGGESTIONS
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReviewerSuggestion {
+    pub user_id: i64,
+    pub login: String,
+    pub name: Option<String>,
+    pub avatar_url: Option<String>,
+    pub overlapping_reviews: i32, // Past PRs reviewed that touched the same directories
+    pub repo_reviews: i32,        // All past PRs reviewed in the same repository
+}
+
+/// Directory part of a file path ("" for files at the repository root)
+fn path_dir(path: &str) -> &str {
+    path.rsplit_once('/').map(|(dir, _)| dir).unwrap_or("")
+}
+
+/// Suggest reviewers for a PR, ranked by how many past PRs touching the same directories
+/// they reviewed, then by overall reviews in the repository. Excludes the author and bots.
+pub fn suggest_reviewers(
+    conn: &Connection,
+    pr_id: i64,
+    limit: usize,
+    excluded_bots: &[String],
+) -> Result<Vec<ReviewerSuggestion>> {
+    use std::collections::{HashMap, HashSet};
+
+    let (repo_id, author_id): (i64, Option<i64>) = conn
+        .query_row(
+            "SELECT repo_id, author_id FROM pull_requests WHERE id = ?1",
+            params![pr_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .map_err(|_| anyhow::anyhow!("Pull request {} not found", pr_id))?;
+
+    let target_dirs: HashSet<String> = conn
+        .prepare("SELECT path FROM pr_files WHERE pr_id = ?1")?
+        .query_map(params![pr_id], |row| row.get::<_, String>(0))?
+        .collect::<Result<Vec<_>, _>>()?
+        .iter()
+        .map(|path| path_dir(path).to_string())
+        .collect();
+
+    // One row per (reviewer, reviewed PR) in this repository, with that PR's files
+    let mut stmt = conn.prepare(
+        "SELECT u.id, u.login, u.name, u.avatar_url, r.pr_id, f.path
+         FROM (SELECT DISTINCT r.reviewer_id, r.pr_id
+               FROM pr_reviews r
+               JOIN pull_requests pr ON r.pr_id = pr.id
+               WHERE pr.repo_id = ?1 AND r.pr_id != ?2) r
+         JOIN users u ON r.reviewer_id = u.id
+         LEFT JOIN pr_files f ON f.pr_id = r.pr_id
+         WHERE COALESCE(u.is_bot, FALSE) = FALSE
+           AND (?3 IS NULL OR u.id != ?3)",
+    )?;
+    let rows = stmt
+        .query_map(params![repo_id, pr_id, author_id], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, Option<String>>(2)?,
+                row.get::<_, Option<String>>(3)?,
+                row.get::<_, i64>(4)?,
+                row.get::<_, Option<String>>(5)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut candidates: HashMap<i64, ReviewerSuggestion> = HashMap::new();
+    let mut reviewed: HashSet<(i64, i64)> = HashSet::new();
+    let mut overlapping: HashSet<(i64, i64)> = HashSet::new();
+    for (user_id, login, name, avatar_url, reviewed_pr, path) in rows {
+        if login.ends_with("[bot]") || super::queries::is_bot_user(&login, excluded_bots) {
+            continue;
+        }
+
+        let candidate = candidates.entry(user_id).or_insert_with(|| ReviewerSuggestion {
+            user_id,
+            login,
+            name,
+            avatar_url,
+            overlapping_reviews: 0,
+            repo_reviews: 0,
+        });
+        if reviewed.insert((user_id, reviewed_pr)) {
+            candidate.repo_reviews += 1;
+        }
+        let touches_target = path.as_deref().is_some_and(|p| target_dirs.contains(path_dir(p)));
+        if touches_target && overlapping.insert((user_id, reviewed_pr)) {
+            candidate.overlapping_reviews += 1;
+        }
+    }
+
+    let mut suggestions: Vec<ReviewerSuggestion> = candidates.into_values().collect();
+    suggestions.sort_by(|a, b| {
+        b.overlapping_reviews
+            .cmp(&a.overlapping_reviews)
+            .then_with(|| b.repo_reviews.cmp(&a.repo_reviews))
+            .then_with(|| a.login.cmp(&b.login))
+    });
+    suggestions.truncate(limit);
+
+    Ok(suggestions)
+}
+
+// ============================================================================
+// REVIEW-ONLY CONTRIBUTORS
+// ============================================================================
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReviewOnlyContributor {
+    pub user_id: i64,
+    pub login: String,
+    pub name: Option<String>,
+    pub avatar_url: Option<String>,
+    pub reviews: i32,      // Reviews submitted in the window
+    pub prs_reviewed: i32, // Distinct PRs those reviews covered
+}
+
+/// Get tracked users who reviewed others' PRs since `since` but authored none in that window
+pub fn get_review_only_contributors(conn: &Connection, since: &str) -> Result<Vec<ReviewOnlyContributor>> {
+    let mut stmt = conn.prepare(
+        "SELECT u.id, u.login, u.name, u.avatar_url, COUNT(*), COUNT(DISTINCT r.pr_id)
+         FROM pr_reviews r
+         JOIN users u ON r.reviewer_id = u.id
+         JOIN pull_requests pr ON r.pr_id = pr.id
+         WHERE u.tracked = TRUE
+           AND r.submitted_at >= ?1
+           AND COALESCE(pr.author_id, -1) != u.id
+           AND NOT EXISTS (
+               SELECT 1 FROM pull_requests authored
+               WHERE authored.author_id = u.id AND authored.created_at >= ?1
+           )
+         GROUP BY u.id
+         ORDER BY COUNT(*) DESC, u.login ASC",
+    )?;
+
+    let contributors = stmt
+        .query_map(params![since], |row| {
+            Ok(ReviewOnlyContributor {
+                user_id: row.get(0)?,
+                login: row.get(1)?,
+                name: row.get(2)?,
+                avatar_url: row.get(3)?,
+                reviews: row.get(4)?,
+                prs_reviewed: row.get(5)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(contributors)
+}
+
+// ============================================================================
+// INACTIVE USERS
+// ============================================================================
+
+/// Drop tracked users with no PRs, reviews, issues or commits since `since`.
+/// Untracked users are kept as-is.
+pub fn hide_inactive_users(conn: &Connection, users: Vec<User>, since: &str) -> Result<Vec<User>> {
+    let mut stmt = conn.prepare(
+        "SELECT author_id FROM pull_requests WHERE created_at >= ?1
+         UNION SELECT reviewer_id FROM pr_reviews WHERE submitted_at >= ?1
+         UNION SELECT author_id FROM issues WHERE created_at >= ?1
+         UNION SELECT author_id FROM commits WHERE committed_at >= ?1",
+    )?;
+    let active = stmt
+        .query_map(params![since], |row| row.get::<_, Option<i64>>(0))?
+        .collect::<Result<std::collections::HashSet<_>, _>>()?;
+
+    Ok(users
+        .into_iter()
+        .filter(|user| !user.tracked || active.contains(&Some(user.id)))
+        .collect())
+}
+
+// ============================================================================
+// DECLINING CONTRIBUTORS
+// ============================================================================
+
+/// Minimum weeks of activity history (from a user's first active week in the window)
+pub const MIN_DECLINE_WEEKS: usize = 4;
+
+/// Weekly trend, relative to the user's average week, at or below which they are flagged.
+/// -0.1 means losing about a tenth of an average week's activity every week.
+pub const DECLINE_THRESHOLD: f64 = -0.1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DecliningContributor {
+    pub user_id: i64,
+    pub login: String,
+    pub name: Option<String>,
+    pub avatar_url: Option<String>,
+    pub weekly_activity: Vec<i32>, // PRs opened + reviews submitted per week, oldest first
+    pub slope: f64,                // Change in weekly activity per week
+    pub relative_slope: f64,       // slope / average weekly activity
+}
+
+/// Get tracked users whose weekly activity (PRs opened plus reviews submitted) has trended
+/// down since `since`, steepest decline first. Only full weeks before `now` are counted, and
+/// users with fewer than MIN_DECLINE_WEEKS weeks of history are left out.
+pub fn get_declining_contributors(
+    conn: &Connection,
+    since: &str,
+    now: chrono::DateTime<chrono::Utc>,
+) -> Result<Vec<DecliningContributor>> {
+    let Some(start) = crate::metrics::business_days::parse_date(since) else {
+        return Ok(Vec::new());
+    };
+    let total_weeks = ((now.date_naive() - start).num_days() / 7).max(0) as usize;
+
+    let mut stmt = conn.prepare(
+        "SELECT u.id, u.login, u.name, u.avatar_url, activity.at
+         FROM (
+             SELECT author_id as user_id, created_at as at FROM pull_requests WHERE created_at >= ?1
+             UNION ALL
+             SELECT reviewer_id, submitted_at FROM pr_reviews WHERE submitted_at >= ?1
+         ) activity
+         JOIN users u ON activity.user_id = u.id
+         WHERE u.tracked = TRUE
+         ORDER BY u.id",
+    )?;
+    let rows = stmt
+        .query_map(params![since], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, Option<String>>(2)?,
+                row.get::<_, Option<String>>(3)?,
+                row.get::<_, String>(4)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut contributors: Vec<DecliningContributor> = Vec::new();
+    for (user_id, login, name, avatar_url, at) in rows {
+        let Some(date) = crate::metrics::business_days::parse_date(&at) else {
+            continue;
+        };
+        let week = ((date - start).num_days() / 7) as usize;
+        if week >= total_weeks {
+            continue; // Current, partial week
+        }
+
+        if contributors.last().map(|c| c.user_id) != Some(user_id) {
+            contributors.push(DecliningContributor {
+                user_id,
+                login,
+                name,
+                avatar_url,
+                weekly_activity: vec![0; total_weeks],
+                slope: 0.0,
+                relative_slope: 0.0,
+            });
+        }
+        if let Some(contributor) = contributors.last_mut() {
+            contributor.weekly_activity[week] += 1;
+        }
+    }
+
+    let mut declining: Vec<DecliningContributor> = contributors
+        .into_iter()
+        .filter_map(|mut contributor| {
+            // History starts at the user's first active week, so newcomers aren't judged on empty weeks
+            let first_active = contributor.weekly_activity.iter().position(|&count| count > 0)?;
+            contributor.weekly_activity.drain(..first_active);
+            if contributor.weekly_activity.len() < MIN_DECLINE_WEEKS {
+                return None;
+            }
+
+            let ys: Vec<f64> = contributor.weekly_activity.iter().map(|&c| c as f64).collect();
+            let mean = ys.iter().sum::<f64>() / ys.len() as f64;
+            contributor.slope = trend_slope(&ys);
+            contributor.relative_slope = contributor.slope / mean;
+            (contributor.relative_slope <= DECLINE_THRESHOLD).then_some(contributor)
+        })
+        .collect();
+
+    declining.sort_by(|a, b| {
+        a.relative_slope
+            .partial_cmp(&b.relative_slope)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a.login.cmp(&b.login))
+    });
+
+    Ok(declining)
+}
+
+/// Least-squares slope of a series against its index
+fn trend_slope(ys: &[f64]) -> f64 {
+    let n = ys.len() as f64;
+    if ys.len() < 2 {
+        return 0.0;
+    }
+    let mean_x = (n - 1.0) / 2.0;
+    let mean_y = ys.iter().sum::<f64>() / n;
+    let (mut cov, mut var) = (0.0, 0.0);
+    for (x, y) in ys.iter().enumerate() {
+        let dx = x as f64 - mean_x;
+        cov += dx * (y - mean_y);
+        var += dx * dx;
+    }
+    cov / var
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ramping_series_reaches_target() {
+        // Median of the series is 4; 75% of that is 3, first reached in week 3
+        let weekly = [1, 1, 2, 3, 4, 4, 5, 4, 5, 4];
+        let ramp = compute_ramp_time(&weekly, 0.75);
+
+        assert_eq!(ramp.status, "ramped");
+        assert_eq!(ramp.median_weekly_merged, 4.0);
+        assert_eq!(ramp.ramp_weeks, Some(3));
+
+        // A stricter target pushes the ramp week out
+        let strict = compute_ramp_time(&weekly, 1.0);
+        assert_eq!(strict.ramp_weeks, Some(4));
+    }
+
+    #[test]
+    fn test_short_history_is_insufficient_data() {
+        let ramp = compute_ramp_time(&[1, 2, 3], 0.75);
+        assert_eq!(ramp.status, "insufficient_data");
+        assert_eq!(ramp.ramp_weeks, None);
+
+        let sparse = compute_ramp_time(&[1, 0, 0, 0, 0, 0, 0, 1], 0.75);
+        assert_eq!(sparse.status, "insufficient_data");
+    }
+
+    #[test]
+    fn test_ramp_time_from_merged_prs() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::db::migrations::run_migrations(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO repositories (id, owner, name) VALUES (1, 'acme', 'api');
+             INSERT INTO users (id, github_id, login) VALUES (1, 101, 'newbie');",
+        )
+        .unwrap();
+
+        // Weekly merged counts: 1, 1, 2, 3, 3, 3, 3 (median 3)
+        let weekly = [1, 1, 2, 3, 3, 3, 3];
+        let start = chrono::NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let mut number = 0;
+        for (week, &count) in weekly.iter().enumerate() {
+            for day in 0..count {
+                number += 1;
+                let merged = start + chrono::Duration::days(week as i64 * 7 + day as i64);
+                let merged_at = format!("{}T12:00:00Z", merged);
+                conn.execute(
+                    "INSERT INTO pull_requests (github_id, repo_id, number, title, state, author_id,
+                                                created_at, updated_at, merged_at)
+                     VALUES (?1, 1, ?1, 'PR', 'MERGED', 1, ?2, ?2, ?2)",
+                    params![number, merged_at],
+                )
+                .unwrap();
+            }
+        }
+
+        let ramp = get_user_ramp_time(&conn, 1, 0.6).unwrap();
+        assert_eq!(ramp.weeks_observed, 7);
+        assert_eq!(ramp.ramp_weeks, Some(2));
+        assert_eq!(ramp.first_merged_at.as_deref(), Some("2024-01-01T12:00:00Z"));
+    }
+
+    #[test]
+    fn test_first_contributions_flags_only_new_contributors() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::db::migrations::run_migrations(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO repositories (id, owner, name) VALUES (1, 'acme', 'api');
+             INSERT INTO users (id, github_id, login) VALUES (1, 101, 'newbie'), (2, 102, 'veteran');",
+        )
+        .unwrap();
+
+        for (number, author_id, merged_days_ago) in [(1, 1, 2), (2, 2, 200), (3, 2, 1)] {
+            conn.execute(
+                "INSERT INTO pull_requests (github_id, repo_id, number, title, state, author_id,
+                                            created_at, updated_at, merged_at)
+                 VALUES (?1, 1, ?1, 'PR', 'MERGED', ?2, datetime('now', '-' || ?3 || ' days'),
+                         datetime('now', '-' || ?3 || ' days'), datetime('now', '-' || ?3 || ' days'))",
+                params![number, author_id, merged_days_ago],
+            )
+            .unwrap();
+        }
+
+        let firsts = get_first_contributions(&conn, &super::metrics_queries::Period::new(30, "rolling", chrono::Utc::now()), &[]).unwrap();
+        assert_eq!(firsts.len(), 1);
+        assert_eq!(firsts[0].login, "newbie");
+        assert_eq!(firsts[0].pr_number, 1);
+        assert_eq!(firsts[0].pr_url, "https://github.com/acme/api/pull/1");
+    }
+
+    #[test]
+    fn test_suggest_reviewers_prefers_path_history() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::db::migrations::run_migrations(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO repositories (id, owner, name) VALUES (1, 'acme', 'api');
+             INSERT INTO users (id, github_id, login, is_bot) VALUES
+                 (1, 101, 'author', FALSE), (2, 102, 'expert', FALSE), (3, 103, 'generalist', FALSE),
+                 (4, 104, 'review-helper[bot]', TRUE);
+             INSERT INTO pull_requests (id, github_id, repo_id, number, title, state, author_id, created_at, updated_at)
+             VALUES
+                 (1, 1, 1, 1, 'Auth tweak', 'MERGED', 1, '2024-01-01T00:00:00Z', '2024-01-01T00:00:00Z'),
+                 (2, 2, 1, 2, 'Docs', 'MERGED', 2, '2024-01-02T00:00:00Z', '2024-01-02T00:00:00Z'),
+                 (3, 3, 1, 3, 'Docs again', 'MERGED', 2, '2024-01-03T00:00:00Z', '2024-01-03T00:00:00Z'),
+                 (4, 4, 1, 4, 'New auth flow', 'OPEN', 1, '2024-02-01T00:00:00Z', '2024-02-01T00:00:00Z');
+             INSERT INTO pr_files (pr_id, path) VALUES
+                 (1, 'src/auth/login.rs'), (2, 'docs/setup.md'), (3, 'docs/faq.md'),
+                 (4, 'src/auth/session.rs');
+             INSERT INTO pr_reviews (github_id, pr_id, reviewer_id, state, submitted_at) VALUES
+                 (11, 1, 2, 'APPROVED', '2024-01-01T01:00:00Z'),
+                 (12, 1, 4, 'COMMENTED', '2024-01-01T01:00:00Z'),
+                 (13, 2, 3, 'APPROVED', '2024-01-02T01:00:00Z'),
+                 (14, 3, 3, 'APPROVED', '2024-01-03T01:00:00Z'),
+                 (15, 2, 1, 'APPROVED', '2024-01-02T01:00:00Z');",
+        )
+        .unwrap();
+
+        let suggestions = suggest_reviewers(&conn, 4, 5, &[]).unwrap();
+        let logins: Vec<&str> = suggestions.iter().map(|s| s.login.as_str()).collect();
+        assert_eq!(logins, vec!["expert", "generalist"]);
+        assert_eq!(suggestions[0].overlapping_reviews, 1);
+        assert_eq!(suggestions[1].overlapping_reviews, 0);
+        assert_eq!(suggestions[1].repo_reviews, 2);
+
+        assert!(suggest_reviewers(&conn, 99, 5, &[]).is_err());
+    }
+
+    #[test]
+    fn test_shipping_streaks_with_gap() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::db::migrations::run_migrations(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO repositories (id, owner, name) VALUES (1, 'acme', 'api');
+             INSERT INTO users (id, github_id, login) VALUES (1, 101, 'shipper');",
+        )
+        .unwrap();
+
+        // Weeks starting Mon 2024-01-01: shipped in weeks 0-3, gap in week 4, then weeks 5-6.
+        // Week 1 has two merges (Mon and Sun) which must count once.
+        let merges = [
+            "2024-01-01T09:00:00Z", "2024-01-08T09:00:00Z", "2024-01-14T23:00:00Z",
+            "2024-01-15T09:00:00Z", "2024-01-22T09:00:00Z",
+            "2024-02-05T09:00:00Z", "2024-02-12T09:00:00Z",
+        ];
+        for (number, merged_at) in merges.iter().enumerate() {
+            conn.execute(
+                "INSERT INTO pull_requests (github_id, repo_id, number, title, state, author_id, created_at, updated_at, merged_at)
+                 VALUES (?1, 1, ?1, 'PR', 'MERGED', 1, ?2, ?2, ?2)",
+                params![number as i64 + 1, merged_at],
+            )
+            .unwrap();
+        }
+
+        // Wednesday of the week after the last merge: streak is still alive
+        let today = chrono::NaiveDate::from_ymd_opt(2024, 2, 21).unwrap();
+        let streaks = get_shipping_streaks(&conn, 1, today).unwrap();
+        assert_eq!(streaks.longest_streak_weeks, 4);
+        assert_eq!(streaks.current_streak_weeks, 2);
+        assert_eq!(streaks.last_shipped_week.as_deref(), Some("2024-02-12"));
+
+        // A full week without a merge breaks the current streak
+        let later = chrono::NaiveDate::from_ymd_opt(2024, 2, 28).unwrap();
+        let streaks = get_shipping_streaks(&conn, 1, later).unwrap();
+        assert_eq!(streaks.current_streak_weeks, 0);
+        assert_eq!(streaks.longest_streak_weeks, 4);
+    }
+
+    #[test]
+    fn test_review_only_contributors() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::db::migrations::run_migrations(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO repositories (id, owner, name) VALUES (1, 'acme', 'api');
+             INSERT INTO users (id, github_id, login, tracked) VALUES
+                 (1, 101, 'staff', 1), (2, 102, 'mixed', 1), (3, 103, 'untracked', 0), (4, 104, 'author', 1);
+             INSERT INTO pull_requests (id, github_id, repo_id, number, title, state, author_id, created_at, updated_at, labels) VALUES
+                 (1, 1, 1, 1, 'PR', 'OPEN', 4, '2024-03-01T00:00:00Z', '2024-03-01T00:00:00Z', '[]'),
+                 (2, 2, 1, 2, 'PR', 'OPEN', 2, '2024-03-02T00:00:00Z', '2024-03-02T00:00:00Z', '[]'),
+                 (3, 3, 1, 3, 'Old PR', 'MERGED', 1, '2023-06-01T00:00:00Z', '2023-06-01T00:00:00Z', '[]');
+             INSERT INTO pr_reviews (github_id, pr_id, reviewer_id, state, submitted_at) VALUES
+                 (11, 1, 1, 'COMMENTED', '2024-03-01T10:00:00Z'),
+                 (12, 1, 1, 'APPROVED', '2024-03-01T12:00:00Z'),
+                 (13, 2, 1, 'APPROVED', '2024-03-02T10:00:00Z'),
+                 (14, 1, 2, 'APPROVED', '2024-03-01T11:00:00Z'),
+                 (15, 1, 3, 'APPROVED', '2024-03-01T11:00:00Z');",
+        )
+        .unwrap();
+
+        // 'staff' only authored before the window; 'mixed' authored PR 2 inside it
+        let contributors = get_review_only_contributors(&conn, "2024-01-01T00:00:00Z").unwrap();
+        assert_eq!(contributors.len(), 1);
+        assert_eq!(contributors[0].login, "staff");
+        assert_eq!((contributors[0].reviews, contributors[0].prs_reviewed), (3, 2));
+
+        // Over all time 'staff' authored too
+        assert!(get_review_only_contributors(&conn, "2023-01-01T00:00:00Z").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_hide_inactive_users_drops_tracked_users_without_activity() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::db::migrations::run_migrations(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO repositories (id, owner, name) VALUES (1, 'acme', 'api');
+             INSERT INTO users (id, github_id, login, tracked) VALUES (1, 101, 'active', 1), (2, 102, 'idle', 1);
+             INSERT INTO pull_requests (github_id, repo_id, number, title, state, author_id, created_at, updated_at, labels)
+             VALUES (1, 1, 1, 'PR', 'OPEN', 1, '2024-03-10T00:00:00Z', '2024-03-10T00:00:00Z', '[]');
+             INSERT INTO issues (github_id, repo_id, number, title, state, author_id, created_at, updated_at, labels)
+             VALUES (2, 1, 2, 'Old issue', 'OPEN', 2, '2024-01-01T00:00:00Z', '2024-01-01T00:00:00Z', '[]');",
+        )
+        .unwrap();
+
+        let user = |id: i64, login: &str| User {
+            id,
+            github_id: 100 + id,
+            login: login.to_string(),
+            name: None,
+            avatar_url: None,
+            is_bot: false,
+            tracked: true,
+            tracked_at: None,
+        };
+        let users = vec![user(1, "active"), user(2, "idle")];
+
+        let visible = hide_inactive_users(&conn, users.clone(), "2024-03-01T00:00:00Z").unwrap();
+        let logins: Vec<_> = visible.iter().map(|u| u.login.as_str()).collect();
+        assert_eq!(logins, vec!["active"]);
+
+        // The idle user's issue counts once the window reaches back to it
+        let visible = hide_inactive_users(&conn, users, "2023-12-01T00:00:00Z").unwrap();
+        assert_eq!(visible.len(), 2);
+    }
+
+    #[test]
+    fn test_declining_contributors_flags_only_declining_user() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::db::migrations::run_migrations(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO repositories (id, owner, name) VALUES (1, 'acme', 'api');
+             INSERT INTO users (id, github_id, login, tracked) VALUES
+                 (1, 101, 'fading', 1), (2, 102, 'steady', 1), (3, 103, 'newcomer', 1);",
+        )
+        .unwrap();
+
+        // Eight full weeks from Mon 2024-01-01; 'newcomer' only shows up in the last two
+        let fading = [6, 5, 5, 4, 3, 2, 1, 1];
+        let steady = [3, 2, 3, 3, 2, 3, 3, 2];
+        let newcomer = [0, 0, 0, 0, 0, 0, 4, 1];
+        let start = chrono::NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let mut number = 0;
+        for (author_id, weekly) in [(1, fading), (2, steady), (3, newcomer)] {
+            for (week, &count) in weekly.iter().enumerate() {
+                for _ in 0..count {
+                    number += 1;
+                    let created = start + chrono::Duration::days(week as i64 * 7 + 1);
+                    let created_at = format!("{}T12:00:00Z", created);
+                    conn.execute(
+                        "INSERT INTO pull_requests (github_id, repo_id, number, title, state, author_id, created_at, updated_at, labels)
+                         VALUES (?1, 1, ?1, 'PR', 'OPEN', ?2, ?3, ?3, '[]')",
+                        params![number, author_id, created_at],
+                    )
+                    .unwrap();
+                }
+            }
+        }
+
+        // Wednesday of week 8: the partial current week is ignored
+        let now = chrono::DateTime::parse_from_rfc3339("2024-02-28T12:00:00Z").unwrap().with_timezone(&chrono::Utc);
+        let declining = get_declining_contributors(&conn, "2024-01-01T00:00:00Z", now).unwrap();
+
+        assert_eq!(declining.len(), 1);
+        assert_eq!(declining[0].login, "fading");
+        assert_eq!(declining[0].weekly_activity, fading.to_vec());
+        assert!(declining[0].slope < 0.0);
+        assert!(declining[0].relative_slope <= DECLINE_THRESHOLD);
+    }
+
+    #[test]
+    fn test_trend_slope() {
+        assert_eq!(trend_slope(&[1.0, 2.0, 3.0, 4.0]), 1.0);
+        assert_eq!(trend_slope(&[3.0, 3.0, 3.0]), 0.0);
+        assert_eq!(trend_slope(&[5.0]), 0.0);
+    }
+
+    #[test]
+    fn test_user_metadata_round_trip() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::db::migrations::run_migrations(&conn).unwrap();
+        conn.execute_batch("INSERT INTO users (id, github_id, login) VALUES (1, 101, 'alice'), (2, 102, 'bob');")
+            .unwrap();
+
+        set_user_metadata(&conn, 1, "role", "Backend").unwrap();
+        set_user_metadata(&conn, 1, "location", "Berlin").unwrap();
+        set_user_metadata(&conn, 1, "manager", "carol").unwrap();
+        set_user_metadata(&conn, 1, "role", "Staff Engineer").unwrap();
+        set_user_metadata(&conn, 2, "role", "Frontend").unwrap();
+
+        let metadata = get_user_metadata(&conn, 1).unwrap();
+        assert_eq!(metadata.len(), 3);
+        assert_eq!(metadata["role"], "Staff Engineer");
+        assert_eq!(metadata["location"], "Berlin");
+        assert_eq!(metadata["manager"], "carol");
+
+        assert!(delete_user_metadata(&conn, 1, "manager").unwrap());
+        assert!(!delete_user_metadata(&conn, 1, "manager").unwrap());
+
+        // Annotations come back with the user summary
+        let summary = get_user_summary_data(&conn, 1, None, None).unwrap();
+        let keys: Vec<&str> = summary.metadata.keys().map(|k| k.as_str()).collect();
+        assert_eq!(keys, vec!["location", "role"]);
+        assert_eq!(get_user_metadata(&conn, 2).unwrap()["role"], "Frontend");
+    }
+
+    #[test]
+    fn test_specialization_index_single_repo_vs_spread() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::db::migrations::run_migrations(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO repositories (id, owner, name) VALUES
+                 (1, 'acme', 'api'), (2, 'acme', 'web'), (3, 'acme', 'cli'), (4, 'acme', 'docs');
+             INSERT INTO users (id, github_id, login) VALUES (1, 101, 'specialist'), (2, 102, 'generalist'), (3, 103, 'idle');",
+        )
+        .unwrap();
+
+        // specialist: 4 PRs in api; generalist: 1 PR in each of the 4 repos
+        let prs = [(1, 1), (1, 1), (1, 1), (1, 1), (2, 1), (2, 2), (2, 3), (2, 4)];
+        for (number, (author_id, repo_id)) in prs.iter().enumerate() {
+            conn.execute(
+                "INSERT INTO pull_requests (github_id, repo_id, number, title, state, author_id, created_at, updated_at, labels)
+                 VALUES (?1, ?2, ?1, 'PR', 'OPEN', ?3, '2024-03-10T00:00:00Z', '2024-03-10T00:00:00Z', '[]')",
+                params![number as i64 + 1, repo_id, author_id],
+            )
+            .unwrap();
+        }
+
+        let since = "2024-03-01T00:00:00Z";
+        let specialist = get_specialization(&conn, 1, since).unwrap();
+        assert_eq!(specialist.repos_touched, 1);
+        assert!((specialist.concentration_index - 1.0).abs() < 1e-9);
+        assert_eq!(specialist.profile, "specialist");
+
+        let generalist = get_specialization(&conn, 2, since).unwrap();
+        assert_eq!(generalist.repos_touched, 4);
+        assert!((generalist.concentration_index - 0.25).abs() < 1e-9);
+        assert_eq!(generalist.profile, "generalist");
+
+        let idle = get_specialization(&conn, 3, since).unwrap();
+        assert_eq!((idle.profile.as_str(), idle.concentration_index), ("inactive", 0.0));
+    }
+
+    #[test]
+    fn test_profile_enrichment_fills_missing_fields_once() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::db::migrations::run_migrations(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO users (id, github_id, login, name, avatar_url, is_bot) VALUES
+                 (1, 101, 'alice', NULL, NULL, FALSE),
+                 (2, 102, 'bob', 'Bob Jones', 'https://avatars/bob', FALSE),
+                 (3, 103, 'renovate[bot]', NULL, NULL, TRUE),
+                 (4, 104, 'carol', 'Carol', NULL, FALSE);",
+        )
+        .unwrap();
+
+        assert_eq!(
+            get_users_missing_profile(&conn, 30, 10).unwrap(),
+            vec![(1, "alice".to_string()), (4, "carol".to_string())]
+        );
+        assert_eq!(get_users_missing_profile(&conn, 30, 1).unwrap().len(), 1);
+
+        update_user_profile(&conn, 1, Some("Alice Smith"), Some("https://avatars/alice"), Some("Acme")).unwrap();
+        // A profile without a display name leaves the stored one alone
+        update_user_profile(&conn, 4, None, Some("https://avatars/carol"), None).unwrap();
+
+        let (name, avatar, company): (Option<String>, Option<String>, Option<String>) = conn
+            .query_row("SELECT name, avatar_url, company FROM users WHERE id = 1", [], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+            })
+            .unwrap();
+        assert_eq!(name.as_deref(), Some("Alice Smith"));
+        assert_eq!(avatar.as_deref(), Some("https://avatars/alice"));
+        assert_eq!(company.as_deref(), Some("Acme"));
+        let carol: Option<String> = conn.query_row("SELECT name FROM users WHERE id = 4", [], |row| row.get(0)).unwrap();
+        assert_eq!(carol.as_deref(), Some("Carol"));
+
+        assert!(get_users_missing_profile(&conn, 30, 10).unwrap().is_empty());
+
+        // Looked up but still incomplete: retried once the cache expires
+        conn.execute("UPDATE users SET avatar_url = NULL, profile_fetched_at = datetime('now', '-40 days') WHERE id = 4", [])
+            .unwrap();
+        assert_eq!(get_users_missing_profile(&conn, 30, 10).unwrap(), vec![(4, "carol".to_string())]);
+    }
+}
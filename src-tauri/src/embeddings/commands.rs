@@ -0,0 +1,198 @@
+use super::{generate_embeddings, generator, ACTIVE_EMBEDDING_MODEL};
+use crate::db::{queries, AppState};
+use anyhow::Result;
+use rusqlite::Connection;
+use serde::Serialize;
+use std::sync::{Mutex, MutexGuard};
+use tauri::State;
+
+/// Stored embeddings that were produced by a model other than the active one
+#[derive(Debug, Clone, Serialize)]
+pub struct StaleEmbeddingCount {
+    pub active_model: String,
+    pub issues: i64,
+    pub pull_requests: i64,
+    pub total: i64,
+}
+
+/// Count embeddings that are incomparable with the active model's vectors
+#[tauri::command]
+pub async fn get_stale_embedding_count(
+    state: State<'_, AppState>,
+) -> Result<StaleEmbeddingCount, String> {
+    let conn = state.sqlite.lock().map_err(|e| e.to_string())?;
+
+    let (issues, pull_requests) = queries::count_stale_embeddings(&conn, ACTIVE_EMBEDDING_MODEL)
+        .map_err(|e| e.to_string())?;
+
+    Ok(StaleEmbeddingCount {
+        active_model: ACTIVE_EMBEDDING_MODEL.to_string(),
+        issues,
+        pull_requests,
+        total: issues + pull_requests,
+    })
+}
+
+/// Regenerate only the stale embeddings with the active model; returns how many were replaced
+#[tauri::command]
+pub async fn reembed_stale(state: State<'_, AppState>) -> Result<usize, String> {
+    reembed_stale_items(&state.sqlite, ACTIVE_EMBEDDING_MODEL, generate_embeddings)
+        .map_err(|e| e.to_string())
+}
+
+/// Re-embed stale issues and PRs in batches using `embed`, tagging the results with `model`.
+/// The connection is only locked to read a batch and to store its vectors, never while embedding.
+pub fn reembed_stale_items<F>(db: &Mutex<Connection>, model: &str, embed: F) -> Result<usize>
+where
+    F: Fn(&[String]) -> Result<Vec<Vec<f32>>>,
+{
+    const BATCH_SIZE: i64 = 50;
+    let mut reembedded = 0;
+
+    for table in ["issues", "pull_requests"] {
+        loop {
+            let mut ids = Vec::new();
+            let mut texts = Vec::new();
+            {
+                let conn = lock(db)?;
+                let items = queries::get_stale_embedding_items(&conn, table, model, BATCH_SIZE)?;
+                if items.is_empty() {
+                    break;
+                }
+
+                // Items with too little text drop their old vector instead of being re-embedded
+                for (id, title, body) in &items {
+                    let text = match table {
+                        "issues" => generator::prepare_issue_text(title, body),
+                        _ => generator::prepare_pr_text(title, body),
+                    };
+                    match text {
+                        Some(text) => {
+                            ids.push(*id);
+                            texts.push(text);
+                        }
+                        None => queries::mark_embedding_skipped(&conn, table, *id)?,
+                    }
+                }
+            }
+            if texts.is_empty() {
+                continue;
+            }
+
+            let embeddings = embed(&texts)?;
+            if embeddings.len() != texts.len() {
+                return Err(anyhow::anyhow!(
+                    "Expected {} embeddings but got {}",
+                    texts.len(),
+                    embeddings.len()
+                ));
+            }
+
+            let conn = lock(db)?;
+            for (id, embedding) in ids.iter().zip(embeddings.iter()) {
+                match table {
+                    "issues" => queries::set_issue_embedding(&conn, *id, embedding, model)?,
+                    _ => queries::set_pr_embedding(&conn, *id, embedding, model)?,
+                }
+            }
+            reembedded += ids.len();
+        }
+    }
+
+    tracing::info!("Re-embedded {} stale items with {}", reembedded, model);
+    Ok(reembedded)
+}
+
+fn lock(db: &Mutex<Connection>) -> Result<MutexGuard<'_, Connection>> {
+    db.lock().map_err(|e| anyhow::anyhow!(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rusqlite::params;
+
+    fn setup_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::db::migrations::run_migrations(&conn).unwrap();
+        conn.execute("INSERT INTO repositories (id, owner, name) VALUES (1, 'acme', 'api')", [])
+            .unwrap();
+
+        let old_vector: Vec<u8> = [0.5f32; 4].iter().flat_map(|f| f.to_le_bytes()).collect();
+        for (id, model) in [(1, Some(ACTIVE_EMBEDDING_MODEL)), (2, Some("old-model")), (3, None)] {
+            conn.execute(
+                "INSERT INTO issues (id, github_id, repo_id, number, title, state, created_at, updated_at,
+                                     labels, embedding, embedding_model)
+                 VALUES (?1, ?1, 1, ?1, 'Login page crashes', 'OPEN', '2024-01-01T00:00:00Z', '2024-01-01T00:00:00Z', '[]', ?2, ?3)",
+                params![id, old_vector, model],
+            )
+            .unwrap();
+        }
+        for (id, model) in [(1, Some(ACTIVE_EMBEDDING_MODEL)), (2, Some("old-model"))] {
+            conn.execute(
+                "INSERT INTO pull_requests (id, github_id, repo_id, number, title, state, created_at, updated_at,
+                                            labels, embedding, embedding_model)
+                 VALUES (?1, ?1, 1, ?1, 'Fix login redirect', 'OPEN', '2024-01-01T00:00:00Z', '2024-01-01T00:00:00Z', '[]', ?2, ?3)",
+                params![id, old_vector, model],
+            )
+            .unwrap();
+        }
+        // Items without an embedding are never stale
+        conn.execute(
+            "INSERT INTO issues (id, github_id, repo_id, number, title, state, created_at, updated_at, labels)
+             VALUES (4, 4, 1, 4, 'Issue', 'OPEN', '2024-01-01T00:00:00Z', '2024-01-01T00:00:00Z', '[]')",
+            [],
+        )
+        .unwrap();
+
+        conn
+    }
+
+    #[test]
+    fn test_stale_embeddings_are_counted_and_reembedded_selectively() {
+        let db = Mutex::new(setup_db());
+        assert_eq!(queries::count_stale_embeddings(&db.lock().unwrap(), ACTIVE_EMBEDDING_MODEL).unwrap(), (2, 1));
+
+        let reembedded = reembed_stale_items(&db, ACTIVE_EMBEDDING_MODEL, |texts: &[String]| {
+            // The connection stays available to other commands while embedding
+            assert!(db.try_lock().is_ok());
+            Ok(texts.iter().map(|_| vec![1.0f32; 4]).collect())
+        })
+        .unwrap();
+        assert_eq!(reembedded, 3);
+
+        let conn = db.into_inner().unwrap();
+        assert_eq!(queries::count_stale_embeddings(&conn, ACTIVE_EMBEDDING_MODEL).unwrap(), (0, 0));
+
+        // Only stale vectors were replaced
+        assert_eq!(queries::get_issue_embedding(&conn, 1).unwrap(), Some(vec![0.5; 4]));
+        assert_eq!(queries::get_issue_embedding(&conn, 2).unwrap(), Some(vec![1.0; 4]));
+        assert_eq!(queries::get_issue_embedding(&conn, 3).unwrap(), Some(vec![1.0; 4]));
+        assert_eq!(queries::get_issue_embedding(&conn, 4).unwrap(), None);
+        assert_eq!(queries::get_pr_embedding(&conn, 1).unwrap(), Some(vec![0.5; 4]));
+        assert_eq!(queries::get_pr_embedding(&conn, 2).unwrap(), Some(vec![1.0; 4]));
+    }
+
+    #[test]
+    fn test_stale_item_without_text_is_skipped_not_reembedded() {
+        let conn = setup_db();
+        conn.execute("UPDATE issues SET title = '', body = NULL WHERE id = 2", []).unwrap();
+
+        let db = Mutex::new(conn);
+        let reembedded = reembed_stale_items(&db, ACTIVE_EMBEDDING_MODEL, |texts: &[String]| {
+            Ok(texts.iter().map(|_| vec![1.0f32; 4]).collect())
+        })
+        .unwrap();
+        assert_eq!(reembedded, 2);
+        let conn = db.into_inner().unwrap();
+
+        // The empty issue loses its vector (out of vector search) and isn't queued for embedding again
+        assert_eq!(queries::get_issue_embedding(&conn, 2).unwrap(), None);
+        let pending: Vec<i64> = queries::get_issues_without_embeddings(&conn, 10)
+            .unwrap()
+            .iter()
+            .map(|issue| issue.id)
+            .collect();
+        assert_eq!(pending, vec![4]);
+    }
+}
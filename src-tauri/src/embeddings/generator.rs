@@ -2,8 +2,25 @@ use super::generate_embeddings;
 use crate::db::models::{Issue, PullRequest};
 use anyhow::Result;
 
-/// Prepare text for embedding from title and body
-pub fn prepare_issue_text(title: &str, body: &Option<String>) -> String {
+/// Minimum letters/digits across title and body before an item is embedded.
+/// Near-empty text embeds to a vector that matches everything and pollutes similarity search.
+pub const MIN_EMBEDDING_TEXT_CHARS: usize = 8;
+
+/// Stored in embedding_model for items deliberately left without an embedding
+pub const SKIPPED_EMBEDDING_MODEL: &str = "skipped:too-short";
+
+/// Whether a title and body carry enough text to be worth embedding
+pub fn has_meaningful_text(title: &str, body: &Option<String>) -> bool {
+    let count = |text: &str| text.chars().filter(|c| c.is_alphanumeric()).count();
+    count(title) + body.as_deref().map(count).unwrap_or(0) >= MIN_EMBEDDING_TEXT_CHARS
+}
+
+/// Prepare text for embedding from title and body (None if there's too little text to embed)
+pub fn prepare_issue_text(title: &str, body: &Option<String>) -> Option<String> {
+    if !has_meaningful_text(title, body) {
+        return None;
+    }
+
     let mut parts = vec![title.to_string()];
 
     if let Some(b) = body {
@@ -11,7 +28,7 @@ pub fn prepare_issue_text(title: &str, body: &Option<String>) -> String {
         parts.push(truncated);
     }
 
-    parts.join("\n\n")
+    Some(parts.join("\n\n"))
 }
 
 /// Prepare text for embedding from an issue
@@ -32,8 +49,12 @@ pub fn issue_to_embedding_text(issue: &Issue) -> String {
     parts.join("\n\n")
 }
 
-/// Prepare text for embedding from PR title and body
-pub fn prepare_pr_text(title: &str, body: &Option<String>) -> String {
+/// Prepare text for embedding from PR title and body (None if there's too little text to embed)
+pub fn prepare_pr_text(title: &str, body: &Option<String>) -> Option<String> {
+    if !has_meaningful_text(title, body) {
+        return None;
+    }
+
     let mut parts = vec![title.to_string()];
 
     if let Some(b) = body {
@@ -41,7 +62,7 @@ pub fn prepare_pr_text(title: &str, body: &Option<String>) -> String {
         parts.push(truncated);
     }
 
-    parts.join("\n\n")
+    Some(parts.join("\n\n"))
 }
 
 /// Prepare text for embedding from a PR
@@ -164,4 +185,16 @@ mod tests {
         let result = truncate_text(short_text, 50);
         assert_eq!(result, short_text);
     }
+
+    #[test]
+    fn test_empty_items_are_skipped_for_embedding() {
+        assert_eq!(prepare_issue_text("", &None), None);
+        assert_eq!(prepare_issue_text("  ", &Some("\n\n".to_string())), None);
+        assert_eq!(prepare_pr_text("WIP", &Some("--".to_string())), None);
+
+        // A short title is fine once the body carries some text
+        let text = prepare_pr_text("WIP", &Some("Retry failed webhooks".to_string())).unwrap();
+        assert!(text.contains("Retry failed webhooks"));
+        assert!(prepare_issue_text("Login page crashes", &None).is_some());
+    }
 }
@@ -0,0 +1,392 @@
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::sync::Arc;
+use std::time::Duration;
+use thiserror::Error;
+use tokio::sync::Semaphore;
+
+/// Upper bound for the `embedding_http_concurrency` setting
+pub const MAX_CONCURRENCY: i32 = 32;
+
+/// Texts sent per request
+pub const DEFAULT_BATCH_SIZE: usize = 16;
+
+/// Rounds of requests before giving up on batches that keep failing
+pub const MAX_ATTEMPTS: usize = 3;
+
+/// Wait before the first retry round; doubled for each later round
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(500);
+
+/// How long one embedding request may take before it counts as failed
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// A batch failure that retrying won't fix (wrong model or dimensions, rejected request)
+#[derive(Debug, Error)]
+#[error(transparent)]
+struct PermanentError(anyhow::Error);
+
+/// Client for an embedding endpoint that accepts `{"input": [texts]}` and answers
+/// `{"data": [{"index": i, "embedding": [...]}]}` (the OpenAI embeddings shape)
+#[derive(Clone)]
+pub struct HttpEmbeddingClient {
+    url: String,
+    concurrency: usize,
+    batch_size: usize,
+    expected_model: Option<(String, usize)>, // Model name and dimensions responses must match
+    client: reqwest::Client,
+}
+
+#[derive(Debug, Deserialize)]
+struct EmbeddingResponse {
+    data: Vec<EmbeddingData>,
+    model: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct EmbeddingData {
+    index: usize,
+    embedding: Vec<f32>,
+}
+
+impl HttpEmbeddingClient {
+    pub fn new(url: &str, concurrency: i32) -> Self {
+        Self {
+            url: url.to_string(),
+            concurrency: concurrency.clamp(1, MAX_CONCURRENCY) as usize,
+            batch_size: DEFAULT_BATCH_SIZE,
+            expected_model: None,
+            client: reqwest::Client::builder()
+                .timeout(REQUEST_TIMEOUT)
+                .build()
+                .expect("Failed to create HTTP client"),
+        }
+    }
+
+    /// Client for the configured endpoint, or None when the local model should be used.
+    /// Its vectors are stored as the active model's, so responses must match that model.
+    pub fn from_settings(settings: &crate::db::models::Settings) -> Option<Self> {
+        let url = settings.embedding_http_url.trim();
+        (!url.is_empty()).then(|| {
+            Self::new(url, settings.embedding_http_concurrency)
+                .with_expected_model(super::ACTIVE_EMBEDDING_MODEL, super::ACTIVE_EMBEDDING_DIMENSIONS)
+        })
+    }
+
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Reject responses whose vectors don't have `dimensions` entries or that name another model
+    pub fn with_expected_model(mut self, model: &str, dimensions: usize) -> Self {
+        self.expected_model = Some((model.to_string(), dimensions));
+        self
+    }
+
+    /// Embed `texts`, returning one vector per text in the same order
+    pub async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        let mut results = vec![Vec::new(); texts.len()];
+        self.embed_each(texts, |offset, vectors| {
+            for (position, vector) in vectors.into_iter().enumerate() {
+                results[offset + position] = vector;
+            }
+            Ok(())
+        })
+        .await?;
+        Ok(results)
+    }
+
+    /// Embed `texts`, handing each batch's vectors to `on_batch` (with the index of its first
+    /// text) as soon as it arrives, so finished batches survive a later failure. Batches are
+    /// sent with at most `concurrency` requests in flight; failed batches are retried as a
+    /// group, with exponential backoff between rounds, until they succeed or `MAX_ATTEMPTS`
+    /// rounds have run. Failures retrying can't fix (e.g. a model mismatch) end it immediately.
+    pub async fn embed_each<F>(&self, texts: &[String], mut on_batch: F) -> Result<()>
+    where
+        F: FnMut(usize, Vec<Vec<f32>>) -> Result<()>,
+    {
+        let batches: Vec<Vec<String>> = texts.chunks(self.batch_size).map(|chunk| chunk.to_vec()).collect();
+        let mut pending: Vec<usize> = (0..batches.len()).collect();
+        let mut last_error = None;
+
+        for attempt in 1..=MAX_ATTEMPTS {
+            if pending.is_empty() {
+                break;
+            }
+            if attempt > 1 {
+                let delay = retry_delay(attempt);
+                tracing::warn!(
+                    "Retrying {} failed embedding batches in {:?} (attempt {})",
+                    pending.len(),
+                    delay,
+                    attempt
+                );
+                tokio::time::sleep(delay).await;
+            }
+
+            let semaphore = Arc::new(Semaphore::new(self.concurrency));
+            let mut tasks = tokio::task::JoinSet::new();
+            for &batch_idx in &pending {
+                let semaphore = semaphore.clone();
+                let client = self.clone();
+                let batch = batches[batch_idx].clone();
+                tasks.spawn(async move {
+                    let _permit = semaphore.acquire_owned().await;
+                    (batch_idx, client.post_batch(&batch).await)
+                });
+            }
+
+            let mut failed = Vec::new();
+            while let Some(joined) = tasks.join_next().await {
+                let (batch_idx, result) = joined.context("Embedding request task panicked")?;
+                match result {
+                    Ok(vectors) => on_batch(batch_idx * self.batch_size, vectors)?,
+                    Err(e) if e.is::<PermanentError>() => {
+                        return Err(e.context(format!("Embedding batch {} failed", batch_idx)));
+                    }
+                    Err(e) => {
+                        tracing::warn!("Embedding batch {} failed: {}", batch_idx, e);
+                        last_error = Some(e);
+                        failed.push(batch_idx);
+                    }
+                }
+            }
+            failed.sort_unstable();
+            pending = failed;
+        }
+
+        if !pending.is_empty() {
+            return Err(last_error
+                .unwrap_or_else(|| anyhow::anyhow!("Embedding request failed"))
+                .context(format!("{} embedding batches failed after {} attempts", pending.len(), MAX_ATTEMPTS)));
+        }
+
+        Ok(())
+    }
+
+    /// POST one batch and return its vectors ordered like the input
+    async fn post_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        let response = self
+            .client
+            .post(&self.url)
+            .json(&serde_json::json!({ "input": texts }))
+            .send()
+            .await?;
+        let status = response.status();
+        if status.is_client_error() && !matches!(status.as_u16(), 408 | 429) {
+            return Err(PermanentError(anyhow::anyhow!("Embedding endpoint rejected the request ({})", status)).into());
+        }
+        let response = response.error_for_status()?.json::<EmbeddingResponse>().await?;
+        self.check_model(&response).map_err(PermanentError)?;
+        let mut data = response.data;
+
+        if data.len() != texts.len() {
+            return Err(anyhow::anyhow!("Expected {} embeddings but got {}", texts.len(), data.len()));
+        }
+        data.sort_by_key(|item| item.index);
+        if data.iter().enumerate().any(|(position, item)| item.index != position) {
+            return Err(anyhow::anyhow!("Embedding response has missing or duplicate indexes"));
+        }
+
+        Ok(data.into_iter().map(|item| item.embedding).collect())
+    }
+
+    /// Check a response against the expected model; org prefixes ("sentence-transformers/...") are ignored
+    fn check_model(&self, response: &EmbeddingResponse) -> Result<()> {
+        let Some((expected, dimensions)) = &self.expected_model else {
+            return Ok(());
+        };
+        if let Some(model) = &response.model {
+            let name = model.rsplit('/').next().unwrap_or(model);
+            if !name.eq_ignore_ascii_case(expected) {
+                return Err(anyhow::anyhow!("Embedding endpoint serves '{}' but '{}' is required", model, expected));
+            }
+        }
+        if let Some(item) = response.data.iter().find(|item| item.embedding.len() != *dimensions) {
+            return Err(anyhow::anyhow!(
+                "Embedding endpoint returned {} dimensions but {} requires {}",
+                item.embedding.len(),
+                expected,
+                dimensions
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Backoff before retry round `attempt` (the first retry is attempt 2)
+fn retry_delay(attempt: usize) -> Duration {
+    RETRY_BASE_DELAY * 2u32.pow(attempt.saturating_sub(2) as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    #[derive(Default)]
+    struct MockStats {
+        requests: AtomicUsize,
+        in_flight: AtomicUsize,
+        max_in_flight: AtomicUsize,
+    }
+
+    /// Embedding endpoint serving "mock-model" that maps "item-N" to `[N]`, answers slowly so
+    /// requests overlap, fails the first request containing "flaky" and every one containing "broken"
+    async fn serve_embeddings() -> (String, Arc<MockStats>) {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/embed", listener.local_addr().unwrap());
+        let stats = Arc::new(MockStats::default());
+        let failed_once = Arc::new(std::sync::atomic::AtomicBool::new(false));
+
+        let server_stats = stats.clone();
+        tokio::spawn(async move {
+            loop {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let stats = server_stats.clone();
+                let failed_once = failed_once.clone();
+                tokio::spawn(async move {
+                    let body = read_request_body(&mut stream).await;
+                    stats.requests.fetch_add(1, Ordering::SeqCst);
+                    let now = stats.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                    stats.max_in_flight.fetch_max(now, Ordering::SeqCst);
+                    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+                    stats.in_flight.fetch_sub(1, Ordering::SeqCst);
+
+                    let input: Vec<String> = serde_json::from_slice::<serde_json::Value>(&body).unwrap()["input"]
+                        .as_array()
+                        .unwrap()
+                        .iter()
+                        .map(|v| v.as_str().unwrap().to_string())
+                        .collect();
+                    let (status, payload) = if input.iter().any(|t| t.contains("broken"))
+                        || (input.iter().any(|t| t.contains("flaky")) && !failed_once.swap(true, Ordering::SeqCst))
+                    {
+                        ("500 Internal Server Error", r#"{"error":"overloaded"}"#.to_string())
+                    } else {
+                        // Reverse the data so the client has to reorder by index
+                        let data: Vec<serde_json::Value> = input
+                            .iter()
+                            .enumerate()
+                            .rev()
+                            .map(|(index, text)| {
+                                let n: f32 = text.trim_start_matches("flaky-").trim_start_matches("item-").parse().unwrap();
+                                serde_json::json!({ "index": index, "embedding": [n] })
+                            })
+                            .collect();
+                        ("200 OK", serde_json::json!({ "data": data, "model": "acme/mock-model" }).to_string())
+                    };
+                    let response = format!(
+                        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                        status,
+                        payload.len(),
+                        payload
+                    );
+                    stream.write_all(response.as_bytes()).await.unwrap();
+                    stream.shutdown().await.ok();
+                });
+            }
+        });
+
+        (url, stats)
+    }
+
+    async fn read_request_body(stream: &mut tokio::net::TcpStream) -> Vec<u8> {
+        let mut request = Vec::new();
+        let mut buf = [0u8; 4096];
+        loop {
+            let n = stream.read(&mut buf).await.unwrap();
+            if n == 0 {
+                return Vec::new();
+            }
+            request.extend_from_slice(&buf[..n]);
+            let Some(header_end) = request.windows(4).position(|w| w == b"\r\n\r\n") else {
+                continue;
+            };
+            let headers = String::from_utf8_lossy(&request[..header_end]).to_lowercase();
+            let content_length: usize = headers
+                .lines()
+                .find_map(|line| line.strip_prefix("content-length:"))
+                .map(|value| value.trim().parse().unwrap())
+                .unwrap_or(0);
+            if request.len() >= header_end + 4 + content_length {
+                return request[header_end + 4..].to_vec();
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_batches_keep_item_order() {
+        let (url, stats) = serve_embeddings().await;
+        let texts: Vec<String> = (0..20).map(|n| format!("item-{}", n)).collect();
+
+        let client = HttpEmbeddingClient::new(&url, 4).with_batch_size(2);
+        let vectors = client.embed(&texts).await.unwrap();
+
+        assert_eq!(vectors, (0..20).map(|n| vec![n as f32]).collect::<Vec<_>>());
+        assert_eq!(stats.requests.load(Ordering::SeqCst), 10);
+        let max_in_flight = stats.max_in_flight.load(Ordering::SeqCst);
+        assert!(max_in_flight > 1, "requests never overlapped");
+        assert!(max_in_flight <= 4, "{} requests in flight exceeds the limit", max_in_flight);
+    }
+
+    #[tokio::test]
+    async fn test_failed_batch_is_retried() {
+        let (url, stats) = serve_embeddings().await;
+        let mut texts: Vec<String> = (0..6).map(|n| format!("item-{}", n)).collect();
+        texts[3] = "flaky-3".to_string();
+
+        let client = HttpEmbeddingClient::new(&url, 3).with_batch_size(2);
+        let vectors = client.embed(&texts).await.unwrap();
+
+        assert_eq!(vectors, (0..6).map(|n| vec![n as f32]).collect::<Vec<_>>());
+        // Three batches plus one retry of the batch holding "flaky-3"
+        assert_eq!(stats.requests.load(Ordering::SeqCst), 4);
+    }
+
+    #[tokio::test]
+    async fn test_finished_batches_are_handed_over_before_a_failure() {
+        let (url, _) = serve_embeddings().await;
+        let mut texts: Vec<String> = (0..6).map(|n| format!("item-{}", n)).collect();
+        texts[5] = "broken-5".to_string();
+
+        let client = HttpEmbeddingClient::new(&url, 3).with_batch_size(2);
+        let mut received = Vec::new();
+        let result = client
+            .embed_each(&texts, |offset, vectors| {
+                received.push((offset, vectors));
+                Ok(())
+            })
+            .await;
+
+        assert!(result.is_err());
+        received.sort_by_key(|(offset, _)| *offset);
+        assert_eq!(received, vec![(0, vec![vec![0.0], vec![1.0]]), (2, vec![vec![2.0], vec![3.0]])]);
+    }
+
+    #[test]
+    fn test_retry_rounds_back_off_exponentially() {
+        assert_eq!(retry_delay(2), RETRY_BASE_DELAY);
+        assert_eq!(retry_delay(3), RETRY_BASE_DELAY * 2);
+        assert_eq!(retry_delay(4), RETRY_BASE_DELAY * 4);
+    }
+
+    #[tokio::test]
+    async fn test_responses_from_another_model_are_rejected() {
+        let (url, stats) = serve_embeddings().await;
+        let texts = vec!["item-1".to_string()];
+
+        let matching = HttpEmbeddingClient::new(&url, 1).with_expected_model("mock-model", 1);
+        assert_eq!(matching.embed(&texts).await.unwrap(), vec![vec![1.0]]);
+
+        let other_model = HttpEmbeddingClient::new(&url, 1).with_expected_model("all-MiniLM-L6-v2", 1);
+        assert!(other_model.embed(&texts).await.is_err());
+
+        let other_dimensions = HttpEmbeddingClient::new(&url, 1).with_expected_model("mock-model", 384);
+        assert!(other_dimensions.embed(&texts).await.is_err());
+
+        // A mismatch won't go away on retry, so each client sent a single request
+        assert_eq!(stats.requests.load(Ordering::SeqCst), 3);
+    }
+}
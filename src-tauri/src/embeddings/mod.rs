@@ -1,9 +1,17 @@
+pub mod commands;
 pub mod generator;
+pub mod http;
 
 use anyhow::{Context, Result};
 use fastembed::{TextEmbedding, InitOptions, EmbeddingModel};
 use std::sync::Mutex;
 
+/// Name recorded alongside every stored embedding; embeddings from any other model are stale
+pub const ACTIVE_EMBEDDING_MODEL: &str = "all-MiniLM-L6-v2";
+
+/// Length of the active model's vectors
+pub const ACTIVE_EMBEDDING_DIMENSIONS: usize = 384;
+
 /// Global embedding model instance (lazy-initialized)
 static EMBEDDING_MODEL: Mutex<Option<TextEmbedding>> = Mutex::new(None);
 
@@ -12,7 +20,7 @@ fn get_model() -> Result<()> {
     let mut model_lock = EMBEDDING_MODEL.lock().unwrap();
 
     if model_lock.is_none() {
-        tracing::info!("Initializing FastEmbed model (all-MiniLM-L6-v2)...");
+        tracing::info!("Initializing FastEmbed model ({})...", ACTIVE_EMBEDDING_MODEL);
         let options = InitOptions::new(EmbeddingModel::AllMiniLML6V2)
             .with_show_download_progress(true);
 
@@ -6,13 +6,19 @@ use std::path::PathBuf;
 
 const SERVICE_NAME: &str = "made-activity-tracker";
 const ACCOUNT_NAME: &str = "github-token";
+const TOKEN_FILE_NAME: &str = ".token";
 
-/// Get the path to the token file (fallback when keyring unavailable)
-fn get_token_file_path() -> Result<PathBuf> {
+/// Get the path to a secret's file (fallback when keyring unavailable)
+fn get_secret_file_path(file_name: &str) -> Result<PathBuf> {
     let home = dirs::home_dir().ok_or_else(|| anyhow!("Could not find home directory"))?;
     let token_dir = home.join(".config").join("made-activity-tracker");
     fs::create_dir_all(&token_dir)?;
-    Ok(token_dir.join(".token"))
+    Ok(token_dir.join(file_name))
+}
+
+/// Keyring account and fallback file name for a profile's webhook secret
+fn webhook_secret_location(profile: &str) -> (String, String) {
+    (format!("webhook-secret:{}", profile), format!(".webhook_secret_{}", profile))
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -27,19 +33,65 @@ pub struct GitHubUser {
 pub struct AuthResult {
     pub user: GitHubUser,
     pub access_token: String,
+    pub token_scopes: Option<TokenScopeCheck>,
+}
+
+/// Whether a token can read issues/PRs for the configured repositories
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TokenScopeCheck {
+    pub adequate: bool,
+    pub fine_grained: bool,
+    pub scopes: Vec<String>,
+    pub message: Option<String>,
 }
 
 /// Device Flow URLs for GitHub OAuth
 const DEVICE_CODE_URL: &str = "https://github.com/login/device/code";
 const ACCESS_TOKEN_URL: &str = "https://github.com/login/oauth/access_token";
 const USER_API_URL: &str = "https://api.github.com/user";
+const REPOS_API_URL: &str = "https://api.github.com/repos";
+
+/// Classic scope needed to read issues/PRs (matches the scope requested by the device flow)
+const REQUIRED_SCOPE: &str = "repo";
 
 /// Store the access token securely in the system keychain (or file fallback)
 pub fn store_token(token: &str) -> Result<()> {
+    store_secret(ACCOUNT_NAME, TOKEN_FILE_NAME, token)
+}
+
+/// Retrieve the access token from the system keychain (or file fallback)
+pub fn get_token() -> Result<Option<String>> {
+    get_secret(ACCOUNT_NAME, TOKEN_FILE_NAME)
+}
+
+/// Delete the access token from the system keychain (and file fallback)
+pub fn delete_token() -> Result<()> {
+    delete_secret(ACCOUNT_NAME, TOKEN_FILE_NAME)
+}
+
+/// Store a profile's webhook secret in the system keychain (or file fallback)
+pub fn store_webhook_secret(profile: &str, secret: &str) -> Result<()> {
+    let (account, file_name) = webhook_secret_location(profile);
+    store_secret(&account, &file_name, secret)
+}
+
+/// Retrieve a profile's webhook secret from the system keychain (or file fallback)
+pub fn get_webhook_secret(profile: &str) -> Result<Option<String>> {
+    let (account, file_name) = webhook_secret_location(profile);
+    get_secret(&account, &file_name)
+}
+
+/// Delete a profile's webhook secret from the system keychain (and file fallback)
+pub fn delete_webhook_secret(profile: &str) -> Result<()> {
+    let (account, file_name) = webhook_secret_location(profile);
+    delete_secret(&account, &file_name)
+}
+
+fn store_secret(account: &str, file_name: &str, value: &str) -> Result<()> {
     // Try keyring first
-    match Entry::new(SERVICE_NAME, ACCOUNT_NAME) {
+    match Entry::new(SERVICE_NAME, account) {
         Ok(entry) => {
-            if entry.set_password(token).is_ok() {
+            if entry.set_password(value).is_ok() {
                 return Ok(());
             }
         }
@@ -47,29 +99,28 @@ pub fn store_token(token: &str) -> Result<()> {
     }
 
     // Fallback to file storage (for WSL and systems without keyring)
-    let token_path = get_token_file_path()?;
-    fs::write(&token_path, token)?;
+    let secret_path = get_secret_file_path(file_name)?;
+    fs::write(&secret_path, value)?;
 
     // Set restrictive permissions (Unix only)
     #[cfg(unix)]
     {
         use std::os::unix::fs::PermissionsExt;
-        let mut perms = fs::metadata(&token_path)?.permissions();
+        let mut perms = fs::metadata(&secret_path)?.permissions();
         perms.set_mode(0o600); // Read/write for owner only
-        fs::set_permissions(&token_path, perms)?;
+        fs::set_permissions(&secret_path, perms)?;
     }
 
     Ok(())
 }
 
-/// Retrieve the access token from the system keychain (or file fallback)
-pub fn get_token() -> Result<Option<String>> {
+fn get_secret(account: &str, file_name: &str) -> Result<Option<String>> {
     // Try keyring first
-    match Entry::new(SERVICE_NAME, ACCOUNT_NAME) {
+    match Entry::new(SERVICE_NAME, account) {
         Ok(entry) => match entry.get_password() {
-            Ok(token) => return Ok(Some(token)),
+            Ok(value) => return Ok(Some(value)),
             Err(keyring::Error::NoEntry) => {
-                // No token in keyring, try file fallback
+                // Nothing in keyring, try file fallback
             }
             Err(_) => {
                 // Keyring unavailable, try file fallback
@@ -81,24 +132,23 @@ pub fn get_token() -> Result<Option<String>> {
     }
 
     // Fallback to file storage
-    let token_path = get_token_file_path()?;
-    match fs::read_to_string(&token_path) {
-        Ok(token) => Ok(Some(token)),
+    let secret_path = get_secret_file_path(file_name)?;
+    match fs::read_to_string(&secret_path) {
+        Ok(value) => Ok(Some(value)),
         Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
-        Err(e) => Err(anyhow!("Failed to read token file: {}", e)),
+        Err(e) => Err(anyhow!("Failed to read secret file: {}", e)),
     }
 }
 
-/// Delete the access token from the system keychain (and file fallback)
-pub fn delete_token() -> Result<()> {
+fn delete_secret(account: &str, file_name: &str) -> Result<()> {
     // Try deleting from keyring
-    if let Ok(entry) = Entry::new(SERVICE_NAME, ACCOUNT_NAME) {
+    if let Ok(entry) = Entry::new(SERVICE_NAME, account) {
         let _ = entry.delete_password(); // Ignore errors, continue to file cleanup
     }
 
     // Also delete from file storage
-    if let Ok(token_path) = get_token_file_path() {
-        let _ = fs::remove_file(&token_path); // Ignore errors if file doesn't exist
+    if let Ok(secret_path) = get_secret_file_path(file_name) {
+        let _ = fs::remove_file(&secret_path); // Ignore errors if file doesn't exist
     }
 
     Ok(())
@@ -188,3 +238,97 @@ pub async fn get_authenticated_user(access_token: &str) -> Result<GitHubUser> {
     
     Ok(user)
 }
+
+/// Fetch the raw `X-OAuth-Scopes` header for a token.
+/// Fine-grained tokens don't send this header, so `None` means fine-grained.
+pub async fn fetch_token_scopes_header(access_token: &str) -> Result<Option<String>> {
+    let client = reqwest::Client::new();
+
+    let response = client
+        .get(USER_API_URL)
+        .header("Authorization", format!("Bearer {}", access_token))
+        .header("User-Agent", "MADE-Activity-Tracker")
+        .send()
+        .await?
+        .error_for_status()?;
+
+    Ok(response
+        .headers()
+        .get("x-oauth-scopes")
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.to_string()))
+}
+
+/// Check a classic token's `X-OAuth-Scopes` header for the scope sync needs
+pub fn evaluate_scopes_header(header: &str) -> TokenScopeCheck {
+    let scopes: Vec<String> = header
+        .split(',')
+        .map(|scope| scope.trim().to_string())
+        .filter(|scope| !scope.is_empty())
+        .collect();
+
+    let adequate = scopes.iter().any(|scope| scope == REQUIRED_SCOPE);
+    let message = (!adequate).then(|| {
+        format!(
+            "Token is missing the '{}' scope (has: {}); issues and pull requests of private repositories cannot be read",
+            REQUIRED_SCOPE,
+            if scopes.is_empty() { "none".to_string() } else { scopes.join(", ") }
+        )
+    });
+
+    TokenScopeCheck {
+        adequate,
+        fine_grained: false,
+        scopes,
+        message,
+    }
+}
+
+/// Probe a repository's issues and pull requests endpoints with a fine-grained token.
+/// Returns the resources the token cannot read.
+pub async fn find_unreadable_resources(access_token: &str, owner: &str, name: &str) -> Result<Vec<String>> {
+    let client = reqwest::Client::new();
+    let mut unreadable = Vec::new();
+
+    for resource in ["issues", "pulls"] {
+        let status = client
+            .get(format!("{}/{}/{}/{}?per_page=1", REPOS_API_URL, owner, name, resource))
+            .header("Authorization", format!("Bearer {}", access_token))
+            .header("User-Agent", "MADE-Activity-Tracker")
+            .header("Accept", "application/vnd.github.v3+json")
+            .send()
+            .await?
+            .status();
+
+        if !status.is_success() {
+            unreadable.push(resource.to_string());
+        }
+    }
+
+    Ok(unreadable)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scopes_header_with_repo_is_adequate() {
+        let check = evaluate_scopes_header("read:user, repo, workflow");
+        assert!(check.adequate);
+        assert!(check.message.is_none());
+        assert_eq!(check.scopes, vec!["read:user", "repo", "workflow"]);
+    }
+
+    #[test]
+    fn test_scopes_header_without_repo_is_inadequate() {
+        let check = evaluate_scopes_header("public_repo, read:user");
+        assert!(!check.adequate);
+        assert!(check.message.unwrap().contains("missing the 'repo' scope"));
+
+        let empty = evaluate_scopes_header("");
+        assert!(!empty.adequate);
+        assert!(empty.scopes.is_empty());
+        assert!(empty.message.unwrap().contains("has: none"));
+    }
+}
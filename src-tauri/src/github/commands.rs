@@ -1,5 +1,7 @@
-use super::auth::{self, AuthResult};
+use super::auth::{self, AuthResult, TokenScopeCheck};
+use super::graphql::{self, RepositoryCounts, RepositoryCountsResponse, REPOSITORY_COUNTS_QUERY};
 use super::sync;
+use crate::db::models::Milestone;
 use crate::db::AppState;
 use tauri::{AppHandle, Manager, State}; // Added Manager import
 
@@ -45,7 +47,7 @@ pub async fn github_login(app: AppHandle) -> Result<AuthResult, String> {
     auth::store_token(&access_token)
         .map_err(|e| format!("Failed to save credentials: {}", e))?;
 
-    Ok(AuthResult { user, access_token })
+    Ok(AuthResult { user, access_token, token_scopes: None })
 }
 
 /// Log out by deleting stored credentials
@@ -56,14 +58,24 @@ pub async fn github_logout() -> Result<(), String> {
 
 /// Check if user is already authenticated
 #[tauri::command]
-pub async fn check_auth() -> Result<Option<AuthResult>, String> {
+pub async fn check_auth(state: State<'_, AppState>) -> Result<Option<AuthResult>, String> {
     let token = auth::get_token().map_err(|e| e.to_string())?;
 
     match token {
         Some(access_token) => {
             // Verify token is still valid by fetching user
             match auth::get_authenticated_user(&access_token).await {
-                Ok(user) => Ok(Some(AuthResult { user, access_token })),
+                Ok(user) => {
+                    // Scope problems are reported to the UI rather than failing auth
+                    let token_scopes = match check_token_scopes(&state, &access_token).await {
+                        Ok(check) => Some(check),
+                        Err(e) => {
+                            tracing::warn!("Failed to validate token scopes: {}", e);
+                            None
+                        }
+                    };
+                    Ok(Some(AuthResult { user, access_token, token_scopes }))
+                }
                 Err(_) => {
                     // Token invalid, clean up
                     auth::delete_token().ok();
@@ -75,6 +87,54 @@ pub async fn check_auth() -> Result<Option<AuthResult>, String> {
     }
 }
 
+/// Check whether the stored token can read issues/PRs of the configured repositories
+#[tauri::command]
+pub async fn validate_token_scopes(state: State<'_, AppState>) -> Result<TokenScopeCheck, String> {
+    let token = auth::get_token()
+        .map_err(|e| e.to_string())?
+        .ok_or("Not authenticated")?;
+
+    check_token_scopes(&state, &token).await
+}
+
+async fn check_token_scopes(state: &AppState, token: &str) -> Result<TokenScopeCheck, String> {
+    let header = auth::fetch_token_scopes_header(token)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if let Some(header) = header {
+        return Ok(auth::evaluate_scopes_header(&header));
+    }
+
+    // Fine-grained token: probe each configured repository
+    let repos = {
+        let conn = state.sqlite.lock().map_err(|e| e.to_string())?;
+        crate::db::queries::get_enabled_repositories(&conn).map_err(|e| e.to_string())?
+    };
+
+    let mut problems = Vec::new();
+    for repo in repos {
+        let unreadable = auth::find_unreadable_resources(token, &repo.owner, &repo.name)
+            .await
+            .map_err(|e| e.to_string())?;
+        if !unreadable.is_empty() {
+            problems.push(format!("{}/{} ({})", repo.owner, repo.name, unreadable.join(", ")));
+        }
+    }
+
+    Ok(TokenScopeCheck {
+        adequate: problems.is_empty(),
+        fine_grained: true,
+        scopes: Vec::new(),
+        message: (!problems.is_empty()).then(|| {
+            format!(
+                "Fine-grained token lacks read access to issues/pull requests for: {}",
+                problems.join("; ")
+            )
+        }),
+    })
+}
+
 /// Sync all GitHub data for configured repositories
 #[tauri::command]
 pub async fn sync_github_data(
@@ -85,7 +145,7 @@ pub async fn sync_github_data(
         .map_err(|e| e.to_string())?
         .ok_or("Not authenticated")?;
 
-    sync::sync_all_repos(&app, &state, &token)
+    sync::sync_all_repos(&app, &state, &token, false)
         .await
         .map_err(|e| e.to_string())
 }
@@ -101,17 +161,171 @@ pub async fn sync_repository(
         .map_err(|e| e.to_string())?
         .ok_or("Not authenticated")?;
 
-    sync::sync_single_repo(&app, &state, &token, repo_id)
+    sync::sync_single_repo(&app, &state, &token, repo_id, false)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Re-fetch the full history window, ignoring incremental watermarks (e.g. after raising history_days).
+/// Syncs one repository when `repo_id` is given, otherwise all enabled repositories.
+#[tauri::command]
+pub async fn force_full_sync(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    repo_id: Option<i64>,
+) -> Result<(), String> {
+    let token = auth::get_token()
+        .map_err(|e| e.to_string())?
+        .ok_or("Not authenticated")?;
+
+    match repo_id {
+        Some(repo_id) => sync::sync_single_repo(&app, &state, &token, repo_id, true).await,
+        None => sync::sync_all_repos(&app, &state, &token, true).await,
+    }
+    .map_err(|e| e.to_string())
+}
+
+/// Re-fetch missing LOC/merge data for a repository's PRs without a full re-sync
+#[tauri::command]
+pub async fn refresh_pr_details(
+    state: State<'_, AppState>,
+    repo_id: i64,
+) -> Result<usize, String> {
+    let token = auth::get_token()
+        .map_err(|e| e.to_string())?
+        .ok_or("Not authenticated")?;
+
+    sync::refresh_pr_details(&state, &token, repo_id)
         .await
         .map_err(|e| e.to_string())
 }
 
+/// Fetch real names, avatars and companies for users synced without them.
+/// Users looked up within `refresh_days` (default 30) are skipped; at most `limit` (default 100) per call.
+#[tauri::command]
+pub async fn enrich_users(
+    state: State<'_, AppState>,
+    refresh_days: Option<i64>,
+    limit: Option<i64>,
+) -> Result<usize, String> {
+    let token = auth::get_token()
+        .map_err(|e| e.to_string())?
+        .ok_or("Not authenticated")?;
+
+    sync::enrich_users(&state, &token, refresh_days.unwrap_or(30), limit.unwrap_or(100))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Default sync throughput heuristic used for ETA estimates
+const DEFAULT_SYNC_ITEMS_PER_SECOND: f64 = 10.0;
+
+/// Estimate item count and duration of a sync for the given repositories
+#[tauri::command]
+pub async fn estimate_sync(
+    state: State<'_, AppState>,
+    repo_ids: Vec<i64>,
+    items_per_second: Option<f64>,
+) -> Result<SyncEstimate, String> {
+    let token = auth::get_token()
+        .map_err(|e| e.to_string())?
+        .ok_or("Not authenticated")?;
+
+    let repos = {
+        let conn = state.sqlite.lock().map_err(|e| e.to_string())?;
+        repo_ids
+            .iter()
+            .map(|&id| crate::db::queries::get_repository_by_id(&conn, id))
+            .collect::<anyhow::Result<Vec<_>>>()
+            .map_err(|e| e.to_string())?
+    };
+
+    let mut estimates = Vec::new();
+    for repo in repos {
+        let variables = serde_json::json!({
+            "owner": repo.owner,
+            "name": repo.name
+        });
+        let response: RepositoryCountsResponse =
+            graphql::execute_query(&token, REPOSITORY_COUNTS_QUERY, variables)
+                .await
+                .map_err(|e| format!("Failed to fetch counts for {}/{}: {}", repo.owner, repo.name, e))?;
+
+        estimates.push(RepoSyncEstimate::from_counts(
+            repo.id,
+            format!("{}/{}", repo.owner, repo.name),
+            &response.repository,
+        ));
+    }
+
+    Ok(build_sync_estimate(
+        estimates,
+        items_per_second.unwrap_or(DEFAULT_SYNC_ITEMS_PER_SECOND),
+    ))
+}
+
+/// Sum per-repo counts into an overall estimate
+fn build_sync_estimate(repos: Vec<RepoSyncEstimate>, items_per_second: f64) -> SyncEstimate {
+    let items_per_second = if items_per_second > 0.0 {
+        items_per_second
+    } else {
+        DEFAULT_SYNC_ITEMS_PER_SECOND
+    };
+    let total_items: i64 = repos.iter().map(|r| r.total_items).sum();
+    let estimated_seconds = (total_items as f64 / items_per_second).ceil() as i64;
+
+    SyncEstimate {
+        repos,
+        total_items,
+        items_per_second,
+        estimated_seconds,
+    }
+}
+
+#[derive(serde::Serialize)]
+pub struct SyncEstimate {
+    pub repos: Vec<RepoSyncEstimate>,
+    pub total_items: i64,
+    pub items_per_second: f64,
+    pub estimated_seconds: i64,
+}
+
+#[derive(serde::Serialize)]
+pub struct RepoSyncEstimate {
+    pub repo_id: i64,
+    pub repo: String,
+    pub open_issues: i64,
+    pub total_issues: i64,
+    pub open_prs: i64,
+    pub total_prs: i64,
+    pub total_items: i64,
+}
+
+impl RepoSyncEstimate {
+    fn from_counts(repo_id: i64, repo: String, counts: &RepositoryCounts) -> Self {
+        let total_issues = counts.issues.total_count as i64;
+        let total_prs = counts.pull_requests.total_count as i64;
+        Self {
+            repo_id,
+            repo,
+            open_issues: counts.open_issues.total_count as i64,
+            total_issues,
+            open_prs: counts.open_pull_requests.total_count as i64,
+            total_prs,
+            total_items: total_issues + total_prs,
+        }
+    }
+}
+
 /// Get roadmap data (milestones grouped by cycle)
 #[tauri::command]
 pub async fn get_roadmap(state: State<'_, AppState>) -> Result<Vec<CycleGroup>, String> {
     let conn = state.sqlite.lock().map_err(|e| e.to_string())?;
     
-    let milestones = crate::db::queries::get_milestones_by_cycle(&conn)
+    let counts_source = crate::db::queries::get_settings(&conn)
+        .map_err(|e| e.to_string())?
+        .milestone_counts_source;
+    let milestones = crate::db::queries::get_milestones_by_cycle(&conn, &counts_source)
         .map_err(|e| e.to_string())?;
     
     // Group milestones by title (cycle name)
@@ -146,6 +360,115 @@ pub async fn get_roadmap(state: State<'_, AppState>) -> Result<Vec<CycleGroup>,
     Ok(result)
 }
 
+/// Export milestone due dates as an iCal calendar (`format` "ical", the default) or CSV.
+/// Milestones without a due date are skipped.
+#[tauri::command]
+pub async fn export_roadmap_ical(
+    state: State<'_, AppState>,
+    format: Option<String>,
+) -> Result<String, String> {
+    let conn = state.sqlite.lock().map_err(|e| e.to_string())?;
+
+    let counts_source = crate::db::queries::get_settings(&conn)
+        .map_err(|e| e.to_string())?
+        .milestone_counts_source;
+    let milestones = crate::db::queries::get_milestones_by_cycle(&conn, &counts_source)
+        .map_err(|e| e.to_string())?;
+    let repo_names: std::collections::HashMap<i64, String> = crate::db::queries::get_all_repositories(&conn)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|repo| (repo.id, format!("{}/{}", repo.owner, repo.name)))
+        .collect();
+
+    match format.as_deref().unwrap_or("ical") {
+        "ical" => Ok(roadmap_ical(&milestones, &repo_names, chrono::Utc::now())),
+        "csv" => Ok(roadmap_csv(&milestones, &repo_names)),
+        other => Err(format!("Unknown roadmap export format '{}' (expected 'ical' or 'csv')", other)),
+    }
+}
+
+/// Calendar date of a milestone's `due_on` timestamp, if it has a usable one
+fn milestone_due_date(milestone: &Milestone) -> Option<chrono::NaiveDate> {
+    let due_on = milestone.due_on.as_deref()?;
+    chrono::NaiveDate::parse_from_str(due_on.get(..10)?, "%Y-%m-%d").ok()
+}
+
+fn repo_label(repo_names: &std::collections::HashMap<i64, String>, repo_id: i64) -> String {
+    repo_names.get(&repo_id).cloned().unwrap_or_else(|| format!("repo-{}", repo_id))
+}
+
+/// Escape TEXT values per RFC 5545
+fn ical_escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// One all-day VEVENT per milestone with a due date
+fn roadmap_ical(
+    milestones: &[Milestone],
+    repo_names: &std::collections::HashMap<i64, String>,
+    now: chrono::DateTime<chrono::Utc>,
+) -> String {
+    let stamp = now.format("%Y%m%dT%H%M%SZ").to_string();
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//MADE Activity Tracker//Roadmap//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+    ];
+
+    for milestone in milestones {
+        let Some(due) = milestone_due_date(milestone) else { continue };
+        let repo = repo_label(repo_names, milestone.repo_id);
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!("UID:milestone-{}@made-activity-tracker", milestone.github_id));
+        lines.push(format!("DTSTAMP:{}", stamp));
+        lines.push(format!("DTSTART;VALUE=DATE:{}", due.format("%Y%m%d")));
+        lines.push(format!("DTEND;VALUE=DATE:{}", (due + chrono::Duration::days(1)).format("%Y%m%d")));
+        lines.push(format!("SUMMARY:{}", ical_escape(&format!("{} ({})", milestone.title, repo))));
+        lines.push(format!(
+            "DESCRIPTION:{}",
+            ical_escape(&format!(
+                "{} open / {} closed issues ({})",
+                milestone.open_issues, milestone.closed_issues, milestone.state
+            ))
+        ));
+        lines.push("END:VEVENT".to_string());
+    }
+
+    lines.push("END:VCALENDAR".to_string());
+    lines.join("\r\n") + "\r\n"
+}
+
+/// Quote a CSV field when it contains a separator, quote or newline
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// One row per milestone with a due date
+fn roadmap_csv(milestones: &[Milestone], repo_names: &std::collections::HashMap<i64, String>) -> String {
+    let mut csv = String::from("repo,milestone,due_date,state,open_issues,closed_issues\n");
+    for milestone in milestones {
+        let Some(due) = milestone_due_date(milestone) else { continue };
+        csv.push_str(&format!(
+            "{},{},{},{},{},{}\n",
+            csv_field(&repo_label(repo_names, milestone.repo_id)),
+            csv_field(&milestone.title),
+            due.format("%Y-%m-%d"),
+            csv_field(&milestone.state),
+            milestone.open_issues,
+            milestone.closed_issues
+        ));
+    }
+    csv
+}
+
 #[derive(serde::Serialize)]
 pub struct CycleGroup {
     pub title: String,
@@ -166,3 +489,99 @@ pub struct MilestoneInfo {
     pub closed_issues: i32,
     pub state: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn counts_payload(open_issues: i32, issues: i32, open_prs: i32, prs: i32) -> RepositoryCountsResponse {
+        serde_json::from_value(serde_json::json!({
+            "repository": {
+                "openIssues": { "totalCount": open_issues },
+                "issues": { "totalCount": issues },
+                "openPullRequests": { "totalCount": open_prs },
+                "pullRequests": { "totalCount": prs }
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_parse_total_counts() {
+        let response = counts_payload(12, 340, 3, 150);
+        let estimate = RepoSyncEstimate::from_counts(1, "acme/api".to_string(), &response.repository);
+
+        assert_eq!(estimate.open_issues, 12);
+        assert_eq!(estimate.total_issues, 340);
+        assert_eq!(estimate.open_prs, 3);
+        assert_eq!(estimate.total_prs, 150);
+        assert_eq!(estimate.total_items, 490);
+    }
+
+    #[test]
+    fn test_estimate_sums_repos() {
+        let api = counts_payload(1, 100, 1, 50);
+        let web = counts_payload(0, 30, 2, 21);
+        let repos = vec![
+            RepoSyncEstimate::from_counts(1, "acme/api".to_string(), &api.repository),
+            RepoSyncEstimate::from_counts(2, "acme/web".to_string(), &web.repository),
+        ];
+
+        let estimate = build_sync_estimate(repos, 20.0);
+        assert_eq!(estimate.total_items, 201);
+        assert_eq!(estimate.estimated_seconds, 11);
+
+        let fallback = build_sync_estimate(Vec::new(), 0.0);
+        assert_eq!(fallback.items_per_second, DEFAULT_SYNC_ITEMS_PER_SECOND);
+        assert_eq!(fallback.estimated_seconds, 0);
+    }
+
+    fn milestone(github_id: i64, title: &str, due_on: Option<&str>) -> Milestone {
+        Milestone {
+            id: github_id,
+            github_id,
+            repo_id: 1,
+            title: title.to_string(),
+            description: None,
+            state: "open".to_string(),
+            due_on: due_on.map(str::to_string),
+            open_issues: 3,
+            closed_issues: 7,
+        }
+    }
+
+    #[test]
+    fn test_roadmap_ical_has_event_per_dated_milestone() {
+        let milestones = vec![
+            milestone(1, "Cycle 12", Some("2024-07-01T07:00:00Z")),
+            milestone(2, "Someday", None),
+            milestone(3, "Cycle 13, beta", Some("2024-07-15T07:00:00Z")),
+        ];
+        let repos = std::collections::HashMap::from([(1, "acme/api".to_string())]);
+        let now = chrono::DateTime::parse_from_rfc3339("2024-06-01T12:00:00Z").unwrap().with_timezone(&chrono::Utc);
+
+        let ical = roadmap_ical(&milestones, &repos, now);
+        assert!(ical.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(ical.ends_with("END:VCALENDAR\r\n"));
+        assert_eq!(ical.matches("BEGIN:VEVENT").count(), 2);
+        assert!(ical.contains("DTSTART;VALUE=DATE:20240701\r\nDTEND;VALUE=DATE:20240702"));
+        assert!(ical.contains("DTSTART;VALUE=DATE:20240715"));
+        assert!(ical.contains("SUMMARY:Cycle 13\\, beta (acme/api)"));
+        assert!(ical.contains("DTSTAMP:20240601T120000Z"));
+        assert!(!ical.contains("Someday"));
+    }
+
+    #[test]
+    fn test_roadmap_csv_skips_undated_and_quotes_fields() {
+        let milestones = vec![
+            milestone(1, "Cycle 12", Some("2024-07-01T07:00:00Z")),
+            milestone(2, "Someday", None),
+            milestone(3, "Cycle 13, beta", Some("2024-07-15T07:00:00Z")),
+        ];
+        let csv = roadmap_csv(&milestones, &std::collections::HashMap::new());
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[1], "repo-1,Cycle 12,2024-07-01,open,3,7");
+        assert_eq!(lines[2], "repo-1,\"Cycle 13, beta\",2024-07-15,open,3,7");
+    }
+}
@@ -4,6 +4,15 @@ use thiserror::Error;
 
 const GITHUB_GRAPHQL_URL: &str = "https://api.github.com/graphql";
 
+/// Retries after a secondary (abuse) rate limit before giving up on the request
+const SECONDARY_RATE_LIMIT_RETRIES: u32 = 3;
+/// Backoff when a secondary rate limit response carries no Retry-After header
+const SECONDARY_RATE_LIMIT_DEFAULT_BACKOFF_SECS: u64 = 60;
+/// Longest single wait honoured from a Retry-After header
+const SECONDARY_RATE_LIMIT_MAX_BACKOFF_SECS: u64 = 120;
+/// Total time spent waiting out secondary rate limits before giving up on the request
+const SECONDARY_RATE_LIMIT_MAX_WAIT_SECS: u64 = 300;
+
 #[derive(Debug, Error)]
 pub enum GraphQLExecuteError {
     #[error("SAML SSO required for {owner}/{repo}. Please authorize at: https://github.com/orgs/{org}/sso")]
@@ -21,6 +30,9 @@ pub enum GraphQLExecuteError {
     #[error("GitHub API error ({status}): {body}")]
     ApiError { status: u16, body: String },
 
+    #[error("GitHub secondary rate limit still in effect after {retries} retries ({waited_secs}s waited)")]
+    SecondaryRateLimited { retries: u32, waited_secs: u64 },
+
     #[error("No data in response")]
     NoData,
 }
@@ -30,22 +42,59 @@ pub async fn execute_query<T: for<'de> Deserialize<'de>>(
     token: &str,
     query: &str,
     variables: serde_json::Value,
+) -> Result<T, GraphQLExecuteError> {
+    execute_query_at(GITHUB_GRAPHQL_URL, token, query, variables).await
+}
+
+async fn execute_query_at<T: for<'de> Deserialize<'de>>(
+    url: &str,
+    token: &str,
+    query: &str,
+    variables: serde_json::Value,
 ) -> Result<T, GraphQLExecuteError> {
     let client = reqwest::Client::new();
+    let mut retries = 0;
+    let mut waited = std::time::Duration::ZERO;
 
-    let response = client
-        .post(GITHUB_GRAPHQL_URL)
-        .header("Authorization", format!("Bearer {}", token))
-        .header("User-Agent", "MADE-Activity-Tracker")
-        .json(&serde_json::json!({
-            "query": query,
-            "variables": variables
-        }))
-        .send()
-        .await?;
+    let (status, body) = loop {
+        let response = client
+            .post(url)
+            .header("Authorization", format!("Bearer {}", token))
+            .header("User-Agent", "MADE-Activity-Tracker")
+            .json(&serde_json::json!({
+                "query": query,
+                "variables": variables
+            }))
+            .send()
+            .await?;
 
-    let status = response.status();
-    let body = response.text().await?;
+        let status = response.status();
+        let retry_after = response
+            .headers()
+            .get(reqwest::header::RETRY_AFTER)
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.to_string());
+        let body = response.text().await?;
+
+        // Secondary limits are short-lived and per-client, so wait them out rather than failing the repo
+        if let Some(backoff) = secondary_rate_limit_backoff(status.as_u16(), retry_after.as_deref(), &body) {
+            if !within_secondary_rate_limit_budget(retries, waited, backoff) {
+                return Err(GraphQLExecuteError::SecondaryRateLimited { retries, waited_secs: waited.as_secs() });
+            }
+            retries += 1;
+            waited += backoff;
+            tracing::warn!(
+                "Hit GitHub secondary rate limit, retrying in {}s (attempt {}/{})",
+                backoff.as_secs(),
+                retries,
+                SECONDARY_RATE_LIMIT_RETRIES
+            );
+            tokio::time::sleep(backoff).await;
+            continue;
+        }
+
+        break (status, body);
+    };
 
     if !status.is_success() {
         return Err(GraphQLExecuteError::ApiError {
@@ -80,6 +129,26 @@ pub async fn execute_query<T: for<'de> Deserialize<'de>>(
     response_body.data.ok_or(GraphQLExecuteError::NoData)
 }
 
+/// Backoff for a secondary rate limit response, or None for any other response.
+/// Primary rate limits (exhausted X-RateLimit-Remaining) are not retried here.
+fn secondary_rate_limit_backoff(status: u16, retry_after: Option<&str>, body: &str) -> Option<std::time::Duration> {
+    if !matches!(status, 403 | 429) || !body.to_lowercase().contains("secondary rate limit") {
+        return None;
+    }
+
+    let secs = retry_after
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .unwrap_or(SECONDARY_RATE_LIMIT_DEFAULT_BACKOFF_SECS)
+        .min(SECONDARY_RATE_LIMIT_MAX_BACKOFF_SECS);
+    Some(std::time::Duration::from_secs(secs))
+}
+
+/// Whether another secondary rate limit retry fits within the retry count and total wait budget
+fn within_secondary_rate_limit_budget(retries: u32, waited: std::time::Duration, backoff: std::time::Duration) -> bool {
+    retries < SECONDARY_RATE_LIMIT_RETRIES
+        && waited + backoff <= std::time::Duration::from_secs(SECONDARY_RATE_LIMIT_MAX_WAIT_SECS)
+}
+
 /// Detect if errors contain SAML SSO requirement and construct helpful error
 fn detect_saml_error(errors: &[GraphQLError], variables: &serde_json::Value) -> Option<GraphQLExecuteError> {
     for error in errors {
@@ -175,6 +244,31 @@ query($owner: String!, $name: String!, $cursor: String, $since: DateTime) {
                     number
                     title
                 }
+                comments(first: 20) {
+                    nodes {
+                        createdAt
+                        author {
+                            login
+                        }
+                    }
+                }
+                labelEvents: timelineItems(itemTypes: [LABELED_EVENT, UNLABELED_EVENT], last: 50) {
+                    nodes {
+                        __typename
+                        ... on LabeledEvent {
+                            createdAt
+                            label {
+                                name
+                            }
+                        }
+                        ... on UnlabeledEvent {
+                            createdAt
+                            label {
+                                name
+                            }
+                        }
+                    }
+                }
             }
         }
     }
@@ -204,6 +298,33 @@ query($owner: String!, $name: String!, $cursor: String) {
                 additions
                 deletions
                 changedFiles
+                isDraft
+                mergeable
+                baseRefName
+                authorAssociation
+                commits(last: 50) {
+                    totalCount
+                    nodes {
+                        commit {
+                            oid
+                            committedDate
+                        }
+                    }
+                }
+                closingIssuesReferences(first: 20) {
+                    nodes {
+                        number
+                    }
+                }
+                autoMergeRequest {
+                    enabledAt
+                }
+                mergeQueueEvents: timelineItems(itemTypes: [ADDED_TO_MERGE_QUEUE_EVENT], first: 1) {
+                    totalCount
+                }
+                autoMergeEvents: timelineItems(itemTypes: [AUTO_MERGE_ENABLED_EVENT], first: 1) {
+                    totalCount
+                }
                 author {
                     login
                     ... on User {
@@ -211,6 +332,15 @@ query($owner: String!, $name: String!, $cursor: String) {
                         avatarUrl
                     }
                 }
+                assignees(first: 10) {
+                    nodes {
+                        login
+                        ... on User {
+                            databaseId
+                            avatarUrl
+                        }
+                    }
+                }
                 labels(first: 20) {
                     nodes {
                         name
@@ -261,6 +391,212 @@ query($owner: String!, $name: String!) {
 }
 "#;
 
+/// Query for fetching repository metadata
+pub const REPOSITORY_QUERY: &str = r#"
+query($owner: String!, $name: String!) {
+    repository(owner: $owner, name: $name) {
+        databaseId
+        nameWithOwner
+        isPrivate
+        defaultBranchRef {
+            name
+        }
+        primaryLanguage {
+            name
+        }
+        languages(first: 100, orderBy: {field: SIZE, direction: DESC}) {
+            edges {
+                size
+                node {
+                    name
+                    color
+                }
+            }
+        }
+    }
+}
+"#;
+
+/// Query for fetching commit history of a branch
+pub const COMMIT_HISTORY_QUERY: &str = r#"
+query($owner: String!, $name: String!, $branch: String!, $cursor: String, $since: GitTimestamp) {
+    repository(owner: $owner, name: $name) {
+        ref(qualifiedName: $branch) {
+            target {
+                ... on Commit {
+                    history(first: 100, after: $cursor, since: $since) {
+                        pageInfo {
+                            hasNextPage
+                            endCursor
+                        }
+                        nodes {
+                            oid
+                            messageHeadline
+                            committedDate
+                            additions
+                            deletions
+                            parents {
+                                totalCount
+                            }
+                            author {
+                                user {
+                                    login
+                                    databaseId
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+"#;
+
+/// Query for fetching the changed files of a single pull request
+pub const PR_FILES_QUERY: &str = r#"
+query($owner: String!, $name: String!, $number: Int!, $cursor: String) {
+    repository(owner: $owner, name: $name) {
+        pullRequest(number: $number) {
+            files(first: 100, after: $cursor) {
+                pageInfo {
+                    hasNextPage
+                    endCursor
+                }
+                nodes {
+                    path
+                    additions
+                    deletions
+                }
+            }
+        }
+    }
+}
+"#;
+
+/// Query for cheap issue/PR totals used to estimate sync time
+pub const REPOSITORY_COUNTS_QUERY: &str = r#"
+query($owner: String!, $name: String!) {
+    repository(owner: $owner, name: $name) {
+        openIssues: issues(states: OPEN) {
+            totalCount
+        }
+        issues {
+            totalCount
+        }
+        openPullRequests: pullRequests(states: OPEN) {
+            totalCount
+        }
+        pullRequests {
+            totalCount
+        }
+    }
+}
+"#;
+
+/// Build variables for COMMIT_HISTORY_QUERY against the given branch
+pub fn commit_history_variables(
+    owner: &str,
+    name: &str,
+    branch: &str,
+    cursor: Option<&str>,
+    since: Option<&str>,
+) -> serde_json::Value {
+    serde_json::json!({
+        "owner": owner,
+        "name": name,
+        "branch": format!("refs/heads/{}", branch),
+        "cursor": cursor,
+        "since": since
+    })
+}
+
+// ============================================================================
+// REPOSITORY RESPONSE TYPES
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct RepositoryInfoResponse {
+    pub repository: RepositoryInfo,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepositoryInfo {
+    pub database_id: Option<i64>,
+    #[serde(default)]
+    pub name_with_owner: Option<String>, // Canonical path; differs from the request after a rename/transfer
+    #[serde(default)]
+    pub is_private: Option<bool>,
+    pub default_branch_ref: Option<BranchRef>,
+    #[serde(default)]
+    pub primary_language: Option<LanguageNode>,
+    #[serde(default)]
+    pub languages: Option<LanguageConnection>,
+}
+
+impl RepositoryInfo {
+    /// Default branch name, or None for empty repositories
+    pub fn default_branch(&self) -> Option<&str> {
+        self.default_branch_ref.as_ref().map(|r| r.name.as_str())
+    }
+
+    /// Primary language name, or None when GitHub detected no code
+    pub fn primary_language(&self) -> Option<&str> {
+        self.primary_language.as_ref().map(|l| l.name.as_str())
+    }
+
+    /// Language sizes as (name, color, bytes)
+    pub fn language_sizes(&self) -> Vec<(String, Option<String>, i64)> {
+        self.languages
+            .as_ref()
+            .map(|langs| {
+                langs.edges.iter()
+                    .map(|edge| (edge.node.name.clone(), edge.node.color.clone(), edge.size))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LanguageConnection {
+    #[serde(default)]
+    pub edges: Vec<LanguageEdge>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LanguageEdge {
+    pub size: i64,
+    pub node: LanguageNode,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LanguageNode {
+    pub name: String,
+    #[serde(default)]
+    pub color: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BranchRef {
+    pub name: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RepositoryCountsResponse {
+    pub repository: RepositoryCounts,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepositoryCounts {
+    pub open_issues: IssueCount,
+    pub issues: IssueCount,
+    pub open_pull_requests: IssueCount,
+    pub pull_requests: IssueCount,
+}
+
 // ============================================================================
 // ISSUES RESPONSE TYPES
 // ============================================================================
@@ -299,6 +635,59 @@ pub struct IssueNode {
     pub assignees: AssigneeConnection,
     pub labels: LabelConnection,
     pub milestone: Option<MilestoneRef>,
+    #[serde(default)]
+    pub comments: Option<IssueCommentConnection>,
+    #[serde(default)]
+    pub label_events: Option<LabelEventConnection>,
+}
+
+impl IssueNode {
+    /// Label events as (label, "added"/"removed", created_at)
+    pub fn label_event_rows(&self) -> Vec<(&str, &str, &str)> {
+        self.label_events
+            .iter()
+            .flat_map(|events| events.nodes.iter())
+            .filter_map(|event| {
+                let action = match event.typename.as_str() {
+                    "LabeledEvent" => "added",
+                    "UnlabeledEvent" => "removed",
+                    _ => return None,
+                };
+                Some((event.label.as_ref()?.name.as_str(), action, event.created_at.as_deref()?))
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LabelEventConnection {
+    pub nodes: Vec<LabelEventNode>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LabelEventNode {
+    #[serde(rename = "__typename")]
+    pub typename: String,
+    pub created_at: Option<String>,
+    pub label: Option<LabelNode>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct IssueCommentConnection {
+    pub nodes: Vec<IssueCommentNode>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IssueCommentNode {
+    pub created_at: String,
+    pub author: Option<CommentAuthor>, // None for deleted accounts
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CommentAuthor {
+    pub login: String,
 }
 
 // ============================================================================
@@ -339,11 +728,143 @@ pub struct PullRequestNode {
     pub additions: i32,
     pub deletions: i32,
     pub changed_files: i32,
+    #[serde(default)]
+    pub is_draft: bool,
+    pub mergeable: Option<String>,
+    pub base_ref_name: Option<String>,
+    pub author_association: Option<String>,
+    pub commits: Option<PrCommitConnection>,
+    pub closing_issues_references: Option<ClosingIssueConnection>,
+    pub auto_merge_request: Option<AutoMergeRequest>,
+    pub merge_queue_events: Option<TimelineItemCount>,
+    pub auto_merge_events: Option<TimelineItemCount>,
     pub author: Option<Actor>,
+    #[serde(default)]
+    pub assignees: Option<AssigneeConnection>,
     pub labels: LabelConnection,
     pub reviews: ReviewConnection,
 }
 
+impl PullRequestNode {
+    /// How the PR was merged: "merge_queue", "auto_merge", "manual", or None if unmerged
+    pub fn merge_method(&self) -> Option<&'static str> {
+        self.merged_at.as_ref()?;
+
+        let has_events = |events: &Option<TimelineItemCount>| {
+            events.as_ref().map(|e| e.total_count > 0).unwrap_or(false)
+        };
+
+        if has_events(&self.merge_queue_events) {
+            Some("merge_queue")
+        } else if self.auto_merge_request.is_some() || has_events(&self.auto_merge_events) {
+            Some("auto_merge")
+        } else {
+            Some("manual")
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AutoMergeRequest {
+    pub enabled_at: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimelineItemCount {
+    pub total_count: i32,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CommitHistoryResponse {
+    pub repository: CommitHistoryRepository,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CommitHistoryRepository {
+    #[serde(rename = "ref")]
+    pub git_ref: Option<CommitHistoryRef>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CommitHistoryRef {
+    pub target: Option<CommitHistoryTarget>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CommitHistoryTarget {
+    pub history: Option<CommitHistoryConnection>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommitHistoryConnection {
+    pub page_info: PageInfo,
+    pub nodes: Vec<CommitNode>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommitNode {
+    pub oid: String,
+    pub message_headline: String,
+    pub committed_date: String,
+    pub additions: i32,
+    pub deletions: i32,
+    pub parents: TimelineItemCount,
+    pub author: Option<CommitAuthor>,
+}
+
+impl CommitNode {
+    /// Merge commits have more than one parent
+    pub fn is_merge(&self) -> bool {
+        self.parents.total_count > 1
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CommitAuthor {
+    pub user: Option<CommitUser>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommitUser {
+    pub login: String,
+    pub database_id: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PrFilesResponse {
+    pub repository: RepositoryPullRequestFiles,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepositoryPullRequestFiles {
+    pub pull_request: Option<PullRequestFiles>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PullRequestFiles {
+    pub files: Option<PrFileConnection>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrFileConnection {
+    pub page_info: PageInfo,
+    pub nodes: Vec<PrFileNode>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PrFileNode {
+    pub path: String,
+    pub additions: i32,
+    pub deletions: i32,
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ReviewConnection {
@@ -432,6 +953,37 @@ pub struct LabelNode {
     pub name: String,
 }
 
+/// A PR's commits (total plus the most recent 50, for ordering against reviews)
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrCommitConnection {
+    pub total_count: i32,
+    #[serde(default)]
+    pub nodes: Vec<PrCommitNode>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PrCommitNode {
+    pub commit: PrCommit,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrCommit {
+    pub oid: String,
+    pub committed_date: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ClosingIssueConnection {
+    pub nodes: Vec<ClosingIssueNode>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ClosingIssueNode {
+    pub number: i32,
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct MilestoneRef {
@@ -439,3 +991,231 @@ pub struct MilestoneRef {
     pub number: i32,
     pub title: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Serve the given raw HTTP responses in order, one per connection
+    async fn serve_responses(responses: Vec<String>) -> (String, tokio::task::JoinHandle<usize>) {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/graphql", listener.local_addr().unwrap());
+        let handle = tokio::spawn(async move {
+            let mut served = 0;
+            for response in responses {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut buf = vec![0u8; 16 * 1024];
+                let _ = stream.read(&mut buf).await.unwrap();
+                stream.write_all(response.as_bytes()).await.unwrap();
+                stream.shutdown().await.ok();
+                served += 1;
+            }
+            served
+        });
+        (url, handle)
+    }
+
+    fn http_response(status: &str, extra_headers: &str, body: &str) -> String {
+        format!(
+            "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n{}\r\n{}",
+            status,
+            body.len(),
+            extra_headers,
+            body
+        )
+    }
+
+    #[test]
+    fn test_secondary_rate_limit_detection() {
+        let body = r#"{"message":"You have exceeded a secondary rate limit. Please wait a few minutes before you try again."}"#;
+        assert_eq!(secondary_rate_limit_backoff(403, Some("30"), body), Some(std::time::Duration::from_secs(30)));
+        assert_eq!(
+            secondary_rate_limit_backoff(403, None, body),
+            Some(std::time::Duration::from_secs(SECONDARY_RATE_LIMIT_DEFAULT_BACKOFF_SECS))
+        );
+        // An oversized Retry-After is capped rather than honoured as-is
+        assert_eq!(
+            secondary_rate_limit_backoff(429, Some("3600"), body),
+            Some(std::time::Duration::from_secs(SECONDARY_RATE_LIMIT_MAX_BACKOFF_SECS))
+        );
+        // Primary rate limit and other 403s are not retried
+        assert_eq!(secondary_rate_limit_backoff(403, None, r#"{"message":"API rate limit exceeded"}"#), None);
+        assert_eq!(secondary_rate_limit_backoff(200, None, body), None);
+    }
+
+    #[tokio::test]
+    async fn test_secondary_rate_limit_is_retried() {
+        let limited = http_response(
+            "403 Forbidden",
+            "Retry-After: 0\r\n",
+            r#"{"message":"You have exceeded a secondary rate limit."}"#,
+        );
+        let ok = http_response(
+            "200 OK",
+            "",
+            r#"{"data":{"repository":{"databaseId":1,"defaultBranchRef":{"name":"main"}}}}"#,
+        );
+        let (url, server) = serve_responses(vec![limited, ok]).await;
+
+        let response: RepositoryInfoResponse =
+            execute_query_at(&url, "token", REPOSITORY_QUERY, serde_json::json!({})).await.unwrap();
+        assert_eq!(response.repository.default_branch(), Some("main"));
+        assert_eq!(server.await.unwrap(), 2);
+    }
+
+    #[test]
+    fn test_secondary_rate_limit_wait_budget() {
+        let secs = std::time::Duration::from_secs;
+        assert!(within_secondary_rate_limit_budget(0, secs(0), secs(SECONDARY_RATE_LIMIT_MAX_BACKOFF_SECS)));
+        // Out of retries
+        assert!(!within_secondary_rate_limit_budget(SECONDARY_RATE_LIMIT_RETRIES, secs(0), secs(0)));
+        // The next wait would overrun the total budget
+        assert!(!within_secondary_rate_limit_budget(
+            1,
+            secs(SECONDARY_RATE_LIMIT_MAX_WAIT_SECS - 10),
+            secs(SECONDARY_RATE_LIMIT_DEFAULT_BACKOFF_SECS)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_secondary_rate_limit_gives_up_after_max_retries() {
+        let limited = || {
+            http_response(
+                "403 Forbidden",
+                "Retry-After: 0\r\n",
+                r#"{"message":"You have exceeded a secondary rate limit."}"#,
+            )
+        };
+        let responses = (0..=SECONDARY_RATE_LIMIT_RETRIES).map(|_| limited()).collect();
+        let (url, server) = serve_responses(responses).await;
+
+        let result: Result<RepositoryInfoResponse, _> =
+            execute_query_at(&url, "token", REPOSITORY_QUERY, serde_json::json!({})).await;
+        assert!(matches!(
+            result,
+            Err(GraphQLExecuteError::SecondaryRateLimited { retries: SECONDARY_RATE_LIMIT_RETRIES, .. })
+        ));
+        assert_eq!(server.await.unwrap(), SECONDARY_RATE_LIMIT_RETRIES as usize + 1);
+    }
+
+    #[test]
+    fn test_parse_default_branch_ref() {
+        let payload = serde_json::json!({
+            "repository": {
+                "databaseId": 1296269,
+                "defaultBranchRef": { "name": "develop" }
+            }
+        });
+
+        let response: RepositoryInfoResponse = serde_json::from_value(payload).unwrap();
+        let branch = response.repository.default_branch().unwrap();
+        assert_eq!(branch, "develop");
+
+        let variables = commit_history_variables("octocat", "hello-world", branch, None, None);
+        assert_eq!(variables["branch"], "refs/heads/develop");
+    }
+
+    #[test]
+    fn test_commit_with_multiple_parents_is_merge() {
+        let commit = |parents: i32| {
+            serde_json::from_value::<CommitNode>(serde_json::json!({
+                "oid": "abc123",
+                "messageHeadline": "Merge pull request #7",
+                "committedDate": "2024-03-01T10:00:00Z",
+                "additions": 10,
+                "deletions": 2,
+                "parents": { "totalCount": parents },
+                "author": { "user": { "login": "octocat", "databaseId": 583231 } }
+            }))
+            .unwrap()
+        };
+
+        assert!(commit(2).is_merge());
+        assert!(!commit(1).is_merge());
+    }
+
+    fn pr_payload(merged_at: Option<&str>, auto_merge: bool, queue_events: i32) -> serde_json::Value {
+        serde_json::json!({
+            "id": "PR_1",
+            "databaseId": 1,
+            "number": 1,
+            "title": "Bump deps",
+            "body": null,
+            "state": if merged_at.is_some() { "MERGED" } else { "OPEN" },
+            "createdAt": "2024-01-01T00:00:00Z",
+            "updatedAt": "2024-01-02T00:00:00Z",
+            "mergedAt": merged_at,
+            "closedAt": merged_at,
+            "additions": 1,
+            "deletions": 1,
+            "changedFiles": 1,
+            "autoMergeRequest": if auto_merge {
+                serde_json::json!({ "enabledAt": "2024-01-01T01:00:00Z" })
+            } else {
+                serde_json::Value::Null
+            },
+            "mergeQueueEvents": { "totalCount": queue_events },
+            "autoMergeEvents": { "totalCount": if auto_merge { 1 } else { 0 } },
+            "author": null,
+            "labels": { "nodes": [] },
+            "reviews": { "nodes": [] }
+        })
+    }
+
+    #[test]
+    fn test_merge_method_detection() {
+        let auto: PullRequestNode = serde_json::from_value(pr_payload(Some("2024-01-02T00:00:00Z"), true, 0)).unwrap();
+        assert_eq!(auto.merge_method(), Some("auto_merge"));
+
+        let queued: PullRequestNode = serde_json::from_value(pr_payload(Some("2024-01-02T00:00:00Z"), true, 1)).unwrap();
+        assert_eq!(queued.merge_method(), Some("merge_queue"));
+
+        let manual: PullRequestNode = serde_json::from_value(pr_payload(Some("2024-01-02T00:00:00Z"), false, 0)).unwrap();
+        assert_eq!(manual.merge_method(), Some("manual"));
+
+        let open: PullRequestNode = serde_json::from_value(pr_payload(None, true, 0)).unwrap();
+        assert_eq!(open.merge_method(), None);
+    }
+
+    #[test]
+    fn test_parse_languages_breakdown() {
+        let payload = serde_json::json!({
+            "repository": {
+                "databaseId": 1,
+                "defaultBranchRef": { "name": "main" },
+                "primaryLanguage": { "name": "Rust" },
+                "languages": {
+                    "edges": [
+                        { "size": 7000, "node": { "name": "Rust", "color": "#dea584" } },
+                        { "size": 2000, "node": { "name": "TypeScript", "color": "#3178c6" } },
+                        { "size": 1000, "node": { "name": "Shell", "color": null } }
+                    ]
+                }
+            }
+        });
+
+        let response: RepositoryInfoResponse = serde_json::from_value(payload).unwrap();
+        assert_eq!(response.repository.primary_language(), Some("Rust"));
+
+        let breakdown = crate::db::project_queries::language_breakdown(response.repository.language_sizes());
+        assert_eq!(breakdown.len(), 3);
+        assert_eq!(breakdown[0].name, "Rust");
+        assert!((breakdown[0].percentage - 70.0).abs() < 1e-9);
+        let total: f64 = breakdown.iter().map(|l| l.percentage).sum();
+        assert!((total - 100.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_parse_empty_repo_without_default_branch() {
+        let payload = serde_json::json!({
+            "repository": { "databaseId": 1, "defaultBranchRef": null }
+        });
+
+        let response: RepositoryInfoResponse = serde_json::from_value(payload).unwrap();
+        assert!(response.repository.default_branch().is_none());
+        assert!(response.repository.primary_language().is_none());
+        assert!(response.repository.language_sizes().is_empty());
+    }
+}
@@ -5,3 +5,4 @@ pub mod graphql;
 pub mod rest_api;
 pub mod sync;
 pub mod sync_user;
+pub mod webhooks;
@@ -101,6 +101,79 @@ pub async fn fetch_pull_requests_rest(
     Ok(all_prs)
 }
 
+/// Rate limit budget reported by REST response headers
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimit {
+    pub remaining: u32,
+    pub reset_at: i64, // Unix epoch seconds
+}
+
+/// Read `X-RateLimit-Remaining`/`X-RateLimit-Reset` from a response
+pub fn parse_rate_limit(headers: &reqwest::header::HeaderMap) -> Option<RateLimit> {
+    let header = |name: &str| headers.get(name)?.to_str().ok()?.parse::<i64>().ok();
+
+    Some(RateLimit {
+        remaining: header("x-ratelimit-remaining")?.max(0) as u32,
+        reset_at: header("x-ratelimit-reset")?,
+    })
+}
+
+/// Fetch a single pull request; unlike the list endpoint this includes LOC and file counts
+pub async fn fetch_pull_request_rest(
+    token: &str,
+    owner: &str,
+    repo: &str,
+    number: i32,
+) -> Result<(RestPullRequest, Option<RateLimit>)> {
+    let client = reqwest::Client::new();
+    let url = format!("{}/repos/{}/{}/pulls/{}", GITHUB_API_BASE, owner, repo, number);
+
+    let response = client
+        .get(&url)
+        .header("Authorization", format!("Bearer {}", token))
+        .header("User-Agent", "MADE-Activity-Tracker")
+        .header("Accept", "application/vnd.github.v3+json")
+        .send()
+        .await?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await?;
+        anyhow::bail!("REST API error ({}): {}", status, body);
+    }
+
+    let rate_limit = parse_rate_limit(response.headers());
+    let pr: RestPullRequest = response.json().await?;
+    Ok((pr, rate_limit))
+}
+
+/// Fetch a user's public profile; `None` when the account no longer exists
+pub async fn fetch_user_rest(token: &str, login: &str) -> Result<(Option<RestUserProfile>, Option<RateLimit>)> {
+    let client = reqwest::Client::new();
+    let url = format!("{}/users/{}", GITHUB_API_BASE, login);
+
+    let response = client
+        .get(&url)
+        .header("Authorization", format!("Bearer {}", token))
+        .header("User-Agent", "MADE-Activity-Tracker")
+        .header("Accept", "application/vnd.github.v3+json")
+        .send()
+        .await?;
+
+    let rate_limit = parse_rate_limit(response.headers());
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok((None, rate_limit));
+    }
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await?;
+        anyhow::bail!("REST API error ({}): {}", status, body);
+    }
+
+    let profile: RestUserProfile = response.json().await?;
+    Ok((Some(profile), rate_limit))
+}
+
 /// Fallback: Fetch milestones using REST API
 pub async fn fetch_milestones_rest(
     token: &str,
@@ -146,9 +219,22 @@ pub struct RestIssue {
     pub created_at: String,
     pub updated_at: String,
     pub closed_at: Option<String>,
+    pub html_url: Option<String>,
     pub pull_request: Option<RestPullRequestRef>,
 }
 
+impl RestIssue {
+    /// The issues endpoint also returns PRs; detect them by the `pull_request`
+    /// key or, when that is missing, by a `/pull/` web URL
+    pub fn is_pull_request(&self) -> bool {
+        self.pull_request.is_some()
+            || self
+                .html_url
+                .as_deref()
+                .map_or(false, |url| url.split('/').rev().nth(1) == Some("pull"))
+    }
+}
+
 #[derive(Debug, Deserialize)]
 pub struct RestPullRequest {
     pub id: i64,
@@ -165,6 +251,8 @@ pub struct RestPullRequest {
     pub additions: Option<i32>,
     pub deletions: Option<i32>,
     pub changed_files: Option<i32>,
+    pub base: Option<RestBranchRef>,
+    pub author_association: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -186,11 +274,26 @@ pub struct RestUser {
     pub avatar_url: Option<String>,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct RestUserProfile {
+    pub id: i64,
+    pub login: String,
+    pub name: Option<String>,
+    pub avatar_url: Option<String>,
+    pub company: Option<String>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct RestLabel {
     pub name: String,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct RestBranchRef {
+    #[serde(rename = "ref")]
+    pub ref_name: String,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct RestPullRequestRef {
     pub url: String,
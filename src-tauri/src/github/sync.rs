@@ -1,20 +1,25 @@
+use crate::db::path_owner_queries;
 use crate::db::queries::{self, is_bot_user};
 use crate::db::AppState;
 use crate::github::cli::GitHubCli;
-use crate::github::graphql::{self, GraphQLExecuteError, *};
+use crate::github::graphql::{self, *};
 use crate::github::rest_api;
-use crate::embeddings::{generate_embeddings, generator};
+use crate::embeddings::http::HttpEmbeddingClient;
+use crate::embeddings::{generate_embeddings, generator, ACTIVE_EMBEDDING_MODEL};
 use anyhow::{Context, Result};
 use chrono::{Duration, Utc};
 use tauri::{AppHandle, Manager};
 
-/// Sync all data for all enabled repositories
-pub async fn sync_all_repos(app: &AppHandle, state: &AppState, token: &str) -> Result<()> {
-    // Load settings from SQLite to get history_days and excluded_bots
-    let (history_days, excluded_bots) = {
+/// Sync all data for all enabled repositories.
+/// `full` ignores the incremental watermarks and re-fetches the whole history window.
+pub async fn sync_all_repos(app: &AppHandle, state: &AppState, token: &str, full: bool) -> Result<()> {
+    let _sync = state.begin_sync()?;
+
+    // Load settings from SQLite to get history_days, excluded_bots and sync_old_reopened
+    let (history_days, excluded_bots, sync_old_reopened) = {
         let conn = state.sqlite.lock().unwrap();
         let settings = queries::get_settings(&conn)?;
-        (settings.history_days, settings.excluded_bots)
+        (settings.history_days, settings.excluded_bots, settings.sync_old_reopened)
     };
 
     // Get enabled repos from database
@@ -32,25 +37,39 @@ pub async fn sync_all_repos(app: &AppHandle, state: &AppState, token: &str) -> R
     let since_date = Utc::now() - Duration::days(history_days as i64);
     let since = since_date.format("%Y-%m-%dT%H:%M:%SZ").to_string();
 
-    tracing::info!("Starting sync for {} repos, since {}", total_repos, since);
+    tracing::info!("Starting {} sync for {} repos, since {}", if full { "full" } else { "incremental" }, total_repos, since);
 
     for (idx, repo) in repos.iter().enumerate() {
         emit_progress(app, "syncing", idx + 1, total_repos,
             &format!("Syncing {}/{}", repo.owner, repo.name));
 
+        // Refresh repository metadata (default branch, renames/transfers)
+        let (owner, name) = match sync_repository_info(state, token, repo.id, &repo.owner, &repo.name).await {
+            Ok(path) => path,
+            Err(e) => {
+                tracing::error!("Failed to sync repository info for {}/{}: {}", repo.owner, repo.name, e);
+                (repo.owner.clone(), repo.name.clone())
+            }
+        };
+
         // Sync milestones first (needed for issue references)
-        if let Err(e) = sync_milestones(state, token, repo.id, &repo.owner, &repo.name).await {
-            tracing::error!("Failed to sync milestones for {}/{}: {}", repo.owner, repo.name, e);
+        if let Err(e) = sync_milestones(state, token, repo.id, &owner, &name).await {
+            tracing::error!("Failed to sync milestones for {}/{}: {}", owner, name, e);
         }
 
         // Sync issues
-        if let Err(e) = sync_issues(state, token, repo.id, &repo.owner, &repo.name, &since, &excluded_bots).await {
-            tracing::error!("Failed to sync issues for {}/{}: {}", repo.owner, repo.name, e);
+        if let Err(e) = sync_issues(state, token, repo.id, &owner, &name, &since, old_item_cutoff(&since, sync_old_reopened), &excluded_bots, full).await {
+            tracing::error!("Failed to sync issues for {}/{}: {}", owner, name, e);
         }
 
         // Sync PRs
-        if let Err(e) = sync_pull_requests(state, token, repo.id, &repo.owner, &repo.name, &excluded_bots).await {
-            tracing::error!("Failed to sync PRs for {}/{}: {}", repo.owner, repo.name, e);
+        if let Err(e) = sync_pull_requests(state, token, repo.id, &owner, &name, &excluded_bots, full).await {
+            tracing::error!("Failed to sync PRs for {}/{}: {}", owner, name, e);
+        }
+
+        // Sync default-branch commits
+        if let Err(e) = sync_commits(state, token, repo.id, &owner, &name, &since, &excluded_bots).await {
+            tracing::error!("Failed to sync commits for {}/{}: {}", owner, name, e);
         }
 
         // Update last synced timestamp
@@ -76,18 +95,23 @@ pub async fn sync_all_repos(app: &AppHandle, state: &AppState, token: &str) -> R
     Ok(())
 }
 
-/// Sync a single repository by ID
-pub async fn sync_single_repo(app: &AppHandle, state: &AppState, token: &str, repo_id: i64) -> Result<()> {
-    // Load settings from SQLite to get history_days and excluded_bots
-    let (history_days, excluded_bots) = {
+/// Sync a single repository by ID (`full` ignores the incremental watermarks)
+pub async fn sync_single_repo(app: &AppHandle, state: &AppState, token: &str, repo_id: i64, full: bool) -> Result<()> {
+    let _sync = state.begin_sync()?;
+
+    // Load settings from SQLite to get history_days, excluded_bots and sync_old_reopened
+    let (history_days, excluded_bots, sync_old_reopened) = {
         let conn = state.sqlite.lock().unwrap();
         let settings = queries::get_settings(&conn)?;
-        (settings.history_days, settings.excluded_bots)
+        (settings.history_days, settings.excluded_bots, settings.sync_old_reopened)
     };
 
     // Get the repository from database
     let repo = {
         let conn = state.sqlite.lock().unwrap();
+        if queries::is_demo_repository(&conn, repo_id)? {
+            anyhow::bail!("Repository {} holds demo data and can't be synced", repo_id);
+        }
         queries::get_repository_by_id(&conn, repo_id)?
     };
 
@@ -97,19 +121,33 @@ pub async fn sync_single_repo(app: &AppHandle, state: &AppState, token: &str, re
     tracing::info!("Starting sync for {}/{}", repo.owner, repo.name);
     emit_progress(app, "syncing", 1, 1, &format!("Syncing {}/{}", repo.owner, repo.name));
 
+    // Refresh repository metadata (default branch, renames/transfers)
+    let (owner, name) = match sync_repository_info(state, token, repo.id, &repo.owner, &repo.name).await {
+        Ok(path) => path,
+        Err(e) => {
+            tracing::error!("Failed to sync repository info for {}/{}: {}", repo.owner, repo.name, e);
+            (repo.owner.clone(), repo.name.clone())
+        }
+    };
+
     // Sync milestones first (needed for issue references)
-    if let Err(e) = sync_milestones(state, token, repo.id, &repo.owner, &repo.name).await {
-        tracing::error!("Failed to sync milestones for {}/{}: {}", repo.owner, repo.name, e);
+    if let Err(e) = sync_milestones(state, token, repo.id, &owner, &name).await {
+        tracing::error!("Failed to sync milestones for {}/{}: {}", owner, name, e);
     }
 
     // Sync issues
-    if let Err(e) = sync_issues(state, token, repo.id, &repo.owner, &repo.name, &since, &excluded_bots).await {
-        tracing::error!("Failed to sync issues for {}/{}: {}", repo.owner, repo.name, e);
+    if let Err(e) = sync_issues(state, token, repo.id, &owner, &name, &since, old_item_cutoff(&since, sync_old_reopened), &excluded_bots, full).await {
+        tracing::error!("Failed to sync issues for {}/{}: {}", owner, name, e);
     }
 
     // Sync PRs
-    if let Err(e) = sync_pull_requests(state, token, repo.id, &repo.owner, &repo.name, &excluded_bots).await {
-        tracing::error!("Failed to sync PRs for {}/{}: {}", repo.owner, repo.name, e);
+    if let Err(e) = sync_pull_requests(state, token, repo.id, &owner, &name, &excluded_bots, full).await {
+        tracing::error!("Failed to sync PRs for {}/{}: {}", owner, name, e);
+    }
+
+    // Sync default-branch commits
+    if let Err(e) = sync_commits(state, token, repo.id, &owner, &name, &since, &excluded_bots).await {
+        tracing::error!("Failed to sync commits for {}/{}: {}", owner, name, e);
     }
 
     // Update last synced timestamp
@@ -129,7 +167,7 @@ pub async fn sync_single_repo(app: &AppHandle, state: &AppState, token: &str, re
     }
 
     emit_progress(app, "complete", 1, 1, "Sync complete!");
-    tracing::info!("Sync completed successfully for {}/{}", repo.owner, repo.name);
+    tracing::info!("Sync completed successfully for {}/{}", owner, name);
     Ok(())
 }
 
@@ -154,7 +192,27 @@ pub async fn generate_embeddings_for_new_items(app: &AppHandle, state: &AppState
         queries::get_prs_without_embeddings(&conn, BATCH_SIZE)?
     };
 
-    let total_items = issues_to_process.len() + prs_to_process.len();
+    // Near-empty items are marked skipped so they stay out of vector search; keyword search still finds them
+    let (issue_texts, pr_texts) = {
+        let conn = state.sqlite.lock().unwrap();
+        let issue_texts = embeddable_texts(
+            &conn,
+            "issues",
+            issues_to_process
+                .iter()
+                .map(|issue| (issue.id, generator::prepare_issue_text(&issue.title, &issue.body))),
+        )?;
+        let pr_texts = embeddable_texts(
+            &conn,
+            "pull_requests",
+            prs_to_process
+                .iter()
+                .map(|pr| (pr.id, generator::prepare_pr_text(&pr.title, &pr.body))),
+        )?;
+        (issue_texts, pr_texts)
+    };
+
+    let total_items = issue_texts.len() + pr_texts.len();
 
     if total_items == 0 {
         tracing::info!("No items need embeddings");
@@ -164,17 +222,54 @@ pub async fn generate_embeddings_for_new_items(app: &AppHandle, state: &AppState
     tracing::info!("Generating embeddings for {} items", total_items);
     emit_progress(app, "embeddings", 0, total_items, &format!("Generating embeddings for {} items...", total_items));
 
+    // Offload to the configured embedding endpoint, batching requests in parallel
+    let http_client = {
+        let conn = state.sqlite.lock().unwrap();
+        HttpEmbeddingClient::from_settings(&queries::get_settings(&conn)?)
+    };
+    if let Some(client) = http_client {
+        // The client validates responses against the active model; each batch is stored as it arrives
+        let texts = |items: &[(i64, String)]| items.iter().map(|(_, text)| text.clone()).collect::<Vec<_>>();
+        let mut processed = 0;
+        let issues_result = client
+            .embed_each(&texts(&issue_texts), |offset, embeddings| {
+                let conn = state.sqlite.lock().unwrap();
+                for ((issue_id, _), embedding) in issue_texts[offset..].iter().zip(&embeddings) {
+                    queries::set_issue_embedding(&conn, *issue_id, embedding, ACTIVE_EMBEDDING_MODEL)
+                        .context("Failed to store issue embedding")?;
+                }
+                processed += embeddings.len();
+                Ok(())
+            })
+            .await;
+        let prs_result = client
+            .embed_each(&texts(&pr_texts), |offset, embeddings| {
+                let conn = state.sqlite.lock().unwrap();
+                for ((pr_id, _), embedding) in pr_texts[offset..].iter().zip(&embeddings) {
+                    queries::set_pr_embedding(&conn, *pr_id, embedding, ACTIVE_EMBEDDING_MODEL)
+                        .context("Failed to store PR embedding")?;
+                }
+                processed += embeddings.len();
+                Ok(())
+            })
+            .await;
+
+        tracing::info!("Generated {}/{} embeddings via HTTP", processed, total_items);
+        emit_progress(app, "embeddings", processed, total_items, &format!("Generated {} embeddings", processed));
+        issues_result?;
+        prs_result?;
+        return Ok(());
+    }
+
     let mut processed = 0;
 
     // Process issues
-    for issue in issues_to_process {
-        let text = generator::prepare_issue_text(&issue.title, &issue.body);
-
+    for (issue_id, text) in issue_texts {
         match generate_embeddings(&[text]) {
             Ok(embeddings) => {
                 if let Some(embedding) = embeddings.first() {
                     let conn = state.sqlite.lock().unwrap();
-                    queries::set_issue_embedding(&conn, issue.id, embedding)
+                    queries::set_issue_embedding(&conn, issue_id, embedding, ACTIVE_EMBEDDING_MODEL)
                         .context("Failed to store issue embedding")?;
                     processed += 1;
 
@@ -182,25 +277,23 @@ pub async fn generate_embeddings_for_new_items(app: &AppHandle, state: &AppState
                         emit_progress(app, "embeddings", processed, total_items, &format!("Generated {}/{} embeddings...", processed, total_items));
                     }
                 } else {
-                    tracing::warn!("No embedding generated for issue {}", issue.id);
+                    tracing::warn!("No embedding generated for issue {}", issue_id);
                 }
             }
             Err(e) => {
-                tracing::error!("Failed to generate embedding for issue {}: {}", issue.id, e);
+                tracing::error!("Failed to generate embedding for issue {}: {}", issue_id, e);
                 // Continue processing other items
             }
         }
     }
 
     // Process PRs
-    for pr in prs_to_process {
-        let text = generator::prepare_pr_text(&pr.title, &pr.body);
-
+    for (pr_id, text) in pr_texts {
         match generate_embeddings(&[text]) {
             Ok(embeddings) => {
                 if let Some(embedding) = embeddings.first() {
                     let conn = state.sqlite.lock().unwrap();
-                    queries::set_pr_embedding(&conn, pr.id, embedding)
+                    queries::set_pr_embedding(&conn, pr_id, embedding, ACTIVE_EMBEDDING_MODEL)
                         .context("Failed to store PR embedding")?;
                     processed += 1;
 
@@ -208,11 +301,11 @@ pub async fn generate_embeddings_for_new_items(app: &AppHandle, state: &AppState
                         emit_progress(app, "embeddings", processed, total_items, &format!("Generated {}/{} embeddings...", processed, total_items));
                     }
                 } else {
-                    tracing::warn!("No embedding generated for PR {}", pr.id);
+                    tracing::warn!("No embedding generated for PR {}", pr_id);
                 }
             }
             Err(e) => {
-                tracing::error!("Failed to generate embedding for PR {}: {}", pr.id, e);
+                tracing::error!("Failed to generate embedding for PR {}: {}", pr_id, e);
                 // Continue processing other items
             }
         }
@@ -224,6 +317,25 @@ pub async fn generate_embeddings_for_new_items(app: &AppHandle, state: &AppState
     Ok(())
 }
 
+/// Keep items with enough text to embed; mark the rest as skipped so they aren't picked up again
+fn embeddable_texts(
+    conn: &rusqlite::Connection,
+    table: &str,
+    items: impl Iterator<Item = (i64, Option<String>)>,
+) -> Result<Vec<(i64, String)>> {
+    let mut texts = Vec::new();
+    for (id, text) in items {
+        match text {
+            Some(text) => texts.push((id, text)),
+            None => {
+                tracing::debug!("Skipping embedding for {} {}: too little text", table, id);
+                queries::mark_embedding_skipped(conn, table, id)?;
+            }
+        }
+    }
+    Ok(texts)
+}
+
 fn emit_progress(app: &AppHandle, phase: &str, current: usize, total: usize, message: &str) {
     app.emit_all(
         "sync-progress",
@@ -245,6 +357,80 @@ pub struct SyncProgress {
     pub message: String,
 }
 
+// ============================================================================
+// SYNC STRATEGY
+// ============================================================================
+
+/// A way of fetching repository data, tried in the order given by the sync_strategy setting
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncMethod {
+    Graphql,
+    Rest,
+    Cli,
+}
+
+impl SyncMethod {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "graphql" => Some(Self::Graphql),
+            "rest" => Some(Self::Rest),
+            "cli" => Some(Self::Cli),
+            _ => None,
+        }
+    }
+}
+
+/// Default order when no valid strategy is configured
+const DEFAULT_SYNC_STRATEGY: [SyncMethod; 3] = [SyncMethod::Graphql, SyncMethod::Rest, SyncMethod::Cli];
+
+/// Ordered sync methods from the setting, ignoring unknown entries and repeats
+pub fn parse_sync_strategy(strategy: &[String]) -> Vec<SyncMethod> {
+    let mut methods = Vec::new();
+    for method in strategy.iter().filter_map(|m| SyncMethod::parse(m)) {
+        if !methods.contains(&method) {
+            methods.push(method);
+        }
+    }
+    if methods.is_empty() {
+        methods.extend(DEFAULT_SYNC_STRATEGY);
+    }
+    methods
+}
+
+fn load_sync_strategy(state: &AppState) -> Result<Vec<SyncMethod>> {
+    let conn = state.sqlite.lock().unwrap();
+    Ok(parse_sync_strategy(&queries::get_settings(&conn)?.sync_strategy))
+}
+
+/// Error once every configured method has failed, with hints for fixing access
+fn all_methods_failed(kind: &str, owner: &str, name: &str, last_error: Option<anyhow::Error>) -> anyhow::Error {
+    tracing::warn!("   All sync methods failed for {} in {}/{}. Please ensure:", kind, owner, name);
+    tracing::warn!("   1. You have access to this repository");
+    tracing::warn!("   2. GitHub CLI is installed and authenticated: gh auth login");
+    tracing::warn!("   3. For SAML-protected repos: gh auth status");
+    last_error.unwrap_or_else(|| anyhow::anyhow!("No sync methods configured"))
+}
+
+/// Run `attempt` with each method in order until one succeeds. Methods missing from the
+/// strategy are never attempted.
+async fn run_sync_strategy<F, Fut>(methods: &[SyncMethod], kind: &str, owner: &str, name: &str, mut attempt: F) -> Result<()>
+where
+    F: FnMut(SyncMethod) -> Fut,
+    Fut: std::future::Future<Output = Result<()>>,
+{
+    let mut last_error = None;
+    for &method in methods {
+        match attempt(method).await {
+            Ok(()) => return Ok(()),
+            Err(e) => {
+                tracing::warn!("⚠️  {:?} sync of {} failed for {}/{}: {}", method, kind, owner, name, e);
+                last_error = Some(e);
+            }
+        }
+    }
+    Err(all_methods_failed(kind, owner, name, last_error))
+}
+
 async fn sync_issues(
     state: &AppState,
     token: &str,
@@ -252,10 +438,72 @@ async fn sync_issues(
     owner: &str,
     name: &str,
     since: &str,
+    old_item_cutoff: Option<&str>,
+    excluded_bots: &[String],
+    full: bool,
+) -> Result<()> {
+    run_sync_strategy(&load_sync_strategy(state)?, "issues", owner, name, |method| async move {
+        match method {
+            SyncMethod::Graphql => {
+                sync_issues_graphql(state, token, repo_id, owner, name, since, old_item_cutoff, excluded_bots, full).await
+            }
+            SyncMethod::Rest => sync_issues_rest(state, token, repo_id, owner, name, since, old_item_cutoff, excluded_bots).await,
+            SyncMethod::Cli => sync_issues_cli(state, repo_id, owner, name, excluded_bots).await,
+        }
+    })
+    .await
+}
+
+async fn sync_pull_requests(
+    state: &AppState,
+    token: &str,
+    repo_id: i64,
+    owner: &str,
+    name: &str,
+    excluded_bots: &[String],
+    full: bool,
+) -> Result<()> {
+    run_sync_strategy(&load_sync_strategy(state)?, "PRs", owner, name, |method| async move {
+        match method {
+            SyncMethod::Graphql => sync_pull_requests_graphql(state, token, repo_id, owner, name, excluded_bots, full).await,
+            SyncMethod::Rest => sync_pull_requests_rest(state, token, repo_id, owner, name, excluded_bots).await,
+            SyncMethod::Cli => sync_pull_requests_cli(state, repo_id, owner, name, excluded_bots).await,
+        }
+    })
+    .await
+}
+
+async fn sync_milestones(state: &AppState, token: &str, repo_id: i64, owner: &str, name: &str) -> Result<()> {
+    run_sync_strategy(&load_sync_strategy(state)?, "milestones", owner, name, |method| async move {
+        match method {
+            SyncMethod::Graphql => sync_milestones_graphql(state, token, repo_id, owner, name).await,
+            SyncMethod::Rest => sync_milestones_rest(state, token, repo_id, owner, name).await,
+            SyncMethod::Cli => sync_milestones_cli(state, repo_id, owner, name).await,
+        }
+    })
+    .await
+}
+
+async fn sync_issues_graphql(
+    state: &AppState,
+    token: &str,
+    repo_id: i64,
+    owner: &str,
+    name: &str,
+    since: &str,
+    old_item_cutoff: Option<&str>,
     excluded_bots: &[String],
+    full: bool,
 ) -> Result<()> {
     tracing::info!("Syncing issues for {}/{}", owner, name);
 
+    // Merge repo-specific bot exclusions into the global list
+    let repo_excluded_bots = {
+        let conn = state.sqlite.lock().unwrap();
+        queries::get_repo_excluded_bots(&conn, repo_id, excluded_bots)?
+    };
+    let excluded_bots = repo_excluded_bots.as_slice();
+
     // Record sync start
     let log_id = {
         let conn = state.sqlite.lock().unwrap();
@@ -268,12 +516,13 @@ async fn sync_issues(
         queries::get_issues_watermark(&conn, repo_id)?
     };
 
-    // Use watermark if available, otherwise fall back to provided since
-    let effective_since = watermark_since.as_deref().unwrap_or(since);
-    tracing::info!("Using since={} for issues (watermark: {:?})", effective_since, watermark_since);
+    // Use watermark if available (and this isn't a full sync), otherwise fall back to provided since
+    let effective_since = effective_since(watermark_since.as_deref(), since, full);
+    tracing::info!("Using since={} for issues (watermark: {:?}, full: {})", effective_since, watermark_since, full);
 
     let mut cursor: Option<String> = None;
     let mut total_synced = 0;
+    let mut skipped_old = 0;
 
     loop {
         let variables = serde_json::json!({
@@ -285,15 +534,6 @@ async fn sync_issues(
 
         let response: IssuesResponse = match graphql::execute_query(token, ISSUES_QUERY, variables).await {
             Ok(resp) => resp,
-            Err(GraphQLExecuteError::SamlRequired { owner: err_owner, repo: err_repo, org }) => {
-                tracing::warn!(
-                    "⚠️  SAML SSO required for {}/{}, trying REST API fallback...",
-                    err_owner, err_repo
-                );
-
-                // Try REST API fallback
-                return sync_issues_rest_fallback(state, token, repo_id, owner, name, since, excluded_bots).await;
-            }
             Err(e) => {
                 return Err(anyhow::anyhow!("GraphQL error: {}", e));
             }
@@ -307,6 +547,12 @@ async fn sync_issues(
                     continue;
                 }
             }
+
+            // Skip items created before the history window that were only touched recently
+            if should_skip_old_item(&issue_node.created_at, old_item_cutoff) {
+                skipped_old += 1;
+                continue;
+            }
             
             // Get or create author
             let author_id = if let Some(author) = &issue_node.author {
@@ -348,7 +594,7 @@ async fn sync_issues(
             // Upsert issue
             {
                 let conn = state.sqlite.lock().unwrap();
-                queries::upsert_issue(
+                let issue_id = queries::upsert_issue(
                     &conn,
                     issue_node.database_id,
                     repo_id,
@@ -365,6 +611,16 @@ async fn sync_issues(
                     &labels,
                     &issue_node.updated_at, // Use updated_at as sync_updated_at
                 )?;
+                if issue_node.comments.is_some() {
+                    queries::set_issue_first_response(
+                        &conn,
+                        issue_node.database_id,
+                        first_human_response(issue_node, excluded_bots),
+                    )?;
+                }
+                if issue_node.label_events.is_some() {
+                    queries::replace_issue_label_events(&conn, issue_id, &issue_node.label_event_rows())?;
+                }
             }
             
             total_synced += 1;
@@ -381,34 +637,337 @@ async fn sync_issues(
     {
         let conn = state.sqlite.lock().unwrap();
         queries::record_sync_complete(&conn, log_id, total_synced)?;
+        record_skipped_old(&conn, log_id, skipped_old, owner, name)?;
     }
     
     tracing::info!("Synced {} issues for {}/{}", total_synced, owner, name);
     Ok(())
 }
 
-async fn sync_pull_requests(
+/// Fetch and store the changed-file list of a single PR
+async fn sync_pr_file_list(
+    state: &AppState,
+    token: &str,
+    pr_id: i64,
+    owner: &str,
+    name: &str,
+    number: i32,
+) -> Result<()> {
+    let mut cursor: Option<String> = None;
+    let mut files = Vec::new();
+
+    loop {
+        let variables = serde_json::json!({
+            "owner": owner,
+            "name": name,
+            "number": number,
+            "cursor": cursor
+        });
+
+        let response: PrFilesResponse = graphql::execute_query(token, PR_FILES_QUERY, variables)
+            .await
+            .map_err(|e| anyhow::anyhow!("GraphQL error: {}", e))?;
+
+        let Some(connection) = response.repository.pull_request.and_then(|pr| pr.files) else {
+            break;
+        };
+
+        files.extend(connection.nodes.into_iter().map(|node| path_owner_queries::PrFile {
+            path: node.path,
+            additions: node.additions,
+            deletions: node.deletions,
+        }));
+
+        if connection.page_info.has_next_page {
+            cursor = connection.page_info.end_cursor;
+        } else {
+            break;
+        }
+    }
+
+    let conn = state.sqlite.lock().unwrap();
+    path_owner_queries::replace_pr_files(&conn, pr_id, &files)
+}
+
+/// Sync commits on the repository's default branch since the start of the history window
+async fn sync_commits(
+    state: &AppState,
+    token: &str,
+    repo_id: i64,
+    owner: &str,
+    name: &str,
+    since: &str,
+    excluded_bots: &[String],
+) -> Result<()> {
+    let (log_id, branch) = {
+        let conn = state.sqlite.lock().unwrap();
+        let branch = queries::get_repository_by_id(&conn, repo_id)?
+            .default_branch
+            .unwrap_or_else(|| queries::DEFAULT_BRANCH.to_string());
+        (queries::record_sync_start(&conn, repo_id, "commits")?, branch)
+    };
+
+    let mut cursor: Option<String> = None;
+    let mut total_synced = 0;
+    let mut merge_commits = 0;
+
+    loop {
+        let variables = commit_history_variables(owner, name, &branch, cursor.as_deref(), Some(since));
+        let response: CommitHistoryResponse = match graphql::execute_query(token, COMMIT_HISTORY_QUERY, variables).await {
+            Ok(response) => response,
+            Err(e) => {
+                let conn = state.sqlite.lock().unwrap();
+                queries::record_sync_error(&conn, log_id, &e.to_string())?;
+                return Err(anyhow::anyhow!("GraphQL error: {}", e));
+            }
+        };
+
+        let Some(history) = response
+            .repository
+            .git_ref
+            .and_then(|r| r.target)
+            .and_then(|t| t.history)
+        else {
+            break;
+        };
+
+        {
+            let conn = state.sqlite.lock().unwrap();
+            for commit in &history.nodes {
+                let user = commit.author.as_ref().and_then(|a| a.user.as_ref());
+                if user.is_some_and(|u| is_bot_user(&u.login, excluded_bots)) {
+                    continue;
+                }
+                let author_id = match user {
+                    Some(CommitUser { login, database_id: Some(github_id) }) => Some(
+                        queries::get_or_create_user(&conn, *github_id, login, None, None, None, None, None, Some(true))?,
+                    ),
+                    _ => None,
+                };
+
+                queries::upsert_commit(
+                    &conn,
+                    repo_id,
+                    &commit.oid,
+                    author_id,
+                    &commit.message_headline,
+                    &commit.committed_date,
+                    commit.additions,
+                    commit.deletions,
+                    commit.is_merge(),
+                )?;
+                total_synced += 1;
+                if commit.is_merge() {
+                    merge_commits += 1;
+                }
+            }
+        }
+
+        if history.page_info.has_next_page {
+            cursor = history.page_info.end_cursor;
+        } else {
+            break;
+        }
+    }
+
+    let conn = state.sqlite.lock().unwrap();
+    queries::record_sync_complete(&conn, log_id, total_synced)?;
+
+    tracing::info!(
+        "Synced {} commits ({} merge commits) on {} for {}/{}",
+        total_synced, merge_commits, branch, owner, name
+    );
+    Ok(())
+}
+
+/// Start of the incremental fetch: the stored watermark, unless there is none or a full
+/// sync was requested, in which case the whole history window is fetched
+fn effective_since<'a>(watermark: Option<&'a str>, window_start: &'a str, full: bool) -> &'a str {
+    match watermark {
+        Some(watermark) if !full => watermark,
+        _ => window_start,
+    }
+}
+
+/// Creation cutoff for skipping old items, or None when `sync_old_reopened` keeps them
+fn old_item_cutoff(window_start: &str, sync_old_reopened: bool) -> Option<&str> {
+    (!sync_old_reopened).then_some(window_start)
+}
+
+/// Whether an item created before the cutoff should be skipped even though it
+/// was updated recently (e.g. a bot relabelling an old closed issue)
+fn should_skip_old_item(created_at: &str, cutoff: Option<&str>) -> bool {
+    let Some(cutoff) = cutoff else {
+        return false;
+    };
+
+    match (
+        chrono::DateTime::parse_from_rfc3339(created_at),
+        chrono::DateTime::parse_from_rfc3339(cutoff),
+    ) {
+        (Ok(created), Ok(start)) => created < start,
+        _ => false,
+    }
+}
+
+/// Record and log issues skipped for predating the history window, whichever method synced them
+fn record_skipped_old(conn: &rusqlite::Connection, log_id: i64, skipped: i32, owner: &str, name: &str) -> Result<()> {
+    if skipped > 0 {
+        tracing::info!("Skipped {} issues created before the history window in {}/{}", skipped, owner, name);
+    }
+    queries::record_sync_skipped_old(conn, log_id, skipped)
+}
+
+/// Requests kept in reserve before pausing until the rate limit window resets
+const RATE_LIMIT_RESERVE: u32 = 100;
+
+/// Delay between per-PR detail requests when the budget is healthy
+const DETAIL_REQUEST_DELAY_MS: u64 = 250;
+
+/// Re-fetch LOC and merge data for PRs that are missing it, one PR at a time.
+/// Returns the number of PRs updated.
+pub async fn refresh_pr_details(state: &AppState, token: &str, repo_id: i64) -> Result<usize> {
+    let _sync = state.begin_sync()?;
+    let (repo, prs) = {
+        let conn = state.sqlite.lock().unwrap();
+        (
+            queries::get_repository_by_id(&conn, repo_id)?,
+            queries::get_prs_missing_details(&conn, repo_id)?,
+        )
+    };
+
+    tracing::info!("Refreshing details for {} PRs in {}/{}", prs.len(), repo.owner, repo.name);
+
+    let mut updated = 0;
+    for (pr_id, number) in prs {
+        let (pr, rate_limit) = rest_api::fetch_pull_request_rest(token, &repo.owner, &repo.name, number)
+            .await
+            .with_context(|| format!("Failed to fetch PR #{} in {}/{}", number, repo.owner, repo.name))?;
+
+        {
+            let conn = state.sqlite.lock().unwrap();
+            apply_pr_details(&conn, pr_id, &pr)?;
+        }
+        updated += 1;
+
+        tokio::time::sleep(detail_request_delay(rate_limit, Utc::now().timestamp())).await;
+    }
+
+    tracing::info!("Refreshed details for {} PRs in {}/{}", updated, repo.owner, repo.name);
+    Ok(updated)
+}
+
+/// Fill in name/avatar/company for up to `limit` users missing them, one REST
+/// `users/{login}` lookup each, pacing requests against the rate limit
+pub async fn enrich_users(state: &AppState, token: &str, refresh_days: i64, limit: i64) -> Result<usize> {
+    let _sync = state.begin_sync()?;
+    let users = {
+        let conn = state.sqlite.lock().unwrap();
+        crate::db::user_queries::get_users_missing_profile(&conn, refresh_days, limit)?
+    };
+
+    tracing::info!("Enriching profiles for {} users", users.len());
+
+    let mut enriched = 0;
+    for (user_id, login) in users {
+        let (profile, rate_limit) = rest_api::fetch_user_rest(token, &login)
+            .await
+            .with_context(|| format!("Failed to fetch profile for {}", login))?;
+
+        {
+            let conn = state.sqlite.lock().unwrap();
+            match &profile {
+                Some(profile) => {
+                    apply_user_profile(&conn, user_id, profile)?;
+                    enriched += 1;
+                }
+                None => {
+                    // Deleted account: remember the lookup so it isn't retried every run
+                    tracing::warn!("GitHub user {} no longer exists", login);
+                    crate::db::user_queries::update_user_profile(&conn, user_id, None, None, None)?;
+                }
+            }
+        }
+
+        tokio::time::sleep(detail_request_delay(rate_limit, Utc::now().timestamp())).await;
+    }
+
+    tracing::info!("Enriched {} user profiles", enriched);
+    Ok(enriched)
+}
+
+/// Copy profile fields from a REST user onto the stored row
+fn apply_user_profile(conn: &rusqlite::Connection, user_id: i64, profile: &rest_api::RestUserProfile) -> Result<()> {
+    crate::db::user_queries::update_user_profile(
+        conn,
+        user_id,
+        profile.name.as_deref(),
+        profile.avatar_url.as_deref(),
+        profile.company.as_deref(),
+    )
+}
+
+/// Copy LOC/merge fields from a REST pull request onto the stored row
+fn apply_pr_details(conn: &rusqlite::Connection, pr_id: i64, pr: &rest_api::RestPullRequest) -> Result<()> {
+    queries::update_pr_details(
+        conn,
+        pr_id,
+        pr.additions.unwrap_or(0),
+        pr.deletions.unwrap_or(0),
+        pr.changed_files.unwrap_or(0),
+        pr.merged_at.as_deref(),
+    )
+}
+
+/// How long to wait before the next request: wait for the reset when the budget is nearly spent
+fn detail_request_delay(rate_limit: Option<rest_api::RateLimit>, now: i64) -> std::time::Duration {
+    match rate_limit {
+        Some(limit) if limit.remaining <= RATE_LIMIT_RESERVE => {
+            let wait_secs = (limit.reset_at - now).max(0) as u64 + 1;
+            tracing::warn!("REST rate limit nearly exhausted ({} left), waiting {}s", limit.remaining, wait_secs);
+            std::time::Duration::from_secs(wait_secs)
+        }
+        _ => std::time::Duration::from_millis(DETAIL_REQUEST_DELAY_MS),
+    }
+}
+
+async fn sync_pull_requests_graphql(
     state: &AppState,
     token: &str,
     repo_id: i64,
     owner: &str,
     name: &str,
     excluded_bots: &[String],
+    full: bool,
 ) -> Result<()> {
     tracing::info!("Syncing PRs for {}/{}", owner, name);
 
+    // Merge repo-specific bot exclusions into the global list
+    let repo_excluded_bots = {
+        let conn = state.sqlite.lock().unwrap();
+        queries::get_repo_excluded_bots(&conn, repo_id, excluded_bots)?
+    };
+    let excluded_bots = repo_excluded_bots.as_slice();
+
     let log_id = {
         let conn = state.sqlite.lock().unwrap();
         queries::record_sync_start(&conn, repo_id, "pull_requests")?
     };
 
     // Get watermark for PRs (note: PRs query doesn't support 'since' filter like issues, so we rely on upsert guards)
-    let watermark = {
+    let watermark = if full {
+        None
+    } else {
         let conn = state.sqlite.lock().unwrap();
         queries::get_prs_watermark(&conn, repo_id)?
     };
     tracing::info!("PR watermark for {}/{}: {:?}", owner, name, watermark);
 
+    let sync_pr_files = {
+        let conn = state.sqlite.lock().unwrap();
+        queries::get_settings(&conn)?.sync_pr_files
+    };
+
     let mut cursor: Option<String> = None;
     let mut total_synced = 0;
 
@@ -421,15 +980,6 @@ async fn sync_pull_requests(
 
         let response: PullRequestsResponse = match graphql::execute_query(token, PULL_REQUESTS_QUERY, variables).await {
             Ok(resp) => resp,
-            Err(GraphQLExecuteError::SamlRequired { owner: err_owner, repo: err_repo, org: _org }) => {
-                tracing::warn!(
-                    "⚠️  SAML SSO required for {}/{}, trying REST API fallback...",
-                    err_owner, err_repo
-                );
-
-                // Try REST API fallback
-                return sync_pull_requests_rest_fallback(state, token, repo_id, owner, name, excluded_bots).await;
-            }
             Err(e) => {
                 return Err(anyhow::anyhow!("GraphQL error: {}", e));
             }
@@ -456,6 +1006,15 @@ async fn sync_pull_requests(
                 None
             };
             
+            // Get or create assignees
+            let mut assignee_ids = Vec::new();
+            for assignee in pr_node.assignees.iter().flat_map(|a| a.nodes.iter()) {
+                if let Some(github_id) = assignee.database_id {
+                    let conn = state.sqlite.lock().unwrap();
+                    assignee_ids.push(queries::get_or_create_user(&conn, github_id, &assignee.login, None, assignee.avatar_url.as_deref(), None, None, None, Some(true))?);
+                }
+            }
+
             // Extract labels
             let labels: Vec<String> = pr_node.labels.nodes.iter()
                 .map(|l| l.name.clone())
@@ -464,7 +1023,7 @@ async fn sync_pull_requests(
             // Upsert PR
             let pr_id = {
                 let conn = state.sqlite.lock().unwrap();
-                queries::upsert_pull_request(
+                let pr_id = queries::upsert_pull_request(
                     &conn,
                     pr_node.database_id,
                     repo_id,
@@ -482,8 +1041,40 @@ async fn sync_pull_requests(
                     pr_node.changed_files,
                     &labels,
                     &pr_node.updated_at, // Use updated_at as sync_updated_at
-                )?
+                )?;
+                queries::set_pr_merge_method(&conn, pr_id, pr_node.merge_method())?;
+                queries::set_pr_blocking_state(&conn, pr_id, pr_node.is_draft, pr_node.mergeable.as_deref())?;
+                queries::set_pr_base_branch(&conn, pr_id, pr_node.base_ref_name.as_deref())?;
+                queries::set_pr_author_association(&conn, pr_id, pr_node.author_association.as_deref())?;
+                queries::set_pr_commit_count(&conn, pr_id, pr_node.commits.as_ref().map(|c| c.total_count))?;
+                let closing_issues: Vec<i32> = pr_node.closing_issues_references.iter()
+                    .flat_map(|refs| refs.nodes.iter().map(|issue| issue.number))
+                    .collect();
+                queries::replace_pr_issue_links(&conn, pr_id, &closing_issues)?;
+                if pr_node.assignees.is_some() {
+                    queries::replace_pr_assignees(&conn, pr_id, &assignee_ids)?;
+                }
+                if let Some(commits) = &pr_node.commits {
+                    let commit_times: Vec<(&str, &str)> = commits.nodes.iter()
+                        .map(|node| (node.commit.oid.as_str(), node.commit.committed_date.as_str()))
+                        .collect();
+                    queries::replace_pr_commits(&conn, pr_id, &commit_times)?;
+                }
+                pr_id
             };
+
+            // Sync changed files (opt-in); closed PRs keep their stored list
+            if sync_pr_files {
+                let needs_files = pr_node.state == "OPEN" || {
+                    let conn = state.sqlite.lock().unwrap();
+                    !path_owner_queries::has_pr_files(&conn, pr_id)?
+                };
+                if needs_files {
+                    if let Err(e) = sync_pr_file_list(state, token, pr_id, owner, name, pr_node.number).await {
+                        tracing::warn!("Failed to sync files for PR #{} in {}/{}: {}", pr_node.number, owner, name, e);
+                    }
+                }
+            }
             
             // Sync reviews for this PR
             for review in &pr_node.reviews.nodes {
@@ -531,7 +1122,88 @@ async fn sync_pull_requests(
     Ok(())
 }
 
-async fn sync_milestones(
+/// Sync repository metadata such as the default branch and canonical path.
+/// Returns the owner/name to use for the rest of the sync (updated if the repo was renamed/transferred).
+async fn sync_repository_info(
+    state: &AppState,
+    token: &str,
+    repo_id: i64,
+    owner: &str,
+    name: &str,
+) -> Result<(String, String)> {
+    let variables = serde_json::json!({
+        "owner": owner,
+        "name": name
+    });
+
+    let response: RepositoryInfoResponse = graphql::execute_query(token, REPOSITORY_QUERY, variables)
+        .await
+        .map_err(|e| anyhow::anyhow!("GraphQL error: {}", e))?;
+
+    let conn = state.sqlite.lock().unwrap();
+    if let Some(branch) = response.repository.default_branch() {
+        queries::update_repo_default_branch(&conn, repo_id, branch)?;
+        tracing::info!("Default branch for {}/{} is '{}'", owner, name, branch);
+    }
+    if let Some(is_private) = response.repository.is_private {
+        queries::set_repo_private(&conn, repo_id, is_private)?;
+    }
+    queries::replace_repo_languages(
+        &conn,
+        repo_id,
+        response.repository.primary_language(),
+        &response.repository.language_sizes(),
+    )?;
+
+    match response.repository.name_with_owner.as_deref() {
+        Some(name_with_owner) => apply_repository_rename(&conn, repo_id, owner, name, name_with_owner),
+        None => Ok((owner.to_string(), name.to_string())),
+    }
+}
+
+/// Timestamp of the earliest comment left by someone other than the issue author or a bot
+fn first_human_response<'a>(issue: &'a IssueNode, excluded_bots: &[String]) -> Option<&'a str> {
+    let author = issue.author.as_ref().map(|a| a.login.as_str());
+
+    issue.comments.as_ref()?.nodes.iter()
+        .filter(|comment| match &comment.author {
+            Some(commenter) => {
+                Some(commenter.login.as_str()) != author
+                    && !commenter.login.ends_with("[bot]")
+                    && !is_bot_user(&commenter.login, excluded_bots)
+            }
+            None => false,
+        })
+        .map(|comment| comment.created_at.as_str())
+        .min()
+}
+
+/// Update the repository row in place when GitHub reports a different `owner/name`
+/// than the one we requested (GitHub follows renames and transfers transparently).
+/// The row id is preserved so all synced history stays attached.
+fn apply_repository_rename(
+    conn: &rusqlite::Connection,
+    repo_id: i64,
+    owner: &str,
+    name: &str,
+    name_with_owner: &str,
+) -> Result<(String, String)> {
+    let Some((new_owner, new_name)) = name_with_owner.split_once('/') else {
+        return Ok((owner.to_string(), name.to_string()));
+    };
+
+    if new_owner == owner && new_name == name {
+        return Ok((owner.to_string(), name.to_string()));
+    }
+
+    tracing::info!("Repository {}/{} moved to {}/{}, updating", owner, name, new_owner, new_name);
+    queries::update_repo_path(conn, repo_id, new_owner, new_name)
+        .with_context(|| format!("Failed to rename {}/{} to {}", owner, name, name_with_owner))?;
+
+    Ok((new_owner.to_string(), new_name.to_string()))
+}
+
+async fn sync_milestones_graphql(
     state: &AppState,
     token: &str,
     repo_id: i64,
@@ -552,15 +1224,6 @@ async fn sync_milestones(
 
     let response: MilestonesResponse = match graphql::execute_query(token, MILESTONES_QUERY, variables).await {
         Ok(resp) => resp,
-        Err(GraphQLExecuteError::SamlRequired { owner: err_owner, repo: err_repo, org: _org }) => {
-            tracing::warn!(
-                "⚠️  SAML SSO required for {}/{}, trying REST API fallback...",
-                err_owner, err_repo
-            );
-
-            // Try REST API fallback
-            return sync_milestones_rest_fallback(state, token, repo_id, owner, name).await;
-        }
         Err(e) => {
             return Err(anyhow::anyhow!("GraphQL error: {}", e));
         }
@@ -592,14 +1255,23 @@ async fn sync_milestones(
     Ok(())
 }
 
-/// REST API fallback for syncing issues when GraphQL fails due to SAML
-async fn sync_issues_rest_fallback(
+/// Drop pull requests from a REST issues payload; returns the real issues and how many PRs were removed
+fn split_rest_issues(items: Vec<rest_api::RestIssue>) -> (Vec<rest_api::RestIssue>, usize) {
+    let total = items.len();
+    let issues: Vec<_> = items.into_iter().filter(|item| !item.is_pull_request()).collect();
+    let skipped = total - issues.len();
+    (issues, skipped)
+}
+
+/// Sync issues through the REST API (works for SAML-protected orgs without GraphQL access)
+async fn sync_issues_rest(
     state: &AppState,
     token: &str,
     repo_id: i64,
     owner: &str,
     name: &str,
     since: &str,
+    old_item_cutoff: Option<&str>,
     excluded_bots: &[String],
 ) -> Result<()> {
     tracing::info!("Using REST API fallback for issues in {}/{}", owner, name);
@@ -612,13 +1284,17 @@ async fn sync_issues_rest_fallback(
     match rest_api::fetch_issues_rest(token, owner, name, since).await {
         Ok(issues) => {
             let mut total_synced = 0;
+            let mut skipped_old = 0;
 
-            for issue in &issues {
-                // Skip pull requests (they come mixed in the issues endpoint)
-                if issue.pull_request.is_some() {
-                    continue;
-                }
+            // Pull requests come mixed in the issues endpoint
+            let (issues, skipped_prs) = split_rest_issues(issues);
+            if skipped_prs > 0 {
+                tracing::info!("Skipped {} pull requests from the REST issues endpoint for {}/{}", skipped_prs, owner, name);
+                let conn = state.sqlite.lock().unwrap();
+                queries::record_sync_skipped_prs(&conn, log_id, skipped_prs as i32)?;
+            }
 
+            for issue in &issues {
                 // Skip bot authors
                 if let Some(user) = &issue.user {
                     if is_bot_user(&user.login, excluded_bots) {
@@ -626,6 +1302,12 @@ async fn sync_issues_rest_fallback(
                     }
                 }
 
+                // Skip items created before the history window that were only touched recently
+                if should_skip_old_item(&issue.created_at, old_item_cutoff) {
+                    skipped_old += 1;
+                    continue;
+                }
+
                 // Get or create author
                 let author_id = if let Some(user) = &issue.user {
                     let conn = state.sqlite.lock().unwrap();
@@ -680,22 +1362,20 @@ async fn sync_issues_rest_fallback(
 
             let conn = state.sqlite.lock().unwrap();
             queries::record_sync_complete(&conn, log_id, total_synced)?;
+            record_skipped_old(&conn, log_id, skipped_old, owner, name)?;
 
             tracing::info!("✅ REST API fallback succeeded: Synced {} issues for {}/{}", total_synced, owner, name);
             Ok(())
         }
         Err(rest_error) => {
-            tracing::warn!("❌ REST API fallback failed for {}/{}: {}", owner, name, rest_error);
-            tracing::info!("⚙️  Trying GitHub CLI fallback...");
-
-            // Try CLI as final fallback
-            return sync_issues_cli_fallback(state, repo_id, owner, name, excluded_bots).await;
+            tracing::warn!("❌ REST API sync failed for {}/{}: {}", owner, name, rest_error);
+            Err(anyhow::anyhow!("REST API error: {}", rest_error))
         }
     }
 }
 
-/// REST API fallback for syncing pull requests when GraphQL fails due to SAML
-async fn sync_pull_requests_rest_fallback(
+/// Sync pull requests through the REST API
+async fn sync_pull_requests_rest(
     state: &AppState,
     token: &str,
     repo_id: i64,
@@ -736,7 +1416,7 @@ async fn sync_pull_requests_rest_fallback(
                 // Upsert PR
                 {
                     let conn = state.sqlite.lock().unwrap();
-                    queries::upsert_pull_request(
+                    let pr_id = queries::upsert_pull_request(
                         &conn,
                         pr.id,
                         repo_id,
@@ -755,6 +1435,8 @@ async fn sync_pull_requests_rest_fallback(
                         &labels,
                         &pr.updated_at, // Use updated_at as sync_updated_at
                     )?;
+                    queries::set_pr_base_branch(&conn, pr_id, pr.base.as_ref().map(|b| b.ref_name.as_str()))?;
+                    queries::set_pr_author_association(&conn, pr_id, pr.author_association.as_deref())?;
                 }
 
                 total_synced += 1;
@@ -767,17 +1449,14 @@ async fn sync_pull_requests_rest_fallback(
             Ok(())
         }
         Err(rest_error) => {
-            tracing::warn!("❌ REST API fallback failed for {}/{}: {}", owner, name, rest_error);
-            tracing::info!("⚙️  Trying GitHub CLI fallback...");
-
-            // Try CLI as final fallback
-            return sync_pull_requests_cli_fallback(state, repo_id, owner, name, excluded_bots).await;
+            tracing::warn!("❌ REST API sync failed for {}/{}: {}", owner, name, rest_error);
+            Err(anyhow::anyhow!("REST API error: {}", rest_error))
         }
     }
 }
 
-/// REST API fallback for syncing milestones when GraphQL fails due to SAML
-async fn sync_milestones_rest_fallback(
+/// Sync milestones through the REST API
+async fn sync_milestones_rest(
     state: &AppState,
     token: &str,
     repo_id: i64,
@@ -817,17 +1496,14 @@ async fn sync_milestones_rest_fallback(
             Ok(())
         }
         Err(rest_error) => {
-            tracing::warn!("❌ REST API fallback failed for {}/{}: {}", owner, name, rest_error);
-            tracing::info!("⚙️  Trying GitHub CLI fallback...");
-
-            // Try CLI as final fallback
-            return sync_milestones_cli_fallback(state, repo_id, owner, name).await;
+            tracing::warn!("❌ REST API sync failed for {}/{}: {}", owner, name, rest_error);
+            Err(anyhow::anyhow!("REST API error: {}", rest_error))
         }
     }
 }
 
-/// GitHub CLI fallback for syncing issues when both GraphQL and REST API fail
-async fn sync_issues_cli_fallback(
+/// Sync issues through the GitHub CLI (uses the CLI's own authentication)
+async fn sync_issues_cli(
     state: &AppState,
     repo_id: i64,
     owner: &str,
@@ -847,7 +1523,7 @@ async fn sync_issues_cli_fallback(
         Err(e) => {
             tracing::error!("❌ GitHub CLI not available: {}", e);
             tracing::warn!("   Install GitHub CLI from: https://cli.github.com");
-            return Ok(()); // Don't fail the entire sync
+            return Err(anyhow::anyhow!("GitHub CLI not available: {}", e));
         }
     };
 
@@ -855,7 +1531,7 @@ async fn sync_issues_cli_fallback(
     if let Err(e) = cli.check_auth() {
         tracing::error!("❌ GitHub CLI not authenticated: {}", e);
         tracing::warn!("   Run: gh auth login");
-        return Ok(()); // Don't fail the entire sync
+        return Err(anyhow::anyhow!("GitHub CLI not authenticated: {}", e));
     }
 
     match cli.fetch_issues(owner, name).await {
@@ -896,17 +1572,13 @@ async fn sync_issues_cli_fallback(
         }
         Err(e) => {
             tracing::error!("❌ GitHub CLI fallback failed for {}/{}: {}", owner, name, e);
-            tracing::warn!("   All sync methods failed. Please ensure:");
-            tracing::warn!("   1. You have access to this repository");
-            tracing::warn!("   2. GitHub CLI is installed and authenticated: gh auth login");
-            tracing::warn!("   3. For SAML-protected repos: gh auth status");
-            Ok(()) // Don't fail the entire sync
+                        Err(anyhow::anyhow!("GitHub CLI error: {}", e))
         }
     }
 }
 
-/// GitHub CLI fallback for syncing pull requests when both GraphQL and REST API fail
-async fn sync_pull_requests_cli_fallback(
+/// Sync pull requests through the GitHub CLI
+async fn sync_pull_requests_cli(
     state: &AppState,
     repo_id: i64,
     owner: &str,
@@ -926,7 +1598,7 @@ async fn sync_pull_requests_cli_fallback(
         Err(e) => {
             tracing::error!("❌ GitHub CLI not available: {}", e);
             tracing::warn!("   Install GitHub CLI from: https://cli.github.com");
-            return Ok(()); // Don't fail the entire sync
+            return Err(anyhow::anyhow!("GitHub CLI not available: {}", e));
         }
     };
 
@@ -934,7 +1606,7 @@ async fn sync_pull_requests_cli_fallback(
     if let Err(e) = cli.check_auth() {
         tracing::error!("❌ GitHub CLI not authenticated: {}", e);
         tracing::warn!("   Run: gh auth login");
-        return Ok(()); // Don't fail the entire sync
+        return Err(anyhow::anyhow!("GitHub CLI not authenticated: {}", e));
     }
 
     match cli.fetch_pull_requests_with_authors(owner, name).await {
@@ -1014,17 +1686,13 @@ async fn sync_pull_requests_cli_fallback(
         }
         Err(e) => {
             tracing::error!("❌ GitHub CLI fallback failed for {}/{}: {}", owner, name, e);
-            tracing::warn!("   All sync methods failed. Please ensure:");
-            tracing::warn!("   1. You have access to this repository");
-            tracing::warn!("   2. GitHub CLI is installed and authenticated: gh auth login");
-            tracing::warn!("   3. For SAML-protected repos: gh auth status");
-            Ok(()) // Don't fail the entire sync
+                        Err(anyhow::anyhow!("GitHub CLI error: {}", e))
         }
     }
 }
 
-/// GitHub CLI fallback for syncing milestones when both GraphQL and REST API fail
-async fn sync_milestones_cli_fallback(
+/// Sync milestones through the GitHub CLI
+async fn sync_milestones_cli(
     state: &AppState,
     repo_id: i64,
     owner: &str,
@@ -1043,7 +1711,7 @@ async fn sync_milestones_cli_fallback(
         Err(e) => {
             tracing::error!("❌ GitHub CLI not available: {}", e);
             tracing::warn!("   Install GitHub CLI from: https://cli.github.com");
-            return Ok(()); // Don't fail the entire sync
+            return Err(anyhow::anyhow!("GitHub CLI not available: {}", e));
         }
     };
 
@@ -1051,7 +1719,7 @@ async fn sync_milestones_cli_fallback(
     if let Err(e) = cli.check_auth() {
         tracing::error!("❌ GitHub CLI not authenticated: {}", e);
         tracing::warn!("   Run: gh auth login");
-        return Ok(()); // Don't fail the entire sync
+        return Err(anyhow::anyhow!("GitHub CLI not authenticated: {}", e));
     }
 
     match cli.fetch_milestones(owner, name).await {
@@ -1081,11 +1749,352 @@ async fn sync_milestones_cli_fallback(
         }
         Err(e) => {
             tracing::error!("❌ GitHub CLI fallback failed for {}/{}: {}", owner, name, e);
-            tracing::warn!("   All sync methods failed. Please ensure:");
-            tracing::warn!("   1. You have access to this repository");
-            tracing::warn!("   2. GitHub CLI is installed and authenticated: gh auth login");
-            tracing::warn!("   3. For SAML-protected repos: gh auth status");
-            Ok(()) // Don't fail the entire sync
+                        Err(anyhow::anyhow!("GitHub CLI error: {}", e))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_old_recently_updated_issue_is_skipped() {
+        let window_start = "2024-01-01T00:00:00Z";
+
+        // Created years ago, relabelled by a bot yesterday
+        assert!(should_skip_old_item("2020-06-01T12:00:00Z", old_item_cutoff(window_start, false)));
+        // Created inside the window
+        assert!(!should_skip_old_item("2024-02-01T12:00:00Z", old_item_cutoff(window_start, false)));
+    }
+
+    #[test]
+    fn test_full_sync_ignores_watermark() {
+        let window_start = "2024-01-01T00:00:00Z";
+        let watermark = Some("2024-05-20T08:00:00Z");
+
+        assert_eq!(effective_since(watermark, window_start, false), "2024-05-20T08:00:00Z");
+        assert_eq!(effective_since(watermark, window_start, true), window_start);
+        // Never-synced repos use the window either way
+        assert_eq!(effective_since(None, window_start, false), window_start);
+    }
+
+    #[test]
+    fn test_sync_old_reopened_override_keeps_old_issue() {
+        let window_start = "2024-01-01T00:00:00Z";
+
+        assert_eq!(old_item_cutoff(window_start, true), None);
+        assert!(!should_skip_old_item("2020-06-01T12:00:00Z", old_item_cutoff(window_start, true)));
+        // Unparseable timestamps are never skipped
+        assert!(!should_skip_old_item("not-a-date", old_item_cutoff(window_start, false)));
+    }
+
+    #[test]
+    fn test_skipped_old_issues_are_recorded_in_sync_log() {
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        crate::db::migrations::run_migrations(&conn).unwrap();
+        let repo_id = queries::upsert_repository(&conn, "acme", "api", None, true).unwrap();
+        let log_id = queries::record_sync_start(&conn, repo_id, "issues").unwrap();
+
+        record_skipped_old(&conn, log_id, 3, "acme", "api").unwrap();
+
+        let skipped: i32 = conn
+            .query_row("SELECT skipped_old FROM sync_log WHERE id = ?1", [log_id], |row| row.get(0))
+            .unwrap();
+        assert_eq!(skipped, 3);
+    }
+
+    #[test]
+    fn test_first_human_response_skips_author_and_bots() {
+        let issue: IssueNode = serde_json::from_value(serde_json::json!({
+            "id": "I_1",
+            "databaseId": 1,
+            "number": 1,
+            "title": "Crash on start",
+            "body": null,
+            "state": "OPEN",
+            "createdAt": "2024-01-01T00:00:00Z",
+            "updatedAt": "2024-01-03T00:00:00Z",
+            "closedAt": null,
+            "author": { "login": "alice" },
+            "assignees": { "nodes": [] },
+            "labels": { "nodes": [] },
+            "milestone": null,
+            "comments": { "nodes": [
+                { "createdAt": "2024-01-01T01:00:00Z", "author": { "login": "alice" } },
+                { "createdAt": "2024-01-01T02:00:00Z", "author": { "login": "triage-helper[bot]" } },
+                { "createdAt": "2024-01-01T03:00:00Z", "author": null },
+                { "createdAt": "2024-01-02T00:00:00Z", "author": { "login": "bob" } }
+            ]}
+        }))
+        .unwrap();
+
+        assert_eq!(first_human_response(&issue, &[]), Some("2024-01-02T00:00:00Z"));
+    }
+
+    #[test]
+    fn test_renamed_repository_is_updated_in_place() {
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        crate::db::migrations::run_migrations(&conn).unwrap();
+        let repo_id = queries::upsert_repository(&conn, "old-org", "widgets", None, true).unwrap();
+        conn.execute(
+            "INSERT INTO issues (github_id, repo_id, number, title, state, created_at, updated_at)
+             VALUES (1, ?1, 1, 'Issue', 'open', '2024-01-01T00:00:00Z', '2024-01-01T00:00:00Z')",
+            [repo_id],
+        )
+        .unwrap();
+
+        let response: RepositoryInfoResponse = serde_json::from_value(serde_json::json!({
+            "repository": {
+                "databaseId": 42,
+                "nameWithOwner": "new-org/gadgets",
+                "defaultBranchRef": { "name": "main" }
+            }
+        }))
+        .unwrap();
+        let name_with_owner = response.repository.name_with_owner.unwrap();
+
+        let path = apply_repository_rename(&conn, repo_id, "old-org", "widgets", &name_with_owner).unwrap();
+        assert_eq!(path, ("new-org".to_string(), "gadgets".to_string()));
+
+        let repo = queries::get_repository_by_id(&conn, repo_id).unwrap();
+        assert_eq!((repo.owner.as_str(), repo.name.as_str()), ("new-org", "gadgets"));
+        let issue_count: i64 = conn
+            .query_row("SELECT COUNT(*) FROM issues WHERE repo_id = ?1", [repo_id], |row| row.get(0))
+            .unwrap();
+        assert_eq!(issue_count, 1);
+    }
+
+    #[test]
+    fn test_refresh_populates_missing_loc() {
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        crate::db::migrations::run_migrations(&conn).unwrap();
+        let repo_id = queries::upsert_repository(&conn, "acme", "api", None, true).unwrap();
+        conn.execute(
+            "INSERT INTO pull_requests (github_id, repo_id, number, title, state, created_at, updated_at, additions, deletions)
+             VALUES (1, ?1, 7, 'PR', 'MERGED', '2024-01-01T00:00:00Z', '2024-01-02T00:00:00Z', 0, 0)",
+            [repo_id],
+        )
+        .unwrap();
+
+        let missing = queries::get_prs_missing_details(&conn, repo_id).unwrap();
+        assert_eq!(missing.len(), 1);
+        let (pr_id, number) = missing[0];
+        assert_eq!(number, 7);
+
+        let pr: rest_api::RestPullRequest = serde_json::from_value(serde_json::json!({
+            "id": 1,
+            "number": 7,
+            "title": "PR",
+            "body": null,
+            "state": "closed",
+            "user": null,
+            "labels": [],
+            "created_at": "2024-01-01T00:00:00Z",
+            "updated_at": "2024-01-02T00:00:00Z",
+            "merged_at": "2024-01-02T00:00:00Z",
+            "closed_at": "2024-01-02T00:00:00Z",
+            "additions": 42,
+            "deletions": 8,
+            "changed_files": 3
+        }))
+        .unwrap();
+        apply_pr_details(&conn, pr_id, &pr).unwrap();
+
+        let (additions, deletions, changed_files, merged_at): (i32, i32, i32, Option<String>) = conn
+            .query_row(
+                "SELECT additions, deletions, changed_files, merged_at FROM pull_requests WHERE id = ?1",
+                [pr_id],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+            )
+            .unwrap();
+        assert_eq!((additions, deletions, changed_files), (42, 8, 3));
+        assert_eq!(merged_at.as_deref(), Some("2024-01-02T00:00:00Z"));
+        assert!(queries::get_prs_missing_details(&conn, repo_id).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_zero_loc_pr_is_not_refetched_until_it_changes() {
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        crate::db::migrations::run_migrations(&conn).unwrap();
+        let repo_id = queries::upsert_repository(&conn, "acme", "api", None, true).unwrap();
+        let upsert = |updated_at: &str| {
+            queries::upsert_pull_request(
+                &conn, 1, repo_id, 7, "Empty PR", None, "OPEN", None,
+                "2024-01-01T00:00:00Z", updated_at, None, None, 0, 0, 0, &[], updated_at,
+            )
+            .unwrap()
+        };
+        let pr_id = upsert("2024-01-02T00:00:00Z");
+        assert_eq!(queries::get_prs_missing_details(&conn, repo_id).unwrap().len(), 1);
+
+        // REST reports a genuinely empty diff
+        queries::update_pr_details(&conn, pr_id, 0, 0, 0, None).unwrap();
+        assert!(queries::get_prs_missing_details(&conn, repo_id).unwrap().is_empty());
+
+        // Re-syncing the unchanged PR keeps it marked as fetched
+        upsert("2024-01-02T00:00:00Z");
+        assert!(queries::get_prs_missing_details(&conn, repo_id).unwrap().is_empty());
+
+        // A newer version of the PR needs its details fetched again
+        upsert("2024-01-05T00:00:00Z");
+        assert_eq!(queries::get_prs_missing_details(&conn, repo_id).unwrap(), vec![(pr_id, 7)]);
+    }
+
+    #[test]
+    fn test_detail_requests_pause_when_rate_limit_is_low() {
+        let healthy = rest_api::RateLimit { remaining: 4000, reset_at: 1_000 };
+        assert_eq!(detail_request_delay(Some(healthy), 900), std::time::Duration::from_millis(DETAIL_REQUEST_DELAY_MS));
+
+        let low = rest_api::RateLimit { remaining: 10, reset_at: 1_000 };
+        assert_eq!(detail_request_delay(Some(low), 940), std::time::Duration::from_secs(61));
+    }
+
+    #[test]
+    fn test_unchanged_repository_path_is_left_alone() {
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        crate::db::migrations::run_migrations(&conn).unwrap();
+        let repo_id = queries::upsert_repository(&conn, "acme", "api", None, true).unwrap();
+
+        let path = apply_repository_rename(&conn, repo_id, "acme", "api", "acme/api").unwrap();
+        assert_eq!(path, ("acme".to_string(), "api".to_string()));
+        assert_eq!(queries::get_repository_by_id(&conn, repo_id).unwrap().owner, "acme");
+    }
+
+    #[test]
+    fn test_rest_issues_payload_separates_pull_requests() {
+        let item = |number: i32, kind: &str, pull_request: bool| {
+            let mut value = serde_json::json!({
+                "id": number,
+                "number": number,
+                "title": "Item",
+                "body": null,
+                "state": "open",
+                "user": null,
+                "assignee": null,
+                "milestone": null,
+                "labels": [],
+                "created_at": "2024-01-01T00:00:00Z",
+                "updated_at": "2024-01-01T00:00:00Z",
+                "closed_at": null,
+                "html_url": format!("https://github.com/acme/api/{}/{}", kind, number),
+            });
+            if pull_request {
+                value["pull_request"] = serde_json::json!({ "url": "https://api.github.com/repos/acme/api/pulls/1" });
+            }
+            value
+        };
+        let payload = serde_json::json!([
+            item(1, "issues", false),
+            item(2, "pull", true),
+            // PR whose pull_request key is missing is still caught by its URL
+            item(3, "pull", false),
+            item(4, "issues", false),
+        ]);
+        let items: Vec<rest_api::RestIssue> = serde_json::from_value(payload).unwrap();
+
+        let (issues, skipped_prs) = split_rest_issues(items);
+        assert_eq!(issues.iter().map(|i| i.number).collect::<Vec<_>>(), vec![1, 4]);
+        assert_eq!(skipped_prs, 2);
+    }
+
+    #[test]
+    fn test_cli_only_strategy_skips_graphql_and_rest() {
+        let strategy = parse_sync_strategy(&["cli".to_string()]);
+        assert_eq!(strategy, vec![SyncMethod::Cli]);
+    }
+
+    #[test]
+    fn test_rest_then_cli_strategy_skips_graphql() {
+        let strategy = parse_sync_strategy(&["REST".to_string(), "cli".to_string(), "rest".to_string()]);
+        assert_eq!(strategy, vec![SyncMethod::Rest, SyncMethod::Cli]);
+        assert!(!strategy.contains(&SyncMethod::Graphql));
+
+        // Nothing usable falls back to the original chain
+        assert_eq!(parse_sync_strategy(&["ftp".to_string()]), DEFAULT_SYNC_STRATEGY.to_vec());
+    }
+
+    fn state_with_strategy(strategy: &[&str]) -> AppState {
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        crate::db::migrations::run_migrations(&conn).unwrap();
+        let mut settings = queries::get_settings(&conn).unwrap();
+        settings.sync_strategy = strategy.iter().map(|m| m.to_string()).collect();
+        queries::update_settings(&conn, &settings).unwrap();
+
+        AppState {
+            sqlite: std::sync::Mutex::new(conn),
+            lancedb_path: std::path::PathBuf::new(),
+            profile: std::sync::Mutex::new("default".to_string()),
+            sync_in_progress: std::sync::atomic::AtomicBool::new(false),
+            webhook_server: std::sync::Mutex::new(None),
         }
     }
+
+    /// Methods attempted for a stored strategy when the listed methods fail
+    async fn attempted_methods(strategy: &[&str], failing: &[SyncMethod]) -> (Vec<SyncMethod>, bool) {
+        let state = state_with_strategy(strategy);
+        let mut attempted = Vec::new();
+        let result = run_sync_strategy(&load_sync_strategy(&state).unwrap(), "issues", "acme", "api", |method| {
+            attempted.push(method);
+            let fails = failing.contains(&method);
+            async move {
+                if fails {
+                    anyhow::bail!("{:?} unavailable", method);
+                }
+                Ok(())
+            }
+        })
+        .await;
+        (attempted, result.is_ok())
+    }
+
+    #[tokio::test]
+    async fn test_disabled_sync_methods_are_never_attempted() {
+        // issues, PRs and milestones all dispatch through the stored strategy this way
+        let (attempted, ok) = attempted_methods(&["cli"], &[]).await;
+        assert_eq!((attempted, ok), (vec![SyncMethod::Cli], true));
+
+        // A failing REST falls through to CLI but never to the disabled GraphQL
+        let (attempted, ok) = attempted_methods(&["rest", "cli"], &[SyncMethod::Rest]).await;
+        assert_eq!((attempted, ok), (vec![SyncMethod::Rest, SyncMethod::Cli], true));
+
+        // When every enabled method fails, the disabled ones still aren't tried
+        let (attempted, ok) = attempted_methods(&["graphql"], &[SyncMethod::Graphql, SyncMethod::Rest, SyncMethod::Cli]).await;
+        assert_eq!((attempted, ok), (vec![SyncMethod::Graphql], false));
+    }
+
+    #[test]
+    fn test_apply_user_profile_fills_nameless_user() {
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        crate::db::migrations::run_migrations(&conn).unwrap();
+        conn.execute("INSERT INTO users (id, github_id, login) VALUES (1, 101, 'alice')", []).unwrap();
+
+        let profile: rest_api::RestUserProfile = serde_json::from_value(serde_json::json!({
+            "id": 101,
+            "login": "alice",
+            "name": "Alice Smith",
+            "avatar_url": "https://avatars.githubusercontent.com/u/101",
+            "company": "@acme",
+            "bio": null
+        }))
+        .unwrap();
+        apply_user_profile(&conn, 1, &profile).unwrap();
+
+        let row: (Option<String>, Option<String>, Option<String>, bool) = conn
+            .query_row(
+                "SELECT name, avatar_url, company, profile_fetched_at IS NOT NULL FROM users WHERE id = 1",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+            )
+            .unwrap();
+        assert_eq!(
+            row,
+            (
+                Some("Alice Smith".to_string()),
+                Some("https://avatars.githubusercontent.com/u/101".to_string()),
+                Some("@acme".to_string()),
+                true
+            )
+        );
+    }
 }
@@ -31,6 +31,7 @@ fn emit_progress(app: &AppHandle, phase: &str, current: usize, total: usize, mes
 
 /// Sync activity for a specific tracked user across all enabled repositories
 pub async fn sync_tracked_user(app: &AppHandle, state: &AppState, token: &str, username: &str) -> Result<()> {
+    let _sync = state.begin_sync()?;
     tracing::info!("Starting user-centric sync for '{}'", username);
 
     // Load settings
@@ -0,0 +1,596 @@
+use crate::db::queries::{self, is_bot_user};
+use crate::db::models::Settings;
+use crate::db::AppState;
+use crate::github::auth;
+use crate::github::rest_api::{RestIssue, RestPullRequest, RestUser};
+use hmac::{Hmac, Mac};
+use rusqlite::{Connection, OptionalExtension};
+use serde::Deserialize;
+use sha2::Sha256;
+use std::collections::HashMap;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use thiserror::Error;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+
+/// Largest webhook body we accept (GitHub caps payloads at 25 MB; ours are far smaller)
+const MAX_BODY_BYTES: usize = 5 * 1024 * 1024;
+/// Largest request line plus headers we accept
+const MAX_HEADER_BYTES: usize = 16 * 1024;
+/// Most header lines we accept
+const MAX_HEADER_COUNT: usize = 100;
+/// How long a client gets to send the whole request
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Error, Debug)]
+pub enum WebhookError {
+    #[error("Missing or invalid signature")]
+    InvalidSignature,
+
+    #[error("Malformed payload: {0}")]
+    Malformed(#[from] serde_json::Error),
+
+    #[error("Database error: {0}")]
+    Database(#[from] anyhow::Error),
+}
+
+#[derive(Debug, PartialEq)]
+pub enum WebhookOutcome {
+    Upserted(&'static str),
+    Ignored(String),
+}
+
+// ============================================================================
+// PAYLOADS
+// ============================================================================
+
+#[derive(Debug, Deserialize)]
+struct WebhookRepository {
+    name: String,
+    owner: RestUser,
+}
+
+#[derive(Debug, Deserialize)]
+struct IssuesEvent {
+    issue: RestIssue,
+    repository: WebhookRepository,
+}
+
+#[derive(Debug, Deserialize)]
+struct PullRequestEvent {
+    pull_request: RestPullRequest,
+    repository: WebhookRepository,
+}
+
+#[derive(Debug, Deserialize)]
+struct PullRequestReviewEvent {
+    review: WebhookReview,
+    pull_request: RestPullRequest,
+    repository: WebhookRepository,
+}
+
+#[derive(Debug, Deserialize)]
+struct WebhookReview {
+    id: i64,
+    user: Option<RestUser>,
+    state: String,
+    submitted_at: Option<String>,
+}
+
+// ============================================================================
+// EVENT HANDLING
+// ============================================================================
+
+/// Verify GitHub's `X-Hub-Signature-256` header (`sha256=<hex hmac of body>`)
+pub fn verify_signature(secret: &str, body: &[u8], signature: Option<&str>) -> bool {
+    let Some(hex_digest) = signature.and_then(|s| s.strip_prefix("sha256=")) else {
+        return false;
+    };
+    let Ok(expected) = hex::decode(hex_digest) else {
+        return false;
+    };
+    let Ok(mut mac) = Hmac::<Sha256>::new_from_slice(secret.as_bytes()) else {
+        return false;
+    };
+
+    mac.update(body);
+    mac.verify_slice(&expected).is_ok()
+}
+
+/// Verify and apply a single webhook delivery, upserting the affected item
+pub fn handle_webhook(
+    conn: &Connection,
+    secret: &str,
+    event: &str,
+    signature: Option<&str>,
+    body: &[u8],
+) -> Result<WebhookOutcome, WebhookError> {
+    if secret.is_empty() || !verify_signature(secret, body, signature) {
+        return Err(WebhookError::InvalidSignature);
+    }
+
+    let excluded_bots = queries::get_settings(conn)?.excluded_bots;
+
+    match event {
+        "issues" => {
+            let payload: IssuesEvent = serde_json::from_slice(body)?;
+            let Some(repo_id) = tracked_repo_id(conn, &payload.repository)? else {
+                return Ok(WebhookOutcome::Ignored("repository is not tracked".to_string()));
+            };
+            let excluded_bots = queries::get_repo_excluded_bots(conn, repo_id, &excluded_bots)?;
+            upsert_issue(conn, repo_id, &payload.issue, &excluded_bots)
+        }
+        "pull_request" => {
+            let payload: PullRequestEvent = serde_json::from_slice(body)?;
+            let Some(repo_id) = tracked_repo_id(conn, &payload.repository)? else {
+                return Ok(WebhookOutcome::Ignored("repository is not tracked".to_string()));
+            };
+            let excluded_bots = queries::get_repo_excluded_bots(conn, repo_id, &excluded_bots)?;
+            upsert_pull_request(conn, repo_id, &payload.pull_request, &excluded_bots)
+        }
+        "pull_request_review" => {
+            let payload: PullRequestReviewEvent = serde_json::from_slice(body)?;
+            let Some(repo_id) = tracked_repo_id(conn, &payload.repository)? else {
+                return Ok(WebhookOutcome::Ignored("repository is not tracked".to_string()));
+            };
+            // Review payloads carry a trimmed PR without LOC, so only attach to an already stored PR
+            let Some(pr_id) = stored_pull_request_id(conn, repo_id, payload.pull_request.number)? else {
+                return Ok(WebhookOutcome::Ignored("pull request has not been synced".to_string()));
+            };
+            upsert_review(conn, pr_id, &payload.review)
+        }
+        other => Ok(WebhookOutcome::Ignored(format!("unsupported event '{}'", other))),
+    }
+}
+
+fn tracked_repo_id(conn: &Connection, repo: &WebhookRepository) -> anyhow::Result<Option<i64>> {
+    let Some(repo) = queries::get_repository_by_name(conn, &repo.owner.login, &repo.name)? else {
+        return Ok(None);
+    };
+    // Demo data is read-only, even if a real repository shares its name
+    Ok((!queries::is_demo_repository(conn, repo.id)?).then_some(repo.id))
+}
+
+fn stored_pull_request_id(conn: &Connection, repo_id: i64, number: i32) -> anyhow::Result<Option<i64>> {
+    Ok(conn
+        .query_row(
+            "SELECT id FROM pull_requests WHERE repo_id = ?1 AND number = ?2",
+            rusqlite::params![repo_id, number],
+            |row| row.get(0),
+        )
+        .optional()?)
+}
+
+fn get_or_create_author(conn: &Connection, user: &RestUser) -> anyhow::Result<i64> {
+    queries::get_or_create_user(conn, user.id, &user.login, None, user.avatar_url.as_deref(), None, None, None, Some(true))
+}
+
+fn upsert_issue(
+    conn: &Connection,
+    repo_id: i64,
+    issue: &RestIssue,
+    excluded_bots: &[String],
+) -> Result<WebhookOutcome, WebhookError> {
+    if let Some(user) = &issue.user {
+        if is_bot_user(&user.login, excluded_bots) {
+            return Ok(WebhookOutcome::Ignored(format!("bot author '{}'", user.login)));
+        }
+    }
+
+    let author_id = issue.user.as_ref().map(|u| get_or_create_author(conn, u)).transpose()?;
+    let assignee_id = issue.assignee.as_ref().map(|u| get_or_create_author(conn, u)).transpose()?;
+    let milestone_id = match &issue.milestone {
+        Some(milestone) => queries::get_milestone_id_by_github_id(conn, milestone.id)?,
+        None => None,
+    };
+    let labels: Vec<String> = issue.labels.iter().map(|l| l.name.clone()).collect();
+
+    queries::upsert_issue(
+        conn,
+        issue.id,
+        repo_id,
+        issue.number,
+        &issue.title,
+        issue.body.as_deref(),
+        &issue.state,
+        author_id,
+        assignee_id,
+        milestone_id,
+        &issue.created_at,
+        &issue.updated_at,
+        issue.closed_at.as_deref(),
+        &labels,
+        &issue.updated_at, // Use updated_at as sync_updated_at
+    )?;
+
+    Ok(WebhookOutcome::Upserted("issue"))
+}
+
+fn upsert_pull_request(
+    conn: &Connection,
+    repo_id: i64,
+    pr: &RestPullRequest,
+    excluded_bots: &[String],
+) -> Result<WebhookOutcome, WebhookError> {
+    if let Some(user) = &pr.user {
+        if is_bot_user(&user.login, excluded_bots) {
+            return Ok(WebhookOutcome::Ignored(format!("bot author '{}'", user.login)));
+        }
+    }
+
+    let author_id = pr.user.as_ref().map(|u| get_or_create_author(conn, u)).transpose()?;
+    let labels: Vec<String> = pr.labels.iter().map(|l| l.name.clone()).collect();
+
+    // Webhooks report "open"/"closed"; store GraphQL-style states like the main sync
+    let state = if pr.merged_at.is_some() {
+        "MERGED".to_string()
+    } else {
+        pr.state.to_uppercase()
+    };
+
+    let pr_id = queries::upsert_pull_request(
+        conn,
+        pr.id,
+        repo_id,
+        pr.number,
+        &pr.title,
+        pr.body.as_deref(),
+        &state,
+        author_id,
+        &pr.created_at,
+        &pr.updated_at,
+        pr.merged_at.as_deref(),
+        pr.closed_at.as_deref(),
+        pr.additions.unwrap_or(0),
+        pr.deletions.unwrap_or(0),
+        pr.changed_files.unwrap_or(0),
+        &labels,
+        &pr.updated_at, // Use updated_at as sync_updated_at
+    )?;
+    queries::set_pr_base_branch(conn, pr_id, pr.base.as_ref().map(|b| b.ref_name.as_str()))?;
+    queries::set_pr_author_association(conn, pr_id, pr.author_association.as_deref())?;
+
+    Ok(WebhookOutcome::Upserted("pull_request"))
+}
+
+fn upsert_review(
+    conn: &Connection,
+    pr_id: i64,
+    review: &WebhookReview,
+) -> Result<WebhookOutcome, WebhookError> {
+    let Some(submitted_at) = review.submitted_at.as_deref() else {
+        return Ok(WebhookOutcome::Ignored("review has not been submitted".to_string()));
+    };
+
+    let reviewer_id = review.user.as_ref().map(|u| get_or_create_author(conn, u)).transpose()?;
+
+    queries::upsert_pr_review(
+        conn,
+        review.id,
+        pr_id,
+        reviewer_id,
+        &review.state.to_uppercase(), // Webhooks send "approved"; GraphQL stores "APPROVED"
+        submitted_at,
+        submitted_at,
+    )?;
+
+    Ok(WebhookOutcome::Upserted("pull_request_review"))
+}
+
+// ============================================================================
+// HTTP SERVER
+// ============================================================================
+
+/// Start the webhook receiver when the settings enable it, tracking it in app state
+pub fn start_webhook_server(app: &AppHandle, settings: &Settings) {
+    if !settings.enable_webhooks {
+        return;
+    }
+
+    let port = settings.webhook_port;
+    let webhook_app = app.clone();
+    let handle = tauri::async_runtime::spawn(async move {
+        if let Err(e) = run_webhook_server(webhook_app, port).await {
+            tracing::error!("Webhook receiver stopped: {}", e);
+        }
+    });
+
+    if let Ok(mut server) = app.state::<AppState>().webhook_server.lock() {
+        *server = Some(handle);
+    }
+}
+
+/// Stop the running webhook receiver (if any), waiting for it to release its port
+pub async fn stop_webhook_server(app: &AppHandle) {
+    let handle = app.state::<AppState>().webhook_server.lock().ok().and_then(|mut server| server.take());
+    if let Some(handle) = handle {
+        handle.abort();
+        let _ = handle.await;
+    }
+}
+
+/// Listen for GitHub webhook deliveries on localhost until the app exits
+pub async fn run_webhook_server(app: AppHandle, port: u16) -> anyhow::Result<()> {
+    let listener = TcpListener::bind(("127.0.0.1", port)).await?;
+    tracing::info!("Webhook receiver listening on 127.0.0.1:{}", port);
+
+    loop {
+        let (stream, _) = listener.accept().await?;
+        let app = app.clone();
+        tokio::spawn(async move {
+            if let Err(e) = serve_connection(&app, stream).await {
+                tracing::warn!("Webhook connection failed: {}", e);
+            }
+        });
+    }
+}
+
+/// A webhook delivery read off the socket
+struct WebhookRequest {
+    headers: HashMap<String, String>,
+    body: Vec<u8>,
+}
+
+async fn serve_connection(app: &AppHandle, stream: TcpStream) -> anyhow::Result<()> {
+    let mut reader = BufReader::new(stream);
+
+    let (status, message) = match tokio::time::timeout(READ_TIMEOUT, read_request(&mut reader)).await {
+        Err(_) => ("408 Request Timeout", "Timed out reading request".to_string()),
+        Ok(Err((status, message))) => (status, message),
+        Ok(Ok(request)) => {
+            let event = request.headers.get("x-github-event").map(String::as_str).unwrap_or("");
+            let signature = request.headers.get("x-hub-signature-256").map(String::as_str);
+
+            let state = app.state::<AppState>();
+            let profile = state.profile.lock().map_err(|e| anyhow::anyhow!(e.to_string()))?.clone();
+            let secret = auth::get_webhook_secret(&profile)?.unwrap_or_default();
+            let conn = state.sqlite.lock().map_err(|e| anyhow::anyhow!(e.to_string()))?;
+
+            match handle_webhook(&conn, &secret, event, signature, &request.body) {
+                Ok(WebhookOutcome::Upserted(kind)) => ("200 OK", format!("Upserted {}", kind)),
+                Ok(WebhookOutcome::Ignored(reason)) => ("202 Accepted", format!("Ignored: {}", reason)),
+                Err(WebhookError::InvalidSignature) => {
+                    tracing::warn!("Rejected webhook '{}' with invalid signature", event);
+                    ("401 Unauthorized", "Invalid signature".to_string())
+                }
+                Err(WebhookError::Malformed(e)) => ("400 Bad Request", e.to_string()),
+                Err(e) => {
+                    tracing::error!("Failed to apply webhook '{}': {}", event, e);
+                    ("500 Internal Server Error", e.to_string())
+                }
+            }
+        }
+    };
+
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: text/plain\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        message.len(),
+        message
+    );
+    reader.get_mut().write_all(response.as_bytes()).await?;
+
+    Ok(())
+}
+
+/// Read a POST request, or the status line to reject it with
+async fn read_request<R: AsyncBufRead + Unpin>(reader: &mut R) -> Result<WebhookRequest, (&'static str, String)> {
+    let bad_request = |e: std::io::Error| ("400 Bad Request", e.to_string());
+    let too_large = || ("431 Request Header Fields Too Large", "Request headers too large".to_string());
+
+    let mut budget = MAX_HEADER_BYTES;
+    let request_line = read_capped_line(reader, &mut budget).await.map_err(bad_request)?.ok_or_else(too_large)?;
+
+    let mut headers = HashMap::new();
+    loop {
+        let line = read_capped_line(reader, &mut budget).await.map_err(bad_request)?.ok_or_else(too_large)?;
+        if line.trim().is_empty() {
+            break;
+        }
+        if headers.len() >= MAX_HEADER_COUNT {
+            return Err(too_large());
+        }
+        if let Some((key, value)) = line.split_once(':') {
+            headers.insert(key.trim().to_ascii_lowercase(), value.trim().to_string());
+        }
+    }
+
+    if !request_line.starts_with("POST ") {
+        return Err(("405 Method Not Allowed", "Only POST is supported".to_string()));
+    }
+
+    let length: usize = match headers.get("content-length") {
+        None => return Err(("411 Length Required", "Content-Length is required".to_string())),
+        Some(value) => value
+            .parse()
+            .map_err(|_| ("400 Bad Request", "Invalid Content-Length".to_string()))?,
+    };
+    if length > MAX_BODY_BYTES {
+        return Err(("413 Payload Too Large", "Payload too large".to_string()));
+    }
+
+    let mut body = vec![0u8; length];
+    reader.read_exact(&mut body).await.map_err(bad_request)?;
+
+    Ok(WebhookRequest { headers, body })
+}
+
+/// Read one header line within the remaining byte budget (None once it is exceeded)
+async fn read_capped_line<R: AsyncBufRead + Unpin>(reader: &mut R, budget: &mut usize) -> std::io::Result<Option<String>> {
+    let mut line = String::new();
+    let read = (&mut *reader).take(*budget as u64).read_line(&mut line).await?;
+    *budget -= read;
+    if *budget == 0 && !line.ends_with('\n') {
+        return Ok(None);
+    }
+    Ok(Some(line))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECRET: &str = "s3cret";
+
+    fn setup_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::db::migrations::run_migrations(&conn).unwrap();
+        queries::upsert_repository(&conn, "acme", "api", None, true).unwrap();
+        conn
+    }
+
+    fn sign(body: &[u8]) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(SECRET.as_bytes()).unwrap();
+        mac.update(body);
+        format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+    }
+
+    fn pull_request_payload() -> Vec<u8> {
+        serde_json::json!({
+            "action": "closed",
+            "pull_request": {
+                "id": 9001,
+                "number": 42,
+                "title": "Add webhooks",
+                "body": null,
+                "state": "closed",
+                "user": { "id": 7, "login": "alice", "avatar_url": null },
+                "labels": [{ "name": "feature" }],
+                "created_at": "2024-03-01T10:00:00Z",
+                "updated_at": "2024-03-02T10:00:00Z",
+                "merged_at": "2024-03-02T10:00:00Z",
+                "closed_at": "2024-03-02T10:00:00Z",
+                "additions": 120,
+                "deletions": 4,
+                "changed_files": 3
+            },
+            "repository": { "name": "api", "owner": { "id": 1, "login": "acme", "avatar_url": null } }
+        })
+        .to_string()
+        .into_bytes()
+    }
+
+    #[test]
+    fn test_signed_pull_request_is_upserted() {
+        let conn = setup_db();
+        let body = pull_request_payload();
+        let signature = sign(&body);
+
+        let outcome = handle_webhook(&conn, SECRET, "pull_request", Some(&signature), &body).unwrap();
+        assert_eq!(outcome, WebhookOutcome::Upserted("pull_request"));
+
+        let (state, additions, labels): (String, i32, String) = conn
+            .query_row(
+                "SELECT state, additions, labels FROM pull_requests WHERE github_id = 9001",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .unwrap();
+        assert_eq!(state, "MERGED");
+        assert_eq!(additions, 120);
+        assert_eq!(labels, r#"["feature"]"#);
+    }
+
+    #[test]
+    fn test_review_webhook_leaves_pr_loc_unchanged() {
+        let conn = setup_db();
+        let body = pull_request_payload();
+        handle_webhook(&conn, SECRET, "pull_request", Some(&sign(&body)), &body).unwrap();
+
+        // Review events embed a simplified PR with a newer updated_at and no LOC fields
+        let review = serde_json::json!({
+            "action": "submitted",
+            "review": {
+                "id": 555,
+                "user": { "id": 8, "login": "bob", "avatar_url": null },
+                "state": "approved",
+                "submitted_at": "2024-03-03T10:00:00Z"
+            },
+            "pull_request": {
+                "id": 9001,
+                "number": 42,
+                "title": "Add webhooks",
+                "body": null,
+                "state": "closed",
+                "user": { "id": 7, "login": "alice", "avatar_url": null },
+                "labels": [],
+                "created_at": "2024-03-01T10:00:00Z",
+                "updated_at": "2024-03-03T10:00:00Z",
+                "merged_at": "2024-03-02T10:00:00Z",
+                "closed_at": "2024-03-02T10:00:00Z"
+            },
+            "repository": { "name": "api", "owner": { "id": 1, "login": "acme", "avatar_url": null } }
+        })
+        .to_string()
+        .into_bytes();
+        let outcome = handle_webhook(&conn, SECRET, "pull_request_review", Some(&sign(&review)), &review).unwrap();
+        assert_eq!(outcome, WebhookOutcome::Upserted("pull_request_review"));
+
+        let (additions, deletions, changed_files): (i32, i32, i32) = conn
+            .query_row(
+                "SELECT additions, deletions, changed_files FROM pull_requests WHERE github_id = 9001",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .unwrap();
+        assert_eq!((additions, deletions, changed_files), (120, 4, 3));
+        let reviews: i64 = conn
+            .query_row("SELECT COUNT(*) FROM pr_reviews WHERE github_id = 555", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(reviews, 1);
+    }
+
+    #[test]
+    fn test_unsigned_or_forged_payload_is_rejected() {
+        let conn = setup_db();
+        let body = pull_request_payload();
+
+        let unsigned = handle_webhook(&conn, SECRET, "pull_request", None, &body);
+        assert!(matches!(unsigned, Err(WebhookError::InvalidSignature)));
+
+        let forged = format!("sha256={}", "00".repeat(32));
+        let forged = handle_webhook(&conn, SECRET, "pull_request", Some(&forged), &body);
+        assert!(matches!(forged, Err(WebhookError::InvalidSignature)));
+
+        let count: i64 = conn
+            .query_row("SELECT COUNT(*) FROM pull_requests", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 0);
+    }
+
+    async fn read(raw: &[u8]) -> Result<WebhookRequest, (&'static str, String)> {
+        let mut reader = raw;
+        read_request(&mut reader).await
+    }
+
+    #[tokio::test]
+    async fn test_read_request_parses_headers_and_body() {
+        let request = read(b"POST /webhook HTTP/1.1\r\nX-GitHub-Event: issues\r\nContent-Length: 2\r\n\r\n{}")
+            .await
+            .unwrap();
+        assert_eq!(request.headers.get("x-github-event").map(String::as_str), Some("issues"));
+        assert_eq!(request.body, b"{}");
+    }
+
+    #[tokio::test]
+    async fn test_read_request_requires_content_length() {
+        let (status, _) = read(b"POST /webhook HTTP/1.1\r\nX-GitHub-Event: issues\r\n\r\n{}").await.err().unwrap();
+        assert_eq!(status, "411 Length Required");
+    }
+
+    #[tokio::test]
+    async fn test_read_request_rejects_oversized_body_and_headers() {
+        let raw = format!("POST /webhook HTTP/1.1\r\nContent-Length: {}\r\n\r\n", MAX_BODY_BYTES + 1);
+        let (status, _) = read(raw.as_bytes()).await.err().unwrap();
+        assert_eq!(status, "413 Payload Too Large");
+
+        let raw = format!("POST /webhook HTTP/1.1\r\nX-Padding: {}\r\n\r\n", "a".repeat(MAX_HEADER_BYTES));
+        let (status, _) = read(raw.as_bytes()).await.err().unwrap();
+        assert_eq!(status, "431 Request Header Fields Too Large");
+
+        let raw = format!("POST /webhook HTTP/1.1\r\n{}\r\n", "X-A: b\r\n".repeat(MAX_HEADER_COUNT + 1));
+        let (status, _) = read(raw.as_bytes()).await.err().unwrap();
+        assert_eq!(status, "431 Request Header Fields Too Large");
+    }
+}
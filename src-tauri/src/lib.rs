@@ -2,10 +2,12 @@
 //!
 //! This module exposes the internal modules for testing and potential library usage.
 
+use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::sync::Mutex as TokioMutex;
 
 pub mod ai;
+pub mod config;
 pub mod db;
 pub mod embeddings;
 pub mod github;
@@ -17,4 +19,75 @@ pub mod team;
 /// AI-specific application state
 pub struct AiState {
     pub amplifier_client: Arc<TokioMutex<ai::AmplifierClient>>,
+    pub sidecar: Arc<std::sync::Mutex<ai::AmplifierSidecar>>,
+    /// Profile whose database the sidecar reads; None while it restarts for another profile
+    pub profile: std::sync::Mutex<Option<String>>,
+}
+
+impl AiState {
+    /// Fail unless the sidecar is reading the active profile's database
+    pub fn ensure_profile(&self, active_profile: &str) -> Result<(), String> {
+        let serving = self.profile.lock().map_err(|e| e.to_string())?;
+        match serving.as_deref() {
+            Some(profile) if profile == active_profile => Ok(()),
+            _ => Err(format!(
+                "AI features are restarting for profile '{}', try again shortly",
+                active_profile
+            )),
+        }
+    }
+
+    /// Stop answering AI requests until the sidecar has been restarted
+    pub fn suspend(&self) {
+        if let Ok(mut profile) = self.profile.lock() {
+            *profile = None;
+        }
+    }
+
+    /// Restart the sidecar against a profile's database and resume answering once it is healthy
+    pub async fn restart_for_profile(&self, profile: &str, db_path: PathBuf) -> anyhow::Result<()> {
+        self.suspend();
+
+        let sidecar = self.sidecar.clone();
+        let (port, auth_token) = tokio::task::spawn_blocking(move || -> anyhow::Result<(u16, String)> {
+            let mut sidecar = sidecar.lock().map_err(|e| anyhow::anyhow!(e.to_string()))?;
+            sidecar.stop()?;
+            sidecar.start(db_path)?;
+            Ok((sidecar.port, sidecar.auth_token.clone()))
+        })
+        .await??;
+
+        let client = ai::AmplifierClient::new(port, auth_token);
+        client.wait_until_ready(ai::ReadinessConfig::from_env()).await?;
+        *self.amplifier_client.lock().await = client;
+
+        *self.profile.lock().map_err(|e| anyhow::anyhow!(e.to_string()))? = Some(profile.to_string());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ai_state(profile: &str) -> AiState {
+        AiState {
+            amplifier_client: Arc::new(TokioMutex::new(ai::AmplifierClient::new(0, "token".to_string()))),
+            sidecar: Arc::new(std::sync::Mutex::new(ai::AmplifierSidecar::new())),
+            profile: std::sync::Mutex::new(Some(profile.to_string())),
+        }
+    }
+
+    #[test]
+    fn test_ai_requests_fail_after_profile_switch_until_restarted() {
+        let state = ai_state("client-a");
+        assert!(state.ensure_profile("client-a").is_ok());
+        // A sidecar still reading client A's database must not answer for client B
+        assert!(state.ensure_profile("client-b").is_err());
+
+        state.suspend();
+        assert_eq!(*state.profile.lock().unwrap(), None);
+        assert!(state.ensure_profile("client-a").is_err());
+        assert!(state.ensure_profile("client-b").is_err());
+    }
 }
@@ -48,6 +48,15 @@ fn main() {
                 }
             });
 
+            // Start the webhook receiver if enabled in settings
+            let settings = app_handle.try_state::<db::AppState>().and_then(|state| {
+                let conn = state.sqlite.lock().ok()?;
+                db::queries::get_settings(&conn).ok()
+            });
+            if let Some(settings) = settings {
+                github::webhooks::start_webhook_server(&app_handle, &settings);
+            }
+
             // Initialize Amplifier sidecar
             tracing::info!("=== Initializing AI Features ===");
             let amplifier_client = tauri::async_runtime::block_on(async {
@@ -76,26 +85,20 @@ fn main() {
                         tracing::info!("Creating Amplifier HTTP client...");
                         let client = ai::AmplifierClient::new(sidecar.port, sidecar.auth_token.clone());
 
-                        // Health check
-                        tracing::info!("Running initial health check...");
-                        match client.health_check().await {
-                            Ok(true) => {
-                                tracing::info!("✓ Amplifier health check passed - AI features ready");
-                            }
-                            Ok(false) => {
-                                tracing::warn!("⚠ Amplifier health check returned false - server may not be ready");
+                        // Poll the health check until the sidecar is up
+                        tracing::info!("Waiting for Amplifier to pass its health check...");
+                        match client.wait_until_ready(ai::ReadinessConfig::from_env()).await {
+                            Ok(attempts) => {
+                                tracing::info!("✓ Amplifier health check passed after {} attempt(s) - AI features ready", attempts);
                             }
                             Err(e) => {
-                                tracing::error!("✗ Amplifier health check error: {}", e);
+                                tracing::error!("✗ Amplifier did not become ready: {}", e);
                                 tracing::error!("AI features may not work correctly");
                             }
                         }
 
-                        // Keep sidecar alive by moving it into app state
-                        tracing::debug!("Registering sidecar in app state...");
-                        app.manage(sidecar);
-
-                        Some(client)
+                        // Keep sidecar alive by moving it into the AI state
+                        Some((client, sidecar))
                     }
                     Err(e) => {
                         tracing::error!("✗ Failed to start Amplifier sidecar: {}", e);
@@ -106,10 +109,15 @@ fn main() {
             });
 
             // Create and manage AI state if we have a client
-            if let Some(client) = amplifier_client {
+            if let Some((client, sidecar)) = amplifier_client {
                 tracing::info!("Creating AI state and registering commands...");
+                let profile = app_handle
+                    .try_state::<db::AppState>()
+                    .and_then(|state| state.profile.lock().ok().map(|profile| profile.clone()));
                 let ai_state = AiState {
                     amplifier_client: Arc::new(TokioMutex::new(client)),
+                    sidecar: Arc::new(std::sync::Mutex::new(sidecar)),
+                    profile: std::sync::Mutex::new(profile),
                 };
                 app.manage(ai_state);
                 tracing::info!("✓ AI features initialized successfully");
@@ -124,10 +132,15 @@ fn main() {
             github::commands::github_login,
             github::commands::github_logout,
             github::commands::check_auth,
+            github::commands::validate_token_scopes,
 
             // Sync commands
             github::commands::sync_github_data,
             github::commands::sync_repository,
+            github::commands::force_full_sync,
+            github::commands::refresh_pr_details,
+            github::commands::enrich_users,
+            github::commands::estimate_sync,
 
             // Database CRUD commands
             db::commands::get_settings,
@@ -135,18 +148,42 @@ fn main() {
             db::commands::add_repository,
             db::commands::remove_repository,
             db::commands::toggle_repository,
+            db::commands::set_repo_excluded_bots,
             db::commands::clear_all_database_data,
+            db::commands::seed_demo_data,
             db::commands::add_squad,
             db::commands::update_squad,
             db::commands::remove_squad,
             db::commands::get_all_squads_command,
+            db::commands::add_path_owner,
+            db::commands::remove_path_owner,
+            db::commands::get_path_owners,
+            db::commands::create_initiative,
+            db::commands::delete_initiative,
+            db::commands::get_initiatives,
+            db::commands::add_initiative_item,
+            db::commands::remove_initiative_item,
+            db::commands::get_initiative_progress,
+            db::commands::save_filter,
+            db::commands::get_saved_filters,
+            db::commands::delete_saved_filter,
             db::commands::toggle_user_tracked,
             db::commands::fix_invalid_users,
+            config::commands::import_repositories_from_text,
 
             // Query helper commands
             db::commands::get_sync_stats,
+            db::commands::get_repo_freshness,
+            db::commands::detect_sync_gaps,
+            db::commands::get_changes_since,
+            db::commands::get_repo_sync_errors,
+            db::commands::get_diagnostics,
+            db::commands::list_profiles,
+            db::commands::switch_profile,
             db::commands::get_all_users,
             db::commands::get_all_repositories,
+            db::commands::get_all_labels,
+            db::commands::query_items,
 
             // Metrics commands
             metrics::commands::get_dashboard_metrics,
@@ -154,14 +191,44 @@ fn main() {
             metrics::commands::get_metrics_timeseries,
             metrics::commands::get_user_metrics,
             metrics::commands::get_squad_metrics,
+            metrics::commands::get_all_squads_overview,
             metrics::commands::get_pr_based_metrics,
+            metrics::commands::get_blocked_prs,
+            metrics::commands::get_abandoned_prs,
+            metrics::commands::get_path_owner_metrics,
+            metrics::commands::get_slowest_prs,
+            metrics::commands::get_pr_funnel,
+            metrics::commands::get_review_latency_distribution,
+            metrics::commands::get_approval_latency,
+            metrics::commands::get_triage_time_by_label,
+            metrics::commands::get_sla_breaches,
+            metrics::commands::get_close_velocity_trend,
+            metrics::commands::get_collaboration_index,
+            metrics::commands::get_review_debt,
+            metrics::commands::forecast_volume,
+            metrics::commands::get_squad_work_pattern,
+            metrics::commands::get_ping_pong_index,
+            metrics::commands::get_review_balance,
+            metrics::commands::get_planned_vs_unplanned,
+            metrics::commands::get_review_bypasses,
+            metrics::commands::get_throughput_by_weekday,
+            metrics::commands::get_contribution_split,
+            metrics::commands::get_per_capita_velocity,
+            metrics::commands::get_org_bus_factor,
+            metrics::commands::get_contribution_by_tenure,
+            metrics::commands::get_label_dwell_times,
             
             // Search commands
             search::commands::hybrid_search,
             search::commands::find_duplicates,
+            search::commands::rebuild_fts_index,
+            search::commands::get_embedding_coverage,
+            embeddings::commands::get_stale_embedding_count,
+            embeddings::commands::reembed_stale,
             
             // Roadmap commands
             github::commands::get_roadmap,
+            github::commands::export_roadmap_ical,
 
             // Project deep dive commands
             project::commands::get_project_timeline,
@@ -169,18 +236,33 @@ fn main() {
             project::commands::get_project_activity_heatmap,
             project::commands::get_project_lifecycle_metrics,
             project::commands::get_project_summary,
+            project::commands::get_contributor_leaderboard,
+            project::commands::get_repo_languages,
+            project::commands::get_pr_detail,
+            project::commands::get_file_hotspots,
 
             // Team/user-centric commands
             team::commands::add_tracked_user,
             team::commands::remove_tracked_user,
             team::commands::get_tracked_users,
             team::commands::update_user_tracked_status,
+            team::commands::sync_tracked_with_squads,
             team::commands::get_user_summary,
+            team::commands::set_user_metadata,
+            team::commands::delete_user_metadata,
+            team::commands::get_user_metadata,
             team::commands::get_user_activity_timeline,
             team::commands::get_user_repository_distribution,
             team::commands::get_team_collaboration_matrix,
             team::commands::get_user_activity_trend,
             team::commands::get_user_focus_metrics,
+            team::commands::get_specialization,
+            team::commands::get_ramp_time,
+            team::commands::get_first_contributions,
+            team::commands::get_shipping_streaks,
+            team::commands::get_review_only_contributors,
+            team::commands::suggest_reviewers,
+            team::commands::get_declining_contributors,
 
             // AI commands
             ai::commands::send_chat_message,
@@ -188,6 +270,20 @@ fn main() {
             ai::commands::check_api_keys,
             ai::commands::set_api_key,
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app_handle, event| {
+            // Close out syncs cut short by quitting so history and freshness stay accurate
+            if let tauri::RunEvent::Exit = event {
+                if let Some(state) = app_handle.try_state::<db::AppState>() {
+                    if let Ok(conn) = state.sqlite.lock() {
+                        match db::queries::mark_interrupted_syncs(&conn) {
+                            Ok(0) => {}
+                            Ok(n) => tracing::info!("Marked {} in-progress sync(s) as interrupted on exit", n),
+                            Err(e) => tracing::error!("Failed to flush in-progress syncs on exit: {}", e),
+                        }
+                    }
+                }
+            }
+        });
 }
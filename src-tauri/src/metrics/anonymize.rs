@@ -0,0 +1,296 @@
+use crate::db::{
+    metrics_queries::{AbandonedPr, BlockedPr, OrgBusFactor, ReviewBalance, ReviewBypass, SlowPr},
+    models::User,
+    project_queries::{ContributorStats, LeaderboardEntry, PrDetail},
+    user_queries::{
+        CollaborationMatrix, DecliningContributor, FirstContribution, ReviewOnlyContributor, ReviewerSuggestion, ShippingStreaks,
+        Specialization, UserSummary,
+    },
+};
+use anyhow::Result;
+use rusqlite::Connection;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+
+/// Stable pseudonym for a user, derived from their local id
+pub fn pseudonym(user_id: i64) -> String {
+    format!("dev-{}", user_id)
+}
+
+/// Stable pseudonym for a login that doesn't match any known user, derived from a hash
+/// of the login so distinct unknown logins never share a pseudonym
+fn unknown_pseudonym(login: &str) -> String {
+    let digest = Sha256::digest(login.to_lowercase().as_bytes());
+    format!("dev-x{}", &hex::encode(digest)[..10])
+}
+
+/// Maps logins to pseudonyms using the users table
+pub struct Anonymizer {
+    ids_by_login: HashMap<String, i64>,
+}
+
+impl Anonymizer {
+    pub fn load(conn: &Connection) -> Result<Self> {
+        let mut stmt = conn.prepare("SELECT login, id FROM users")?;
+        let ids_by_login = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<rusqlite::Result<HashMap<String, i64>>>()?;
+
+        Ok(Self { ids_by_login })
+    }
+
+    /// Pseudonym for a login
+    pub fn login(&self, login: &str) -> String {
+        self.ids_by_login
+            .get(login)
+            .map(|id| pseudonym(*id))
+            .unwrap_or_else(|| unknown_pseudonym(login))
+    }
+}
+
+/// Replace user-identifying fields (logins, names, avatars) in returned data
+pub trait Anonymize {
+    fn anonymize(&mut self, anonymizer: &Anonymizer);
+}
+
+/// Anonymize a command result when requested. Only the returned value is rewritten;
+/// stored data is never modified.
+pub fn apply<T: Anonymize>(conn: &Connection, mut value: T, enabled: bool) -> Result<T> {
+    if enabled {
+        let anonymizer = Anonymizer::load(conn)?;
+        value.anonymize(&anonymizer);
+    }
+    Ok(value)
+}
+
+impl<T: Anonymize> Anonymize for Vec<T> {
+    fn anonymize(&mut self, anonymizer: &Anonymizer) {
+        for item in self.iter_mut() {
+            item.anonymize(anonymizer);
+        }
+    }
+}
+
+impl Anonymize for User {
+    fn anonymize(&mut self, _anonymizer: &Anonymizer) {
+        self.login = pseudonym(self.id);
+        self.name = None;
+        self.avatar_url = None;
+    }
+}
+
+impl Anonymize for UserSummary {
+    fn anonymize(&mut self, anonymizer: &Anonymizer) {
+        self.user.anonymize(anonymizer);
+        // Annotations like manager or location can identify someone too
+        self.metadata.clear();
+    }
+}
+
+impl Anonymize for CollaborationMatrix {
+    fn anonymize(&mut self, anonymizer: &Anonymizer) {
+        // Interaction maps are keyed by login on both levels
+        self.interactions = std::mem::take(&mut self.interactions)
+            .into_iter()
+            .map(|(login, others)| {
+                let others = others
+                    .into_iter()
+                    .map(|(other, stats)| (anonymizer.login(&other), stats))
+                    .collect();
+                (anonymizer.login(&login), others)
+            })
+            .collect();
+        self.users.anonymize(anonymizer);
+    }
+}
+
+impl Anonymize for ContributorStats {
+    fn anonymize(&mut self, anonymizer: &Anonymizer) {
+        self.user.anonymize(anonymizer);
+    }
+}
+
+impl Anonymize for LeaderboardEntry {
+    fn anonymize(&mut self, anonymizer: &Anonymizer) {
+        self.user.anonymize(anonymizer);
+    }
+}
+
+impl Anonymize for ReviewBalance {
+    fn anonymize(&mut self, anonymizer: &Anonymizer) {
+        for reviewer in self.reviewers.iter_mut() {
+            reviewer.login = anonymizer.login(&reviewer.login);
+        }
+    }
+}
+
+impl Anonymize for OrgBusFactor {
+    fn anonymize(&mut self, anonymizer: &Anonymizer) {
+        for contributor in self.key_contributors.iter_mut() {
+            contributor.login = anonymizer.login(&contributor.login);
+        }
+    }
+}
+
+macro_rules! anonymize_user_row {
+    ($($ty:ty),*) => {
+        $(
+            impl Anonymize for $ty {
+                fn anonymize(&mut self, _anonymizer: &Anonymizer) {
+                    self.login = pseudonym(self.user_id);
+                    self.name = None;
+                    self.avatar_url = None;
+                }
+            }
+        )*
+    };
+}
+
+anonymize_user_row!(DecliningContributor, FirstContribution, ReviewOnlyContributor, ReviewerSuggestion);
+
+macro_rules! anonymize_pr_author {
+    ($($ty:ty),*) => {
+        $(
+            impl Anonymize for $ty {
+                fn anonymize(&mut self, anonymizer: &Anonymizer) {
+                    self.author = self.author.as_deref().map(|login| anonymizer.login(login));
+                }
+            }
+        )*
+    };
+}
+
+anonymize_pr_author!(BlockedPr, AbandonedPr, SlowPr, ReviewBypass);
+
+impl Anonymize for PrDetail {
+    fn anonymize(&mut self, anonymizer: &Anonymizer) {
+        self.author = self.author.as_deref().map(|login| anonymizer.login(login));
+        for review in self.reviews.iter_mut() {
+            review.reviewer = review.reviewer.as_deref().map(|login| anonymizer.login(login));
+        }
+    }
+}
+
+// These only carry the local user id (the same one pseudonyms are derived from), so there
+// is nothing to rewrite; the impls let their commands take the flag like the rest
+impl Anonymize for Specialization {
+    fn anonymize(&mut self, _anonymizer: &Anonymizer) {}
+}
+
+impl Anonymize for ShippingStreaks {
+    fn anonymize(&mut self, _anonymizer: &Anonymizer) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::db::migrations::run_migrations(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO users (id, github_id, login, name, avatar_url) VALUES
+                (7, 101, 'alice', 'Alice Smith', 'https://example.com/a.png'),
+                (9, 102, 'bob', 'Bob Jones', NULL);",
+        )
+        .unwrap();
+        conn
+    }
+
+    fn slow_pr(author: &str) -> SlowPr {
+        SlowPr {
+            id: 1,
+            repo: "acme/api".to_string(),
+            number: 1,
+            title: "PR".to_string(),
+            author: Some(author.to_string()),
+            created_at: "2024-01-01T00:00:00Z".to_string(),
+            merged_at: "2024-01-02T00:00:00Z".to_string(),
+            duration_hours: 24.0,
+        }
+    }
+
+    #[test]
+    fn test_same_user_maps_to_same_pseudonym_across_calls() {
+        let conn = setup_db();
+
+        let first = apply(&conn, vec![slow_pr("alice"), slow_pr("bob")], true).unwrap();
+        let second = apply(&conn, vec![slow_pr("bob"), slow_pr("alice")], true).unwrap();
+
+        assert_eq!(first[0].author.as_deref(), Some("dev-7"));
+        assert_eq!(first[1].author.as_deref(), Some("dev-9"));
+        assert_eq!(second[0].author, first[1].author);
+        assert_eq!(second[1].author, first[0].author);
+    }
+
+    #[test]
+    fn test_unknown_logins_get_distinct_stable_pseudonyms() {
+        let conn = setup_db();
+
+        let first = apply(&conn, vec![slow_pr("ghost"), slow_pr("phantom")], true).unwrap();
+        let second = apply(&conn, vec![slow_pr("phantom"), slow_pr("ghost")], true).unwrap();
+
+        assert_ne!(first[0].author, first[1].author);
+        assert_eq!(first[0].author, second[1].author);
+        assert_eq!(first[1].author, second[0].author);
+        assert!(first[0].author.as_deref().is_some_and(|a| a.starts_with("dev-x") && !a.contains("ghost")));
+    }
+
+    #[test]
+    fn test_anonymize_leaves_stored_data_unchanged() {
+        let conn = setup_db();
+        let user = User {
+            id: 7,
+            github_id: 101,
+            login: "alice".to_string(),
+            name: Some("Alice Smith".to_string()),
+            avatar_url: Some("https://example.com/a.png".to_string()),
+            is_bot: false,
+            tracked: true,
+            tracked_at: None,
+        };
+
+        let anonymized = apply(&conn, vec![user.clone()], true).unwrap();
+        assert_eq!(anonymized[0].login, "dev-7");
+        assert!(anonymized[0].name.is_none());
+        assert!(anonymized[0].avatar_url.is_none());
+
+        let stored: (String, Option<String>) = conn
+            .query_row("SELECT login, name FROM users WHERE id = 7", [], |row| Ok((row.get(0)?, row.get(1)?)))
+            .unwrap();
+        assert_eq!(stored, ("alice".to_string(), Some("Alice Smith".to_string())));
+
+        // Disabled mode returns the data untouched
+        let raw = apply(&conn, vec![user], false).unwrap();
+        assert_eq!(raw[0].login, "alice");
+    }
+
+    #[test]
+    fn test_pr_detail_and_streaks_return_no_real_logins() {
+        let conn = setup_db();
+        conn.execute_batch(
+            "INSERT INTO repositories (id, owner, name) VALUES (1, 'acme', 'api');
+             INSERT INTO pull_requests (id, github_id, repo_id, number, title, state, author_id, created_at, updated_at, merged_at)
+             VALUES (10, 1000, 1, 42, 'Fix', 'MERGED', 7, '2024-03-01T00:00:00Z', '2024-03-02T00:00:00Z', '2024-03-02T00:00:00Z');
+             INSERT INTO pr_reviews (github_id, pr_id, reviewer_id, state, submitted_at)
+             VALUES (1, 10, 9, 'APPROVED', '2024-03-01T12:00:00Z');",
+        )
+        .unwrap();
+
+        let detail = crate::db::project_queries::get_pr_detail(&conn, 1, 42).unwrap().unwrap();
+        let detail = apply(&conn, detail, true).unwrap();
+        assert_eq!(detail.author.as_deref(), Some("dev-7"));
+        assert_eq!(detail.reviews[0].reviewer.as_deref(), Some("dev-9"));
+
+        let today = chrono::NaiveDate::from_ymd_opt(2024, 3, 5).unwrap();
+        let streaks = crate::db::user_queries::get_shipping_streaks(&conn, 7, today).unwrap();
+        let specialization = crate::db::user_queries::get_specialization(&conn, 7, "2024-01-01T00:00:00Z").unwrap();
+        let json = serde_json::to_string(&(
+            detail,
+            apply(&conn, streaks, true).unwrap(),
+            apply(&conn, specialization, true).unwrap(),
+        ))
+        .unwrap();
+        assert!(!json.contains("alice") && !json.contains("bob"));
+    }
+}
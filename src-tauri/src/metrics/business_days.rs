@@ -18,7 +18,7 @@ pub fn business_hours_between(start: &str, end: &str) -> f64 {
     days as f64 * 8.0
 }
 
-fn parse_date(date_str: &str) -> Option<NaiveDate> {
+pub(crate) fn parse_date(date_str: &str) -> Option<NaiveDate> {
     // Try ISO 8601 format first
     if let Ok(dt) = DateTime::parse_from_rfc3339(date_str) {
         return Some(dt.date_naive());
@@ -1,5 +1,6 @@
-use super::business_days::business_days_between;
+use super::business_days::{business_days_between, parse_date};
 use crate::db::models::{Issue, PullRequest};
+use crate::metrics::precision::serialize_rounded;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -11,25 +12,37 @@ pub struct DashboardMetrics {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SpeedMetrics {
+    #[serde(serialize_with = "serialize_rounded")]
     pub avg_cycle_time_days: f64,
+    #[serde(serialize_with = "serialize_rounded")]
     pub avg_pr_lead_time_hours: f64,
+    #[serde(serialize_with = "serialize_rounded")]
     pub throughput_per_week: f64,
+    #[serde(serialize_with = "serialize_rounded")]
     pub trend: f64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct EaseMetrics {
+    #[serde(serialize_with = "serialize_rounded")]
     pub avg_pr_size_lines: f64,
+    #[serde(serialize_with = "serialize_rounded")]
     pub avg_review_rounds: f64,
+    #[serde(serialize_with = "serialize_rounded")]
     pub avg_time_to_first_review_hours: f64,
+    #[serde(serialize_with = "serialize_rounded")]
     pub rework_rate: f64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct QualityMetrics {
+    #[serde(serialize_with = "serialize_rounded")]
     pub bug_rate: f64,
+    #[serde(serialize_with = "serialize_rounded")]
     pub reopen_rate: f64,
+    #[serde(serialize_with = "serialize_rounded")]
     pub pr_rejection_rate: f64,
+    #[serde(serialize_with = "serialize_rounded")]
     pub test_coverage_trend: f64,
 }
 
@@ -55,12 +68,16 @@ fn calculate_speed_metrics(
     // Cycle time: time from issue creation to close (business days)
     let closed_issues: Vec<_> = issues.iter().filter(|i| i.closed_at.is_some()).collect();
     
-    let avg_cycle_time = if !closed_issues.is_empty() {
-        let total_days: i64 = closed_issues
-            .iter()
-            .map(|i| business_days_between(&i.created_at, i.closed_at.as_ref().unwrap()))
-            .sum();
-        total_days as f64 / closed_issues.len() as f64
+    let cycle_days: Vec<i64> = closed_issues
+        .iter()
+        .filter_map(|i| {
+            let closed_at = i.closed_at.as_deref()?;
+            elapsed_business_days("issue", i.id, &i.created_at, closed_at)
+        })
+        .collect();
+
+    let avg_cycle_time = if !cycle_days.is_empty() {
+        cycle_days.iter().sum::<i64>() as f64 / cycle_days.len() as f64
     } else {
         0.0
     };
@@ -68,15 +85,17 @@ fn calculate_speed_metrics(
     // PR lead time: time from PR open to merge (in hours)
     let merged_prs: Vec<_> = prs.iter().filter(|p| p.merged_at.is_some()).collect();
     
-    let avg_pr_lead_time = if !merged_prs.is_empty() {
-        let total_hours: f64 = merged_prs
-            .iter()
-            .map(|p| {
-                let days = business_days_between(&p.created_at, p.merged_at.as_ref().unwrap());
-                days as f64 * 8.0 // Convert to hours (8-hour day)
-            })
-            .sum();
-        total_hours / merged_prs.len() as f64
+    let lead_hours: Vec<f64> = merged_prs
+        .iter()
+        .filter_map(|p| {
+            let merged_at = p.merged_at.as_deref()?;
+            let days = elapsed_business_days("PR", p.id, &p.created_at, merged_at)?;
+            Some(days as f64 * 8.0) // Convert to hours (8-hour day)
+        })
+        .collect();
+
+    let avg_pr_lead_time = if !lead_hours.is_empty() {
+        lead_hours.iter().sum::<f64>() / lead_hours.len() as f64
     } else {
         0.0
     };
@@ -177,6 +196,25 @@ fn calculate_quality_metrics(
     }
 }
 
+/// Business days between two timestamps, clamping negative spans to 0.
+/// Returns None (and logs) when either timestamp can't be parsed.
+fn elapsed_business_days(kind: &str, id: i64, start: &str, end: &str) -> Option<i64> {
+    let (start_date, end_date) = match (parse_date(start), parse_date(end)) {
+        (Some(s), Some(e)) => (s, e),
+        _ => {
+            tracing::warn!("Skipping {} {} with unparseable timestamps ({} -> {})", kind, id, start, end);
+            return None;
+        }
+    };
+
+    if end_date < start_date {
+        tracing::warn!("{} {} ends before it was created ({} -> {}), clamping duration to 0", kind, id, start, end);
+        return Some(0);
+    }
+
+    Some(business_days_between(start, end))
+}
+
 fn round_to_decimals(value: f64, decimals: u32) -> f64 {
     let multiplier = 10_f64.powi(decimals as i32);
     (value * multiplier).round() / multiplier
@@ -257,4 +295,52 @@ mod tests {
         let metrics = calculate_quality_metrics(&[], &prs, &[]);
         assert!((metrics.pr_rejection_rate - 0.333).abs() < 0.01);
     }
+
+    #[test]
+    fn test_merged_before_created_contributes_zero() {
+        let prs = vec![
+            make_pr("2024-02-09T10:00:00Z", Some("2024-02-05T10:00:00Z"), 10, 5), // clock skew
+        ];
+
+        let metrics = calculate_speed_metrics(&[], &prs, 7);
+        assert_eq!(metrics.avg_pr_lead_time_hours, 0.0);
+    }
+
+    #[test]
+    fn test_negative_duration_does_not_drag_average_below_zero() {
+        let prs = vec![
+            make_pr("2024-02-19", Some("2024-02-21"), 10, 5), // 2 business days = 16h
+            make_pr("2024-02-21", Some("2024-02-19"), 10, 5), // skewed, counts as 0
+        ];
+        let issues = vec![make_issue("2024-02-23", Some("2024-02-20"), vec![])];
+
+        let metrics = calculate_speed_metrics(&issues, &prs, 7);
+        assert_eq!(metrics.avg_pr_lead_time_hours, 8.0);
+        assert_eq!(metrics.avg_cycle_time_days, 0.0);
+    }
+
+    #[test]
+    fn test_unparseable_timestamps_are_skipped() {
+        let prs = vec![
+            make_pr("2024-02-19", Some("2024-02-21"), 10, 5),
+            make_pr("not-a-date", Some("2024-02-21"), 10, 5),
+        ];
+
+        let metrics = calculate_speed_metrics(&[], &prs, 7);
+        assert_eq!(metrics.avg_pr_lead_time_hours, 16.0);
+    }
+
+    #[test]
+    fn test_metrics_serialize_rounded() {
+        let speed = SpeedMetrics {
+            avg_cycle_time_days: 10.0 / 3.0,
+            avg_pr_lead_time_hours: 0.0,
+            throughput_per_week: 2.0 / 3.0,
+            trend: 0.0,
+        };
+        let json = serde_json::to_value(&speed).unwrap();
+        assert_eq!(json["avg_cycle_time_days"], 3.33);
+        assert_eq!(json["throughput_per_week"], 0.67);
+        assert_eq!(speed.avg_cycle_time_days, 10.0 / 3.0);
+    }
 }
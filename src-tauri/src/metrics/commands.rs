@@ -1,8 +1,10 @@
+use super::anonymize;
 use super::calculator::{calculate_dashboard_metrics, DashboardMetrics};
 use super::filter_params::MetricsFilters;
 use crate::db::AppState;
 use crate::db::metrics_queries;
 use chrono::{DateTime, Duration, Utc};
+use rusqlite::Connection;
 use serde::{Deserialize, Serialize};
 use tauri::State;
 
@@ -16,13 +18,10 @@ pub async fn get_dashboard_metrics(
     // Load settings from SQLite
     let settings = crate::db::queries::get_settings(&conn).map_err(|e| e.to_string())?;
     let history_days = settings.history_days;
+    let since = metrics_queries::Period::from_settings(history_days, &settings).since;
     let excluded_bots = settings.excluded_bots;
     let bug_labels = settings.bug_labels;
 
-    let since = (Utc::now() - Duration::days(history_days as i64))
-        .format("%Y-%m-%dT%H:%M:%SZ")
-        .to_string();
-
     let issues = crate::db::queries::get_issues_for_metrics(&conn, &since, &excluded_bots)
         .map_err(|e| e.to_string())?;
 
@@ -54,6 +53,89 @@ pub async fn get_squad_metrics(
     get_dashboard_metrics(state).await
 }
 
+/// One squad's dashboard metrics, as listed side by side in the squads overview
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SquadOverview {
+    pub squad_id: String,
+    pub squad_name: String,
+    pub member_count: usize,
+    pub metrics: DashboardMetrics,
+}
+
+/// Get every configured squad's dashboard metrics in one response, sorted by squad name
+#[tauri::command]
+pub async fn get_all_squads_overview(
+    days: Option<i32>,
+    state: State<'_, AppState>,
+) -> Result<Vec<SquadOverview>, String> {
+    let conn = state.sqlite.lock().map_err(|e| e.to_string())?;
+
+    let days = days.unwrap_or(30); // Default to 30 days
+    let settings = crate::db::queries::get_settings(&conn).map_err(|e| e.to_string())?;
+
+    let period = metrics_queries::Period::from_settings(days, &settings);
+
+    calculate_all_squads_overview(&conn, &period, &settings)
+}
+
+/// Calculate the squad-filtered dashboard metrics for each squad over a period.
+/// Squads without members get the metrics of an empty data set rather than the
+/// unfiltered totals an empty member filter would otherwise fall back to.
+fn calculate_all_squads_overview(
+    conn: &Connection,
+    period: &metrics_queries::Period,
+    settings: &crate::db::models::Settings,
+) -> Result<Vec<SquadOverview>, String> {
+    let mut squads = crate::db::queries::get_all_squads(conn).map_err(|e| e.to_string())?;
+    squads.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let mut overviews = Vec::with_capacity(squads.len());
+    for squad in squads {
+        let member_ids = crate::db::queries::get_squad_member_ids(conn, &squad.id)
+            .map_err(|e| e.to_string())?;
+
+        let (issues, prs) = if member_ids.is_empty() {
+            (Vec::new(), Vec::new())
+        } else {
+            let issues = crate::db::queries::get_issues_for_metrics_filtered(
+                conn,
+                &period.since,
+                None,
+                &settings.excluded_bots,
+                None,
+                None,
+                Default::default(),
+                Some(&member_ids),
+                Default::default(),
+            ).map_err(|e| e.to_string())?;
+
+            let prs = crate::db::queries::get_prs_for_metrics_filtered(
+                conn,
+                &period.since,
+                None,
+                &settings.excluded_bots,
+                None,
+                None,
+                Default::default(),
+                Some(&member_ids),
+                None,
+                Default::default(),
+            ).map_err(|e| e.to_string())?;
+
+            (issues, prs)
+        };
+
+        overviews.push(SquadOverview {
+            squad_id: squad.id,
+            squad_name: squad.name,
+            member_count: member_ids.len(),
+            metrics: calculate_dashboard_metrics(&issues, &prs, &settings.bug_labels, period.days as i64),
+        });
+    }
+
+    Ok(overviews)
+}
+
 /// Get metrics with filters applied
 #[tauri::command]
 pub async fn get_dashboard_metrics_filtered(
@@ -62,9 +144,15 @@ pub async fn get_dashboard_metrics_filtered(
 ) -> Result<DashboardMetrics, String> {
     let conn = state.sqlite.lock().map_err(|e| e.to_string())?;
 
+    calculate_filtered_metrics(&conn, filters)
+}
+
+/// Calculate dashboard metrics for a set of filters (shared by commands and saved presets)
+fn calculate_filtered_metrics(conn: &Connection, filters: MetricsFilters) -> Result<DashboardMetrics, String> {
     // Load settings from SQLite
-    let settings = crate::db::queries::get_settings(&conn).map_err(|e| e.to_string())?;
+    let settings = crate::db::queries::get_settings(conn).map_err(|e| e.to_string())?;
     let history_days = settings.history_days;
+    let default_period = metrics_queries::Period::from_settings(history_days, &settings);
     let excluded_bots = settings.excluded_bots;
     let bug_labels = settings.bug_labels;
 
@@ -73,15 +161,12 @@ pub async fn get_dashboard_metrics_filtered(
         (range.start, Some(range.end))
     } else {
         // Default to history_days from settings
-        let since = (Utc::now() - Duration::days(history_days as i64))
-            .format("%Y-%m-%dT%H:%M:%SZ")
-            .to_string();
-        (since, None)
+        (default_period.since, None)
     };
 
     // Get squad member IDs if squad filter is set
     let squad_member_ids = if let Some(ref squad_id) = filters.squad_id {
-        Some(crate::db::queries::get_squad_member_ids(&conn, squad_id)
+        Some(crate::db::queries::get_squad_member_ids(conn, squad_id)
             .map_err(|e| e.to_string())?)
     } else {
         None
@@ -89,23 +174,28 @@ pub async fn get_dashboard_metrics_filtered(
 
     // Fetch filtered data
     let issues = crate::db::queries::get_issues_for_metrics_filtered(
-        &conn,
+        conn,
         &since,
         until.as_deref(),
         &excluded_bots,
         filters.repository_ids.as_deref(),
         filters.user_id,
+        filters.role,
         squad_member_ids.as_deref(),
+        filters.visibility,
     ).map_err(|e| e.to_string())?;
 
     let prs = crate::db::queries::get_prs_for_metrics_filtered(
-        &conn,
+        conn,
         &since,
         until.as_deref(),
         &excluded_bots,
         filters.repository_ids.as_deref(),
         filters.user_id,
+        filters.role,
         squad_member_ids.as_deref(),
+        filters.base_branch.as_deref(),
+        filters.visibility,
     ).map_err(|e| e.to_string())?;
 
     // Use history_days from settings
@@ -148,7 +238,7 @@ pub async fn get_metrics_timeseries(
     };
 
     // Generate date buckets based on granularity
-    let date_buckets = generate_date_buckets(&start_date, &end_date, &granularity);
+    let date_buckets = generate_date_buckets(&start_date, &end_date, &granularity)?;
 
     // Load settings from SQLite
     let settings = crate::db::queries::get_settings(&conn).map_err(|e| e.to_string())?;
@@ -172,7 +262,9 @@ pub async fn get_metrics_timeseries(
             &excluded_bots,
             filters.repository_ids.as_deref(),
             filters.user_id,
+            filters.role,
             squad_member_ids.as_deref(),
+            filters.visibility,
         ).map_err(|e| e.to_string())?;
 
         let prs = crate::db::queries::get_prs_for_metrics_filtered(
@@ -182,7 +274,10 @@ pub async fn get_metrics_timeseries(
             &excluded_bots,
             filters.repository_ids.as_deref(),
             filters.user_id,
+            filters.role,
             squad_member_ids.as_deref(),
+            filters.base_branch.as_deref(),
+            filters.visibility,
         ).map_err(|e| e.to_string())?;
 
         let days = 7i64; // Simplified for weekly
@@ -199,9 +294,13 @@ pub async fn get_metrics_timeseries(
     Ok(timeseries)
 }
 
-fn generate_date_buckets(start: &str, end: &str, granularity: &str) -> Vec<(String, String)> {
-    let start_dt = DateTime::parse_from_rfc3339(start).unwrap().with_timezone(&Utc);
-    let end_dt = DateTime::parse_from_rfc3339(end).unwrap().with_timezone(&Utc);
+fn generate_date_buckets(start: &str, end: &str, granularity: &str) -> Result<Vec<(String, String)>, String> {
+    let start_dt = DateTime::parse_from_rfc3339(start)
+        .map_err(|e| format!("Invalid start date '{}': {}", start, e))?
+        .with_timezone(&Utc);
+    let end_dt = DateTime::parse_from_rfc3339(end)
+        .map_err(|e| format!("Invalid end date '{}': {}", end, e))?
+        .with_timezone(&Utc);
 
     let mut buckets = Vec::new();
     let mut current = start_dt;
@@ -221,7 +320,7 @@ fn generate_date_buckets(start: &str, end: &str, granularity: &str) -> Vec<(Stri
         current = next;
     }
 
-    buckets
+    Ok(buckets)
 }
 
 /// Get PR-based dashboard metrics (Amplifier-style)
@@ -234,7 +333,542 @@ pub async fn get_pr_based_metrics(
     let conn = state.sqlite.lock().map_err(|e| e.to_string())?;
 
     let days = days.unwrap_or(30); // Default to 30 days
+    let settings = crate::db::queries::get_settings(&conn).map_err(|e| e.to_string())?;
+    let period = metrics_queries::Period::from_settings(days, &settings);
+
+    metrics_queries::get_dashboard_metrics(&conn, &period)
+        .map_err(|e| e.to_string())
+}
+
+/// Get open PRs that are blocked (draft, blocking label, or merge conflicts)
+#[tauri::command]
+pub async fn get_blocked_prs(
+    days: Option<i32>,
+    anonymize: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<Vec<metrics_queries::BlockedPr>, String> {
+    let conn = state.sqlite.lock().map_err(|e| e.to_string())?;
+
+    let days = days.unwrap_or(30); // Default to 30 days
+    let settings = crate::db::queries::get_settings(&conn).map_err(|e| e.to_string())?;
+    let period = metrics_queries::Period::from_settings(days, &settings);
+
+    metrics_queries::get_blocked_prs(&conn, &period, &settings.blocking_labels)
+        .and_then(|prs| anonymize::apply(&conn, prs, anonymize.unwrap_or(false)))
+        .map_err(|e| e.to_string())
+}
+
+/// Get stale open PRs by authors with no recent activity (cleanup candidates)
+#[tauri::command]
+pub async fn get_abandoned_prs(
+    inactive_days: Option<i32>,
+    anonymize: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<Vec<metrics_queries::AbandonedPr>, String> {
+    let conn = state.sqlite.lock().map_err(|e| e.to_string())?;
+
+    let inactive_days = inactive_days.unwrap_or(30); // Default to 30 days
+    let settings = crate::db::queries::get_settings(&conn).map_err(|e| e.to_string())?;
+    let period = metrics_queries::Period::from_settings(inactive_days, &settings);
+
+    metrics_queries::get_abandoned_prs(&conn, &period)
+        .and_then(|prs| anonymize::apply(&conn, prs, anonymize.unwrap_or(false)))
+        .map_err(|e| e.to_string())
+}
+
+/// Get PR metrics attributed to squads by the paths each PR touches
+#[tauri::command]
+pub async fn get_path_owner_metrics(
+    days: Option<i32>,
+    state: State<'_, AppState>,
+) -> Result<Vec<crate::db::path_owner_queries::PathOwnerMetrics>, String> {
+    let conn = state.sqlite.lock().map_err(|e| e.to_string())?;
+
+    let days = days.unwrap_or(30); // Default to 30 days
+    let settings = crate::db::queries::get_settings(&conn).map_err(|e| e.to_string())?;
+    let period = metrics_queries::Period::from_settings(days, &settings);
+
+    crate::db::path_owner_queries::get_path_owner_metrics(&conn, &period)
+        .map_err(|e| e.to_string())
+}
+
+/// Get the slowest-to-merge PRs in a window (for retros)
+#[tauri::command]
+pub async fn get_slowest_prs(
+    days: Option<i32>,
+    limit: Option<usize>,
+    filters: Option<MetricsFilters>,
+    anonymize: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<Vec<metrics_queries::SlowPr>, String> {
+    let conn = state.sqlite.lock().map_err(|e| e.to_string())?;
+
+    let days = days.unwrap_or(30); // Default to 30 days
+    let limit = limit.unwrap_or(10);
+    let filters = filters.unwrap_or_default();
+    let settings = crate::db::queries::get_settings(&conn).map_err(|e| e.to_string())?;
+
+    let period = metrics_queries::Period::from_settings(days, &settings);
+
+    metrics_queries::get_slowest_prs(&conn, &period, limit, &filters, &settings.excluded_bots)
+        .and_then(|prs| anonymize::apply(&conn, prs, anonymize.unwrap_or(false)))
+        .map_err(|e| e.to_string())
+}
+
+/// Get median time from issue creation to first human response, per label
+#[tauri::command]
+pub async fn get_triage_time_by_label(
+    days: Option<i32>,
+    state: State<'_, AppState>,
+) -> Result<Vec<metrics_queries::LabelTriageTime>, String> {
+    let conn = state.sqlite.lock().map_err(|e| e.to_string())?;
+
+    let days = days.unwrap_or(30); // Default to 30 days
+    let settings = crate::db::queries::get_settings(&conn).map_err(|e| e.to_string())?;
+    let period = metrics_queries::Period::from_settings(days, &settings);
+
+    metrics_queries::get_triage_time_by_label(&conn, &period)
+        .map_err(|e| e.to_string())
+}
+
+/// Get issues that breached the configured per-label SLA rules
+#[tauri::command]
+pub async fn get_sla_breaches(
+    days: Option<i32>,
+    state: State<'_, AppState>,
+) -> Result<Vec<metrics_queries::SlaBreach>, String> {
+    let conn = state.sqlite.lock().map_err(|e| e.to_string())?;
+
+    let days = days.unwrap_or(30); // Default to 30 days
+    let settings = crate::db::queries::get_settings(&conn).map_err(|e| e.to_string())?;
+    let period = metrics_queries::Period::from_settings(days, &settings);
+
+    metrics_queries::get_sla_breaches(&conn, &period, &settings.sla_rules, Utc::now())
+        .map_err(|e| e.to_string())
+}
+
+/// Get issues closed per day/week/month with the median time they had been open
+#[tauri::command]
+pub async fn get_close_velocity_trend(
+    days: Option<i32>,
+    granularity: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Vec<metrics_queries::CloseVelocityBucket>, String> {
+    let conn = state.sqlite.lock().map_err(|e| e.to_string())?;
+
+    let days = days.unwrap_or(90); // Default to a quarter
+    let granularity = granularity.unwrap_or_else(|| "week".to_string());
+    let settings = crate::db::queries::get_settings(&conn).map_err(|e| e.to_string())?;
+    let period = metrics_queries::Period::from_settings(days, &settings);
+
+    metrics_queries::get_close_velocity_trend(&conn, &period, &granularity)
+        .map_err(|e| e.to_string())
+}
+
+/// Get the share of merged PRs with more than one distinct contributor
+#[tauri::command]
+pub async fn get_collaboration_index(
+    days: Option<i32>,
+    state: State<'_, AppState>,
+) -> Result<metrics_queries::CollaborationIndex, String> {
+    let conn = state.sqlite.lock().map_err(|e| e.to_string())?;
+
+    let days = days.unwrap_or(30); // Default to 30 days
+    let settings = crate::db::queries::get_settings(&conn).map_err(|e| e.to_string())?;
+    let period = metrics_queries::Period::from_settings(days, &settings);
+
+    metrics_queries::get_collaboration_index(&conn, &period)
+        .map_err(|e| e.to_string())
+}
+
+/// Get the count and total/median wait of open PRs still awaiting a first review
+#[tauri::command]
+pub async fn get_review_debt(state: State<'_, AppState>) -> Result<metrics_queries::ReviewDebt, String> {
+    let conn = state.sqlite.lock().map_err(|e| e.to_string())?;
+    let settings = crate::db::queries::get_settings(&conn).map_err(|e| e.to_string())?;
+    let bot_reviewers = crate::db::queries::get_bot_reviewer_ids(&conn, &settings.excluded_bots).map_err(|e| e.to_string())?;
+
+    metrics_queries::get_review_debt(&conn, Utc::now(), &bot_reviewers)
+        .map_err(|e| e.to_string())
+}
+
+/// Get a squad's aggregate after-hours/weekend shares and work-pattern heatmap
+#[tauri::command]
+pub async fn get_squad_work_pattern(
+    squad_id: String,
+    days: Option<i32>,
+    state: State<'_, AppState>,
+) -> Result<metrics_queries::SquadWorkPattern, String> {
+    let conn = state.sqlite.lock().map_err(|e| e.to_string())?;
+
+    let days = days.unwrap_or(30); // Default to 30 days
+    let settings = crate::db::queries::get_settings(&conn).map_err(|e| e.to_string())?;
+
+    let period = metrics_queries::Period::from_settings(days, &settings);
+
+    metrics_queries::get_squad_work_pattern(&conn, &squad_id, &period, &settings)
+        .map_err(|e| e.to_string())
+}
+
+/// Get the median number of review→push rounds on recently merged PRs
+#[tauri::command]
+pub async fn get_ping_pong_index(
+    days: Option<i32>,
+    state: State<'_, AppState>,
+) -> Result<metrics_queries::PingPongIndex, String> {
+    let conn = state.sqlite.lock().map_err(|e| e.to_string())?;
+
+    let days = days.unwrap_or(30); // Default to 30 days
+    let settings = crate::db::queries::get_settings(&conn).map_err(|e| e.to_string())?;
+
+    let period = metrics_queries::Period::from_settings(days, &settings);
+
+    metrics_queries::get_ping_pong_index(&conn, &period)
+        .map_err(|e| e.to_string())
+}
+
+/// Get median time issues carry each label before it's removed
+#[tauri::command]
+pub async fn get_label_dwell_times(
+    days: Option<i32>,
+    state: State<'_, AppState>,
+) -> Result<Vec<metrics_queries::LabelDwellTime>, String> {
+    let conn = state.sqlite.lock().map_err(|e| e.to_string())?;
+
+    let days = days.unwrap_or(30); // Default to 30 days
+    let settings = crate::db::queries::get_settings(&conn).map_err(|e| e.to_string())?;
+
+    let period = metrics_queries::Period::from_settings(days, &settings);
+
+    metrics_queries::get_label_dwell_times(&conn, &period)
+        .map_err(|e| e.to_string())
+}
+
+/// Get how evenly review load is shared (0 = even, 1 = one reviewer does everything)
+#[tauri::command]
+pub async fn get_review_balance(
+    days: Option<i32>,
+    anonymize: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<metrics_queries::ReviewBalance, String> {
+    let conn = state.sqlite.lock().map_err(|e| e.to_string())?;
+
+    let days = days.unwrap_or(30); // Default to 30 days
+    let settings = crate::db::queries::get_settings(&conn).map_err(|e| e.to_string())?;
+    let period = metrics_queries::Period::from_settings(days, &settings);
+
+    metrics_queries::get_review_balance(&conn, &period, &settings.excluded_bots)
+        .and_then(|balance| anonymize::apply(&conn, balance, anonymize.unwrap_or(false)))
+        .map_err(|e| e.to_string())
+}
+
+/// Get the share of recent issues and PRs that were planned (milestone or planning label)
+#[tauri::command]
+pub async fn get_planned_vs_unplanned(
+    days: Option<i32>,
+    state: State<'_, AppState>,
+) -> Result<metrics_queries::PlannedWorkSplit, String> {
+    let conn = state.sqlite.lock().map_err(|e| e.to_string())?;
+
+    let days = days.unwrap_or(30); // Default to 30 days
+    let settings = crate::db::queries::get_settings(&conn).map_err(|e| e.to_string())?;
+    let period = metrics_queries::Period::from_settings(days, &settings);
+
+    metrics_queries::get_planned_vs_unplanned(&conn, &period, &settings.planning_labels)
+        .map_err(|e| e.to_string())
+}
+
+/// List recently merged PRs that never got an approval from someone other than the author
+#[tauri::command]
+pub async fn get_review_bypasses(
+    days: Option<i32>,
+    anonymize: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<Vec<metrics_queries::ReviewBypass>, String> {
+    let conn = state.sqlite.lock().map_err(|e| e.to_string())?;
+
+    let days = days.unwrap_or(30); // Default to 30 days
+    let settings = crate::db::queries::get_settings(&conn).map_err(|e| e.to_string())?;
+    let period = metrics_queries::Period::from_settings(days, &settings);
+
+    let bot_users = crate::db::queries::get_bot_reviewer_ids(&conn, &settings.excluded_bots).map_err(|e| e.to_string())?;
+
+    metrics_queries::get_review_bypasses(&conn, &period, &bot_users)
+        .and_then(|prs| anonymize::apply(&conn, prs, anonymize.unwrap_or(false)))
+        .map_err(|e| e.to_string())
+}
+
+/// Get merged-PR counts per day of the week (Mon–Sun) in the team's timezone
+#[tauri::command]
+pub async fn get_throughput_by_weekday(
+    days: Option<i32>,
+    state: State<'_, AppState>,
+) -> Result<Vec<metrics_queries::WeekdayThroughput>, String> {
+    let conn = state.sqlite.lock().map_err(|e| e.to_string())?;
+
+    let days = days.unwrap_or(30); // Default to 30 days
+    let settings = crate::db::queries::get_settings(&conn).map_err(|e| e.to_string())?;
+    let period = metrics_queries::Period::from_settings(days, &settings);
+    let reverts = metrics_queries::RevertExclusion::from_settings(&conn, &period, &settings)
+        .map_err(|e| e.to_string())?;
+
+    metrics_queries::get_throughput_by_weekday(&conn, &period, settings.utc_offset_minutes, &reverts)
+        .map_err(|e| e.to_string())
+}
+
+/// Split recently merged PRs between core-team (internal) and external contributors
+#[tauri::command]
+pub async fn get_contribution_split(
+    days: Option<i32>,
+    state: State<'_, AppState>,
+) -> Result<metrics_queries::ContributionSplit, String> {
+    let conn = state.sqlite.lock().map_err(|e| e.to_string())?;
+
+    let days = days.unwrap_or(30); // Default to 30 days
+    let settings = crate::db::queries::get_settings(&conn).map_err(|e| e.to_string())?;
+
+    let period = metrics_queries::Period::from_settings(days, &settings);
+
+    metrics_queries::get_contribution_split(&conn, &period, &settings)
+        .map_err(|e| e.to_string())
+}
+
+/// Merged PRs and LOC per developer; headcount_basis overrides the configured basis
+#[tauri::command]
+pub async fn get_per_capita_velocity(
+    days: Option<i32>,
+    headcount_basis: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<metrics_queries::PerCapitaVelocity, String> {
+    let conn = state.sqlite.lock().map_err(|e| e.to_string())?;
+
+    let days = days.unwrap_or(30); // Default to 30 days
+    let settings = crate::db::queries::get_settings(&conn).map_err(|e| e.to_string())?;
+    let basis = headcount_basis.unwrap_or_else(|| settings.headcount_basis.clone());
+
+    let period = metrics_queries::Period::from_settings(days, &settings);
+
+    metrics_queries::get_per_capita_velocity(&conn, &period, &basis, settings.min_active_prs)
+        .map_err(|e| e.to_string())
+}
+
+/// Get the fewest contributors covering half of all merged PRs across enabled repositories
+#[tauri::command]
+pub async fn get_org_bus_factor(
+    days: Option<i32>,
+    anonymize: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<metrics_queries::OrgBusFactor, String> {
+    let conn = state.sqlite.lock().map_err(|e| e.to_string())?;
+
+    let days = days.unwrap_or(90); // Default to a quarter
+    let settings = crate::db::queries::get_settings(&conn).map_err(|e| e.to_string())?;
+    let period = metrics_queries::Period::from_settings(days, &settings);
+
+    metrics_queries::get_org_bus_factor(&conn, &period, &settings)
+        .and_then(|bus_factor| anonymize::apply(&conn, bus_factor, anonymize.unwrap_or(false)))
+        .map_err(|e| e.to_string())
+}
+
+/// Split recently merged PRs by author tenure tier (new / established / veteran)
+#[tauri::command]
+pub async fn get_contribution_by_tenure(
+    days: Option<i32>,
+    state: State<'_, AppState>,
+) -> Result<Vec<metrics_queries::TenureTier>, String> {
+    let conn = state.sqlite.lock().map_err(|e| e.to_string())?;
+
+    let days = days.unwrap_or(90); // Default to a quarter
+    let settings = crate::db::queries::get_settings(&conn).map_err(|e| e.to_string())?;
+    let period = metrics_queries::Period::from_settings(days, &settings);
+
+    metrics_queries::get_contribution_by_tenure(&conn, &period, &settings.excluded_bots)
+        .map_err(|e| e.to_string())
+}
+
+/// Project PR and issue volume for the next N days from the recent weekly trend
+#[tauri::command]
+pub async fn forecast_volume(
+    days_ahead: Option<i32>,
+    state: State<'_, AppState>,
+) -> Result<metrics_queries::VolumeForecast, String> {
+    let conn = state.sqlite.lock().map_err(|e| e.to_string())?;
+
+    let days_ahead = days_ahead.unwrap_or(7); // Default to the next week
+
+    metrics_queries::forecast_volume(&conn, days_ahead, Utc::now())
+        .map_err(|e| e.to_string())
+}
+
+/// Get median time PRs spend in each review stage (open → first review → approved → merged)
+#[tauri::command]
+pub async fn get_pr_funnel(
+    days: Option<i32>,
+    state: State<'_, AppState>,
+) -> Result<metrics_queries::PrFunnel, String> {
+    let conn = state.sqlite.lock().map_err(|e| e.to_string())?;
+
+    let days = days.unwrap_or(30); // Default to 30 days
+    let settings = crate::db::queries::get_settings(&conn).map_err(|e| e.to_string())?;
 
-    metrics_queries::get_dashboard_metrics(&conn, days)
+    let period = metrics_queries::Period::from_settings(days, &settings);
+    let bot_reviewers = crate::db::queries::get_bot_reviewer_ids(&conn, &settings.excluded_bots).map_err(|e| e.to_string())?;
+
+    metrics_queries::get_pr_funnel(&conn, &period, settings.exclude_self_reviews, &bot_reviewers)
         .map_err(|e| e.to_string())
 }
+
+/// Get the distribution of time to first review (<1h, 1-4h, 4-24h, 24h+)
+#[tauri::command]
+pub async fn get_review_latency_distribution(
+    days: Option<i32>,
+    state: State<'_, AppState>,
+) -> Result<metrics_queries::ReviewLatencyDistribution, String> {
+    let conn = state.sqlite.lock().map_err(|e| e.to_string())?;
+
+    let days = days.unwrap_or(30); // Default to 30 days
+    let settings = crate::db::queries::get_settings(&conn).map_err(|e| e.to_string())?;
+
+    let period = metrics_queries::Period::from_settings(days, &settings);
+
+    let bot_reviewers = crate::db::queries::get_bot_reviewer_ids(&conn, &settings.excluded_bots).map_err(|e| e.to_string())?;
+
+    metrics_queries::get_review_latency_distribution(&conn, &period, settings.exclude_self_reviews, &bot_reviewers)
+        .map_err(|e| e.to_string())
+}
+
+/// Get per-PR and median/p90 time from opening to first approval for merged PRs
+#[tauri::command]
+pub async fn get_approval_latency(
+    days: Option<i32>,
+    state: State<'_, AppState>,
+) -> Result<metrics_queries::ApprovalLatency, String> {
+    let conn = state.sqlite.lock().map_err(|e| e.to_string())?;
+
+    let days = days.unwrap_or(30); // Default to 30 days
+    let settings = crate::db::queries::get_settings(&conn).map_err(|e| e.to_string())?;
+
+    let period = metrics_queries::Period::from_settings(days, &settings);
+
+    let bot_reviewers = crate::db::queries::get_bot_reviewer_ids(&conn, &settings.excluded_bots).map_err(|e| e.to_string())?;
+
+    metrics_queries::get_approval_latency(&conn, &period, settings.exclude_self_reviews, &bot_reviewers)
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::filter_params::RepoVisibility;
+    use rusqlite::params;
+
+    #[test]
+    fn test_saved_filter_round_trip_applies_to_metrics() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::db::migrations::run_migrations(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO repositories (id, owner, name) VALUES (1, 'acme', 'api'), (2, 'acme', 'web');
+             INSERT INTO users (id, github_id, login) VALUES (1, 101, 'alice');",
+        )
+        .unwrap();
+
+        let created = (Utc::now() - Duration::days(2)).format("%Y-%m-%dT%H:%M:%SZ").to_string();
+        for (github_id, repo_id, size) in [(1, 1, 100), (2, 2, 10)] {
+            conn.execute(
+                "INSERT INTO pull_requests (github_id, repo_id, number, title, state, author_id, created_at, updated_at, additions, deletions, labels)
+                 VALUES (?1, ?2, ?1, 'PR', 'open', 1, ?3, ?3, ?4, 0, '[]')",
+                params![github_id, repo_id, created, size],
+            )
+            .unwrap();
+        }
+
+        let preset = MetricsFilters {
+            repository_ids: Some(vec![1]),
+            ..Default::default()
+        };
+        let id = crate::db::queries::save_filter(&conn, "API only", &preset).unwrap();
+
+        let saved = crate::db::queries::get_saved_filters(&conn).unwrap();
+        assert_eq!(saved.len(), 1);
+        assert_eq!(saved[0].id, id);
+        assert_eq!(saved[0].name, "API only");
+
+        let metrics = calculate_filtered_metrics(&conn, saved[0].filters.clone()).unwrap();
+        assert_eq!(metrics.ease.avg_pr_size_lines, 100.0);
+
+        crate::db::queries::delete_saved_filter(&conn, id).unwrap();
+        assert!(crate::db::queries::get_saved_filters(&conn).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_visibility_filter_restricts_aggregates() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::db::migrations::run_migrations(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO repositories (id, owner, name, is_private) VALUES (1, 'acme', 'sdk', FALSE), (2, 'acme', 'internal', TRUE);
+             INSERT INTO users (id, github_id, login) VALUES (1, 101, 'alice');",
+        )
+        .unwrap();
+
+        let created = (Utc::now() - Duration::days(2)).format("%Y-%m-%dT%H:%M:%SZ").to_string();
+        for (github_id, repo_id, size) in [(1, 1, 40), (2, 1, 60), (3, 2, 500)] {
+            conn.execute(
+                "INSERT INTO pull_requests (github_id, repo_id, number, title, state, author_id, created_at, updated_at, additions, deletions, labels)
+                 VALUES (?1, ?2, ?1, 'PR', 'open', 1, ?3, ?3, ?4, 0, '[]')",
+                params![github_id, repo_id, created, size],
+            )
+            .unwrap();
+        }
+        let size_for = |visibility| {
+            let filters = MetricsFilters { visibility, ..Default::default() };
+            calculate_filtered_metrics(&conn, filters).unwrap().ease.avg_pr_size_lines
+        };
+
+        assert_eq!(size_for(RepoVisibility::All), 200.0);
+        assert_eq!(size_for(RepoVisibility::Public), 50.0);
+        assert_eq!(size_for(RepoVisibility::Private), 500.0);
+
+        let parsed: MetricsFilters = serde_json::from_str(r#"{"visibility": "public"}"#).unwrap();
+        assert_eq!(parsed.visibility, RepoVisibility::Public);
+    }
+
+    #[test]
+    fn test_all_squads_overview_lists_each_squad_by_name() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::db::migrations::run_migrations(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO repositories (id, owner, name) VALUES (1, 'acme', 'api');
+             INSERT INTO users (id, github_id, login) VALUES (1, 101, 'alice'), (2, 102, 'bob');
+             INSERT INTO squads (id, name) VALUES ('s-web', 'Web'), ('s-core', 'Core'), ('s-new', 'New Hires');
+             INSERT INTO squad_members (squad_id, user_id) VALUES ('s-core', 1), ('s-web', 2);",
+        )
+        .unwrap();
+
+        let created = (Utc::now() - Duration::days(2)).format("%Y-%m-%dT%H:%M:%SZ").to_string();
+        for (github_id, author_id, size) in [(1, 1, 100), (2, 1, 300), (3, 2, 50)] {
+            conn.execute(
+                "INSERT INTO pull_requests (github_id, repo_id, number, title, state, author_id, created_at, updated_at, additions, deletions, labels)
+                 VALUES (?1, 1, ?1, 'PR', 'open', ?2, ?3, ?3, ?4, 0, '[]')",
+                params![github_id, author_id, created, size],
+            )
+            .unwrap();
+        }
+
+        let settings = crate::db::queries::get_settings(&conn).unwrap();
+        let period = metrics_queries::Period::new(30, "rolling", Utc::now());
+        let overview = calculate_all_squads_overview(&conn, &period, &settings).unwrap();
+
+        let names: Vec<_> = overview.iter().map(|o| o.squad_name.as_str()).collect();
+        assert_eq!(names, vec!["Core", "New Hires", "Web"]);
+
+        assert_eq!(overview[0].member_count, 1);
+        assert_eq!(overview[0].metrics.ease.avg_pr_size_lines, 200.0);
+        assert_eq!(overview[2].member_count, 1);
+        assert_eq!(overview[2].metrics.ease.avg_pr_size_lines, 50.0);
+
+        // A squad without members doesn't fall back to everyone's PRs
+        assert_eq!(overview[1].member_count, 0);
+        assert_eq!(overview[1].metrics.ease.avg_pr_size_lines, 0.0);
+        assert_eq!(overview[1].metrics.speed.throughput_per_week, 0.0);
+    }
+}
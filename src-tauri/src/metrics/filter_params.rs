@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+pub use crate::db::models::{RepoVisibility, UserRole};
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct MetricsFilters {
@@ -7,6 +9,12 @@ pub struct MetricsFilters {
     pub repository_ids: Option<Vec<i64>>,
     pub squad_id: Option<String>,
     pub user_id: Option<i64>,
+    #[serde(default)]
+    pub role: UserRole, // How user_id relates to the items
+    #[serde(default)]
+    pub base_branch: Option<String>, // Only PRs targeting this branch
+    #[serde(default)]
+    pub visibility: RepoVisibility, // Public/private repositories only
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -23,6 +31,9 @@ impl Default for MetricsFilters {
             repository_ids: None,
             squad_id: None,
             user_id: None,
+            role: UserRole::Author,
+            base_branch: None,
+            visibility: RepoVisibility::All,
         }
     }
 }
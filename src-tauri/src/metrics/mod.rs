@@ -1,4 +1,6 @@
+pub mod anonymize;
 pub mod business_days;
 pub mod calculator;
 pub mod commands;
 pub mod filter_params;
+pub mod precision;
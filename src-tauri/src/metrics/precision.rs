@@ -0,0 +1,47 @@
+use serde::Serializer;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+/// Decimal places used when serializing metric floats
+pub const DEFAULT_METRIC_PRECISION: u32 = 2;
+
+/// Highest precision accepted from settings
+pub const MAX_METRIC_PRECISION: u32 = 6;
+
+static METRIC_PRECISION: AtomicU32 = AtomicU32::new(DEFAULT_METRIC_PRECISION);
+
+/// Set the precision applied when metrics are serialized (clamped to MAX_METRIC_PRECISION)
+pub fn set_metric_precision(precision: u32) {
+    METRIC_PRECISION.store(precision.min(MAX_METRIC_PRECISION), Ordering::Relaxed);
+}
+
+/// Current serialization precision
+pub fn metric_precision() -> u32 {
+    METRIC_PRECISION.load(Ordering::Relaxed)
+}
+
+/// Round a value to the given number of decimal places
+pub fn round_to(value: f64, precision: u32) -> f64 {
+    let factor = 10f64.powi(precision as i32);
+    (value * factor).round() / factor
+}
+
+/// Serde `serialize_with` helper: rounds on the way out so computation keeps full precision
+pub fn serialize_rounded<S: Serializer>(value: &f64, serializer: S) -> Result<S::Ok, S::Error> {
+    if value.is_finite() {
+        serializer.serialize_f64(round_to(*value, metric_precision()))
+    } else {
+        serializer.serialize_f64(*value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_to_precision() {
+        assert_eq!(round_to(33.333333333333336, 2), 33.33);
+        assert_eq!(round_to(66.666666666666671, 0), 67.0);
+        assert_eq!(round_to(1.23456, 4), 1.2346);
+    }
+}
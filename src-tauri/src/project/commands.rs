@@ -1,5 +1,6 @@
 use crate::db::project_queries::*;
 use crate::db::AppState;
+use crate::metrics::anonymize;
 use tauri::State;
 
 /// Get timeline events for a project
@@ -35,6 +36,7 @@ pub async fn get_project_contributors(
     repo_id: i64,
     start_date: Option<String>,
     end_date: Option<String>,
+    anonymize: Option<bool>,
     state: State<'_, AppState>,
 ) -> Result<Vec<ContributorStats>, String> {
     let conn = state.sqlite.lock().map_err(|e| e.to_string())?;
@@ -45,6 +47,7 @@ pub async fn get_project_contributors(
         start_date.as_deref(),
         end_date.as_deref(),
     )
+    .and_then(|contributors| anonymize::apply(&conn, contributors, anonymize.unwrap_or(false)))
     .map_err(|e| e.to_string())
 }
 
@@ -104,3 +107,81 @@ pub async fn get_project_summary(
     )
     .map_err(|e| e.to_string())
 }
+
+/// Get a contributor leaderboard for a project over the last N days
+#[tauri::command]
+pub async fn get_contributor_leaderboard(
+    owner: String,
+    repo: String,
+    days: Option<i32>,
+    sort_by: Option<LeaderboardSort>,
+    anonymize: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<Vec<LeaderboardEntry>, String> {
+    let conn = state.sqlite.lock().map_err(|e| e.to_string())?;
+
+    let repository = crate::db::queries::get_repository_by_name(&conn, &owner, &repo)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Repository {}/{} not found", owner, repo))?;
+
+    crate::db::project_queries::get_contributor_leaderboard(
+        &conn,
+        repository.id,
+        days.unwrap_or(30),
+        sort_by.unwrap_or(LeaderboardSort::MergedPrs),
+    )
+    .and_then(|entries| anonymize::apply(&conn, entries, anonymize.unwrap_or(false)))
+    .map_err(|e| e.to_string())
+}
+
+/// Get the language breakdown for a repository, with byte percentages
+#[tauri::command]
+pub async fn get_repo_languages(
+    owner: String,
+    repo: String,
+    state: State<'_, AppState>,
+) -> Result<RepoLanguages, String> {
+    let conn = state.sqlite.lock().map_err(|e| e.to_string())?;
+
+    crate::db::project_queries::get_repo_languages(&conn, &owner, &repo)
+        .map_err(|e| e.to_string())
+}
+
+/// Get a single PR with its reviews, commit count, linked issues and changed files
+#[tauri::command]
+pub async fn get_pr_detail(
+    repo_id: i64,
+    number: i32,
+    anonymize: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<PrDetail, String> {
+    let conn = state.sqlite.lock().map_err(|e| e.to_string())?;
+
+    let detail = crate::db::project_queries::get_pr_detail(&conn, repo_id, number)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("PR #{} not found in repository {}", number, repo_id))?;
+    anonymize::apply(&conn, detail, anonymize.unwrap_or(false)).map_err(|e| e.to_string())
+}
+
+/// Get the files changed by the most PRs in a repository over the last N days
+#[tauri::command]
+pub async fn get_file_hotspots(
+    owner: String,
+    repo: String,
+    days: Option<i32>,
+    limit: Option<usize>,
+    state: State<'_, AppState>,
+) -> Result<Vec<FileHotspot>, String> {
+    let conn = state.sqlite.lock().map_err(|e| e.to_string())?;
+
+    let repository = crate::db::queries::get_repository_by_name(&conn, &owner, &repo)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Repository {}/{} not found", owner, repo))?;
+
+    let days = days.unwrap_or(90); // Default to a quarter
+    let settings = crate::db::queries::get_settings(&conn).map_err(|e| e.to_string())?;
+    let period = crate::db::metrics_queries::Period::from_settings(days, &settings);
+
+    crate::db::project_queries::get_file_hotspots(&conn, repository.id, &period, limit.unwrap_or(20))
+        .map_err(|e| e.to_string())
+}
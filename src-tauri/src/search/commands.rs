@@ -1,5 +1,5 @@
-use super::duplicates::{find_duplicates_for_item, DuplicateMatch};
-use super::hybrid::{hybrid_search as do_hybrid_search, SearchResult};
+use super::duplicates::{find_duplicates_for_item, item_repo_id, DuplicateMatch, RepoScope};
+use super::hybrid::{hybrid_search as do_hybrid_search, SearchResult, DEFAULT_ALPHA, DEFAULT_SEARCH_LIMIT, MAX_SEARCH_LIMIT};
 use super::vector_store::ItemType;
 use crate::db::{queries, AppState};
 use tauri::State;
@@ -11,16 +11,22 @@ pub struct SearchResultWithDuplicates {
     pub duplicates: Option<Vec<DuplicateMatch>>,
 }
 
-/// Perform hybrid search with optional duplicate detection
+/// Perform hybrid search with optional duplicate detection.
+/// `alpha` (0-1) weights semantic similarity against keyword matches.
 #[tauri::command]
 pub async fn hybrid_search(
     query: String,
     include_duplicates: bool,
+    limit: Option<usize>,
+    alpha: Option<f32>,
     state: State<'_, AppState>,
 ) -> Result<Vec<SearchResultWithDuplicates>, String> {
     let conn = state.sqlite.lock().unwrap();
 
-    let results = do_hybrid_search(&query, &conn, 20)
+    let limit = limit.unwrap_or(DEFAULT_SEARCH_LIMIT).clamp(1, MAX_SEARCH_LIMIT);
+    let alpha = alpha.unwrap_or(DEFAULT_ALPHA).clamp(0.0, 1.0);
+
+    let results = do_hybrid_search(&query, &conn, limit, alpha)
         .map_err(|e| e.to_string())?;
 
     // Optionally find duplicates for each result
@@ -45,7 +51,7 @@ pub async fn hybrid_search(
                 };
 
                 if let Some(emb) = embedding {
-                    find_duplicates_for_item(id, item_type, &emb, &conn, false, None)
+                    find_duplicates_for_item(id, item_type, &emb, &conn, RepoScope::AllRepos, None)
                         .ok()
                 } else {
                     None
@@ -66,11 +72,12 @@ pub async fn hybrid_search(
     Ok(results_with_duplicates)
 }
 
-/// Find duplicates for a specific item
+/// Find duplicates for a specific item, within its repository unless `across_repos` is set
 #[tauri::command]
 pub async fn find_duplicates(
     item_id: String,
     item_type: String,
+    across_repos: Option<bool>,
     state: State<'_, AppState>,
 ) -> Result<Vec<DuplicateMatch>, String> {
     let conn = state.sqlite.lock().unwrap();
@@ -103,7 +110,26 @@ pub async fn find_duplicates(
             .ok_or_else(|| "No embedding found for this PR".to_string())?,
     };
 
+    let scope = if across_repos.unwrap_or(false) { RepoScope::AllRepos } else { RepoScope::SameRepo };
+    let repo_id = item_repo_id(&conn, id, &typ).map_err(|e| e.to_string())?;
+
     // Find duplicates
-    find_duplicates_for_item(id, typ, &embedding, &conn, false, None)
+    find_duplicates_for_item(id, typ, &embedding, &conn, scope, repo_id)
         .map_err(|e| e.to_string())
 }
+
+/// Per-repository share of issues/PRs with embeddings, so the UI can flag incomplete search
+#[tauri::command]
+pub async fn get_embedding_coverage(state: State<'_, AppState>) -> Result<Vec<queries::RepoEmbeddingCoverage>, String> {
+    let conn = state.sqlite.lock().map_err(|e| e.to_string())?;
+
+    queries::get_embedding_coverage(&conn, crate::embeddings::ACTIVE_EMBEDDING_MODEL).map_err(|e| e.to_string())
+}
+
+/// Drop and repopulate the full-text search index; returns the number of documents indexed
+#[tauri::command]
+pub async fn rebuild_fts_index(state: State<'_, AppState>) -> Result<usize, String> {
+    let conn = state.sqlite.lock().map_err(|e| e.to_string())?;
+
+    super::fts::rebuild_fts_index(&conn).map_err(|e| e.to_string())
+}
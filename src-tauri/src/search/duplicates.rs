@@ -1,15 +1,28 @@
 use anyhow::Result;
-use rusqlite::Connection;
+use rusqlite::{Connection, OptionalExtension};
 use serde::{Deserialize, Serialize};
 
 use super::vector_store::{find_similar_excluding, ItemType};
 
 const DUPLICATE_THRESHOLD: f32 = 0.85;
+const MAX_DUPLICATES: usize = 10;
+
+/// Which repositories a duplicate may come from, relative to the item's own repository
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RepoScope {
+    /// Any repository, including the item's own
+    AllRepos,
+    /// Only the item's own repository
+    SameRepo,
+    /// Only repositories other than the item's own
+    OtherRepos,
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DuplicateMatch {
     pub id: String,
     pub title: String,
+    pub repo_id: i64,
     pub repo: String,
     pub number: i32,
     pub similarity: f32,
@@ -22,28 +35,38 @@ pub fn find_duplicates_for_item(
     item_type: ItemType,
     item_embedding: &[f32],
     conn: &Connection,
-    exclude_same_repo: bool,
+    scope: RepoScope,
     item_repo_id: Option<i64>,
 ) -> Result<Vec<DuplicateMatch>> {
-    // Find similar items excluding the item itself
+    // Find similar items excluding the item itself; the repo scope is applied before
+    // taking the top matches so a narrow scope still gets a full list
     let similar_items = find_similar_excluding(
         item_embedding,
         conn,
         item_id,
         item_type.clone(),
-        10, // Top 10 potential duplicates
+        usize::MAX,
         DUPLICATE_THRESHOLD,
     )?;
 
     let mut duplicates = Vec::new();
 
     for sim in similar_items {
-        // If exclude_same_repo is true, skip items from the same repo
-        if exclude_same_repo {
-            if let Some(repo_id) = item_repo_id {
-                if sim.repo_id == repo_id {
-                    continue;
-                }
+        if duplicates.len() >= MAX_DUPLICATES {
+            break;
+        }
+
+        // Never report the item as its own duplicate
+        if sim.id == item_id && sim.item_type == item_type {
+            continue;
+        }
+
+        if let Some(repo_id) = item_repo_id {
+            let same_repo = sim.repo_id == repo_id;
+            match scope {
+                RepoScope::SameRepo if !same_repo => continue,
+                RepoScope::OtherRepos if same_repo => continue,
+                _ => {}
             }
         }
 
@@ -60,6 +83,7 @@ pub fn find_duplicates_for_item(
                         Ok(DuplicateMatch {
                             id: format!("issue-{}", sim.id),
                             title: row.get(1)?,
+                            repo_id: sim.repo_id,
                             repo: row.get(3)?,
                             number: row.get(2)?,
                             similarity: sim.similarity,
@@ -79,6 +103,7 @@ pub fn find_duplicates_for_item(
                         Ok(DuplicateMatch {
                             id: format!("pr-{}", sim.id),
                             title: row.get(1)?,
+                            repo_id: sim.repo_id,
                             repo: row.get(3)?,
                             number: row.get(2)?,
                             similarity: sim.similarity,
@@ -123,7 +148,7 @@ pub fn find_all_duplicates(
                 ItemType::Issue,
                 &embedding,
                 conn,
-                false, // Don't exclude same repo for batch processing
+                RepoScope::AllRepos, // Don't exclude same repo for batch processing
                 Some(repo_id),
             )?;
 
@@ -136,6 +161,17 @@ pub fn find_all_duplicates(
     Ok(all_duplicates)
 }
 
+/// Repository an issue or PR belongs to
+pub fn item_repo_id(conn: &Connection, item_id: i64, item_type: &ItemType) -> Result<Option<i64>> {
+    let table = match item_type {
+        ItemType::Issue => "issues",
+        ItemType::PullRequest => "pull_requests",
+    };
+    Ok(conn
+        .query_row(&format!("SELECT repo_id FROM {} WHERE id = ?1", table), [item_id], |row| row.get(0))
+        .optional()?)
+}
+
 /// Calculate cosine similarity between two embeddings
 pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
     if a.len() != b.len() {
@@ -188,4 +224,47 @@ mod tests {
     fn test_threshold() {
         assert!(DUPLICATE_THRESHOLD >= 0.0 && DUPLICATE_THRESHOLD <= 1.0);
     }
+
+    fn setup_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::db::migrations::run_migrations(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO repositories (id, owner, name) VALUES (1, 'acme', 'api'), (2, 'acme', 'web');",
+        )
+        .unwrap();
+
+        for (id, repo_id, embedding) in [
+            (1, 1, [1.0, 0.0, 0.0]),
+            (2, 2, [0.99, 0.05, 0.0]), // Near-duplicate of #1 in another repo
+            (3, 1, [0.0, 1.0, 0.0]),
+        ] {
+            conn.execute(
+                "INSERT INTO issues (id, github_id, repo_id, number, title, state, created_at, updated_at, labels)
+                 VALUES (?1, ?1, ?2, ?1, 'Login fails', 'open', '2024-01-01T00:00:00Z', '2024-01-01T00:00:00Z', '[]')",
+                [id, repo_id],
+            )
+            .unwrap();
+            crate::db::queries::set_issue_embedding(&conn, id, &embedding, "test-model").unwrap();
+        }
+
+        conn
+    }
+
+    #[test]
+    fn test_cross_repo_duplicates_only_when_requested() {
+        let conn = setup_db();
+        let embedding = crate::db::queries::get_issue_embedding(&conn, 1).unwrap().unwrap();
+        let repo_id = item_repo_id(&conn, 1, &ItemType::Issue).unwrap();
+        assert_eq!(repo_id, Some(1));
+
+        let same_repo = find_duplicates_for_item(1, ItemType::Issue, &embedding, &conn, RepoScope::SameRepo, repo_id).unwrap();
+        assert!(same_repo.is_empty());
+
+        let across = find_duplicates_for_item(1, ItemType::Issue, &embedding, &conn, RepoScope::AllRepos, repo_id).unwrap();
+        assert_eq!(across.len(), 1);
+        assert_eq!(across[0].id, "issue-2");
+        assert_eq!(across[0].repo_id, 2);
+        assert_eq!(across[0].repo, "acme/web");
+        assert!(across.iter().all(|d| d.id != "issue-1"));
+    }
 }
@@ -0,0 +1,94 @@
+use anyhow::Result;
+use rusqlite::{params, Connection};
+
+use crate::db::migrations::SEARCH_FTS_SCHEMA;
+
+/// Drop and repopulate the full-text index from the current issues and PRs.
+/// Runs in a single transaction; returns the number of documents indexed.
+pub fn rebuild_fts_index(conn: &Connection) -> Result<usize> {
+    let tx = conn.unchecked_transaction()?;
+
+    tx.execute_batch("DROP TABLE IF EXISTS search_fts;")?;
+    tx.execute_batch(SEARCH_FTS_SCHEMA)?;
+
+    let issues = tx.execute(
+        "INSERT INTO search_fts (rowid, item_type, item_id, title, body)
+         SELECT id * 2, 'issue', id, title, COALESCE(body, '') FROM issues",
+        [],
+    )?;
+    let prs = tx.execute(
+        "INSERT INTO search_fts (rowid, item_type, item_id, title, body)
+         SELECT id * 2 + 1, 'pull_request', id, title, COALESCE(body, '') FROM pull_requests",
+        [],
+    )?;
+
+    tx.commit()?;
+    Ok(issues + prs)
+}
+
+/// Keyword search over titles and bodies; returns (item_type, id) pairs, best match first
+pub fn keyword_search(conn: &Connection, query: &str, limit: usize) -> Result<Vec<(String, i64)>> {
+    // Quote each term so user input can't be parsed as FTS5 query syntax
+    let match_expr = query
+        .split_whitespace()
+        .map(|term| format!("\"{}\"", term.replace('"', "\"\"")))
+        .collect::<Vec<_>>()
+        .join(" ");
+    if match_expr.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let mut stmt = conn.prepare(
+        "SELECT item_type, item_id FROM search_fts
+         WHERE search_fts MATCH ?1
+         ORDER BY rank
+         LIMIT ?2",
+    )?;
+    let results = stmt
+        .query_map(params![match_expr, limit as i64], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::db::migrations::run_migrations(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO repositories (id, owner, name) VALUES (1, 'acme', 'api');
+             INSERT INTO issues (id, github_id, repo_id, number, title, body, state, created_at, updated_at, labels)
+             VALUES (1, 1, 1, 1, 'Login page crashes', 'Stack trace attached', 'open',
+                     '2024-01-01T00:00:00Z', '2024-01-01T00:00:00Z', '[]');
+             INSERT INTO pull_requests (id, github_id, repo_id, number, title, body, state, created_at, updated_at, labels)
+             VALUES (1, 2, 1, 2, 'Fix login crash', NULL, 'open',
+                     '2024-01-02T00:00:00Z', '2024-01-02T00:00:00Z', '[]');",
+        )
+        .unwrap();
+        conn
+    }
+
+    #[test]
+    fn test_index_follows_inserts_and_updates() {
+        let conn = setup_db();
+        assert_eq!(keyword_search(&conn, "login", 10).unwrap().len(), 2);
+
+        conn.execute("UPDATE issues SET title = 'Signup page crashes' WHERE id = 1", []).unwrap();
+        assert_eq!(keyword_search(&conn, "login", 10).unwrap(), vec![("pull_request".to_string(), 1)]);
+        assert_eq!(keyword_search(&conn, "signup", 10).unwrap(), vec![("issue".to_string(), 1)]);
+    }
+
+    #[test]
+    fn test_rebuild_restores_cleared_index() {
+        let conn = setup_db();
+        conn.execute("DELETE FROM search_fts", []).unwrap();
+        assert!(keyword_search(&conn, "login", 10).unwrap().is_empty());
+
+        assert_eq!(rebuild_fts_index(&conn).unwrap(), 2);
+        let results = keyword_search(&conn, "login crash", 10).unwrap();
+        assert_eq!(results, vec![("pull_request".to_string(), 1)]);
+        assert_eq!(keyword_search(&conn, "stack", 10).unwrap(), vec![("issue".to_string(), 1)]);
+    }
+}
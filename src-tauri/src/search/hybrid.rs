@@ -1,8 +1,12 @@
 use anyhow::{Context, Result};
 use rusqlite::Connection;
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 
+use crate::db::queries::{get_issue_embedding, get_pr_embedding};
 use crate::embeddings::generate_embedding;
+use super::duplicates::cosine_similarity;
+use super::fts::keyword_search;
 use super::vector_store::{search_similar, ItemType};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -17,138 +21,312 @@ pub struct SearchResult {
     pub author: String,
     pub created_at: String,
     pub url: String,
-    pub score: f32,
+    pub score: f32,          // Same as combined_score, kept for existing callers
+    pub vector_score: Option<f32>, // Cosine similarity to the query, None when the item has no embedding
+    pub keyword_score: f32,  // Full-text rank of the match, 0-1 (0 when not a keyword hit)
+    pub combined_score: f32, // alpha * vector_score (0 when None) + (1 - alpha) * keyword_score
 }
 
-/// Perform hybrid search using semantic similarity and keyword boost
+/// Weight of vector similarity in the combined score when no alpha is given
+pub const DEFAULT_ALPHA: f32 = 0.7;
+/// Results returned when no limit is given
+pub const DEFAULT_SEARCH_LIMIT: usize = 20;
+/// Most results a single search may return
+pub const MAX_SEARCH_LIMIT: usize = 100;
+
+/// Perform hybrid search blending semantic similarity with full-text keyword matches.
+/// Candidates come from both the vector search and the FTS index; `alpha` weights the
+/// vector score (1.0 = pure semantic, 0.0 = pure keyword).
 pub fn hybrid_search(
     query: &str,
     conn: &Connection,
     limit: usize,
+    alpha: f32,
 ) -> Result<Vec<SearchResult>> {
     // Step 1: Generate query embedding
     let query_embedding = generate_embedding(query)
         .context("Failed to generate query embedding")?;
 
-    // Step 2: Vector similarity search (get top 100 to allow for keyword reranking)
+    // Step 2: Vector similarity search (get extra candidates to allow for keyword reranking)
     let similarity_matches = search_similar(&query_embedding, conn, limit * 2, 0.3)?;
 
-    if similarity_matches.is_empty() {
+    // Step 3: Keyword search over the full-text index, best match first
+    let keyword_hits = keyword_search(conn, query, limit * 2)?;
+
+    if similarity_matches.is_empty() && keyword_hits.is_empty() {
         return Ok(vec![]);
     }
 
-    // Step 3: Convert to SearchResult and enrich with data
+    // Step 4: Convert to SearchResult and enrich with data. Keyword-only hits fell below the
+    // similarity threshold (or have no embedding), so their similarity is computed here.
+    let vector_candidates = similarity_matches.iter().map(|m| (m.item_type.clone(), m.id, Some(m.similarity)));
+    let keyword_candidates = keyword_hits.iter().map(|(item_type, id)| (fts_item_type(item_type), *id, None));
+
+    let mut seen = HashSet::new();
     let mut results = Vec::new();
-    for m in similarity_matches {
-        let search_result = match m.item_type {
-            ItemType::Issue => {
-                // Fetch full issue data
-                let issue_opt = conn.query_row(
-                    "SELECT i.id, i.title, i.body, i.number, i.state, i.created_at,
-                            r.owner || '/' || r.name as repo, u.login as author
-                     FROM issues i
-                     JOIN repositories r ON i.repo_id = r.id
-                     LEFT JOIN users u ON i.author_id = u.id
-                     WHERE i.id = ?1",
-                    [m.id],
-                    |row| {
-                        let body: String = row.get(2)?;
-                        let body_preview = if body.len() > 200 {
-                            format!("{}...", &body[..200])
-                        } else {
-                            body
-                        };
-
-                        Ok(SearchResult {
-                            id: format!("issue-{}", m.id),
-                            item_type: "issue".to_string(),
-                            title: row.get(1)?,
-                            body_preview,
-                            repo: row.get(6)?,
-                            number: row.get(3)?,
-                            state: row.get(4)?,
-                            author: row.get::<_, Option<String>>(7)?.unwrap_or_default(),
-                            created_at: row.get(5)?,
-                            url: format!("https://github.com/{}/issues/{}", row.get::<_, String>(6)?, row.get::<_, i32>(3)?),
-                            score: m.similarity,
-                        })
-                    },
-                );
-                issue_opt.ok()
-            }
-            ItemType::PullRequest => {
-                // Fetch full PR data
-                let pr_opt = conn.query_row(
-                    "SELECT pr.id, pr.title, pr.body, pr.number, pr.state, pr.created_at,
-                            r.owner || '/' || r.name as repo, u.login as author
-                     FROM pull_requests pr
-                     JOIN repositories r ON pr.repo_id = r.id
-                     LEFT JOIN users u ON pr.author_id = u.id
-                     WHERE pr.id = ?1",
-                    [m.id],
-                    |row| {
-                        let body: String = row.get(2)?;
-                        let body_preview = if body.len() > 200 {
-                            format!("{}...", &body[..200])
-                        } else {
-                            body
-                        };
-
-                        Ok(SearchResult {
-                            id: format!("pr-{}", m.id),
-                            item_type: "pull_request".to_string(),
-                            title: row.get(1)?,
-                            body_preview,
-                            repo: row.get(6)?,
-                            number: row.get(3)?,
-                            state: row.get(4)?,
-                            author: row.get::<_, Option<String>>(7)?.unwrap_or_default(),
-                            created_at: row.get(5)?,
-                            url: format!("https://github.com/{}/pull/{}", row.get::<_, String>(6)?, row.get::<_, i32>(3)?),
-                            score: m.similarity,
-                        })
-                    },
-                );
-                pr_opt.ok()
-            }
+    for (item_type, id, similarity) in vector_candidates.chain(keyword_candidates) {
+        if !seen.insert(result_id(&item_type, id)) {
+            continue;
+        }
+        let similarity = match similarity {
+            Some(similarity) => Some(similarity),
+            None => stored_similarity(conn, &item_type, id, &query_embedding)?,
         };
-
-        if let Some(result) = search_result {
+        if let Some(result) = load_result(conn, &item_type, id, similarity) {
             results.push(result);
         }
     }
 
-    // Step 4: Apply keyword boost for reranking
-    apply_keyword_boost(&mut results, query);
+    // Step 5: Blend in keyword ranks for reranking
+    apply_keyword_scores(&mut results, &keyword_scores(&keyword_hits), alpha);
 
-    // Step 5: Re-sort by boosted score and limit
+    // Step 6: Re-sort by combined score and limit
     results.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap());
     results.truncate(limit);
 
     Ok(results)
 }
 
-/// Rerank results using keyword matching boost
-pub fn apply_keyword_boost(results: &mut [SearchResult], query: &str) {
-    // Fixed: Create owned String first to avoid lifetime issues
-    let query_lower = query.to_lowercase();
-    let query_terms: Vec<&str> = query_lower.split_whitespace().collect();
-    
+/// `SearchResult::id` for an item ("issue-12", "pr-34")
+fn result_id(item_type: &ItemType, id: i64) -> String {
+    match item_type {
+        ItemType::Issue => format!("issue-{}", id),
+        ItemType::PullRequest => format!("pr-{}", id),
+    }
+}
+
+/// Item type of a full-text hit ("issue" or "pull_request")
+fn fts_item_type(item_type: &str) -> ItemType {
+    if item_type == "issue" {
+        ItemType::Issue
+    } else {
+        ItemType::PullRequest
+    }
+}
+
+/// Cosine similarity between the query and an item's stored embedding, None if it has none
+fn stored_similarity(conn: &Connection, item_type: &ItemType, id: i64, query_embedding: &[f32]) -> Result<Option<f32>> {
+    let embedding = match item_type {
+        ItemType::Issue => get_issue_embedding(conn, id)?,
+        ItemType::PullRequest => get_pr_embedding(conn, id)?,
+    };
+    Ok(embedding.map(|embedding| cosine_similarity(query_embedding, &embedding)))
+}
+
+/// Fetch the data shown for a search hit
+fn load_result(conn: &Connection, item_type: &ItemType, id: i64, similarity: Option<f32>) -> Option<SearchResult> {
+    match item_type {
+        ItemType::Issue => {
+            // Fetch full issue data
+            let issue_opt = conn.query_row(
+                "SELECT i.id, i.title, i.body, i.number, i.state, i.created_at,
+                        r.owner || '/' || r.name as repo, u.login as author
+                 FROM issues i
+                 JOIN repositories r ON i.repo_id = r.id
+                 LEFT JOIN users u ON i.author_id = u.id
+                 WHERE i.id = ?1",
+                [id],
+                |row| {
+                    let body: String = row.get::<_, Option<String>>(2)?.unwrap_or_default();
+                    let body_preview = if body.len() > 200 {
+                        format!("{}...", &body[..200])
+                    } else {
+                        body
+                    };
+
+                    Ok(SearchResult {
+                        id: format!("issue-{}", id),
+                        item_type: "issue".to_string(),
+                        title: row.get(1)?,
+                        body_preview,
+                        repo: row.get(6)?,
+                        number: row.get(3)?,
+                        state: row.get(4)?,
+                        author: row.get::<_, Option<String>>(7)?.unwrap_or_default(),
+                        created_at: row.get(5)?,
+                        url: format!("https://github.com/{}/issues/{}", row.get::<_, String>(6)?, row.get::<_, i32>(3)?),
+                        score: similarity.unwrap_or(0.0),
+                        vector_score: similarity,
+                        keyword_score: 0.0,
+                        combined_score: similarity.unwrap_or(0.0),
+                    })
+                },
+            );
+            issue_opt.ok()
+        }
+        ItemType::PullRequest => {
+            // Fetch full PR data
+            let pr_opt = conn.query_row(
+                "SELECT pr.id, pr.title, pr.body, pr.number, pr.state, pr.created_at,
+                        r.owner || '/' || r.name as repo, u.login as author
+                 FROM pull_requests pr
+                 JOIN repositories r ON pr.repo_id = r.id
+                 LEFT JOIN users u ON pr.author_id = u.id
+                 WHERE pr.id = ?1",
+                [id],
+                |row| {
+                    let body: String = row.get::<_, Option<String>>(2)?.unwrap_or_default();
+                    let body_preview = if body.len() > 200 {
+                        format!("{}...", &body[..200])
+                    } else {
+                        body
+                    };
+
+                    Ok(SearchResult {
+                        id: format!("pr-{}", id),
+                        item_type: "pull_request".to_string(),
+                        title: row.get(1)?,
+                        body_preview,
+                        repo: row.get(6)?,
+                        number: row.get(3)?,
+                        state: row.get(4)?,
+                        author: row.get::<_, Option<String>>(7)?.unwrap_or_default(),
+                        created_at: row.get(5)?,
+                        url: format!("https://github.com/{}/pull/{}", row.get::<_, String>(6)?, row.get::<_, i32>(3)?),
+                        score: similarity.unwrap_or(0.0),
+                        vector_score: similarity,
+                        keyword_score: 0.0,
+                        combined_score: similarity.unwrap_or(0.0),
+                    })
+                },
+            );
+            pr_opt.ok()
+        }
+    }
+}
+
+/// Keyword scores for full-text hits (best first), keyed by `SearchResult::id`:
+/// 1.0 for the best match, falling linearly with rank
+pub fn keyword_scores(hits: &[(String, i64)]) -> HashMap<String, f32> {
+    let total = hits.len() as f32;
+    hits.iter()
+        .enumerate()
+        .map(|(rank, (item_type, id))| (result_id(&fts_item_type(item_type), *id), (total - rank as f32) / total))
+        .collect()
+}
+
+/// Alpha-weighted blend of vector and keyword scores; an item without an embedding has no vector similarity
+pub fn combined_score(vector_score: Option<f32>, keyword_score: f32, alpha: f32) -> f32 {
+    alpha * vector_score.unwrap_or(0.0) + (1.0 - alpha) * keyword_score
+}
+
+/// Fill in keyword and combined scores so the ranking can be explained
+pub fn apply_keyword_scores(results: &mut [SearchResult], keyword_scores: &HashMap<String, f32>, alpha: f32) {
     for result in results.iter_mut() {
-        let title_lower = result.title.to_lowercase();
-        let body_lower = result.body_preview.to_lowercase();
-        
-        let mut keyword_boost: f32 = 0.0;
-        for term in &query_terms {
-            if title_lower.contains(term) {
-                keyword_boost += 0.1;
-            }
-            if body_lower.contains(term) {
-                keyword_boost += 0.05;
-            }
+        result.keyword_score = keyword_scores.get(&result.id).copied().unwrap_or(0.0);
+        result.combined_score = combined_score(result.vector_score, result.keyword_score, alpha);
+        result.score = result.combined_score;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(id: &str, title: &str, vector_score: f32) -> SearchResult {
+        SearchResult {
+            id: id.to_string(),
+            item_type: "issue".to_string(),
+            title: title.to_string(),
+            body_preview: "Users are logged out after the token refresh".to_string(),
+            repo: "acme/api".to_string(),
+            number: 1,
+            state: "open".to_string(),
+            author: "alice".to_string(),
+            created_at: "2024-01-01T00:00:00Z".to_string(),
+            url: "https://github.com/acme/api/issues/1".to_string(),
+            score: vector_score,
+            vector_score: Some(vector_score),
+            keyword_score: 0.0,
+            combined_score: vector_score,
+        }
+    }
+
+    #[test]
+    fn test_combined_score_is_alpha_weighted_blend() {
+        let mut results = vec![result("issue-1", "Token refresh logs users out", 0.6), result("issue-2", "Unrelated crash", 0.8)];
+        let scores = keyword_scores(&[("issue".to_string(), 1)]);
+        apply_keyword_scores(&mut results, &scores, 0.7);
+
+        for r in &results {
+            assert!((0.0..=1.0).contains(&r.keyword_score));
+            let expected = 0.7 * r.vector_score.unwrap() + 0.3 * r.keyword_score;
+            assert!((r.combined_score - expected).abs() < 1e-6);
+            assert_eq!(r.score, r.combined_score);
+        }
+        // The only full-text hit gets the top keyword score; the other isn't a keyword match
+        assert!((results[0].keyword_score - 1.0).abs() < 1e-6);
+        assert_eq!(results[1].keyword_score, 0.0);
+
+        let json = serde_json::to_value(&results[0]).unwrap();
+        for field in ["vector_score", "keyword_score", "combined_score"] {
+            assert!(json.get(field).is_some(), "missing {}", field);
         }
-        
-        // Apply boost (max 30% boost)
-        result.score *= 1.0 + keyword_boost.min(0.3);
+    }
+
+    #[test]
+    fn test_alpha_extremes_pick_one_component() {
+        let mut results = vec![result("issue-1", "Token refresh logs users out", 0.6)];
+        let scores = keyword_scores(&[("pull_request".to_string(), 4), ("issue".to_string(), 1)]);
+
+        apply_keyword_scores(&mut results, &scores, 1.0);
+        assert!((results[0].combined_score - 0.6).abs() < 1e-6);
+
+        apply_keyword_scores(&mut results, &scores, 0.0);
+        assert!((results[0].combined_score - results[0].keyword_score).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_keyword_scores_follow_fts_rank() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::db::migrations::run_migrations(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO repositories (id, owner, name) VALUES (1, 'acme', 'api');
+             INSERT INTO issues (id, github_id, repo_id, number, title, body, state, created_at, updated_at, labels)
+             VALUES (1, 1, 1, 1, 'Token refresh logs users out', 'The token refresh races', 'open',
+                     '2024-01-01T00:00:00Z', '2024-01-01T00:00:00Z', '[]');
+             INSERT INTO pull_requests (id, github_id, repo_id, number, title, body, state, created_at, updated_at, labels)
+             VALUES (1, 2, 1, 2, 'Bump dependencies', 'Includes a token refresh fix', 'open',
+                     '2024-01-02T00:00:00Z', '2024-01-02T00:00:00Z', '[]'),
+                    (2, 3, 1, 3, 'Unrelated crash', NULL, 'open',
+                     '2024-01-02T00:00:00Z', '2024-01-02T00:00:00Z', '[]');",
+        )
+        .unwrap();
+
+        let hits = keyword_search(&conn, "token refresh", 10).unwrap();
+        let scores = keyword_scores(&hits);
+
+        assert_eq!(scores.len(), 2);
+        assert!(scores["issue-1"] > scores["pr-1"]);
+        assert!((scores["issue-1"] - 1.0).abs() < 1e-6);
+        assert!(!scores.contains_key("pr-2"));
+
+        let loaded = load_result(&conn, &ItemType::PullRequest, 2, None).unwrap();
+        assert_eq!((loaded.id.as_str(), loaded.body_preview.as_str()), ("pr-2", ""));
+    }
+
+    #[test]
+    fn test_keyword_only_hits_get_their_stored_similarity() {
+        let conn = Connection::open_in_memory().unwrap();
+        crate::db::migrations::run_migrations(&conn).unwrap();
+        conn.execute_batch(
+            "INSERT INTO repositories (id, owner, name) VALUES (1, 'acme', 'api');
+             INSERT INTO issues (id, github_id, repo_id, number, title, state, created_at, updated_at, labels)
+             VALUES (1, 1, 1, 1, 'Embedded', 'open', '2024-01-01T00:00:00Z', '2024-01-01T00:00:00Z', '[]'),
+                    (2, 2, 1, 2, 'Not embedded', 'open', '2024-01-01T00:00:00Z', '2024-01-01T00:00:00Z', '[]');",
+        )
+        .unwrap();
+        crate::db::queries::set_issue_embedding(&conn, 1, &[0.6, 0.8], "test-model").unwrap();
+
+        let similarity = stored_similarity(&conn, &ItemType::Issue, 1, &[1.0, 0.0]).unwrap();
+        assert!((similarity.unwrap() - 0.6).abs() < 1e-6);
+        assert_eq!(stored_similarity(&conn, &ItemType::Issue, 2, &[1.0, 0.0]).unwrap(), None);
+
+        // The breakdown reports "not computed" rather than a similarity of 0
+        let mut results = vec![load_result(&conn, &ItemType::Issue, 2, None).unwrap()];
+        apply_keyword_scores(&mut results, &keyword_scores(&[("issue".to_string(), 2)]), 0.7);
+        assert_eq!(results[0].vector_score, None);
+        assert!((results[0].combined_score - 0.3).abs() < 1e-6);
+        assert_eq!(serde_json::to_value(&results[0]).unwrap()["vector_score"], serde_json::Value::Null);
     }
 }
@@ -1,5 +1,6 @@
 pub mod commands;
 pub mod duplicates;
+pub mod fts;
 pub mod hybrid;
 pub mod vector_store;
 
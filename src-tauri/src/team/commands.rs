@@ -2,13 +2,18 @@ use crate::db::{
     models::User,
     project_queries::TimelineEvent,
     queries,
-    user_queries::{ActivityDataPoint, CollaborationMatrix, FocusMetrics, RepositoryContribution, UserSummary},
+    user_queries::{
+        ActivityDataPoint, CollaborationMatrix, DecliningContributor, FirstContribution, FocusMetrics, RampTime, RepositoryContribution,
+        ReviewOnlyContributor, ReviewerSuggestion, ShippingStreaks, Specialization, UserSummary,
+    },
     AppState,
 };
 use crate::github::auth;
+use crate::metrics::anonymize;
 use reqwest::Client;
 use rusqlite::params;
 use serde::Deserialize;
+use std::collections::BTreeMap;
 use tauri::{Manager, State};
 
 /// Add a user to the tracked users list
@@ -172,9 +177,17 @@ pub async fn remove_tracked_user(
     Ok(())
 }
 
-/// Get all tracked users
+/// Get the team roster shown on the Team page: every known user, tracked users first.
+/// This is the roster command (there is no separate `get_team_roster`). With
+/// `hide_inactive`, tracked users with no PRs, reviews, issues or commits in the last
+/// `days` days (default 30) are left out; untracked users are never hidden.
 #[tauri::command]
-pub async fn get_tracked_users(state: State<'_, AppState>) -> Result<Vec<User>, String> {
+pub async fn get_tracked_users(
+    hide_inactive: Option<bool>,
+    days: Option<i32>,
+    anonymize: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<Vec<User>, String> {
     let conn = state.sqlite.lock().map_err(|e| e.to_string())?;
 
     let mut stmt = conn
@@ -202,7 +215,17 @@ pub async fn get_tracked_users(state: State<'_, AppState>) -> Result<Vec<User>,
         .collect::<Result<Vec<_>, _>>()
         .map_err(|e| e.to_string())?;
 
-    Ok(users)
+    // Optionally omit tracked users with no activity in the window (off by default)
+    let users = if hide_inactive.unwrap_or(false) {
+        let days = days.unwrap_or(30); // Default to 30 days
+        let settings = queries::get_settings(&conn).map_err(|e| e.to_string())?;
+        let period = crate::db::metrics_queries::Period::from_settings(days, &settings);
+        crate::db::user_queries::hide_inactive_users(&conn, users, &period.since).map_err(|e| e.to_string())?
+    } else {
+        users
+    };
+
+    anonymize::apply(&conn, users, anonymize.unwrap_or(false)).map_err(|e| e.to_string())
 }
 
 /// Update the tracked status for a user
@@ -250,12 +273,34 @@ pub async fn update_user_tracked_status(
         .ok_or_else(|| format!("User '{}' not found after update", username))
 }
 
+/// Track every squad member who isn't tracked yet; returns how many were (or, with
+/// `dry_run`, would be) newly tracked
+#[tauri::command]
+pub async fn sync_tracked_with_squads(
+    dry_run: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<usize, String> {
+    let conn = state.sqlite.lock().map_err(|e| e.to_string())?;
+    let dry_run = dry_run.unwrap_or(false);
+
+    let count = queries::track_squad_members(&conn, dry_run)
+        .map_err(|e| format!("Failed to track squad members: {}", e))?;
+
+    tracing::info!(
+        "{} {} squad members",
+        if dry_run { "Would track" } else { "Tracked" },
+        count
+    );
+    Ok(count)
+}
+
 /// Get summary statistics for a user
 #[tauri::command]
 pub async fn get_user_summary(
     username: String,
     start_date: Option<String>,
     end_date: Option<String>,
+    anonymize: Option<bool>,
     state: State<'_, AppState>,
 ) -> Result<UserSummary, String> {
     let conn = state.sqlite.lock().map_err(|e| e.to_string())?;
@@ -277,6 +322,62 @@ pub async fn get_user_summary(
         end_date.as_deref(),
     )
     .map_err(|e| format!("Failed to get user summary: {}", e))
+    .and_then(|summary| anonymize::apply(&conn, summary, anonymize.unwrap_or(false)).map_err(|e| e.to_string()))
+}
+
+/// Find a user's id by login
+fn user_id_by_login(conn: &rusqlite::Connection, login: &str) -> Result<i64, String> {
+    conn.query_row("SELECT id FROM users WHERE login = ?1", params![login], |row| row.get(0))
+        .map_err(|e| format!("User '{}' not found: {}", login, e))
+}
+
+/// Set a free-form annotation (role, location, manager, ...) on a user
+#[tauri::command]
+pub async fn set_user_metadata(
+    username: String,
+    key: String,
+    value: String,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let conn = state.sqlite.lock().map_err(|e| e.to_string())?;
+
+    let key = key.trim();
+    if key.is_empty() {
+        return Err("Metadata key cannot be empty".to_string());
+    }
+    let user_id = user_id_by_login(&conn, &username)?;
+
+    crate::db::user_queries::set_user_metadata(&conn, user_id, key, &value)
+        .map_err(|e| format!("Failed to set user metadata: {}", e))
+}
+
+/// Remove an annotation from a user
+#[tauri::command]
+pub async fn delete_user_metadata(
+    username: String,
+    key: String,
+    state: State<'_, AppState>,
+) -> Result<bool, String> {
+    let conn = state.sqlite.lock().map_err(|e| e.to_string())?;
+
+    let user_id = user_id_by_login(&conn, &username)?;
+
+    crate::db::user_queries::delete_user_metadata(&conn, user_id, key.trim())
+        .map_err(|e| format!("Failed to delete user metadata: {}", e))
+}
+
+/// Get all annotations on a user
+#[tauri::command]
+pub async fn get_user_metadata(
+    username: String,
+    state: State<'_, AppState>,
+) -> Result<BTreeMap<String, String>, String> {
+    let conn = state.sqlite.lock().map_err(|e| e.to_string())?;
+
+    let user_id = user_id_by_login(&conn, &username)?;
+
+    crate::db::user_queries::get_user_metadata(&conn, user_id)
+        .map_err(|e| format!("Failed to get user metadata: {}", e))
 }
 
 /// Get activity timeline for a user
@@ -347,6 +448,7 @@ pub async fn get_team_collaboration_matrix(
     usernames: Vec<String>,
     start_date: Option<String>,
     end_date: Option<String>,
+    anonymize: Option<bool>,
     state: State<'_, AppState>,
 ) -> Result<CollaborationMatrix, String> {
     let conn = state.sqlite.lock().map_err(|e| e.to_string())?;
@@ -379,6 +481,7 @@ pub async fn get_team_collaboration_matrix(
         end_date.as_deref(),
     )
     .map_err(|e| format!("Failed to get collaboration matrix: {}", e))
+    .and_then(|matrix| anonymize::apply(&conn, matrix, anonymize.unwrap_or(false)).map_err(|e| e.to_string()))
 }
 
 /// Get activity trend for a user over time
@@ -440,3 +543,145 @@ pub async fn get_user_focus_metrics(
     )
     .map_err(|e| format!("Failed to get focus metrics: {}", e))
 }
+
+/// Get how concentrated a contributor's work is across repositories over the last N days
+#[tauri::command]
+pub async fn get_specialization(
+    login: String,
+    days: Option<i32>,
+    anonymize: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<Specialization, String> {
+    let conn = state.sqlite.lock().map_err(|e| e.to_string())?;
+
+    let user_id = user_id_by_login(&conn, &login)?;
+    let days = days.unwrap_or(90); // Default to 90 days
+    let settings = queries::get_settings(&conn).map_err(|e| e.to_string())?;
+    let period = crate::db::metrics_queries::Period::from_settings(days, &settings);
+
+    crate::db::user_queries::get_specialization(&conn, user_id, &period.since)
+        .map_err(|e| format!("Failed to get specialization: {}", e))
+        .and_then(|specialization| anonymize::apply(&conn, specialization, anonymize.unwrap_or(false)).map_err(|e| e.to_string()))
+}
+
+/// Get onboarding ramp time for a contributor (weeks until steady merged-PR velocity)
+#[tauri::command]
+pub async fn get_ramp_time(
+    login: String,
+    target_fraction: Option<f64>,
+    state: State<'_, AppState>,
+) -> Result<RampTime, String> {
+    let conn = state.sqlite.lock().map_err(|e| e.to_string())?;
+
+    // Find user by username
+    let user_id: i64 = conn
+        .query_row(
+            "SELECT id FROM users WHERE login = ?1",
+            params![login],
+            |row| row.get(0),
+        )
+        .map_err(|e| format!("User '{}' not found: {}", login, e))?;
+
+    let target_fraction = target_fraction.unwrap_or(0.75); // 75% of eventual median
+    if target_fraction <= 0.0 || target_fraction > 1.0 {
+        return Err("target_fraction must be between 0 and 1".to_string());
+    }
+
+    crate::db::user_queries::get_user_ramp_time(&conn, user_id, target_fraction)
+        .map_err(|e| format!("Failed to get ramp time: {}", e))
+}
+
+/// Get a user's current and longest streaks of consecutive weeks with a merged PR
+#[tauri::command]
+pub async fn get_shipping_streaks(
+    login: String,
+    anonymize: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<ShippingStreaks, String> {
+    let conn = state.sqlite.lock().map_err(|e| e.to_string())?;
+
+    // Find user by username
+    let user_id: i64 = conn
+        .query_row(
+            "SELECT id FROM users WHERE login = ?1",
+            params![login],
+            |row| row.get(0),
+        )
+        .map_err(|e| format!("User '{}' not found: {}", login, e))?;
+
+    crate::db::user_queries::get_shipping_streaks(&conn, user_id, chrono::Utc::now().date_naive())
+        .map_err(|e| format!("Failed to get shipping streaks: {}", e))
+        .and_then(|streaks| anonymize::apply(&conn, streaks, anonymize.unwrap_or(false)).map_err(|e| e.to_string()))
+}
+
+/// Get users whose first-ever merged PR landed in the last N days
+#[tauri::command]
+pub async fn get_first_contributions(
+    days: Option<i32>,
+    anonymize: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<Vec<FirstContribution>, String> {
+    let conn = state.sqlite.lock().map_err(|e| e.to_string())?;
+
+    let days = days.unwrap_or(30); // Default to 30 days
+    let settings = queries::get_settings(&conn).map_err(|e| e.to_string())?;
+    let period = crate::db::metrics_queries::Period::from_settings(days, &settings);
+
+    crate::db::user_queries::get_first_contributions(&conn, &period, &settings.excluded_bots)
+        .map_err(|e| format!("Failed to get first contributions: {}", e))
+        .and_then(|contributions| anonymize::apply(&conn, contributions, anonymize.unwrap_or(false)).map_err(|e| e.to_string()))
+}
+
+/// Get tracked users who reviewed PRs in the last N days without authoring any
+#[tauri::command]
+pub async fn get_review_only_contributors(
+    days: Option<i32>,
+    anonymize: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<Vec<ReviewOnlyContributor>, String> {
+    let conn = state.sqlite.lock().map_err(|e| e.to_string())?;
+
+    let days = days.unwrap_or(30); // Default to 30 days
+    let settings = queries::get_settings(&conn).map_err(|e| e.to_string())?;
+    let period = crate::db::metrics_queries::Period::from_settings(days, &settings);
+
+    crate::db::user_queries::get_review_only_contributors(&conn, &period.since)
+        .map_err(|e| format!("Failed to get review-only contributors: {}", e))
+        .and_then(|contributors| anonymize::apply(&conn, contributors, anonymize.unwrap_or(false)).map_err(|e| e.to_string()))
+}
+
+/// Suggest reviewers for a PR based on who reviewed past PRs touching the same paths
+#[tauri::command]
+pub async fn suggest_reviewers(
+    pr_id: i64,
+    limit: Option<usize>,
+    anonymize: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<Vec<ReviewerSuggestion>, String> {
+    let conn = state.sqlite.lock().map_err(|e| e.to_string())?;
+
+    let limit = limit.unwrap_or(5);
+    let settings = queries::get_settings(&conn).map_err(|e| e.to_string())?;
+
+    crate::db::user_queries::suggest_reviewers(&conn, pr_id, limit, &settings.excluded_bots)
+        .map_err(|e| format!("Failed to suggest reviewers: {}", e))
+        .and_then(|reviewers| anonymize::apply(&conn, reviewers, anonymize.unwrap_or(false)).map_err(|e| e.to_string()))
+}
+
+/// Get tracked users whose weekly activity has declined steadily over the last N days
+#[tauri::command]
+pub async fn get_declining_contributors(
+    days: Option<i32>,
+    anonymize: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<Vec<DecliningContributor>, String> {
+    let conn = state.sqlite.lock().map_err(|e| e.to_string())?;
+
+    let days = days.unwrap_or(90); // Default to 90 days, enough weeks for a trend
+    let settings = queries::get_settings(&conn).map_err(|e| e.to_string())?;
+    let period = crate::db::metrics_queries::Period::from_settings(days, &settings);
+
+    crate::db::user_queries::get_declining_contributors(&conn, &period.since, chrono::Utc::now())
+        .map_err(|e| format!("Failed to get declining contributors: {}", e))
+        .and_then(|contributors| anonymize::apply(&conn, contributors, anonymize.unwrap_or(false)).map_err(|e| e.to_string()))
+}
@@ -15,6 +15,9 @@ interface SearchResult {
   created_at: string;
   url: string;
   score: number;
+  vector_score: number | null; // null when the item has no embedding
+  keyword_score: number;
+  combined_score: number;
   duplicates?: DuplicateMatch[];
 }
 
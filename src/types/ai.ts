@@ -1,6 +1,16 @@
 export interface AppContext {
   current_page: string;
   filters: FilterState;
+  sources?: ChatSource[]; // Search/duplicate results the assistant may cite
+}
+
+export interface ChatSource {
+  item_type: 'issue' | 'pull_request';
+  id: number;
+  title: string;
+  repo?: string;
+  number?: number;
+  similarity: number; // 0-1; results below the configured floor are dropped
 }
 
 export interface FilterState {
@@ -13,6 +13,9 @@ export interface MetricsFilters {
   repositoryIds?: number[];
   squadId?: string;
   userId?: number;
+  role?: 'author' | 'reviewer' | 'assignee'; // How userId relates to the items (default author)
+  baseBranch?: string; // Only PRs targeting this branch
+  visibility?: 'all' | 'public' | 'private'; // Repository visibility
 }
 
 /**
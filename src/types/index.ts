@@ -82,6 +82,7 @@ export interface UserSummary {
   first_activity: string | null;
   last_activity: string | null;
   activity_status: 'active' | 'quiet' | 'idle';
+  metadata: Record<string, string>;
 }
 
 export interface RepositoryContribution {
@@ -13,6 +13,17 @@ export interface OverviewMetrics {
   period_days: number;
   total_prs: number;
   active_developers: number;
+  tiers: PerformanceTiers;
+}
+
+export type PerformanceTier = 'Elite' | 'High' | 'Medium' | 'Low';
+
+// null when the period has no PRs to rate
+export interface PerformanceTiers {
+  speed: PerformanceTier | null;
+  ease: PerformanceTier | null;
+  quality: PerformanceTier | null;
+  overall: PerformanceTier | null;
 }
 
 // ============================================================================
@@ -57,6 +68,7 @@ export interface EaseMetrics {
   active_repos: ActiveRepository[];
   repo_distribution: RepoDistribution;
   work_pattern: WorkPatternCell[];
+  after_hours_pct: number;  // % of PRs opened outside core hours
   pr_switch_frequency: number;
   benchmark_comparison: EaseBenchmarks;
 }
@@ -80,6 +92,7 @@ export interface WorkPatternCell {
   day_of_week: number;  // 0=Sunday, 1=Monday, etc.
   hour_of_day: number;  // 0-23
   activity_count: number;
+  is_core_hours: boolean;  // Inside configured core hours
 }
 
 export interface EaseBenchmarks {
@@ -98,6 +111,9 @@ export interface QualityMetrics {
   feature_pr_percentage: number;
   avg_review_cycle_hours: number;
   avg_review_comments: number;
+  avg_commits_per_pr: number;
+  review_coverage_pct: number;
+  reviewed_loc_pct: number;
   pr_type_distribution: PrTypeBreakdown[];
   files_per_pr_distribution: FilesPerPrDistribution;
   merge_rate_trend: MergeRateTrend[];